@@ -201,7 +201,7 @@ impl Account {
         limit: Option<u16>,
     ) -> Result<Self, BasicErrorWith404> {
         let (latest_ledger_info, requested_version) = context
-            .get_latest_ledger_info_and_verify_lookup_version(
+            .get_latest_ledger_info_and_verify_state_lookup_version(
                 requested_ledger_version.map(|inner| inner.0),
             )?;
 
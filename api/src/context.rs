@@ -20,7 +20,9 @@ use aptos_config::config::{GasEstimationConfig, NodeConfig, RoleType};
 use aptos_crypto::HashValue;
 use aptos_gas_schedule::{AptosGasParameters, FromOnChainGasSchedule};
 use aptos_logger::{error, info, Schema};
-use aptos_mempool::{MempoolClientRequest, MempoolClientSender, SubmissionStatus};
+use aptos_mempool::{
+    MempoolClientRequest, MempoolClientSender, SenderTransactions, SubmissionStatus,
+};
 use aptos_storage_interface::{
     state_view::{DbStateView, DbStateViewAtVersion, LatestDbStateCheckpointView},
     AptosDbError, DbReader, Order, MAX_REQUEST_LIMIT,
@@ -79,6 +81,7 @@ pub struct Context {
     simulate_txn_stats: Arc<FunctionStats>,
     pub indexer_reader: Option<Arc<dyn IndexerReader>>,
     pub wait_for_hash_active_connections: Arc<AtomicUsize>,
+    pub stream_active_connections: Arc<AtomicUsize>,
 }
 
 impl std::fmt::Debug for Context {
@@ -131,6 +134,7 @@ impl Context {
             simulate_txn_stats,
             indexer_reader,
             wait_for_hash_active_connections: Arc::new(AtomicUsize::new(0)),
+            stream_active_connections: Arc::new(AtomicUsize::new(0)),
         }
     }
 
@@ -169,7 +173,7 @@ impl Context {
         requested_ledger_version: Option<u64>,
     ) -> Result<(LedgerInfo, u64, DbStateView), E> {
         let (latest_ledger_info, requested_ledger_version) =
-            self.get_latest_ledger_info_and_verify_lookup_version(requested_ledger_version)?;
+            self.get_latest_ledger_info_and_verify_state_lookup_version(requested_ledger_version)?;
 
         let state_view = self
             .state_view_at_version(requested_ledger_version)
@@ -204,6 +208,10 @@ impl Context {
         self.node_config.api.max_submit_transaction_batch_size
     }
 
+    pub fn max_view_function_batch_size(&self) -> usize {
+        self.node_config.api.max_view_function_batch_size
+    }
+
     pub async fn submit_transaction(&self, txn: SignedTransaction) -> Result<SubmissionStatus> {
         let (req_sender, callback) = oneshot::channel();
         self.mp_sender
@@ -306,6 +314,44 @@ impl Context {
         Ok((latest_ledger_info, requested_ledger_version))
     }
 
+    /// Same as [`Self::get_latest_ledger_info_and_verify_lookup_version`], but for state lookups,
+    /// which only need the state values at the requested version. In archive mode, state values
+    /// are kept past the ledger pruning window, so older versions can still be looked up.
+    pub fn get_latest_ledger_info_and_verify_state_lookup_version<E: StdApiError>(
+        &self,
+        requested_ledger_version: Option<Version>,
+    ) -> Result<(LedgerInfo, Version), E> {
+        let latest_ledger_info = self.get_latest_ledger_info()?;
+
+        let requested_ledger_version =
+            requested_ledger_version.unwrap_or_else(|| latest_ledger_info.version());
+
+        // This is too far in the future, a retriable case
+        if requested_ledger_version > latest_ledger_info.version() {
+            return Err(version_not_found(
+                requested_ledger_version,
+                &latest_ledger_info,
+            ));
+        } else if requested_ledger_version < latest_ledger_info.oldest_ledger_version.0 {
+            let first_state_value_version = self
+                .db
+                .get_first_state_value_version()
+                .context("Failed to retrieve the first state value version")
+                .map_err(|err| {
+                    E::internal_with_code(err, AptosErrorCode::InternalError, &latest_ledger_info)
+                })?
+                .unwrap_or_default();
+            if requested_ledger_version < first_state_value_version {
+                return Err(version_pruned(
+                    requested_ledger_version,
+                    &latest_ledger_info,
+                ));
+            }
+        }
+
+        Ok((latest_ledger_info, requested_ledger_version))
+    }
+
     pub fn get_latest_internal_indexer_ledger_info<E: ServiceUnavailableError>(
         &self,
     ) -> Result<LedgerInfo, E> {
@@ -884,6 +930,40 @@ impl Context {
             .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))
     }
 
+    pub fn get_transactions_by_entry_function<E: InternalError>(
+        &self,
+        module_id: &ModuleId,
+        function_name: &Identifier,
+        start_version: u64,
+        limit: u16,
+        ledger_version: u64,
+        ledger_info: &LedgerInfo,
+    ) -> Result<Vec<TransactionOnChainData>, E> {
+        let txns = self
+            .indexer_reader
+            .as_ref()
+            .ok_or_else(|| anyhow!("Internal indexer reader doesn't exist"))
+            .and_then(|indexer_reader| {
+                indexer_reader.get_transactions_by_entry_function(
+                    module_id,
+                    function_name,
+                    start_version,
+                    limit as u64,
+                    true,
+                    ledger_version,
+                )
+            })
+            .context("Failed to retrieve transactions by entry function")
+            .map_err(|err| {
+                E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info)
+            })?;
+        txns.into_iter()
+            .map(|t| self.convert_into_transaction_on_chain_data(t))
+            .collect::<Result<Vec<_>>>()
+            .context("Failed to parse transactions by entry function")
+            .map_err(|err| E::internal_with_code(err, AptosErrorCode::InternalError, ledger_info))
+    }
+
     pub fn get_transaction_by_hash(
         &self,
         hash: HashValue,
@@ -910,6 +990,23 @@ impl Context {
         callback.await.map_err(anyhow::Error::from)
     }
 
+    pub async fn get_pending_transactions_by_sender(
+        &self,
+        sender: AccountAddress,
+    ) -> Result<SenderTransactions> {
+        let (req_sender, callback) = oneshot::channel();
+
+        self.mp_sender
+            .clone()
+            .send(MempoolClientRequest::GetTransactionsBySender(
+                sender, req_sender,
+            ))
+            .await
+            .map_err(anyhow::Error::from)?;
+
+        callback.await.map_err(anyhow::Error::from)
+    }
+
     pub fn get_transaction_by_version(
         &self,
         version: u64,
@@ -968,6 +1065,42 @@ impl Context {
         }
     }
 
+    pub fn get_events_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_version: u64,
+        start_event_index: u64,
+        limit: u16,
+        ledger_version: u64,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.indexer_reader
+            .as_ref()
+            .ok_or_else(|| anyhow!("Internal indexer reader doesn't exist"))?
+            .get_events_by_type(
+                struct_tag,
+                start_version,
+                start_event_index,
+                limit as u64,
+                ledger_version,
+            )
+    }
+
+    pub fn event_by_type_index_enabled(&self) -> bool {
+        self.indexer_reader
+            .as_ref()
+            .map_or(false, |indexer_reader| {
+                indexer_reader.is_event_by_type_index_enabled()
+            })
+    }
+
+    pub fn transaction_by_entry_function_index_enabled(&self) -> bool {
+        self.indexer_reader
+            .as_ref()
+            .map_or(false, |indexer_reader| {
+                indexer_reader.is_transaction_by_entry_function_index_enabled()
+            })
+    }
+
     pub fn get_indexer_reader(&self) -> Option<&Arc<dyn IndexerReader>> {
         self.indexer_reader.as_ref()
     }
@@ -9,8 +9,8 @@ use crate::{
     failpoint::fail_point_poem,
     page::Page,
     response::{
-        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
-        InternalError,
+        api_disabled, BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus,
+        BasicResultWith404, InternalError,
     },
     ApiTags,
 };
@@ -19,7 +19,8 @@ use aptos_api_types::{
     verify_field_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper, LedgerInfo,
     MoveStructTag, VerifyInputWithRecursion, VersionedEvent, U64,
 };
-use aptos_types::event::EventKey;
+use aptos_types::{contract_event::EventWithVersion, event::EventKey};
+use move_core_types::language_storage::StructTag;
 use poem_openapi::{
     param::{Path, Query},
     OpenApi,
@@ -150,6 +151,80 @@ impl EventsApi {
         })
         .await
     }
+
+    /// Get events by type
+    ///
+    /// This API returns the events of the given type (e.g., `0x1::coin::CoinDeposit`),
+    /// emitted by any transaction, in ascending version order. To get the next page,
+    /// use the version of the last returned event as the start version, and the
+    /// number of returned events with that version as the start event index (adding
+    /// the start event index of the request if that's also the requested start version).
+    ///
+    /// This requires the internal indexer of the node to index events by type.
+    #[oai(
+        path = "/events/by_type/:event_type",
+        method = "get",
+        operation_id = "get_events_by_type",
+        tag = "ApiTags::Events"
+    )]
+    async fn get_events_by_type(
+        &self,
+        accept_type: AcceptType,
+        /// Fully qualified struct tag of the event type e.g. `0x1::coin::CoinDeposit`
+        event_type: Path<MoveStructTag>,
+        /// Ledger version to start list of events
+        ///
+        /// If unspecified, defaults to version 0
+        start_version: Query<Option<U64>>,
+        /// Index of the event to start list of events, among the events of the
+        /// given type emitted by the transaction at the start version
+        ///
+        /// If unspecified, defaults to 0
+        start_event_index: Query<Option<U64>>,
+        /// Max number of events to retrieve.
+        ///
+        /// If unspecified, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        event_type
+            .0
+            .verify(0)
+            .context("'event_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        let struct_tag = StructTag::try_from(event_type.0)
+            .context("'event_type' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_events_by_type")?;
+        if !self.context.event_by_type_index_enabled() {
+            return Err(api_disabled("Get events by type"));
+        }
+        self.context
+            .check_api_output_enabled("Get events by type", &accept_type)?;
+        let page = Page::new(
+            start_version.0.map(|v| v.0),
+            limit.0,
+            self.context.max_events_page_size(),
+        );
+
+        let start_event_index = start_event_index.0.map_or(0, |index| index.0);
+
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            let latest_ledger_info = api.context.get_latest_ledger_info()?;
+            api.list_by_type(
+                latest_ledger_info,
+                accept_type,
+                page,
+                start_event_index,
+                struct_tag,
+            )
+        })
+        .await
+    }
 }
 
 impl EventsApi {
@@ -179,6 +254,47 @@ impl EventsApi {
                 )
             })?;
 
+        self.render(latest_ledger_info, accept_type, events)
+    }
+
+    /// List events of the given type
+    fn list_by_type(
+        &self,
+        latest_ledger_info: LedgerInfo,
+        accept_type: AcceptType,
+        page: Page,
+        start_event_index: u64,
+        struct_tag: StructTag,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
+        let ledger_version = latest_ledger_info.version();
+        let events = self
+            .context
+            .get_events_by_type(
+                &struct_tag,
+                page.start_option().unwrap_or(0),
+                start_event_index,
+                page.limit(&latest_ledger_info)?,
+                ledger_version,
+            )
+            .context(format!("Failed to find events by type {}", struct_tag))
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &latest_ledger_info,
+                )
+            })?;
+
+        self.render(latest_ledger_info, accept_type, events)
+    }
+
+    /// Renders the events in the format of the accept type
+    fn render(
+        &self,
+        latest_ledger_info: LedgerInfo,
+        accept_type: AcceptType,
+        events: Vec<EventWithVersion>,
+    ) -> BasicResultWith404<Vec<VersionedEvent>> {
         match accept_type {
             AcceptType::Json => {
                 let events = self
@@ -23,6 +23,8 @@ mod runtime;
 mod set_failpoints;
 pub mod spec;
 mod state;
+mod state_override;
+mod stream;
 #[cfg(test)]
 pub mod tests;
 mod transactions;
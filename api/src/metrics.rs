@@ -96,6 +96,14 @@ pub static WAIT_TRANSACTION_GAUGE: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+pub static STREAM_ACTIVE_CONNECTIONS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_api_stream_active_connections",
+        "Number of open transaction and event streams"
+    )
+    .unwrap()
+});
+
 pub static WAIT_TRANSACTION_POLL_TIME: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         "aptos_api_wait_transaction_poll_time",
@@ -0,0 +1,183 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! State overrides of a transaction simulation, applied on top of the state in the DB.
+
+use crate::context::Context;
+use anyhow::{bail, ensure, format_err, Context as AnyhowContext};
+use aptos_api_types::{
+    AsConverter, BalanceOverride, ModuleOverride, MoveModuleBytecode, ResourceGroup,
+    ResourceOverride, StateOverride,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    account_config::{
+        primary_apt_store, CoinStoreResource, ConcurrentFungibleBalanceResource,
+        FungibleStoreResource, ObjectGroupResource,
+    },
+    state_store::{
+        errors::StateviewError, state_key::StateKey, state_storage_usage::StateStorageUsage,
+        state_value::StateValue, StateView, TStateView,
+    },
+    AptosCoinType,
+};
+use move_core_types::{
+    language_storage::{StructTag, TypeTag},
+    move_resource::MoveStructType,
+};
+use std::collections::HashMap;
+
+/// A state view serving overridden state values instead of those in the base state view.
+pub struct OverriddenStateView<'a, S> {
+    base: &'a S,
+    overrides: HashMap<StateKey, StateValue>,
+    overrides_modules: bool,
+}
+
+impl<'a, S: StateView> OverriddenStateView<'a, S> {
+    /// Overrides are applied in order, so later overrides see the effect of earlier ones.
+    pub fn new(
+        context: &Context,
+        base: &'a S,
+        state_overrides: Vec<StateOverride>,
+    ) -> anyhow::Result<Self> {
+        let mut view = Self {
+            base,
+            overrides: HashMap::new(),
+            overrides_modules: false,
+        };
+        for state_override in state_overrides {
+            view.overrides_modules |= matches!(state_override, StateOverride::Module(_));
+            let (state_key, bytes) = view.resolve(context, state_override)?;
+            view.overrides
+                .insert(state_key, StateValue::new_legacy(bytes.into()));
+        }
+        Ok(view)
+    }
+
+    /// Whether any module is overridden, in which case the VM must not reuse modules it loaded
+    /// from other state views.
+    pub fn overrides_modules(&self) -> bool {
+        self.overrides_modules
+    }
+
+    fn resolve(
+        &self,
+        context: &Context,
+        state_override: StateOverride,
+    ) -> anyhow::Result<(StateKey, Vec<u8>)> {
+        match state_override {
+            StateOverride::Resource(ResourceOverride {
+                address,
+                resource_type,
+                data,
+            }) => {
+                let struct_tag: StructTag = resource_type.try_into()?;
+                let converter =
+                    self.as_converter(context.db.clone(), context.indexer_reader.clone());
+                let value = converter
+                    .try_into_vm_value(&TypeTag::Struct(Box::new(struct_tag.clone())), data)
+                    .with_context(|| format!("Invalid value for resource {}", struct_tag))?;
+                let bytes = value
+                    .simple_serialize()
+                    .ok_or_else(|| format_err!("Failed to serialize resource {}", struct_tag))?;
+
+                // Members of a resource group are stored within the group
+                match converter.find_resource_group(&struct_tag) {
+                    Some(group_tag) => {
+                        let group_key = StateKey::resource_group(address.inner(), &group_tag);
+                        let mut group: ResourceGroup =
+                            match self.get_state_value_bytes(&group_key)? {
+                                Some(group_bytes) => bcs::from_bytes(&group_bytes)?,
+                                None => ResourceGroup::new(),
+                            };
+                        group.insert(struct_tag, bytes);
+                        Ok((group_key, bcs::to_bytes(&group)?))
+                    },
+                    None => Ok((StateKey::resource(address.inner(), &struct_tag)?, bytes)),
+                }
+            },
+            StateOverride::Balance(BalanceOverride { address, amount }) => {
+                self.resolve_balance(*address.inner(), amount.into())
+            },
+            StateOverride::Module(ModuleOverride { address, bytecode }) => {
+                let module = MoveModuleBytecode::new(bytecode.0.clone())
+                    .try_parse_abi()?
+                    .abi
+                    .ok_or_else(|| format_err!("Invalid module bytecode"))?;
+                ensure!(
+                    module.address == address,
+                    "Module {}::{} can't be published at {}",
+                    module.address,
+                    module.name.as_str(),
+                    address,
+                );
+                Ok((StateKey::module(address.inner(), &module.name), bytecode.0))
+            },
+        }
+    }
+
+    /// The APT balance is either in the coin store, or in the primary fungible store of the
+    /// account, possibly as a concurrent balance.
+    fn resolve_balance(
+        &self,
+        address: AccountAddress,
+        amount: u64,
+    ) -> anyhow::Result<(StateKey, Vec<u8>)> {
+        let coin_store_key =
+            StateKey::resource_typed::<CoinStoreResource<AptosCoinType>>(&address)?;
+        if let Some(bytes) = self.get_state_value_bytes(&coin_store_key)? {
+            let coin_store: CoinStoreResource<AptosCoinType> = bcs::from_bytes(&bytes)?;
+            let coin_store = CoinStoreResource::<AptosCoinType>::new(
+                amount,
+                coin_store.frozen(),
+                coin_store.deposit_events().clone(),
+                coin_store.withdraw_events().clone(),
+            );
+            return Ok((coin_store_key, bcs::to_bytes(&coin_store)?));
+        }
+
+        let group_key = StateKey::resource_group(
+            &primary_apt_store(address),
+            &ObjectGroupResource::struct_tag(),
+        );
+        if let Some(bytes) = self.get_state_value_bytes(&group_key)? {
+            let mut group: ObjectGroupResource = bcs::from_bytes(&bytes)?;
+            let concurrent_balance_tag = ConcurrentFungibleBalanceResource::struct_tag();
+            let store_tag = FungibleStoreResource::struct_tag();
+            if group.group.contains_key(&concurrent_balance_tag) {
+                group.insert(
+                    concurrent_balance_tag,
+                    bcs::to_bytes(&ConcurrentFungibleBalanceResource::new(amount))?,
+                );
+            } else if let Some(store_bytes) = group.group.get(&store_tag) {
+                let mut store: FungibleStoreResource = bcs::from_bytes(store_bytes)?;
+                store.balance = amount;
+                group.insert(store_tag, bcs::to_bytes(&store)?);
+            } else {
+                bail!("Primary fungible store of account {} is malformed", address);
+            }
+            return Ok((group_key, group.to_bytes()?));
+        }
+
+        bail!(
+            "Account {} holds no APT to override the balance of",
+            address
+        )
+    }
+}
+
+impl<S: StateView> TStateView for OverriddenStateView<'_, S> {
+    type Key = StateKey;
+
+    fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>, StateviewError> {
+        match self.overrides.get(state_key) {
+            Some(value) => Ok(Some(value.clone())),
+            None => self.base.get_state_value(state_key),
+        }
+    }
+
+    fn get_usage(&self) -> Result<StateStorageUsage, StateviewError> {
+        self.base.get_usage()
+    }
+}
@@ -0,0 +1,416 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    accept_type::AcceptType,
+    context::{api_spawn_blocking, Context},
+    failpoint::fail_point_poem,
+    metrics::STREAM_ACTIVE_CONNECTIONS,
+    response::{
+        version_pruned, BadRequestError, BasicErrorWith404, InternalError, ServiceUnavailableError,
+    },
+    ApiTags,
+};
+use anyhow::Context as AnyhowContext;
+use aptos_api_types::{
+    Address, AptosErrorCode, AsConverter, EntryFunctionId, LedgerInfo, MoveStructTag, Transaction,
+    TransactionOnChainData, VersionedEvent, U64,
+};
+use aptos_logger::warn;
+use aptos_types::{
+    account_address::AccountAddress,
+    contract_event::EventWithVersion,
+    transaction::{
+        EntryFunction, MultisigTransactionPayload, Transaction as RawTransaction,
+        TransactionPayload, Version,
+    },
+};
+use futures::{
+    stream::{self, BoxStream},
+    StreamExt,
+};
+use move_core_types::language_storage::{ModuleId, StructTag, TypeTag};
+use poem_openapi::{param::Query, payload::EventStream, OpenApi};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// API for streaming committed transactions and events as server-sent events
+#[derive(Clone)]
+pub struct StreamApi {
+    pub context: Arc<Context>,
+}
+
+#[OpenApi]
+impl StreamApi {
+    /// Stream transactions
+    ///
+    /// Pushes committed transactions as server-sent events, one JSON encoded
+    /// transaction per event, in version order. The stream doesn't end by
+    /// itself, so the client decides when to disconnect.
+    ///
+    /// To resume after a disconnect, reconnect with `start_version` set to
+    /// one past the version of the last transaction received.
+    #[oai(
+        path = "/transactions/stream",
+        method = "get",
+        operation_id = "stream_transactions",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn stream_transactions(
+        &self,
+        /// Ledger version to start streaming from, inclusive.
+        ///
+        /// If unspecified, only transactions committed from now on are streamed.
+        start_version: Query<Option<U64>>,
+        /// Only stream user transactions sent by this account.
+        sender: Query<Option<Address>>,
+        /// Only stream transactions calling this entry function, e.g.
+        /// `0x1::aptos_account::transfer`, directly or via a multisig account.
+        entry_function: Query<Option<String>>,
+        /// Only stream transactions emitting an event of this type, e.g.
+        /// `0x1::coin::CoinDeposit`. Type arguments are matched only if given.
+        event_type: Query<Option<MoveStructTag>>,
+    ) -> Result<EventStream<BoxStream<'static, Transaction>>, BasicErrorWith404> {
+        fail_point_poem("endpoint_stream_transactions")?;
+        self.context
+            .check_api_output_enabled("Stream transactions", &AcceptType::Json)?;
+        let filter = StreamFilter::new(sender.0, entry_function.0, event_type.0)?;
+        let state = self.start(start_version.0).await?;
+
+        let stream = state.into_stream(move |context, ledger_info, data| {
+            let data = data
+                .into_iter()
+                .filter(|txn| filter.matches_transaction(txn) && filter.matches_any_event(txn))
+                .collect();
+            context.render_transactions_non_sequential(ledger_info, data)
+        });
+        Ok(EventStream::new(stream).keep_alive(KEEP_ALIVE_INTERVAL))
+    }
+
+    /// Stream events
+    ///
+    /// Pushes events emitted by committed transactions as server-sent events,
+    /// one JSON encoded event per event, in version order. The stream doesn't
+    /// end by itself, so the client decides when to disconnect.
+    ///
+    /// To resume after a disconnect, reconnect with `start_version` set to
+    /// one past the version of the last event received.
+    #[oai(
+        path = "/events/stream",
+        method = "get",
+        operation_id = "stream_events",
+        tag = "ApiTags::Events"
+    )]
+    async fn stream_events(
+        &self,
+        /// Ledger version to start streaming from, inclusive.
+        ///
+        /// If unspecified, only events emitted from now on are streamed.
+        start_version: Query<Option<U64>>,
+        /// Only stream events emitted by user transactions sent by this account.
+        sender: Query<Option<Address>>,
+        /// Only stream events emitted by transactions calling this entry
+        /// function, e.g. `0x1::aptos_account::transfer`.
+        entry_function: Query<Option<String>>,
+        /// Only stream events of this type, e.g. `0x1::coin::CoinDeposit`.
+        /// Type arguments are matched only if given.
+        event_type: Query<Option<MoveStructTag>>,
+    ) -> Result<EventStream<BoxStream<'static, VersionedEvent>>, BasicErrorWith404> {
+        fail_point_poem("endpoint_stream_events")?;
+        self.context
+            .check_api_output_enabled("Stream events", &AcceptType::Json)?;
+        let filter = StreamFilter::new(sender.0, entry_function.0, event_type.0)?;
+        let state = self.start(start_version.0).await?;
+
+        let stream = state.into_stream(move |context, ledger_info, data| {
+            let events: Vec<_> = data
+                .into_iter()
+                .filter(|txn| filter.matches_transaction(txn))
+                .flat_map(|txn| {
+                    let version = txn.version;
+                    txn.events
+                        .into_iter()
+                        .filter(|event| filter.matches_event_type(event.type_tag()))
+                        .map(move |event| EventWithVersion::new(version, event))
+                })
+                .collect();
+            if events.is_empty() {
+                return Ok(vec![]);
+            }
+            context
+                .latest_state_view_poem(ledger_info)?
+                .as_converter(context.db.clone(), context.indexer_reader.clone())
+                .try_into_versioned_events(&events)
+                .context("Failed to convert events from storage into response")
+                .map_err(|err| {
+                    BasicErrorWith404::internal_with_code(
+                        err,
+                        AptosErrorCode::InternalError,
+                        ledger_info,
+                    )
+                })
+        });
+        Ok(EventStream::new(stream).keep_alive(KEEP_ALIVE_INTERVAL))
+    }
+}
+
+impl StreamApi {
+    /// Takes up one of the limited streaming connections, and checks `start_version` isn't
+    /// pruned.
+    async fn start(&self, start_version: Option<U64>) -> Result<StreamState, BasicErrorWith404> {
+        let connection = ActiveConnection::acquire(&self.context)?;
+        let context = self.context.clone();
+        let latest_ledger_info =
+            api_spawn_blocking(move || context.get_latest_ledger_info::<BasicErrorWith404>())
+                .await?;
+        let next_version = match start_version {
+            Some(version) => {
+                let version = version.0;
+                if version < latest_ledger_info.oldest_ledger_version.0 {
+                    return Err(version_pruned(version, &latest_ledger_info));
+                }
+                version
+            },
+            None => latest_ledger_info.version() + 1,
+        };
+
+        Ok(StreamState {
+            context: self.context.clone(),
+            next_version,
+            _connection: connection,
+        })
+    }
+}
+
+/// Releases the connection slot once the stream is dropped, i.e. the client is gone.
+struct ActiveConnection(Arc<AtomicUsize>);
+
+impl ActiveConnection {
+    fn acquire(context: &Context) -> Result<Self, BasicErrorWith404> {
+        let max_active_connections = context.node_config.api.stream_max_active_connections;
+        if context
+            .stream_active_connections
+            .fetch_add(1, Ordering::Relaxed)
+            >= max_active_connections
+        {
+            context
+                .stream_active_connections
+                .fetch_sub(1, Ordering::Relaxed);
+            return Err(BasicErrorWith404::service_unavailable_with_code_no_info(
+                format!(
+                    "Too many active streams, the limit is {}",
+                    max_active_connections
+                ),
+                AptosErrorCode::InternalError,
+            ));
+        }
+        STREAM_ACTIVE_CONNECTIONS.inc();
+        Ok(Self(context.stream_active_connections.clone()))
+    }
+}
+
+impl Drop for ActiveConnection {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+        STREAM_ACTIVE_CONNECTIONS.dec();
+    }
+}
+
+struct StreamState {
+    context: Arc<Context>,
+    next_version: Version,
+    _connection: ActiveConnection,
+}
+
+impl StreamState {
+    /// Polls the DB for transactions committed since `next_version`, pushing out what `render`
+    /// makes of them. Errors are logged and end the stream, since they can't be sent as an event.
+    fn into_stream<T, F>(self, render: F) -> BoxStream<'static, T>
+    where
+        T: Send + 'static,
+        F: Fn(
+                &Context,
+                &LedgerInfo,
+                Vec<TransactionOnChainData>,
+            ) -> Result<Vec<T>, BasicErrorWith404>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let render = Arc::new(render);
+        stream::unfold(self, move |mut state| {
+            let render = render.clone();
+            async move {
+                match state.next_batch(render).await {
+                    Ok(items) => Some((stream::iter(items), state)),
+                    Err(err) => {
+                        warn!(
+                            next_version = state.next_version,
+                            error = ?err,
+                            "Transaction stream terminated."
+                        );
+                        None
+                    },
+                }
+            }
+        })
+        .flatten()
+        .boxed()
+    }
+
+    /// Waits until there is anything to push out.
+    async fn next_batch<T, F>(&mut self, render: Arc<F>) -> Result<Vec<T>, BasicErrorWith404>
+    where
+        T: Send + 'static,
+        F: Fn(
+                &Context,
+                &LedgerInfo,
+                Vec<TransactionOnChainData>,
+            ) -> Result<Vec<T>, BasicErrorWith404>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let poll_interval =
+            Duration::from_millis(self.context.node_config.api.stream_poll_interval_ms);
+        loop {
+            let context = self.context.clone();
+            let next_version = self.next_version;
+            let render = render.clone();
+            let (num_txns, items) = api_spawn_blocking(move || {
+                let latest_ledger_info = context.get_latest_ledger_info::<BasicErrorWith404>()?;
+                let ledger_version = latest_ledger_info.version();
+                if next_version > ledger_version {
+                    return Ok((0, vec![]));
+                }
+                let data = context
+                    .get_transactions(
+                        next_version,
+                        context.max_transactions_page_size(),
+                        ledger_version,
+                    )
+                    .context("Failed to read raw transactions from storage")
+                    .map_err(|err| {
+                        BasicErrorWith404::internal_with_code(
+                            err,
+                            AptosErrorCode::InternalError,
+                            &latest_ledger_info,
+                        )
+                    })?;
+                let num_txns = data.len() as u64;
+                Ok((num_txns, render(&context, &latest_ledger_info, data)?))
+            })
+            .await?;
+
+            self.next_version += num_txns;
+            if !items.is_empty() {
+                return Ok(items);
+            }
+            if num_txns == 0 {
+                tokio::time::sleep(poll_interval).await;
+            }
+        }
+    }
+}
+
+/// Conditions a transaction, or an event, needs to meet to be streamed.
+struct StreamFilter {
+    sender: Option<AccountAddress>,
+    entry_function: Option<(ModuleId, String)>,
+    event_type: Option<StructTag>,
+}
+
+impl StreamFilter {
+    fn new(
+        sender: Option<Address>,
+        entry_function: Option<String>,
+        event_type: Option<MoveStructTag>,
+    ) -> Result<Self, BasicErrorWith404> {
+        let entry_function = entry_function
+            .map(|entry_function| {
+                let EntryFunctionId { module, name } = entry_function
+                    .parse::<EntryFunctionId>()
+                    .context("'entry_function' invalid")?;
+                Ok::<_, anyhow::Error>((module.into(), name.to_string()))
+            })
+            .transpose()
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        let event_type = event_type
+            .map(|event_type| StructTag::try_from(event_type).context("'event_type' invalid"))
+            .transpose()
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+
+        Ok(Self {
+            sender: sender.map(Into::into),
+            entry_function,
+            event_type,
+        })
+    }
+
+    /// Checks the sender and the entry function called.
+    fn matches_transaction(&self, txn: &TransactionOnChainData) -> bool {
+        if self.sender.is_none() && self.entry_function.is_none() {
+            return true;
+        }
+        let RawTransaction::UserTransaction(signed_txn) = &txn.transaction else {
+            return false;
+        };
+        if let Some(sender) = &self.sender {
+            if signed_txn.sender() != *sender {
+                return false;
+            }
+        }
+        if let Some((module, function)) = &self.entry_function {
+            let called = match signed_txn.payload() {
+                TransactionPayload::EntryFunction(entry_function) => Some(entry_function),
+                TransactionPayload::Multisig(multisig) => match &multisig.transaction_payload {
+                    Some(MultisigTransactionPayload::EntryFunction(entry_function)) => {
+                        Some(entry_function)
+                    },
+                    None => None,
+                },
+                _ => None,
+            };
+            if !called.map_or(false, |entry_function: &EntryFunction| {
+                entry_function.module() == module && entry_function.function().as_str() == function
+            }) {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn matches_any_event(&self, txn: &TransactionOnChainData) -> bool {
+        self.event_type.is_none()
+            || txn
+                .events
+                .iter()
+                .any(|event| self.matches_event_type(event.type_tag()))
+    }
+
+    fn matches_event_type(&self, type_tag: &TypeTag) -> bool {
+        let Some(expected) = &self.event_type else {
+            return true;
+        };
+        match type_tag {
+            TypeTag::Struct(actual) => {
+                actual.address == expected.address
+                    && actual.module == expected.module
+                    && actual.name == expected.name
+                    && (expected.type_args.is_empty() || actual.type_args == expected.type_args)
+            },
+            _ => false,
+        }
+    }
+}
@@ -5,6 +5,7 @@
 use super::new_test_context;
 use crate::tests::new_test_context_with_db_sharding_and_internal_indexer;
 use aptos_api_test_context::{current_function_name, TestContext};
+use aptos_cached_packages::aptos_stdlib;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use serde_json::json;
 use std::path::PathBuf;
@@ -109,6 +110,136 @@ async fn test_get_events_by_account_event_handle() {
     assert_eq!(resp, new_resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_type() {
+    let context = new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+    let events = context
+        .get("/accounts/0x1/events/0x1::reconfiguration::Configuration/events")
+        .await;
+    let event = &events.as_array().unwrap()[0];
+    let event_type = event["type"].as_str().unwrap();
+
+    // Verify the events of the same type are returned (in version order)
+    let resp = context
+        .get(
+            format!(
+                "/events/by_type/{}",
+                utf8_percent_encode(event_type, NON_ALPHANUMERIC)
+            )
+            .as_str(),
+        )
+        .await;
+    let events_by_type = resp.as_array().unwrap();
+    assert!(events_by_type.contains(event));
+    for event_by_type in events_by_type {
+        assert_eq!(event_by_type["type"].as_str().unwrap(), event_type);
+    }
+
+    // Verify the events before the start version are skipped
+    let resp = context
+        .get(
+            format!(
+                "/events/by_type/{}?start_version=1000000",
+                utf8_percent_encode(event_type, NON_ALPHANUMERIC)
+            )
+            .as_str(),
+        )
+        .await;
+    assert_eq!(resp, json!([]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_type_limit() {
+    let mut context =
+        new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+    let mut root_account = context.root_account().await;
+    let recipients = (0..3).map(|_| context.gen_account().address()).collect();
+    let txn = root_account.sign_with_transaction_builder(context.transaction_factory().payload(
+        aptos_stdlib::aptos_account_batch_transfer(recipients, vec![1; 3]),
+    ));
+    context.commit_block(&vec![txn.clone()]).await;
+    context
+        .get_indexer_reader()
+        .unwrap()
+        .wait_for_internal_indexer(context.get_latest_ledger_info().version())
+        .unwrap();
+
+    // Find a type of event the transaction emitted once per recipient
+    let resp = context
+        .get(&format!(
+            "/transactions/by_hash/{}",
+            txn.committed_hash().to_hex_literal()
+        ))
+        .await;
+    let version = resp["version"].as_str().unwrap();
+    let event_types: Vec<_> = resp["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|event| event["type"].as_str().unwrap())
+        .collect();
+    let event_type = event_types
+        .iter()
+        .find(|event_type| event_types.iter().filter(|t| t == event_type).count() >= 3)
+        .unwrap();
+
+    // The events of the transaction are cut at the limit
+    let resp = context
+        .get(
+            format!(
+                "/events/by_type/{}?start_version={}&limit=2",
+                utf8_percent_encode(event_type, NON_ALPHANUMERIC),
+                version
+            )
+            .as_str(),
+        )
+        .await;
+    let events = resp.as_array().unwrap();
+    assert_eq!(events.len(), 2);
+    for event in events {
+        assert_eq!(event["version"].as_str().unwrap(), version);
+    }
+
+    // The next page starts at the first event of the transaction left out
+    let resp = context
+        .get(
+            format!(
+                "/events/by_type/{}?start_version={}&start_event_index=2&limit=100",
+                utf8_percent_encode(event_type, NON_ALPHANUMERIC),
+                version
+            )
+            .as_str(),
+        )
+        .await;
+    let next_events = resp.as_array().unwrap();
+    let num_events = event_types.iter().filter(|t| t == &event_type).count();
+    assert_eq!(next_events.len(), num_events - 2);
+    for event in next_events {
+        assert_eq!(event["version"].as_str().unwrap(), version);
+        assert!(!events.contains(event));
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_type_without_index() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(403)
+        .get("/events/by_type/0x1::reconfiguration::NewEpochEvent")
+        .await;
+    assert_eq!(resp["error_code"], json!("api_disabled"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_events_by_invalid_type() {
+    let context = new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/events/by_type/0x1::reconfiguration")
+        .await;
+    assert_eq!(resp["error_code"], json!("invalid_input"));
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_events_by_invalid_account_event_handle_struct_address() {
     let mut context = new_test_context(current_function_name!());
@@ -0,0 +1,14 @@
+/// A version of `pack_counter` whose counter can no longer be incremented, to override it with
+module addr::counter {
+    use 0x1::aggregator_v2::Aggregator;
+
+    const EFROZEN: u64 = 1;
+
+    struct Counter has key {
+        counter: Aggregator<u64>,
+    }
+
+    public entry fun increment_counter() {
+        abort EFROZEN
+    }
+}
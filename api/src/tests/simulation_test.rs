@@ -3,7 +3,10 @@
 
 use super::new_test_context;
 use aptos_api_test_context::{current_function_name, pretty, TestContext};
+use aptos_api_types::HexEncodedBytes;
 use aptos_crypto::ed25519::Ed25519Signature;
+use aptos_framework::{BuildOptions, BuiltPackage};
+use aptos_sdk::types::LocalAccount;
 use aptos_types::{
     account_address::AccountAddress,
     transaction::{
@@ -21,6 +24,7 @@ async fn simulate_aptos_transfer(
     transfer_amount: u64,
     expected_status: u16,
     assert_gas_used: bool,
+    state_overrides: Option<fn(AccountAddress) -> serde_json::Value>,
 ) -> serde_json::Value {
     let alice = &mut context.gen_account();
     let bob = &mut context.gen_account();
@@ -37,29 +41,33 @@ async fn simulate_aptos_transfer(
         let signature = use_valid_signature
             .then(|| signature.to_string())
             .unwrap_or(Ed25519Signature::dummy_signature().to_string());
+        let mut body = json!({
+            "sender": txn.sender().to_string(),
+            "sequence_number": txn.sequence_number().to_string(),
+            "max_gas_amount": txn.max_gas_amount().to_string(),
+            "gas_unit_price": txn.gas_unit_price().to_string(),
+            "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
+            "payload": {
+                "type": "entry_function_payload",
+                "function": "0x1::aptos_account::transfer",
+                "type_arguments": [],
+                "arguments": [
+                    bob.address().to_standard_string(), transfer_amount.to_string(),
+                ]
+            },
+            "signature": {
+                "type": "ed25519_signature",
+                "public_key": public_key.to_string(),
+                "signature": signature,
+            }
+        });
+        if let Some(state_overrides) = state_overrides {
+            body["state_overrides"] = state_overrides(alice.address());
+        }
         let req = warp::test::request()
             .method("POST")
             .path("/v1/transactions/simulate")
-            .json(&json!({
-                "sender": txn.sender().to_string(),
-                "sequence_number": txn.sequence_number().to_string(),
-                "max_gas_amount": txn.max_gas_amount().to_string(),
-                "gas_unit_price": txn.gas_unit_price().to_string(),
-                "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
-                "payload": {
-                    "type": "entry_function_payload",
-                    "function": "0x1::aptos_account::transfer",
-                    "type_arguments": [],
-                    "arguments": [
-                        bob.address().to_standard_string(), transfer_amount.to_string(),
-                    ]
-                },
-                "signature": {
-                    "type": "ed25519_signature",
-                    "public_key": public_key.to_string(),
-                    "signature": signature,
-                }
-            }));
+            .json(&body);
         let resp = context.expect_status_code(expected_status).reply(req).await;
         // Assert the gas used header is present if expected.
         if assert_gas_used {
@@ -86,24 +94,191 @@ const LARGE_TRANSFER_AMOUNT: u64 = 1_000_000_000;
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_simulate_transaction_with_valid_signature() {
     let mut context = new_test_context(current_function_name!());
-    let resp = simulate_aptos_transfer(&mut context, true, SMALL_TRANSFER_AMOUNT, 400, false).await;
+    let resp =
+        simulate_aptos_transfer(&mut context, true, SMALL_TRANSFER_AMOUNT, 400, false, None).await;
     context.check_golden_output(resp);
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_simulate_transaction_with_not_valid_signature() {
     let mut context = new_test_context(current_function_name!());
-    let resp = simulate_aptos_transfer(&mut context, false, SMALL_TRANSFER_AMOUNT, 200, true).await;
+    let resp =
+        simulate_aptos_transfer(&mut context, false, SMALL_TRANSFER_AMOUNT, 200, true, None).await;
     assert!(resp[0]["success"].as_bool().is_some_and(|v| v));
 }
 
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_simulate_transaction_with_insufficient_balance() {
     let mut context = new_test_context(current_function_name!());
-    let resp = simulate_aptos_transfer(&mut context, false, LARGE_TRANSFER_AMOUNT, 200, true).await;
+    let resp =
+        simulate_aptos_transfer(&mut context, false, LARGE_TRANSFER_AMOUNT, 200, true, None).await;
     assert!(!resp[0]["success"].as_bool().is_some_and(|v| v));
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_balance_override() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = simulate_aptos_transfer(
+        &mut context,
+        false,
+        LARGE_TRANSFER_AMOUNT,
+        200,
+        true,
+        Some(|alice| {
+            json!([{
+                "type": "balance",
+                "address": alice.to_standard_string(),
+                "amount": (LARGE_TRANSFER_AMOUNT * 2).to_string(),
+            }])
+        }),
+    )
+    .await;
+    assert!(resp[0]["success"].as_bool().is_some_and(|v| v));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_invalid_override() {
+    let mut context = new_test_context(current_function_name!());
+    let resp = simulate_aptos_transfer(
+        &mut context,
+        false,
+        SMALL_TRANSFER_AMOUNT,
+        400,
+        false,
+        Some(|alice| {
+            json!([{
+                "type": "resource",
+                "address": alice.to_standard_string(),
+                "resource_type": "0x1::account::Account",
+                "data": { "sequence_number": "0" },
+            }])
+        }),
+    )
+    .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+}
+
+/// Simulates a call to an entry function without type arguments, against the given overrides
+async fn simulate_entry_function(
+    context: &TestContext,
+    account: &LocalAccount,
+    function: &str,
+    arguments: serde_json::Value,
+    state_overrides: serde_json::Value,
+) -> serde_json::Value {
+    let raw_txn = context
+        .transaction_factory()
+        .transfer(account.address(), 0)
+        .sender(account.address())
+        .sequence_number(account.sequence_number())
+        .build();
+    context
+        .expect_status_code(200)
+        .post(
+            "/transactions/simulate",
+            json!({
+                "sender": account.address().to_string(),
+                "sequence_number": account.sequence_number().to_string(),
+                "max_gas_amount": raw_txn.max_gas_amount().to_string(),
+                "gas_unit_price": raw_txn.gas_unit_price().to_string(),
+                "expiration_timestamp_secs": u64::MAX.to_string(),
+                "payload": {
+                    "type": "entry_function_payload",
+                    "function": function,
+                    "type_arguments": [],
+                    "arguments": arguments,
+                },
+                "signature": {
+                    "type": "ed25519_signature",
+                    "public_key": account.public_key().to_string(),
+                    "signature": Ed25519Signature::dummy_signature().to_string(),
+                },
+                "state_overrides": state_overrides,
+            }),
+        )
+        .await
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_resource_group_member_override() {
+    let mut context = new_test_context(current_function_name!());
+    let mut admin = context.create_account().await;
+    let user = context.create_account().await;
+
+    let path = PathBuf::from(std::env!("CARGO_MANIFEST_DIR"))
+        .join("../aptos-move/move-examples/resource_groups/primary");
+    let payload = TestContext::build_package(path, vec![(
+        "resource_groups_primary".to_string(),
+        admin.address(),
+    )]);
+    context.publish_package(&mut admin, payload).await;
+
+    // The user has no `Primary` resource to remove, unless it's overridden
+    let function = format!("{}::primary::remove", admin.address());
+    let resp = simulate_entry_function(&context, &user, &function, json!([]), json!([])).await;
+    assert!(!resp[0]["success"].as_bool().unwrap(), "{}", pretty(&resp));
+
+    let resp = simulate_entry_function(
+        &context,
+        &user,
+        &function,
+        json!([]),
+        json!([{
+            "type": "resource",
+            "address": user.address().to_string(),
+            "resource_type": format!("{}::primary::Primary", admin.address()),
+            "data": { "value": "7" },
+        }]),
+    )
+    .await;
+    assert!(resp[0]["success"].as_bool().unwrap(), "{}", pretty(&resp));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_loaded_module_override() {
+    let mut context = new_test_context(current_function_name!());
+    let mut account = context.root_account().await;
+
+    let named_addresses = vec![("addr".to_string(), account.address())];
+    let path = PathBuf::from(std::env!("CARGO_MANIFEST_DIR")).join("src/tests/move/pack_counter");
+    let payload = TestContext::build_package(path, named_addresses.clone());
+    context.publish_package(&mut account, payload).await;
+
+    // Load the module into the VMs of the node
+    let function = format!("{}::counter::increment_counter", account.address());
+    let resp = simulate_entry_function(&context, &account, &function, json!([]), json!([])).await;
+    assert!(resp[0]["success"].as_bool().unwrap(), "{}", pretty(&resp));
+
+    // The overriding module is used, even though the original one is already loaded
+    let mut build_options = BuildOptions::default();
+    build_options.named_addresses.extend(named_addresses);
+    let path =
+        PathBuf::from(std::env!("CARGO_MANIFEST_DIR")).join("src/tests/move/pack_counter_override");
+    let bytecode = BuiltPackage::build(path, build_options)
+        .unwrap()
+        .extract_code()
+        .pop()
+        .unwrap();
+    let resp = simulate_entry_function(
+        &context,
+        &account,
+        &function,
+        json!([]),
+        json!([{
+            "type": "module",
+            "address": account.address().to_string(),
+            "bytecode": HexEncodedBytes(bytecode).to_string(),
+        }]),
+    )
+    .await;
+    assert!(!resp[0]["success"].as_bool().unwrap(), "{}", pretty(&resp));
+    assert!(
+        resp[0]["vm_status"].as_str().unwrap().contains("abort"),
+        "{}",
+        pretty(&resp)
+    );
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_simulate_txn_with_aggregator() {
     let mut context = new_test_context(current_function_name!());
@@ -0,0 +1,83 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{new_test_context, new_test_context_with_config};
+use aptos_api_test_context::{current_function_name, ApiSpecificConfig, TestContext};
+use aptos_config::config::NodeConfig;
+use serde_json::Value;
+use std::time::Duration;
+
+/// Reads the first server-sent event off a stream, then disconnects.
+async fn first_stream_item(context: &TestContext, path: &str) -> Value {
+    let ApiSpecificConfig::V1(address) = context.api_specific_config;
+    let mut resp = reqwest::get(format!("http://{}{}", address, context.prepend_path(path)))
+        .await
+        .unwrap();
+    assert_eq!(resp.status(), 200);
+
+    let mut buf = String::new();
+    loop {
+        let chunk = tokio::time::timeout(Duration::from_secs(10), resp.chunk())
+            .await
+            .expect("timed out waiting for stream item")
+            .unwrap()
+            .expect("stream ended");
+        buf.push_str(std::str::from_utf8(&chunk).unwrap());
+        if let Some((event, _)) = buf.split_once("\n\n") {
+            if let Some(data) = event.lines().find_map(|line| line.strip_prefix("data: ")) {
+                return serde_json::from_str(data).unwrap();
+            }
+            // keep-alive comment
+            buf = buf.split_once("\n\n").unwrap().1.to_string();
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_transactions_and_events() {
+    let mut context = new_test_context(current_function_name!());
+    let root_account = context.root_account().await;
+    context.create_account().await;
+
+    let txn = first_stream_item(
+        &context,
+        "/transactions/stream?start_version=0&entry_function=0x1::aptos_account::transfer",
+    )
+    .await;
+    assert_eq!(txn["type"], "user_transaction");
+    assert_eq!(txn["payload"]["function"], "0x1::aptos_account::transfer");
+    assert_eq!(
+        txn["sender"],
+        root_account.address().to_hex_literal().as_str()
+    );
+
+    let event = first_stream_item(
+        &context,
+        &format!(
+            "/events/stream?start_version=0&sender={}",
+            root_account.address().to_hex_literal()
+        ),
+    )
+    .await;
+    assert_eq!(event["version"], txn["version"]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_invalid_filter() {
+    let context = new_test_context(current_function_name!());
+
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions/stream?entry_function=transfer")
+        .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_stream_too_many_connections() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.stream_max_active_connections = 0;
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+
+    context.expect_status_code(503).get("/events/stream").await;
+}
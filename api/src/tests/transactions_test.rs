@@ -658,6 +658,98 @@ async fn test_wait_transaction_by_invalid_hash() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_mempool_transactions() {
+    let mut context = new_test_context(current_function_name!());
+    let mut root_account = context.root_account().await;
+    let txns: Vec<_> = (0..3)
+        .map(|_| {
+            let account = context.gen_account();
+            context.create_user_account_by(&mut root_account, &account)
+        })
+        .collect();
+    let sender = txns[0].sender();
+    let sequence_number = txns[0].sequence_number();
+
+    // Skip the second transaction, which parks the third.
+    for txn in [&txns[0], &txns[2]] {
+        context
+            .expect_status_code(202)
+            .post_bcs_txn("/transactions", bcs::to_bytes(txn).unwrap())
+            .await;
+    }
+
+    let resp = context
+        .get(&format!(
+            "/accounts/{}/mempool_transactions",
+            sender.to_hex_literal()
+        ))
+        .await;
+    assert_eq!(resp["sequence_number"], sequence_number.to_string());
+    assert_eq!(
+        resp["sequence_number_gaps"],
+        json!([{
+            "first": (sequence_number + 1).to_string(),
+            "last": (sequence_number + 1).to_string(),
+        }])
+    );
+    let mempool_txns = resp["transactions"].as_array().unwrap();
+    assert_eq!(mempool_txns.len(), 2);
+    assert_eq!(
+        mempool_txns[0]["transaction"]["hash"],
+        txns[0].committed_hash().to_hex_literal()
+    );
+    assert_eq!(mempool_txns[0]["status"], "ready");
+    assert!(mempool_txns[0]["parked_reason"].is_null());
+    assert_eq!(
+        mempool_txns[1]["transaction"]["hash"],
+        txns[2].committed_hash().to_hex_literal()
+    );
+    assert_eq!(mempool_txns[1]["status"], "parked");
+    assert!(mempool_txns[1]["parked_reason"].is_string());
+
+    // Once the first transaction is committed, it isn't listed anymore, whether or not mempool
+    // has processed the commit yet.
+    context.commit_block(&[txns[0].clone()]).await;
+    let resp = context
+        .get(&format!(
+            "/accounts/{}/mempool_transactions",
+            sender.to_hex_literal()
+        ))
+        .await;
+    assert_eq!(resp["sequence_number"], (sequence_number + 1).to_string());
+    assert_eq!(
+        resp["sequence_number_gaps"],
+        json!([{
+            "first": (sequence_number + 1).to_string(),
+            "last": (sequence_number + 1).to_string(),
+        }])
+    );
+    let mempool_txns = resp["transactions"].as_array().unwrap();
+    assert_eq!(mempool_txns.len(), 1);
+    assert_eq!(
+        mempool_txns[0]["transaction"]["hash"],
+        txns[2].committed_hash().to_hex_literal()
+    );
+    assert_eq!(mempool_txns[0]["status"], "parked");
+
+    // Once the missing transaction is committed without ever going through mempool, the third
+    // one is ready even if mempool still considers it parked.
+    context.commit_block(&[txns[1].clone()]).await;
+    let resp = context
+        .get(&format!(
+            "/accounts/{}/mempool_transactions",
+            sender.to_hex_literal()
+        ))
+        .await;
+    assert_eq!(resp["sequence_number"], (sequence_number + 2).to_string());
+    assert_eq!(resp["sequence_number_gaps"], json!([]));
+    let mempool_txns = resp["transactions"].as_array().unwrap();
+    assert_eq!(mempool_txns.len(), 1);
+    assert_eq!(mempool_txns[0]["status"], "ready");
+    assert!(mempool_txns[0]["parked_reason"].is_null());
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_wait_pending_transaction_by_hash() {
     let mut node_config = NodeConfig::default();
@@ -813,6 +905,53 @@ async fn test_get_account_transactions() {
     test_account_transaction_with_context(shard_context).await;
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_by_entry_function() {
+    let mut context =
+        new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn]).await;
+    context
+        .get_indexer_reader()
+        .unwrap()
+        .wait_for_internal_indexer(2)
+        .unwrap();
+
+    let txns = context
+        .get("/transactions/by_entry_function/0x1::aptos_account::create_account")
+        .await;
+    assert_eq!(1, txns.as_array().unwrap().len());
+    let expected_txns = context.get("/transactions?start=2&limit=1").await;
+    assert_json(txns, expected_txns);
+
+    // Verify that the transactions before the start version are skipped
+    let txns = context
+        .get("/transactions/by_entry_function/0x1::aptos_account::create_account?start_version=3")
+        .await;
+    assert_json(txns, json!([]));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_by_invalid_entry_function() {
+    let context = new_test_context_with_db_sharding_and_internal_indexer(current_function_name!());
+    let resp = context
+        .expect_status_code(400)
+        .get("/transactions/by_entry_function/0x1::aptos_account")
+        .await;
+    assert_eq!(resp["error_code"], json!("invalid_input"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_transactions_by_entry_function_without_index() {
+    let context = new_test_context(current_function_name!());
+    let resp = context
+        .expect_status_code(403)
+        .get("/transactions/by_entry_function/0x1::aptos_account::create_account")
+        .await;
+    assert_eq!(resp["error_code"], json!("api_disabled"));
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_account_transactions_filter_transactions_by_start_sequence_number() {
     let mut context = new_test_context(current_function_name!());
@@ -252,3 +252,81 @@ async fn test_view_aggregator() {
         .await;
     context.check_golden_output_no_prune(resp);
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch() {
+    let mut context = new_test_context(current_function_name!());
+    let creator = &mut context.gen_account();
+    let owner = &mut context.gen_account();
+    let txn1 = context.mint_user_account(creator).await;
+    let txn2 = context.account_transfer(creator, owner, 100_000);
+
+    context.commit_block(&vec![txn1, txn2]).await;
+
+    let resp = context
+        .expect_status_code(200)
+        .post(
+            "/view/batch",
+            json!([
+                build_coin_balance_request(&owner.address()),
+                build_coin_decimals_request(),
+                {
+                    "function": "0x1::coin::not_a_function",
+                    "arguments": [],
+                    "type_arguments": [],
+                },
+            ]),
+        )
+        .await;
+
+    let results = resp.as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["result"], json!(["100000"]));
+    assert_eq!(results[1]["result"], json!([8]));
+    assert!(results[2]["result"].is_null());
+    assert_eq!(results[2]["error"]["error_code"], "invalid_input");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch_gas_budget() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_gas_view_function = 1;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let owner = &mut context.gen_account();
+    let txn = context.mint_user_account(owner).await;
+    context.commit_block(&vec![txn]).await;
+
+    let resp = context
+        .expect_status_code(200)
+        .post(
+            "/view/batch",
+            json!([
+                build_coin_balance_request(&owner.address()),
+                build_coin_decimals_request(),
+            ]),
+        )
+        .await;
+
+    // The first call uses up the whole budget and the second one is not executed.
+    let results = resp.as_array().unwrap();
+    assert_eq!(results[0]["gas_used"], "1");
+    assert!(results[0]["error"].is_object());
+    assert_eq!(results[1]["gas_used"], "0");
+    assert_eq!(results[1]["error"]["error_code"], "invalid_input");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_view_batch_too_large() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.max_view_function_batch_size = 1;
+    let context = new_test_context_with_config(current_function_name!(), node_config);
+
+    let resp = context
+        .expect_status_code(400)
+        .post(
+            "/view/batch",
+            json!([build_coin_decimals_request(), build_coin_decimals_request()]),
+        )
+        .await;
+    assert_eq!(resp["error_code"], "invalid_input");
+}
@@ -17,21 +17,27 @@ use crate::{
         BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResult, BasicResultWith404,
         ForbiddenError, InsufficientStorageError, InternalError,
     },
+    state_override::OverriddenStateView,
     ApiTags,
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
-    verify_function_identifier, verify_module_identifier, Address, AptosError, AptosErrorCode,
-    AsConverter, EncodeSubmissionRequest, GasEstimation, GasEstimationBcs, HashValue,
-    HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
-    Transaction, TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
+    verify_function_identifier, verify_module_identifier, AccountMempoolTransactions, Address,
+    AptosError, AptosErrorCode, AsConverter, EncodeSubmissionRequest, EntryFunctionId,
+    GasEstimation, GasEstimationBcs, HashValue, HexEncodedBytes, LedgerInfo, MempoolTransaction,
+    MempoolTransactionStatus, MoveType, PendingTransaction, SequenceNumberRange,
+    SimulateTransactionRequest, StateOverride, SubmitTransactionRequest, Transaction,
+    TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
     TransactionsBatchSubmissionResult, UserTransaction, VerifyInput, VerifyInputWithRecursion,
     MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
 use aptos_crypto::{hash::CryptoHash, signing_message};
+use aptos_mempool::{SenderTransaction, SenderTransactions};
 use aptos_types::{
     account_address::AccountAddress,
+    account_config::AccountResource,
     mempool_status::MempoolStatusCode,
+    state_store::StateView,
     transaction::{
         EntryFunction, ExecutionStatus, MultisigTransactionPayload, RawTransaction,
         RawTransactionWithData, SignedTransaction, TransactionPayload,
@@ -40,13 +46,18 @@ use aptos_types::{
     AptosCoinType, CoinType,
 };
 use aptos_vm::{AptosSimulationVM, AptosVM};
-use move_core_types::{ident_str, language_storage::ModuleId, vm_status::VMStatus};
+use move_core_types::{
+    ident_str, identifier::Identifier, language_storage::ModuleId, vm_status::VMStatus,
+};
 use poem_openapi::{
     param::{Path, Query},
     payload::Json,
     ApiRequest, OpenApi,
 };
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::Arc,
+    time::{Duration, UNIX_EPOCH},
+};
 
 generate_success_response!(SubmitTransactionResponse, (202, Accepted));
 
@@ -102,6 +113,45 @@ impl VerifyInput for SubmitTransactionPost {
     }
 }
 
+// We need a custom type here because we use different types for each of the
+// content types possible for the POST data.
+#[derive(ApiRequest, Debug)]
+pub enum SimulateTransactionPost {
+    #[oai(content_type = "application/json")]
+    Json(Json<SimulateTransactionRequest>),
+
+    #[oai(content_type = "application/x.aptos.signed_transaction+bcs")]
+    Bcs(Bcs),
+}
+
+impl SimulateTransactionPost {
+    /// Splits the request into the transaction and the state overrides to simulate it against
+    fn into_parts(self) -> (SubmitTransactionPost, Vec<StateOverride>) {
+        match self {
+            SimulateTransactionPost::Json(inner) => {
+                let SimulateTransactionRequest {
+                    transaction,
+                    state_overrides,
+                } = inner.0;
+                (
+                    SubmitTransactionPost::Json(Json(transaction)),
+                    state_overrides,
+                )
+            },
+            SimulateTransactionPost::Bcs(inner) => (SubmitTransactionPost::Bcs(inner), vec![]),
+        }
+    }
+}
+
+impl VerifyInput for SimulateTransactionPost {
+    fn verify(&self) -> anyhow::Result<()> {
+        match self {
+            SimulateTransactionPost::Json(inner) => inner.0.verify(),
+            SimulateTransactionPost::Bcs(_) => Ok(()),
+        }
+    }
+}
+
 // We need a custom type here because we use different types for each of the
 // content types possible for the POST data.
 #[derive(ApiRequest, Debug)]
@@ -344,6 +394,113 @@ impl TransactionsApi {
         api_spawn_blocking(move || api.list_by_account(&accept_type, page, address.0)).await
     }
 
+    /// Get transactions by entry function
+    ///
+    /// Retrieves on-chain committed transactions that call the given entry
+    /// function (e.g., `0x1::aptos_account::transfer`), either directly or
+    /// through a multisig account, in ascending version order. To get the
+    /// next page, use the version after the last returned transaction as the
+    /// start version.
+    ///
+    /// This requires the internal indexer of the node to index transactions
+    /// by entry function.
+    #[oai(
+        path = "/transactions/by_entry_function/:function",
+        method = "get",
+        operation_id = "get_transactions_by_entry_function",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn get_transactions_by_entry_function(
+        &self,
+        accept_type: AcceptType,
+        /// Fully qualified name of the entry function, e.g. `0x1::aptos_account::transfer`
+        function: Path<String>,
+        /// Ledger version to start list of transactions
+        ///
+        /// If not provided, defaults to version 0
+        start_version: Query<Option<U64>>,
+        /// Max number of transactions to retrieve.
+        ///
+        /// If not provided, defaults to default page size
+        limit: Query<Option<u16>>,
+    ) -> BasicResultWith404<Vec<Transaction>> {
+        let EntryFunctionId { module, name } = function
+            .0
+            .parse::<EntryFunctionId>()
+            .and_then(|function| {
+                function.verify()?;
+                Ok(function)
+            })
+            .context("'function' invalid")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code_no_info(err, AptosErrorCode::InvalidInput)
+            })?;
+        fail_point_poem("endpoint_get_transactions_by_entry_function")?;
+        if !self.context.transaction_by_entry_function_index_enabled() {
+            return Err(api_disabled("Get transactions by entry function"));
+        }
+        self.context
+            .check_api_output_enabled("Get transactions by entry function", &accept_type)?;
+        let page = Page::new(
+            start_version.0.map(|v| v.0),
+            limit.0,
+            self.context.max_transactions_page_size(),
+        );
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            api.list_by_entry_function(&accept_type, page, module.into(), name.into())
+        })
+        .await
+    }
+
+    /// Get account mempool transactions
+    ///
+    /// Retrieves the transactions of an account waiting in the mempool of this node. A
+    /// transaction is parked, rather than ready to be committed, while a transaction with a lower
+    /// sequence number is missing from mempool. The missing sequence numbers are listed as gaps.
+    ///
+    /// Only JSON is supported for this endpoint.
+    #[oai(
+        path = "/accounts/:address/mempool_transactions",
+        method = "get",
+        operation_id = "get_account_mempool_transactions",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn get_account_mempool_transactions(
+        &self,
+        accept_type: AcceptType,
+        /// Address of account with or without a `0x` prefix
+        address: Path<Address>,
+    ) -> BasicResultWith404<AccountMempoolTransactions> {
+        fail_point_poem("endpoint_get_account_mempool_transactions")?;
+        if accept_type == AcceptType::Bcs {
+            return Err(api_forbidden(
+                "Get account mempool transactions with BCS output",
+                "Only JSON is supported as an AcceptType.",
+            ));
+        }
+        self.context
+            .check_api_output_enabled("Get account mempool transactions", &accept_type)?;
+        let ledger_info = self.context.get_latest_ledger_info()?;
+        let sender_txns = self
+            .context
+            .get_pending_transactions_by_sender(address.0.into())
+            .await
+            .context("Failed to get transactions from mempool")
+            .map_err(|err| {
+                BasicErrorWith404::internal_with_code(
+                    err,
+                    AptosErrorCode::InternalError,
+                    &ledger_info,
+                )
+            })?;
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            api.render_mempool_transactions(&ledger_info, address.0, sender_txns)
+        })
+        .await
+    }
+
     /// Submit transaction
     ///
     /// This endpoint accepts transaction submissions in two formats.
@@ -471,6 +628,10 @@ impl TransactionsApi {
     ///
     /// To use this endpoint with BCS, you must submit a SignedTransaction
     /// encoded as BCS. See SignedTransaction in types/src/transaction/mod.rs.
+    ///
+    /// With JSON, the request may also carry state overrides, changes to on-chain state that
+    /// only this simulation sees, e.g. to set the APT balance of an account, the value of a
+    /// resource, or to replace a module.
     #[oai(
         path = "/transactions/simulate",
         method = "post",
@@ -489,7 +650,7 @@ impl TransactionsApi {
         /// If set to true, the transaction will use a higher price than the original
         /// estimate.
         estimate_prioritized_gas_unit_price: Query<Option<bool>>,
-        data: SubmitTransactionPost,
+        data: SimulateTransactionPost,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         data.verify()
             .context("Simulated transaction invalid")
@@ -510,6 +671,7 @@ impl TransactionsApi {
         let context = self.context.clone();
         api_spawn_blocking(move || {
             let ledger_info = context.get_latest_ledger_info()?;
+            let (data, state_overrides) = data.into_parts();
             let mut signed_transaction = api.get_signed_transaction(&ledger_info, data)?;
 
             // Confirm the simulation filter allows the transaction. We use HashValue::zero()
@@ -544,6 +706,17 @@ impl TransactionsApi {
                 (false, false) => None,
             };
 
+            let db_state_view = context.latest_state_view_poem(&ledger_info)?;
+            let state_view = OverriddenStateView::new(&context, &db_state_view, state_overrides)
+                .context("Invalid state overrides")
+                .map_err(|err| {
+                    SubmitTransactionError::bad_request_with_code(
+                        err,
+                        AptosErrorCode::InvalidInput,
+                        &ledger_info,
+                    )
+                })?;
+
             // If estimate max gas amount is provided, we will just make it the maximum value
             let estimated_max_gas_amount = if estimate_max_gas_amount.0.unwrap_or_default() {
                 // Retrieve max possible gas units
@@ -556,14 +729,6 @@ impl TransactionsApi {
 
                 // Retrieve account balance to determine max gas available, right now this is using
                 // a view function, but we may want to re-evaluate this based on performance
-                let (_, _, state_view) = context
-                    .state_view::<BasicErrorWith404>(Option::None)
-                    .map_err(|err| {
-                        SubmitTransactionError::bad_request_with_code_no_info(
-                            err,
-                            AptosErrorCode::InvalidInput,
-                        )
-                    })?;
                 let output = AptosVM::execute_view_function(
                     &state_view,
                     ModuleId::new(AccountAddress::ONE, ident_str!("coin").into()),
@@ -617,7 +782,7 @@ impl TransactionsApi {
                 );
             }
 
-            api.simulate(&accept_type, ledger_info, signed_transaction)
+            api.simulate(&accept_type, ledger_info, signed_transaction, &state_view)
         })
         .await
     }
@@ -1002,6 +1167,97 @@ impl TransactionsApi {
         )
     }
 
+    /// Renders the transactions of an account in mempool, and the sequence number gaps between
+    /// them as of the latest ledger version
+    fn render_mempool_transactions(
+        &self,
+        ledger_info: &LedgerInfo,
+        address: Address,
+        sender_txns: SenderTransactions,
+    ) -> BasicResultWith404<AccountMempoolTransactions> {
+        let sequence_number = self
+            .context
+            .get_resource_poem::<AccountResource, BasicErrorWith404>(
+                address.into(),
+                ledger_info.version(),
+                ledger_info,
+            )?
+            .map_or(0, |account| account.sequence_number());
+        let state_view = self.context.latest_state_view_poem(ledger_info)?;
+        let converter =
+            state_view.as_converter(self.context.db.clone(), self.context.indexer_reader.clone());
+
+        let mut transactions = vec![];
+        let mut sequence_number_gaps = vec![];
+        let mut next_sequence_number = sequence_number;
+        let mut missing_sequence_number = None;
+        // Mempool's own status is relative to the sequence number it last saw for the account,
+        // which may lag behind the ledger, so derive the status from the same sequence number as
+        // the gaps instead
+        for SenderTransaction {
+            txn,
+            insertion_time,
+            status: _,
+        } in sender_txns.transactions
+        {
+            // Mempool may not have caught up with the latest ledger version yet, skip the
+            // transactions that are already committed
+            let txn_sequence_number = txn.sequence_number();
+            if txn_sequence_number < sequence_number {
+                continue;
+            }
+            if txn_sequence_number > next_sequence_number {
+                sequence_number_gaps.push(SequenceNumberRange {
+                    first: next_sequence_number.into(),
+                    last: (txn_sequence_number - 1).into(),
+                });
+                missing_sequence_number.get_or_insert(next_sequence_number);
+            }
+            next_sequence_number = next_sequence_number.max(txn_sequence_number + 1);
+
+            let (status, parked_reason) = match missing_sequence_number {
+                None => (MempoolTransactionStatus::Ready, None),
+                Some(missing_sequence_number) => (
+                    MempoolTransactionStatus::Parked,
+                    Some(format!(
+                        "Waiting for the transaction with sequence number {} to arrive in mempool",
+                        missing_sequence_number
+                    )),
+                ),
+            };
+            let insertion_timestamp_usecs = insertion_time
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_micros() as u64;
+            let transaction = converter
+                .try_into_pending_transaction_poem(txn)
+                .context("Failed to convert mempool transaction to PendingTransaction")
+                .map_err(|err| {
+                    BasicErrorWith404::internal_with_code(
+                        err,
+                        AptosErrorCode::InternalError,
+                        ledger_info,
+                    )
+                })?;
+            transactions.push(MempoolTransaction {
+                transaction,
+                status,
+                parked_reason,
+                insertion_timestamp_usecs: insertion_timestamp_usecs.into(),
+            });
+        }
+
+        BasicResponse::try_from_json((
+            AccountMempoolTransactions {
+                sequence_number: sequence_number.into(),
+                transactions,
+                sequence_number_gaps,
+            },
+            ledger_info,
+            BasicResponseStatus::Ok,
+        ))
+    }
+
     /// List all transactions for an account
     fn list_by_account(
         &self,
@@ -1035,6 +1291,36 @@ impl TransactionsApi {
         }
     }
 
+    /// List transactions calling an entry function
+    fn list_by_entry_function(
+        &self,
+        accept_type: &AcceptType,
+        page: Page,
+        module_id: ModuleId,
+        function_name: Identifier,
+    ) -> BasicResultWith404<Vec<Transaction>> {
+        let latest_ledger_info = self.context.get_latest_ledger_info()?;
+        let data = self.context.get_transactions_by_entry_function(
+            &module_id,
+            &function_name,
+            page.start_option().unwrap_or(0),
+            page.limit(&latest_ledger_info)?,
+            latest_ledger_info.version(),
+            &latest_ledger_info,
+        )?;
+        match accept_type {
+            AcceptType::Json => BasicResponse::try_from_json((
+                self.context
+                    .render_transactions_non_sequential(&latest_ledger_info, data)?,
+                &latest_ledger_info,
+                BasicResponseStatus::Ok,
+            )),
+            AcceptType::Bcs => {
+                BasicResponse::try_from_bcs((data, &latest_ledger_info, BasicResponseStatus::Ok))
+            },
+        }
+    }
+
     /// Parses a single signed transaction
     fn get_signed_transaction(
         &self,
@@ -1376,6 +1662,7 @@ impl TransactionsApi {
         accept_type: &AcceptType,
         ledger_info: LedgerInfo,
         txn: SignedTransaction,
+        state_view: &OverriddenStateView<impl StateView>,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         // The caller must ensure that the signature is not valid, as otherwise
         // a malicious actor could execute the transaction without their knowledge
@@ -1387,10 +1674,12 @@ impl TransactionsApi {
             ));
         }
 
-        // Simulate transaction
-        let state_view = self.context.latest_state_view_poem(&ledger_info)?;
-        let (vm_status, output) =
-            AptosSimulationVM::create_vm_and_simulate_signed_transaction(&txn, &state_view);
+        // Simulate transaction, on a VM that hasn't cached the modules being overridden
+        let (vm_status, output) = if state_view.overrides_modules() {
+            AptosSimulationVM::create_cold_vm_and_simulate_signed_transaction(&txn, state_view)
+        } else {
+            AptosSimulationVM::create_vm_and_simulate_signed_transaction(&txn, state_view)
+        };
         let version = ledger_info.version();
 
         // Ensure that all known statuses return their values in the output (even if they aren't supposed to)
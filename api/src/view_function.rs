@@ -7,17 +7,18 @@ use crate::{
     context::{api_spawn_blocking, FunctionStats},
     failpoint::fail_point_poem,
     response::{
-        BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResultWith404,
-        ForbiddenError, InternalError,
+        api_forbidden, BadRequestError, BasicErrorWith404, BasicResponse, BasicResponseStatus,
+        BasicResultWith404, ForbiddenError, InternalError,
     },
     ApiTags, Context,
 };
 use anyhow::Context as anyhowContext;
 use aptos_api_types::{
-    AptosErrorCode, AsConverter, MoveValue, ViewFunction, ViewRequest, MAX_RECURSIVE_TYPES_ALLOWED,
-    U64,
+    AptosError, AptosErrorCode, AsConverter, MoveValue, ViewFunction, ViewFunctionBatchResult,
+    ViewRequest, MAX_RECURSIVE_TYPES_ALLOWED, U64,
 };
 use aptos_bcs_utils::serialize_uleb128;
+use aptos_storage_interface::state_view::DbStateView;
 use aptos_vm::AptosVM;
 use itertools::Itertools;
 use move_core_types::language_storage::TypeTag;
@@ -71,6 +72,51 @@ impl ViewFunctionApi {
         api_spawn_blocking(move || view_request(context, accept_type, request, ledger_version))
             .await
     }
+
+    /// Execute a batch of view functions
+    ///
+    /// Execute multiple Move view functions against the same ledger version and return the
+    /// result of each call, in request order. A failed call does not fail the batch, its error
+    /// is returned in place of its result.
+    ///
+    /// All calls in the batch share one gas budget, the same as the limit for a single view
+    /// function call. Each call may use whatever is left of the budget, and calls made after
+    /// the budget is used up fail without being executed.
+    ///
+    /// Only JSON is supported for this endpoint. The number of calls in a batch is limited by the
+    /// node configuration, larger batches are rejected with a 400.
+    ///
+    /// The Aptos nodes prune account state history, via a configurable time window.
+    /// If the requested ledger version has been pruned, the server responds with a 410.
+    #[oai(
+        path = "/view/batch",
+        method = "post",
+        operation_id = "view_batch",
+        tag = "ApiTags::View"
+    )]
+    async fn view_function_batch(
+        &self,
+        accept_type: AcceptType,
+        /// View function requests, executed in order
+        requests: Json<Vec<ViewRequest>>,
+        /// Ledger version to get state of account
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<Vec<ViewFunctionBatchResult>> {
+        fail_point_poem("endpoint_view_function_batch")?;
+        if accept_type == AcceptType::Bcs {
+            return Err(api_forbidden(
+                "Batch view function with BCS output",
+                "Only JSON is supported as an AcceptType.",
+            ));
+        }
+        self.context
+            .check_api_output_enabled("View function", &accept_type)?;
+
+        let context = self.context.clone();
+        api_spawn_blocking(move || view_request_batch(context, requests.0, ledger_version)).await
+    }
 }
 
 fn view_request(
@@ -81,7 +127,9 @@ fn view_request(
 ) -> BasicResultWith404<Vec<MoveValue>> {
     // Retrieve the current state of the chain
     let (ledger_info, requested_version) = context
-        .get_latest_ledger_info_and_verify_lookup_version(ledger_version.map(|inner| inner.0))?;
+        .get_latest_ledger_info_and_verify_state_lookup_version(
+            ledger_version.map(|inner| inner.0),
+        )?;
 
     let state_view = context
         .state_view_at_version(requested_version)
@@ -118,19 +166,9 @@ fn view_request(
     };
 
     // Reject the request if it's not allowed by the filter.
-    if !context.node_config.api.view_filter.allows(
-        view_function.module.address(),
-        view_function.module.name().as_str(),
-        view_function.function.as_str(),
-    ) {
-        return Err(BasicErrorWith404::forbidden_with_code_no_info(
-            format!(
-                "Function {}::{} is not allowed",
-                view_function.module, view_function.function
-            ),
-            AptosErrorCode::InvalidInput,
-        ));
-    }
+    check_view_filter(&context, &view_function).map_err(|err| {
+        BasicErrorWith404::forbidden_with_code_no_info(err, AptosErrorCode::InvalidInput)
+    })?;
 
     let output = AptosVM::execute_view_function(
         &state_view,
@@ -166,31 +204,7 @@ fn view_request(
             BasicResponse::try_from_encoded((ret, &ledger_info, BasicResponseStatus::Ok))
         },
         AcceptType::Json => {
-            let return_types = state_view
-                .as_converter(context.db.clone(), context.indexer_reader.clone())
-                .function_return_types(&view_function)
-                .and_then(|tys| {
-                    tys.into_iter()
-                        .map(TypeTag::try_from)
-                        .collect::<anyhow::Result<Vec<_>>>()
-                })
-                .map_err(|err| {
-                    BasicErrorWith404::bad_request_with_code(
-                        err,
-                        AptosErrorCode::InternalError,
-                        &ledger_info,
-                    )
-                })?;
-
-            let move_vals = values
-                .into_iter()
-                .zip(return_types.into_iter())
-                .map(|(v, ty)| {
-                    state_view
-                        .as_converter(context.db.clone(), context.indexer_reader.clone())
-                        .try_into_move_value(&ty, &v)
-                })
-                .collect::<anyhow::Result<Vec<_>>>()
+            let move_vals = return_values_to_json(&context, &state_view, &view_function, values)
                 .map_err(|err| {
                     BasicErrorWith404::bad_request_with_code(
                         err,
@@ -208,3 +222,161 @@ fn view_request(
     );
     result.map(|r| r.with_gas_used(Some(output.gas_used)))
 }
+
+fn view_request_batch(
+    context: Arc<Context>,
+    requests: Vec<ViewRequest>,
+    ledger_version: Query<Option<U64>>,
+) -> BasicResultWith404<Vec<ViewFunctionBatchResult>> {
+    // All calls see the state at the same version
+    let (ledger_info, requested_version) = context
+        .get_latest_ledger_info_and_verify_state_lookup_version(
+            ledger_version.map(|inner| inner.0),
+        )?;
+
+    if context.max_view_function_batch_size() < requests.len() {
+        return Err(BasicErrorWith404::bad_request_with_code(
+            format!(
+                "Requested too many view functions: {}, while limit is {}",
+                requests.len(),
+                context.max_view_function_batch_size(),
+            ),
+            AptosErrorCode::InvalidInput,
+            &ledger_info,
+        ));
+    }
+
+    let state_view = context
+        .state_view_at_version(requested_version)
+        .map_err(|err| {
+            BasicErrorWith404::bad_request_with_code(
+                err,
+                AptosErrorCode::InternalError,
+                &ledger_info,
+            )
+        })?;
+
+    let gas_budget = context.node_config.api.max_gas_view_function;
+    let mut gas_remaining = gas_budget;
+    let results = requests
+        .into_iter()
+        .map(|request| {
+            let (result, gas_used) = view_batch_item(&context, &state_view, request, gas_remaining);
+            gas_remaining = gas_remaining.saturating_sub(gas_used);
+            let (result, error) = match result {
+                Ok(values) => (Some(values), None),
+                Err(err) => (None, Some(err)),
+            };
+            ViewFunctionBatchResult {
+                result,
+                error,
+                gas_used: gas_used.into(),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    BasicResponse::try_from_json((results, &ledger_info, BasicResponseStatus::Ok))
+        .map(|r| r.with_gas_used(Some(gas_budget - gas_remaining)))
+}
+
+/// Executes one call of a batch with at most `max_gas_amount` gas, returning its JSON encoded
+/// return values or error, and the gas used.
+fn view_batch_item(
+    context: &Context,
+    state_view: &DbStateView,
+    request: ViewRequest,
+    max_gas_amount: u64,
+) -> (Result<Vec<MoveValue>, AptosError>, u64) {
+    if max_gas_amount == 0 {
+        return (
+            Err(AptosError::new_with_error_code(
+                "Gas budget of the batch is used up",
+                AptosErrorCode::InvalidInput,
+            )),
+            0,
+        );
+    }
+
+    let view_function = match state_view
+        .as_converter(context.db.clone(), context.indexer_reader.clone())
+        .convert_view_function(request)
+    {
+        Ok(view_function) => view_function,
+        Err(err) => {
+            return (
+                Err(AptosError::new_with_error_code(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                )),
+                0,
+            )
+        },
+    };
+    if let Err(err) = check_view_filter(context, &view_function) {
+        return (
+            Err(AptosError::new_with_error_code(
+                err,
+                AptosErrorCode::InvalidInput,
+            )),
+            0,
+        );
+    }
+
+    let output = AptosVM::execute_view_function(
+        state_view,
+        view_function.module.clone(),
+        view_function.function.clone(),
+        view_function.ty_args.clone(),
+        view_function.args.clone(),
+        max_gas_amount,
+    );
+    context.view_function_stats().increment(
+        FunctionStats::function_to_key(&view_function.module, &view_function.function),
+        output.gas_used,
+    );
+    let result = output
+        .values
+        .map_err(|err| AptosError::new_with_error_code(err, AptosErrorCode::InvalidInput))
+        .and_then(|values| {
+            return_values_to_json(context, state_view, &view_function, values)
+                .map_err(|err| AptosError::new_with_error_code(err, AptosErrorCode::InternalError))
+        });
+    (result, output.gas_used)
+}
+
+/// Returns why the function may not be called, if the view filter does not allow it.
+fn check_view_filter(context: &Context, view_function: &ViewFunction) -> Result<(), String> {
+    if context.node_config.api.view_filter.allows(
+        view_function.module.address(),
+        view_function.module.name().as_str(),
+        view_function.function.as_str(),
+    ) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Function {}::{} is not allowed",
+            view_function.module, view_function.function
+        ))
+    }
+}
+
+/// Decodes the BCS encoded return values of a view function into JSON values.
+fn return_values_to_json(
+    context: &Context,
+    state_view: &DbStateView,
+    view_function: &ViewFunction,
+    values: Vec<Vec<u8>>,
+) -> anyhow::Result<Vec<MoveValue>> {
+    let converter = state_view.as_converter(context.db.clone(), context.indexer_reader.clone());
+    let return_types = converter
+        .function_return_types(view_function)?
+        .into_iter()
+        .map(TypeTag::try_from)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    values
+        .into_iter()
+        .zip(return_types.into_iter())
+        .map(|(v, ty)| converter.try_into_move_value(&ty, &v))
+        .collect()
+}
@@ -111,6 +111,11 @@ impl<'a, S: StateView> MoveConverter<'a, S> {
         false
     }
 
+    /// Returns the resource group the resource is a member of, if any
+    pub fn find_resource_group(&self, tag: &StructTag) -> Option<StructTag> {
+        self.inner.view_resource_group_member(tag)
+    }
+
     pub fn find_resource(
         &self,
         state_view: &impl StateView,
@@ -18,6 +18,7 @@ mod ledger_info;
 pub mod mime_types;
 mod move_types;
 mod state;
+mod state_override;
 mod table;
 pub mod transaction;
 mod view;
@@ -42,22 +43,24 @@ pub use move_types::{
 };
 use serde::{Deserialize, Deserializer};
 pub use state::RawStateValueRequest;
+pub use state_override::{BalanceOverride, ModuleOverride, ResourceOverride, StateOverride};
 use std::str::FromStr;
 pub use table::{RawTableItemRequest, TableItemRequest};
 pub use transaction::{
-    AccountSignature, BlockMetadataTransaction, DeleteModule, DeleteResource, DeleteTableItem,
-    DirectWriteSet, Ed25519Signature, EncodeSubmissionRequest, EntryFunctionPayload, Event,
-    FeePayerSignature, GasEstimation, GasEstimationBcs, GenesisPayload, GenesisTransaction,
+    AccountMempoolTransactions, AccountSignature, BlockMetadataTransaction, DeleteModule,
+    DeleteResource, DeleteTableItem, DirectWriteSet, Ed25519Signature, EncodeSubmissionRequest,
+    EntryFunctionPayload, Event, FeePayerSignature, GasEstimation, GasEstimationBcs,
+    GenesisPayload, GenesisTransaction, MempoolTransaction, MempoolTransactionStatus,
     MultiAgentSignature, MultiEd25519Signature, MultiKeySignature, MultisigPayload,
     MultisigTransactionPayload, NoAccountSignature, PendingTransaction, PublicKey, ScriptPayload,
-    ScriptWriteSet, Signature, SingleKeySignature, SubmitTransactionRequest, Transaction,
-    TransactionData, TransactionId, TransactionInfo, TransactionOnChainData, TransactionPayload,
-    TransactionSignature, TransactionSigningMessage, TransactionsBatchSingleSubmissionFailure,
-    TransactionsBatchSubmissionResult, UserCreateSigningMessageRequest, UserTransaction,
-    UserTransactionRequest, VersionedEvent, WriteModule, WriteResource, WriteSet, WriteSetChange,
-    WriteSetPayload, WriteTableItem,
+    ScriptWriteSet, SequenceNumberRange, Signature, SimulateTransactionRequest, SingleKeySignature,
+    SubmitTransactionRequest, Transaction, TransactionData, TransactionId, TransactionInfo,
+    TransactionOnChainData, TransactionPayload, TransactionSignature, TransactionSigningMessage,
+    TransactionsBatchSingleSubmissionFailure, TransactionsBatchSubmissionResult,
+    UserCreateSigningMessageRequest, UserTransaction, UserTransactionRequest, VersionedEvent,
+    WriteModule, WriteResource, WriteSet, WriteSetChange, WriteSetPayload, WriteTableItem,
 };
-pub use view::{ViewFunction, ViewRequest};
+pub use view::{ViewFunction, ViewFunctionBatchResult, ViewRequest};
 pub use wrappers::{EventGuid, IdentifierWrapper, StateKeyWrapper};
 
 pub fn deserialize_from_string<'de, D, T>(deserializer: D) -> Result<T, D::Error>
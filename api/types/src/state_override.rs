@@ -0,0 +1,47 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{Address, HexEncodedBytes, MoveStructTag, U64};
+use poem_openapi::{Object, Union};
+use serde::{Deserialize, Serialize};
+
+/// A change to on-chain state, seen only by a single simulated transaction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Union)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[oai(one_of, discriminator_name = "type", rename_all = "snake_case")]
+pub enum StateOverride {
+    Resource(ResourceOverride),
+    Balance(BalanceOverride),
+    Module(ModuleOverride),
+}
+
+/// Sets a resource of an account to the given value
+///
+/// Resources that are members of a resource group are set within the group, leaving the other
+/// members of the group as they are.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ResourceOverride {
+    pub address: Address,
+    pub resource_type: MoveStructTag,
+    /// The resource value, in the same JSON format as returned by the resource APIs
+    pub data: serde_json::Value,
+}
+
+/// Sets the APT balance of an account
+///
+/// The account must already hold APT, either in a coin store or in its primary fungible store.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct BalanceOverride {
+    pub address: Address,
+    pub amount: U64,
+}
+
+/// Publishes a module, replacing any module of the same name, without running any checks
+///
+/// Simulations with module overrides don't reuse the modules cached by the node, so they are
+/// slower than other simulations.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ModuleOverride {
+    pub address: Address,
+    pub bytecode: HexEncodedBytes,
+}
@@ -5,7 +5,7 @@
 use crate::{
     Address, AptosError, EntryFunctionId, EventGuid, HashValue, HexEncodedBytes,
     MoveModuleBytecode, MoveModuleId, MoveResource, MoveScriptBytecode, MoveStructTag, MoveType,
-    MoveValue, VerifyInput, VerifyInputWithRecursion, U64,
+    MoveValue, StateOverride, VerifyInput, VerifyInputWithRecursion, U64,
 };
 use anyhow::{bail, Context as AnyhowContext, Result};
 use aptos_crypto::{
@@ -22,6 +22,7 @@ use aptos_types::{
     block_metadata_ext::BlockMetadataExt,
     contract_event::{ContractEvent, EventWithVersion},
     dkg::{DKGTranscript, DKGTranscriptMetadata},
+    equivocation::{EquivocationEvidence, EquivocationKind},
     jwks::{jwk::JWK, ProviderJWKs, QuorumCertifiedUpdate},
     keyless,
     transaction::{
@@ -34,7 +35,7 @@ use aptos_types::{
     },
 };
 use once_cell::sync::Lazy;
-use poem_openapi::{Object, Union};
+use poem_openapi::{Enum, Object, Union};
 use serde::{Deserialize, Serialize};
 use std::{
     boxed::Box,
@@ -381,6 +382,47 @@ impl From<(SignedTransaction, TransactionPayload)> for PendingTransaction {
     }
 }
 
+/// Transactions of an account in the mempool of the node
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct AccountMempoolTransactions {
+    /// Sequence number of the account at the latest ledger version
+    pub sequence_number: U64,
+    /// Transactions in mempool, ordered by sequence number
+    pub transactions: Vec<MempoolTransaction>,
+    /// Sequence numbers missing from mempool, between the account sequence number and the
+    /// last transaction in mempool
+    pub sequence_number_gaps: Vec<SequenceNumberRange>,
+}
+
+/// A transaction in mempool, and whether it can be committed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct MempoolTransaction {
+    pub transaction: PendingTransaction,
+    pub status: MempoolTransactionStatus,
+    /// Why the transaction is parked, if it is
+    pub parked_reason: Option<String>,
+    /// When the transaction entered mempool, in microseconds since the Unix epoch
+    pub insertion_timestamp_usecs: U64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[oai(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MempoolTransactionStatus {
+    /// The transaction can be included in a block
+    Ready,
+    /// The transaction can't be included in a block until a transaction with a lower sequence
+    /// number arrives
+    Parked,
+}
+
+/// An inclusive range of sequence numbers
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct SequenceNumberRange {
+    pub first: U64,
+    pub last: U64,
+}
+
 /// A transaction submitted by a user to change the state of the blockchain
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
 pub struct UserTransaction {
@@ -440,6 +482,28 @@ impl VerifyInput for SubmitTransactionRequest {
     }
 }
 
+/// A request to simulate a transaction
+///
+/// This is a SubmitTransactionRequest, plus optional changes to on-chain state to simulate
+/// the transaction against.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct SimulateTransactionRequest {
+    #[serde(flatten)]
+    #[oai(flatten)]
+    pub transaction: SubmitTransactionRequest,
+    /// Changes to on-chain state, applied in order on top of the latest state for this
+    /// simulation only
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[oai(default, skip_serializing_if_is_empty)]
+    pub state_overrides: Vec<StateOverride>,
+}
+
+impl VerifyInput for SimulateTransactionRequest {
+    fn verify(&self) -> anyhow::Result<()> {
+        self.transaction.verify()
+    }
+}
+
 /// Batch transaction submission result
 ///
 /// Tells which transactions failed
@@ -658,6 +722,7 @@ impl BlockMetadataTransaction {
 pub enum ValidatorTransaction {
     ObservedJwkUpdate(JWKUpdateTransaction),
     DkgResult(DKGResultTransaction),
+    EquivocationEvidence(EquivocationEvidenceTransaction),
 }
 
 impl ValidatorTransaction {
@@ -667,6 +732,9 @@ impl ValidatorTransaction {
                 "validator_transaction__observed_jwk_update"
             },
             ValidatorTransaction::DkgResult(_) => "validator_transaction__dkg_result",
+            ValidatorTransaction::EquivocationEvidence(_) => {
+                "validator_transaction__equivocation_evidence"
+            },
         }
     }
 
@@ -674,6 +742,7 @@ impl ValidatorTransaction {
         match self {
             ValidatorTransaction::ObservedJwkUpdate(t) => &t.info,
             ValidatorTransaction::DkgResult(t) => &t.info,
+            ValidatorTransaction::EquivocationEvidence(t) => &t.info,
         }
     }
 
@@ -681,6 +750,7 @@ impl ValidatorTransaction {
         match self {
             ValidatorTransaction::ObservedJwkUpdate(t) => &mut t.info,
             ValidatorTransaction::DkgResult(t) => &mut t.info,
+            ValidatorTransaction::EquivocationEvidence(t) => &mut t.info,
         }
     }
 
@@ -688,6 +758,7 @@ impl ValidatorTransaction {
         match self {
             ValidatorTransaction::ObservedJwkUpdate(t) => t.timestamp,
             ValidatorTransaction::DkgResult(t) => t.timestamp,
+            ValidatorTransaction::EquivocationEvidence(t) => t.timestamp,
         }
     }
 
@@ -695,6 +766,7 @@ impl ValidatorTransaction {
         match self {
             ValidatorTransaction::ObservedJwkUpdate(t) => &t.events,
             ValidatorTransaction::DkgResult(t) => &t.events,
+            ValidatorTransaction::EquivocationEvidence(t) => &t.events,
         }
     }
 }
@@ -732,6 +804,14 @@ impl
                 timestamp: U64::from(timestamp),
                 quorum_certified_update: quorum_certified_update.into(),
             }),
+            aptos_types::validator_txn::ValidatorTransaction::EquivocationEvidence(evidence) => {
+                Self::EquivocationEvidence(EquivocationEvidenceTransaction {
+                    info,
+                    events,
+                    timestamp: U64::from(timestamp),
+                    evidence: evidence.into(),
+                })
+            },
         }
     }
 }
@@ -840,6 +920,66 @@ impl From<DKGTranscript> for ExportedDKGTranscript {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct EquivocationEvidenceTransaction {
+    #[serde(flatten)]
+    #[oai(flatten)]
+    pub info: TransactionInfo,
+    pub events: Vec<Event>,
+    pub timestamp: U64,
+    pub evidence: ExportedEquivocationEvidence,
+}
+
+/// A more API-friendly representation of the on-chain `aptos_types::equivocation::EquivocationEvidence`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ExportedEquivocationEvidence {
+    pub author: Address,
+    pub epoch: U64,
+    pub round: U64,
+    pub kind: ExportedEquivocationKind,
+    pub first_message: HexEncodedBytes,
+    pub second_message: HexEncodedBytes,
+}
+
+impl From<EquivocationEvidence> for ExportedEquivocationEvidence {
+    fn from(value: EquivocationEvidence) -> Self {
+        let EquivocationEvidence {
+            author,
+            epoch,
+            round,
+            kind,
+            first_message,
+            second_message,
+        } = value;
+        Self {
+            author: author.into(),
+            epoch: epoch.into(),
+            round: round.into(),
+            kind: kind.into(),
+            first_message: HexEncodedBytes::from(first_message),
+            second_message: HexEncodedBytes::from(second_message),
+        }
+    }
+}
+
+/// The kind of consensus messages a validator equivocated on
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[oai(rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ExportedEquivocationKind {
+    Vote,
+    Proposal,
+}
+
+impl From<EquivocationKind> for ExportedEquivocationKind {
+    fn from(value: EquivocationKind) -> Self {
+        match value {
+            EquivocationKind::Vote => Self::Vote,
+            EquivocationKind::Proposal => Self::Proposal,
+        }
+    }
+}
+
 /// An event from a transaction
 #[derive(Clone, Debug, Deserialize, Eq, Object, PartialEq, Serialize)]
 pub struct Event {
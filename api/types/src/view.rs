@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{EntryFunctionId, MoveType};
+use crate::{AptosError, EntryFunctionId, MoveType, MoveValue, U64};
 use aptos_types::serde_helper::vec_bytes;
 use move_core_types::{
     identifier::Identifier,
@@ -20,6 +20,19 @@ pub struct ViewRequest {
     pub arguments: Vec<serde_json::Value>,
 }
 
+/// Outcome of one view function call in a batch
+///
+/// Exactly one of `result` and `error` is set.
+#[derive(Clone, Debug, Serialize, Object)]
+pub struct ViewFunctionBatchResult {
+    /// Return values of the function, if it executed successfully
+    pub result: Option<Vec<MoveValue>>,
+    /// Why the call failed, if it did
+    pub error: Option<AptosError>,
+    /// Gas used by the call
+    pub gas_used: U64,
+}
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq, Serialize, Deserialize)]
 pub struct ViewFunction {
     pub module: ModuleId,
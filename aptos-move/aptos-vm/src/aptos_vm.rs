@@ -236,6 +236,15 @@ impl AptosVM {
         env: Arc<Environment>,
         state_view: &impl StateView,
         inject_create_signer_for_gov_sim: bool,
+    ) -> Self {
+        Self::new_impl(env, state_view, inject_create_signer_for_gov_sim, true)
+    }
+
+    fn new_impl(
+        env: Arc<Environment>,
+        state_view: &impl StateView,
+        inject_create_signer_for_gov_sim: bool,
+        use_warm_vm_cache: bool,
     ) -> Self {
         let _timer = TIMER.timer_with(&["AptosVM::new"]);
 
@@ -243,14 +252,18 @@ impl AptosVM {
             get_gas_parameters(env.features(), state_view);
 
         let resolver = state_view.as_move_resolver();
-        let move_vm = MoveVmExt::new_with_extended_options(
-            gas_feature_version,
-            gas_params.as_ref(),
-            env,
-            None,
-            inject_create_signer_for_gov_sim,
-            &resolver,
-        );
+        let move_vm = if use_warm_vm_cache {
+            MoveVmExt::new_with_extended_options(
+                gas_feature_version,
+                gas_params.as_ref(),
+                env,
+                None,
+                inject_create_signer_for_gov_sim,
+                &resolver,
+            )
+        } else {
+            MoveVmExt::new_cold(gas_feature_version, gas_params.as_ref(), env, &resolver)
+        };
 
         // We use an `Option` to handle the VK not being set on-chain, or an incorrect VK being set
         // via governance (although, currently, we do check for that in `keyless_account.move`).
@@ -2737,24 +2750,51 @@ impl AptosSimulationVM {
         Self(vm)
     }
 
+    /// Creates a VM that loads all modules from the state view, rather than reusing the modules
+    /// loaded by warm VMs, for state views that differ from the on-chain modules.
+    pub fn new_cold(state_view: &impl StateView) -> Self {
+        let env = Arc::new(Environment::new(state_view));
+        let mut vm = AptosVM::new_impl(env, state_view, false, false);
+        vm.is_simulation = true;
+        Self(vm)
+    }
+
     /// Simulates a signed transaction (i.e., executes it without performing
     /// signature verification) on a newly created VM instance.
     /// *Precondition:* the transaction must **not** have a valid signature.
     pub fn create_vm_and_simulate_signed_transaction(
         transaction: &SignedTransaction,
         state_view: &impl StateView,
+    ) -> (VMStatus, TransactionOutput) {
+        Self::new(state_view).simulate_signed_transaction(transaction, state_view)
+    }
+
+    /// Simulates a signed transaction like `create_vm_and_simulate_signed_transaction`, on a
+    /// cold VM, so that modules overridden by the state view are used even if they have already
+    /// been loaded by other VMs.
+    pub fn create_cold_vm_and_simulate_signed_transaction(
+        transaction: &SignedTransaction,
+        state_view: &impl StateView,
+    ) -> (VMStatus, TransactionOutput) {
+        Self::new_cold(state_view).simulate_signed_transaction(transaction, state_view)
+    }
+
+    fn simulate_signed_transaction(
+        self,
+        transaction: &SignedTransaction,
+        state_view: &impl StateView,
     ) -> (VMStatus, TransactionOutput) {
         assert_err!(
             transaction.verify_signature(),
             "Simulated transaction should not have a valid signature"
         );
 
-        let vm = Self::new(state_view);
         let log_context = AdapterLogSchema::new(state_view.id(), 0);
 
         let resolver = state_view.as_move_resolver();
         let (vm_status, vm_output) =
-            vm.0.execute_user_transaction(&resolver, transaction, &log_context);
+            self.0
+                .execute_user_transaction(&resolver, transaction, &log_context);
         let txn_output = vm_output
             .try_materialize_into_transaction_output(&resolver)
             .expect("Materializing aggregator V1 deltas should never fail");
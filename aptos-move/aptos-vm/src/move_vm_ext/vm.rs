@@ -100,6 +100,7 @@ impl MoveVmExt {
         env: Arc<Environment>,
         gas_hook: Option<Arc<dyn Fn(DynamicExpression) + Send + Sync>>,
         inject_create_signer_for_gov_sim: bool,
+        use_warm_vm_cache: bool,
         resolver: &impl AptosMoveResolver,
     ) -> Self {
         // TODO(Gas): Right now, we have to use some dummy values for gas parameters if they are not found on-chain.
@@ -141,17 +142,19 @@ impl MoveVmExt {
             .features()
             .is_enabled(FeatureFlag::DISALLOW_USER_NATIVES);
 
-        Self {
-            inner: WarmVmCache::get_warm_vm(
+        let inner = if use_warm_vm_cache {
+            WarmVmCache::get_warm_vm(
                 builder,
                 vm_config,
                 resolver,
                 env.features().is_enabled(FeatureFlag::VM_BINARY_FORMAT_V7),
                 inject_create_signer_for_gov_sim,
             )
-            .expect("should be able to create Move VM; check if there are duplicated natives"),
-            env,
-        }
+            .expect("should be able to create Move VM; check if there are duplicated natives")
+        } else {
+            WarmVmCache::get_cold_vm(builder, vm_config, inject_create_signer_for_gov_sim)
+        };
+        Self { inner, env }
     }
 
     pub fn new(
@@ -160,7 +163,15 @@ impl MoveVmExt {
         env: Arc<Environment>,
         resolver: &impl AptosMoveResolver,
     ) -> Self {
-        Self::new_impl(gas_feature_version, gas_params, env, None, false, resolver)
+        Self::new_impl(
+            gas_feature_version,
+            gas_params,
+            env,
+            None,
+            false,
+            true,
+            resolver,
+        )
     }
 
     pub fn new_with_extended_options(
@@ -177,6 +188,26 @@ impl MoveVmExt {
             env,
             gas_hook,
             inject_create_signer_for_gov_sim,
+            true,
+            resolver,
+        )
+    }
+
+    /// Creates a VM that loads all modules from the given resolver, instead of sharing the
+    /// modules already loaded by the warm VMs, e.g., when the resolver overrides modules.
+    pub fn new_cold(
+        gas_feature_version: u64,
+        gas_params: Result<&AptosGasParameters, &String>,
+        env: Arc<Environment>,
+        resolver: &impl AptosMoveResolver,
+    ) -> Self {
+        Self::new_impl(
+            gas_feature_version,
+            gas_params,
+            env,
+            None,
+            false,
+            false,
             resolver,
         )
     }
@@ -51,9 +51,22 @@ impl WarmVmCache {
         )
     }
 
+    /// Creates a VM that doesn't share its loaded modules with any other VM, so that it loads
+    /// all modules from the resolver it is used with.
+    pub(crate) fn get_cold_vm(
+        mut native_builder: SafeNativeBuilder,
+        vm_config: VMConfig,
+        inject_create_signer_for_gov_sim: bool,
+    ) -> MoveVM {
+        MoveVM::new_with_config(
+            aptos_natives_with_builder(&mut native_builder, inject_create_signer_for_gov_sim),
+            vm_config,
+        )
+    }
+
     fn get(
         &self,
-        mut native_builder: SafeNativeBuilder,
+        native_builder: SafeNativeBuilder,
         vm_config: VMConfig,
         resolver: &impl AptosMoveResolver,
         bin_v7_enabled: bool,
@@ -84,10 +97,7 @@ impl WarmVmCache {
                 return Ok(vm.clone());
             }
 
-            let vm = MoveVM::new_with_config(
-                aptos_natives_with_builder(&mut native_builder, inject_create_signer_for_gov_sim),
-                vm_config,
-            );
+            let vm = Self::get_cold_vm(native_builder, vm_config, inject_create_signer_for_gov_sim);
             Self::warm_vm_up(&vm, resolver);
 
             // Not using LruCache because its `::get()` requires &mut self
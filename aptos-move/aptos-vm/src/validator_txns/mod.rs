@@ -5,10 +5,10 @@ use crate::{
     move_vm_ext::{AptosMoveResolver, SessionId},
     AptosVM,
 };
-use aptos_types::validator_txn::ValidatorTransaction;
+use aptos_types::{transaction::TransactionStatus, validator_txn::ValidatorTransaction};
 use aptos_vm_logging::log_schema::AdapterLogSchema;
 use aptos_vm_types::output::VMOutput;
-use move_core_types::vm_status::VMStatus;
+use move_core_types::vm_status::{StatusCode, VMStatus};
 
 impl AptosVM {
     pub(crate) fn process_validator_transaction(
@@ -25,6 +25,15 @@ impl AptosVM {
             ValidatorTransaction::ObservedJWKUpdate(jwk_update) => {
                 self.process_jwk_update(resolver, log_context, session_id, jwk_update)
             },
+            ValidatorTransaction::EquivocationEvidence(_) => {
+                // There is no on-chain handling of equivocation evidence yet.
+                Ok((
+                    VMStatus::error(StatusCode::FEATURE_UNDER_GATING, None),
+                    VMOutput::empty_with_status(TransactionStatus::Discard(
+                        StatusCode::FEATURE_UNDER_GATING,
+                    )),
+                ))
+            },
         }
     }
 }
@@ -48,17 +48,19 @@ pub fn create_consensus_runtime(
     admin_service: &mut AdminService,
 ) -> Option<Runtime> {
     consensus_network_interfaces.map(|consensus_network_interfaces| {
-        let (consensus_runtime, consensus_db, quorum_store_db) = services::start_consensus_runtime(
-            node_config,
-            db_rw.clone(),
-            consensus_reconfig_subscription,
-            consensus_network_interfaces,
-            consensus_notifier.clone(),
-            consensus_to_mempool_sender.clone(),
-            vtxn_pool,
-            consensus_publisher.clone(),
-        );
+        let (consensus_runtime, consensus_db, quorum_store_db, leader_reputation) =
+            services::start_consensus_runtime(
+                node_config,
+                db_rw.clone(),
+                consensus_reconfig_subscription,
+                consensus_network_interfaces,
+                consensus_notifier.clone(),
+                consensus_to_mempool_sender.clone(),
+                vtxn_pool,
+                consensus_publisher.clone(),
+            );
         admin_service.set_consensus_dbs(consensus_db, quorum_store_db);
+        admin_service.set_leader_reputation(leader_reputation);
 
         consensus_runtime
     })
@@ -622,7 +622,7 @@ pub fn setup_environment_and_start_node(
     let mut admin_service = services::start_admin_service(&node_config);
 
     // Set up the storage database and any RocksDB checkpoints
-    let (db_rw, backup_service, genesis_waypoint, indexer_db_opt, update_receiver) =
+    let (db_rw, backup_service, genesis_waypoint, indexer_db_opt, update_receiver, backup_restorer) =
         storage::initialize_database_and_checkpoints(&mut node_config)?;
 
     admin_service.set_aptos_db(db_rw.clone().into());
@@ -687,6 +687,7 @@ pub fn setup_environment_and_start_node(
             genesis_waypoint,
             event_subscription_service,
             db_rw.clone(),
+            backup_restorer,
         )?;
 
     // Start the node inspection service
@@ -146,7 +146,10 @@ pub fn peer_monitoring_network_configuration(node_config: &NodeConfig) -> Networ
 /// Returns the network application config for the storage service client and server
 pub fn storage_service_network_configuration(node_config: &NodeConfig) -> NetworkApplicationConfig {
     let direct_send_protocols = vec![]; // The storage service does not use direct send
-    let rpc_protocols = vec![ProtocolId::StorageServiceRpc];
+    let rpc_protocols = vec![
+        ProtocolId::StorageServiceRpcZstd, // Preferred, as responses are compressed with zstd
+        ProtocolId::StorageServiceRpc,
+    ];
     let max_network_channel_size = node_config
         .state_sync
         .storage_service
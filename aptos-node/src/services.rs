@@ -8,7 +8,7 @@ use aptos_config::config::NodeConfig;
 use aptos_consensus::{
     consensus_observer::publisher::consensus_publisher::ConsensusPublisher,
     network_interface::ConsensusMsg, persistent_liveness_storage::StorageWriteProxy,
-    quorum_store::quorum_store_db::QuorumStoreDB,
+    quorum_store::quorum_store_db::QuorumStoreDB, LeaderReputationHandle,
 };
 use aptos_consensus_notifications::ConsensusNotifier;
 use aptos_data_client::client::AptosDataClient;
@@ -147,7 +147,12 @@ pub fn start_consensus_runtime(
     consensus_to_mempool_sender: Sender<QuorumStoreRequest>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
-) -> (Runtime, Arc<StorageWriteProxy>, Arc<QuorumStoreDB>) {
+) -> (
+    Runtime,
+    Arc<StorageWriteProxy>,
+    Arc<QuorumStoreDB>,
+    LeaderReputationHandle,
+) {
     let instant = Instant::now();
 
     let reconfig_subscription = consensus_reconfig_subscription
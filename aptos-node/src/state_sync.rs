@@ -21,6 +21,7 @@ use aptos_network::application::{
     storage::PeersAndMetadata,
 };
 use aptos_state_sync_driver::{
+    backup_restore::BackupRestoreInterface,
     driver_factory::{DriverFactory, StateSyncRuntimes},
     metadata_storage::PersistentMetadataStorage,
 };
@@ -131,6 +132,7 @@ pub fn start_state_sync_and_get_notification_handles(
     waypoint: Waypoint,
     event_subscription_service: EventSubscriptionService,
     db_rw: DbReaderWriter,
+    backup_restorer: Option<Arc<dyn BackupRestoreInterface>>,
 ) -> anyhow::Result<(
     AptosDataClient,
     StateSyncRuntimes,
@@ -195,6 +197,7 @@ pub fn start_state_sync_and_get_notification_handles(
         aptos_data_client.clone(),
         streaming_service_client,
         TimeService::real(),
+        backup_restorer,
     );
 
     // Create a new state sync runtime handle
@@ -2,20 +2,34 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use anyhow::{anyhow, Result};
+use aptos_backup_cli::{
+    coordinators::restore::{RestoreCoordinator, RestoreCoordinatorOpt},
+    metadata::cache::MetadataCacheOpt,
+    storage::{BackupStorage, DBToolStorageOpt},
+    utils::{GlobalRestoreOptions, RestoreRunMode},
+};
 use aptos_backup_service::start_backup_service;
-use aptos_config::{config::NodeConfig, utils::get_genesis_txn};
-use aptos_db::{fast_sync_storage_wrapper::FastSyncStorageWrapper, AptosDB};
+use aptos_config::{
+    config::{BackupRestoreConfig, NodeConfig},
+    utils::get_genesis_txn,
+};
+use aptos_db::{
+    fast_sync_storage_wrapper::FastSyncStorageWrapper, get_restore_handler::GetRestoreHandler,
+    AptosDB,
+};
 use aptos_db_indexer::db_indexer::InternalIndexerDB;
 use aptos_executor::db_bootstrapper::maybe_bootstrap;
 use aptos_indexer_grpc_table_info::internal_indexer_db_service::InternalIndexerDBService;
 use aptos_logger::{debug, info};
+use aptos_state_sync_driver::backup_restore::BackupRestoreInterface;
 use aptos_storage_interface::{DbReader, DbReaderWriter};
 use aptos_types::{
     ledger_info::LedgerInfoWithSignatures, transaction::Version, waypoint::Waypoint,
 };
 use aptos_vm::AptosVM;
+use async_trait::async_trait;
 use either::Either;
-use std::{fs, path::Path, sync::Arc, time::Instant};
+use std::{collections::HashMap, fs, path::Path, sync::Arc, time::Instant};
 use tokio::{
     runtime::Runtime,
     sync::watch::{channel, Receiver as WatchReceiver},
@@ -42,6 +56,74 @@ pub(crate) fn maybe_apply_genesis(
     }
 }
 
+/// Restores the fast sync DB from the backup storage specified in the node
+/// config. This is used by state sync when bootstrapping from a backup.
+struct BackupStorageRestorer {
+    backup_restore_config: BackupRestoreConfig,
+    fast_sync_db_wrapper: Arc<FastSyncStorageWrapper>,
+}
+
+impl BackupStorageRestorer {
+    /// Creates the backup storage to restore from
+    async fn create_backup_storage(&self) -> Result<Arc<dyn BackupStorage>> {
+        if !self.backup_restore_config.has_backup_storage() {
+            return Err(anyhow!("No backup storage is configured to restore from!"));
+        }
+        DBToolStorageOpt::new(
+            self.backup_restore_config.local_fs_dir.clone(),
+            self.backup_restore_config.command_adapter_config.clone(),
+            self.backup_restore_config.s3_config.clone(),
+            self.backup_restore_config.encryption_key_file.clone(),
+        )
+        .init_storage()
+        .await
+    }
+}
+
+#[async_trait]
+impl BackupRestoreInterface for BackupStorageRestorer {
+    async fn restore_from_backup(&self, waypoint: Waypoint) -> Result<()> {
+        let backup_storage = self.create_backup_storage().await?;
+
+        // Restore everything in the backup directly into the fast sync DB
+        let restore_handler = self
+            .fast_sync_db_wrapper
+            .get_fast_sync_db()
+            .get_restore_handler();
+        let global_restore_options = GlobalRestoreOptions {
+            target_version: Version::MAX,
+            trusted_waypoints: Arc::new(HashMap::from([(waypoint.version(), waypoint)])),
+            run_mode: Arc::new(RestoreRunMode::Restore { restore_handler }),
+            concurrent_downloads: self
+                .backup_restore_config
+                .concurrent_downloads
+                .unwrap_or_else(num_cpus::get),
+            replay_concurrency_level: num_cpus::get(),
+        };
+        let restore_coordinator_options = RestoreCoordinatorOpt {
+            metadata_cache_opt: MetadataCacheOpt::new(
+                self.backup_restore_config.metadata_cache_dir.clone(),
+            ),
+            replay_all: false,
+            ledger_history_start_version: None,
+            skip_epoch_endings: false,
+            target_time: None,
+            target_epoch: None,
+        };
+        RestoreCoordinator::new(
+            restore_coordinator_options,
+            global_restore_options,
+            backup_storage,
+        )
+        .run()
+        .await?;
+
+        // Switch all reads and writes to the restored DB
+        self.fast_sync_db_wrapper.finish_external_restore();
+        Ok(())
+    }
+}
+
 #[cfg(not(feature = "consensus-only-perf-test"))]
 pub(crate) fn bootstrap_db(
     node_config: &NodeConfig,
@@ -51,6 +133,7 @@ pub(crate) fn bootstrap_db(
     Option<Runtime>,
     Option<InternalIndexerDB>,
     Option<WatchReceiver<u64>>,
+    Option<Arc<dyn BackupRestoreInterface>>,
 )> {
     let internal_indexer_db = InternalIndexerDBService::get_indexer_db(node_config);
     let (update_sender, update_receiver) = if internal_indexer_db.is_some() {
@@ -60,17 +143,23 @@ pub(crate) fn bootstrap_db(
         (None, None)
     };
 
-    let (aptos_db_reader, db_rw, backup_service) = match FastSyncStorageWrapper::initialize_dbs(
+    let dbs = FastSyncStorageWrapper::initialize_dbs(
         node_config,
         internal_indexer_db.clone(),
         update_sender,
-    )? {
+    )?;
+    let (aptos_db_reader, db_rw, backup_service, backup_restorer) = match dbs {
         Either::Left(db) => {
             let (db_arc, db_rw) = DbReaderWriter::wrap(db);
             let db_backup_service =
                 start_backup_service(node_config.storage.backup_service_address, db_arc.clone());
             maybe_apply_genesis(&db_rw, node_config)?;
-            (db_arc as Arc<dyn DbReader>, db_rw, Some(db_backup_service))
+            (
+                db_arc as Arc<dyn DbReader>,
+                db_rw,
+                Some(db_backup_service),
+                None,
+            )
         },
         Either::Right(fast_sync_db_wrapper) => {
             let temp_db = fast_sync_db_wrapper.get_temporary_db_with_genesis();
@@ -94,7 +183,28 @@ pub(crate) fn bootstrap_db(
             }
             let db_backup_service =
                 start_backup_service(node_config.storage.backup_service_address, fast_sync_db);
-            (db_arc as Arc<dyn DbReader>, db_rw, Some(db_backup_service))
+
+            // If required, create the backup restorer to restore the fast sync DB
+            let backup_restorer = if node_config
+                .state_sync
+                .state_sync_driver
+                .bootstrapping_mode
+                .is_restore_from_backup()
+            {
+                let backup_restorer = BackupStorageRestorer {
+                    backup_restore_config: node_config.storage.backup_restore_config.clone(),
+                    fast_sync_db_wrapper: db_arc.clone(),
+                };
+                Some(Arc::new(backup_restorer) as Arc<dyn BackupRestoreInterface>)
+            } else {
+                None
+            };
+            (
+                db_arc as Arc<dyn DbReader>,
+                db_rw,
+                Some(db_backup_service),
+                backup_restorer,
+            )
         },
     };
     Ok((
@@ -103,6 +213,7 @@ pub(crate) fn bootstrap_db(
         backup_service,
         internal_indexer_db,
         update_receiver,
+        backup_restorer,
     ))
 }
 
@@ -177,6 +288,7 @@ pub fn initialize_database_and_checkpoints(
     Waypoint,
     Option<InternalIndexerDB>,
     Option<WatchReceiver<Version>>,
+    Option<Arc<dyn BackupRestoreInterface>>,
 )> {
     // If required, create RocksDB checkpoints and change the working directory.
     // This is test-only.
@@ -186,7 +298,7 @@ pub fn initialize_database_and_checkpoints(
 
     // Open the database
     let instant = Instant::now();
-    let (_aptos_db, db_rw, backup_service, indexer_db_opt, update_receiver) =
+    let (_aptos_db, db_rw, backup_service, indexer_db_opt, update_receiver, backup_restorer) =
         bootstrap_db(node_config)?;
 
     // Log the duration to open storage
@@ -201,5 +313,6 @@ pub fn initialize_database_and_checkpoints(
         node_config.base.waypoint.genesis_waypoint(),
         indexer_db_opt,
         update_receiver,
+        backup_restorer,
     ))
 }
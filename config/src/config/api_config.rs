@@ -51,6 +51,8 @@ pub struct ApiConfig {
     pub transaction_simulation_enabled: bool,
     /// Maximum number of transactions that can be sent with the Batch submit API
     pub max_submit_transaction_batch_size: usize,
+    /// Maximum number of view function calls that can be sent with the Batch view API
+    pub max_view_function_batch_size: usize,
     /// Maximum page size for transaction paginated APIs
     pub max_transactions_page_size: u16,
     /// Maximum page size for block transaction APIs
@@ -89,12 +91,17 @@ pub struct ApiConfig {
     pub wait_by_hash_poll_interval_ms: u64,
     /// The number of active wait_by_hash requests that can be active at any given time.
     pub wait_by_hash_max_active_connections: usize,
+    /// The interval at which transaction and event streams poll the storage for new transactions.
+    pub stream_poll_interval_ms: u64,
+    /// The number of transaction and event streams that can be open at any given time.
+    pub stream_max_active_connections: usize,
 }
 
 const DEFAULT_ADDRESS: &str = "127.0.0.1";
 const DEFAULT_PORT: u16 = 8080;
 const DEFAULT_REQUEST_CONTENT_LENGTH_LIMIT: u64 = 8 * 1024 * 1024; // 8 MB
 pub const DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE: usize = 10;
+pub const DEFAULT_MAX_VIEW_FUNCTION_BATCH_SIZE: usize = 20;
 pub const DEFAULT_MAX_PAGE_SIZE: u16 = 100;
 const DEFAULT_MAX_ACCOUNT_RESOURCES_PAGE_SIZE: u16 = 9999;
 const DEFAULT_MAX_ACCOUNT_MODULES_PAGE_SIZE: u16 = 9999;
@@ -125,6 +132,7 @@ impl Default for ApiConfig {
             transaction_submission_enabled: default_enabled(),
             transaction_simulation_enabled: default_enabled(),
             max_submit_transaction_batch_size: DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE,
+            max_view_function_batch_size: DEFAULT_MAX_VIEW_FUNCTION_BATCH_SIZE,
             max_block_transactions_page_size: *MAX_RECEIVING_BLOCK_TXNS as u16,
             max_transactions_page_size: DEFAULT_MAX_PAGE_SIZE,
             max_events_page_size: DEFAULT_MAX_PAGE_SIZE,
@@ -141,6 +149,8 @@ impl Default for ApiConfig {
             wait_by_hash_timeout_ms: 1_000,
             wait_by_hash_poll_interval_ms: 20,
             wait_by_hash_max_active_connections: 100,
+            stream_poll_interval_ms: 100,
+            stream_max_active_connections: 100,
         }
     }
 }
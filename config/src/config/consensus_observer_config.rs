@@ -38,7 +38,10 @@ pub struct ConsensusObserverConfig {
     /// The maximum number of concurrent subscriptions
     pub max_concurrent_subscriptions: u64,
     /// Maximum timeout (in milliseconds) we'll wait for the synced version to
-    /// increase before terminating the active subscription.
+    /// increase before terminating the primary subscription. Note: each new primary
+    /// gets the full timeout, so failing over N subscriptions could take N times
+    /// this timeout, but the observer falls back to state sync after at most
+    /// `observer_fallback_sync_threshold_ms` without syncing progress.
     pub max_subscription_sync_timeout_ms: u64,
     /// Maximum message timeout (in milliseconds) for active subscriptions
     pub max_subscription_timeout_ms: u64,
@@ -46,6 +49,11 @@ pub struct ConsensusObserverConfig {
     pub subscription_peer_change_interval_ms: u64,
     /// Interval (in milliseconds) to refresh the subscription
     pub subscription_refresh_interval_ms: u64,
+    /// Maximum average delay (in milliseconds) of a publisher behind the first verified
+    /// delivery of each message, before terminating its (non-primary) subscription.
+    pub max_publisher_delivery_lag_ms: u64,
+    /// Maximum number of invalid messages sent by a publisher before terminating its subscription
+    pub max_publisher_invalid_messages: u64,
 
     /// Duration (in milliseconds) to require state sync to synchronize when in fallback mode
     pub observer_fallback_duration_ms: u64,
@@ -69,6 +77,8 @@ impl Default for ConsensusObserverConfig {
             max_subscription_timeout_ms: 15_000,               // 15 seconds
             subscription_peer_change_interval_ms: 60_000,      // 1 minute
             subscription_refresh_interval_ms: 300_000,         // 5 minutes
+            max_publisher_delivery_lag_ms: 2_000,              // 2 seconds
+            max_publisher_invalid_messages: 10,                // 10 messages
             observer_fallback_duration_ms: 600_000,            // 10 minutes
             observer_fallback_sync_threshold_ms: 30_000,       // 30 seconds
         }
@@ -13,6 +13,8 @@ pub struct InternalIndexerDBConfig {
     pub enable_transaction: bool,
     pub enable_event: bool,
     pub enable_statekeys: bool,
+    pub enable_event_by_type: bool,
+    pub enable_transaction_by_entry_function: bool,
     pub batch_size: usize,
 }
 
@@ -21,12 +23,16 @@ impl InternalIndexerDBConfig {
         enable_transaction: bool,
         enable_event: bool,
         enable_statekeys: bool,
+        enable_event_by_type: bool,
+        enable_transaction_by_entry_function: bool,
         batch_size: usize,
     ) -> Self {
         Self {
             enable_transaction,
             enable_event,
             enable_statekeys,
+            enable_event_by_type,
+            enable_transaction_by_entry_function,
             batch_size,
         }
     }
@@ -43,8 +49,20 @@ impl InternalIndexerDBConfig {
         self.enable_statekeys
     }
 
+    pub fn enable_event_by_type(&self) -> bool {
+        self.enable_event_by_type
+    }
+
+    pub fn enable_transaction_by_entry_function(&self) -> bool {
+        self.enable_transaction_by_entry_function
+    }
+
     pub fn is_internal_indexer_db_enabled(&self) -> bool {
-        self.enable_transaction || self.enable_event || self.enable_statekeys
+        self.enable_transaction
+            || self.enable_event
+            || self.enable_statekeys
+            || self.enable_event_by_type
+            || self.enable_transaction_by_entry_function
     }
 
     pub fn batch_size(&self) -> usize {
@@ -58,6 +76,8 @@ impl Default for InternalIndexerDBConfig {
             enable_transaction: false,
             enable_event: false,
             enable_statekeys: false,
+            enable_event_by_type: false,
+            enable_transaction_by_entry_function: false,
             batch_size: 10_000,
         }
     }
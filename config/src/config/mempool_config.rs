@@ -100,6 +100,12 @@ pub struct MempoolConfig {
     /// up to 10 minutes (shared_mempool_priority_update_interval_secs) to enable the load balancing. If this flag is enabled,
     /// then the PFNs will always do load balancing irrespective of the load.
     pub enable_max_load_balancing_at_any_load: bool,
+    /// If set, a transaction with the same sender and sequence number as one already in the
+    /// Mempool, but a different payload, expiration or max gas amount, replaces it as long as its
+    /// gas unit price is higher by at least this percentage. Replacing with a no-op payload
+    /// effectively cancels the pending transaction. If not set, only the gas unit price of a
+    /// pending transaction can be increased.
+    pub replacement_min_gas_price_bump_pct: Option<u64>,
 }
 
 impl Default for MempoolConfig {
@@ -164,6 +170,7 @@ impl Default for MempoolConfig {
                 },
             ],
             enable_max_load_balancing_at_any_load: false,
+            replacement_min_gas_price_bump_pct: None,
         }
     }
 }
@@ -118,12 +118,18 @@ pub struct NetworkConfig {
     pub inbound_rate_limit_config: Option<RateLimitConfig>,
     /// Outbound rate limiting configuration, if not specified, no rate limiting
     pub outbound_rate_limit_config: Option<RateLimitConfig>,
+    /// Per-peer quotas on the inbound messages of each protocol. No quotas by default.
+    pub peer_quota_config: PeerQuotaConfig,
     /// The maximum size of an inbound or outbound message (it may be divided into multiple frame)
     pub max_message_size: usize,
     /// The maximum number of parallel message deserialization tasks that can run (per application)
     pub max_parallel_deserialization_tasks: Option<usize>,
     /// Whether or not to enable latency aware peer dialing
     pub enable_latency_aware_dialing: bool,
+    /// Path to a file describing network faults to inject into the connections this node dials
+    /// (e.g., delays, losses and partitions). The file is reloaded periodically. Only meant for
+    /// testing, e.g., to emulate network conditions between the nodes of a local swarm.
+    pub fault_injection_config_path: Option<PathBuf>,
 }
 
 impl Default for NetworkConfig {
@@ -158,6 +164,7 @@ impl NetworkConfig {
             max_inbound_connections: MAX_INBOUND_CONNECTIONS,
             inbound_rate_limit_config: None,
             outbound_rate_limit_config: None,
+            peer_quota_config: PeerQuotaConfig::default(),
             max_message_size: MAX_MESSAGE_SIZE,
             inbound_rx_buffer_size_bytes: None,
             inbound_tx_buffer_size_bytes: None,
@@ -165,6 +172,7 @@ impl NetworkConfig {
             outbound_tx_buffer_size_bytes: None,
             max_parallel_deserialization_tasks: None,
             enable_latency_aware_dialing: true,
+            fault_injection_config_path: None,
         };
 
         // Configure the number of parallel deserialization tasks
@@ -347,6 +355,7 @@ pub enum DiscoveryMethod {
     Onchain,
     File(FileDiscovery),
     Rest(RestDiscovery),
+    Dns(DnsDiscovery),
     None,
 }
 
@@ -364,6 +373,17 @@ pub struct RestDiscovery {
     pub interval_secs: u64,
 }
 
+/// Discovers seed peers from the DNS records of a domain name. Each TXT record of the name
+/// holds the network address of a seed peer (including its x25519 key), and each SRV record
+/// of the name points to a seed peer whose x25519 key is in a TXT record (`x25519=<key>`) of
+/// the SRV target.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub struct DnsDiscovery {
+    pub name: String,
+    pub interval_secs: u64,
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct RateLimitConfig {
@@ -388,6 +408,46 @@ impl Default for RateLimitConfig {
     }
 }
 
+/// The quotas enforced on the inbound messages of each connected peer. A peer that keeps
+/// exceeding its quotas (i.e., more than `max_throttled_messages_per_sec` of its messages are
+/// dropped within a second) is disconnected. Dropped rpc requests are failed with an error
+/// response if the sender negotiated support for it, so that it doesn't have to wait for them
+/// to time out.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PeerQuotaConfig {
+    /// The quotas of the protocols. If several quotas match a message, the first one applies.
+    pub protocol_quotas: Vec<ProtocolQuota>,
+    /// The number of dropped messages per second above which a peer is disconnected
+    /// (if 0, peers are never disconnected)
+    pub max_throttled_messages_per_sec: usize,
+}
+
+/// A token bucket quota on the inbound messages of a protocol, enforced for each peer
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProtocolQuota {
+    /// The name of the protocol (e.g., `MempoolDirectSend` or `StorageServiceRpc`)
+    pub protocol_id: String,
+    /// The roles of the peers the quota applies to (if empty, the quota applies to all peers)
+    #[serde(default)]
+    pub peer_roles: Vec<PeerRole>,
+    /// The maximum number of messages per second (if 0, the number of messages is unlimited)
+    #[serde(default)]
+    pub max_messages_per_sec: usize,
+    /// The maximum number of bytes per second (if 0, the number of bytes is unlimited). Note:
+    /// messages larger than this are always dropped.
+    #[serde(default)]
+    pub max_bytes_per_sec: usize,
+}
+
+impl ProtocolQuota {
+    /// Returns true iff the quota applies to peers with the given role
+    pub fn applies_to(&self, peer_role: PeerRole) -> bool {
+        self.peer_roles.is_empty() || self.peer_roles.contains(&peer_role)
+    }
+}
+
 pub type PeerSet = HashMap<PeerId, Peer>;
 
 // TODO: Combine with RoleType?
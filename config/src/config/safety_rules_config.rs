@@ -12,11 +12,19 @@ use crate::{
     keys::ConfigKey,
 };
 use anyhow::bail;
-use aptos_crypto::{bls12381, Uniform};
-use aptos_types::{chain_id::ChainId, network_address::NetworkAddress, waypoint::Waypoint, PeerId};
+use aptos_crypto::{bls12381, x25519, Uniform};
+use aptos_types::{
+    chain_id::ChainId,
+    network_address::{NetworkAddress, Protocol},
+    waypoint::Waypoint,
+    PeerId,
+};
 use rand::rngs::StdRng;
 use serde::{Deserialize, Serialize};
 use std::{
+    collections::HashSet,
+    convert::TryInto,
+    fs,
     net::{SocketAddr, ToSocketAddrs},
     path::PathBuf,
 };
@@ -83,6 +91,17 @@ impl ConfigSanitizer for SafetyRulesConfig {
             return Ok(());
         }
 
+        // Verify that a remote safety rules service on another host is authenticated
+        if let SafetyRulesService::Process(service) = &safety_rules_config.service {
+            if service.authentication.is_none() && !service.is_loopback() {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "The safety rules service must be authenticated when it is not on the loopback interface!"
+                        .to_string(),
+                ));
+            }
+        }
+
         if let Some(chain_id) = chain_id {
             // Verify that the secure backend is appropriate for mainnet validators
             if chain_id.is_mainnet()
@@ -96,11 +115,15 @@ impl ConfigSanitizer for SafetyRulesConfig {
                 ));
             }
 
-            // Verify that the safety rules service is set to local for optimal performance
-            if chain_id.is_mainnet() && !safety_rules_config.service.is_local() {
+            // Verify that the safety rules service is set to local for optimal performance, unless
+            // the validator keeps its keys in an authenticated remote signer.
+            if chain_id.is_mainnet()
+                && !safety_rules_config.service.is_local()
+                && !safety_rules_config.service.is_authenticated_remote()
+            {
                 return Err(Error::ConfigSanitizerFailed(
                     sanitizer_name,
-                    format!("The safety rules service should be set to local in mainnet for optimal performance! Given config: {:?}", &safety_rules_config.service)
+                    format!("The safety rules service should be set to local (or an authenticated remote signer) in mainnet! Given config: {:?}", &safety_rules_config.service)
                 ));
             }
 
@@ -221,12 +244,24 @@ impl SafetyRulesService {
     fn is_local(&self) -> bool {
         matches!(self, SafetyRulesService::Local)
     }
+
+    /// Returns true iff the service is a remote process with an authenticated connection
+    fn is_authenticated_remote(&self) -> bool {
+        match self {
+            SafetyRulesService::Process(service) => service.authentication.is_some(),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(deny_unknown_fields)]
 pub struct RemoteService {
     pub server_address: NetworkAddress,
+    /// Mutually authenticates and encrypts the connection between consensus and the SafetyRules
+    /// server, which is required when they run on different hosts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authentication: Option<RemoteServiceAuthentication>,
 }
 
 impl RemoteService {
@@ -237,6 +272,54 @@ impl RemoteService {
             .next()
             .expect("server_address invalid")
     }
+
+    /// Returns true iff the server address is on the loopback interface
+    fn is_loopback(&self) -> bool {
+        match self.server_address.as_slice().first() {
+            Some(Protocol::Ip4(ip)) => ip.is_loopback(),
+            Some(Protocol::Ip6(ip)) => ip.is_loopback(),
+            _ => false,
+        }
+    }
+}
+
+/// The authentication of the connection between consensus and a remote SafetyRules server. Both
+/// hosts can share the same configuration, except for their identity keys.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteServiceAuthentication {
+    /// Path to the file holding the x25519 private key identifying this end of the connection
+    pub identity_key_path: PathBuf,
+    /// The x25519 public key of the SafetyRules server
+    pub server_public_key: x25519::PublicKey,
+    /// The x25519 public keys of the consensus clients allowed to use the SafetyRules server
+    pub client_public_keys: HashSet<x25519::PublicKey>,
+    /// Time after the last request of a consensus client during which the server refuses any
+    /// other consensus client, in milliseconds. This must exceed the network timeout, so that the
+    /// server never serves two consensus clients concurrently.
+    #[serde(default = "RemoteServiceAuthentication::default_client_lease_ms")]
+    pub client_lease_ms: u64,
+    /// Time consensus waits for the server to answer a request, across reconnections, in
+    /// milliseconds.
+    #[serde(default = "RemoteServiceAuthentication::default_request_timeout_ms")]
+    pub request_timeout_ms: u64,
+}
+
+impl RemoteServiceAuthentication {
+    fn default_client_lease_ms() -> u64 {
+        60_000
+    }
+
+    fn default_request_timeout_ms() -> u64 {
+        10_000
+    }
+
+    /// Reads the identity key, stored as raw bytes (like network identity keys)
+    pub fn identity_key(&self) -> anyhow::Result<x25519::PrivateKey> {
+        let bytes = fs::read(&self.identity_key_path)?;
+        let bytes: [u8; x25519::PRIVATE_KEY_SIZE] = bytes.as_slice().try_into()?;
+        Ok(x25519::PrivateKey::from(bytes))
+    }
 }
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
@@ -268,7 +351,7 @@ impl SafetyRulesTestConfig {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::ConsensusConfig;
+    use crate::config::{ConsensusConfig, Pkcs11Config, Token};
 
     #[test]
     fn test_sanitize_invalid_backend_for_mainnet() {
@@ -294,6 +377,30 @@ mod tests {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_pkcs11_backend_for_mainnet() {
+        // Create a node config with a PKCS#11 backend
+        let node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                safety_rules: SafetyRulesConfig {
+                    backend: SecureBackend::Pkcs11(Pkcs11Config {
+                        library_path: PathBuf::from("/usr/lib/softhsm/libsofthsm2.so"),
+                        token_label: "validator".to_string(),
+                        pin: Token::FromConfig("1234".to_string()),
+                        namespace: None,
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config sanitizer passes
+        SafetyRulesConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::mainnet()))
+            .unwrap();
+    }
+
     #[test]
     fn test_sanitize_backend_for_mainnet_fullnodes() {
         // Create a node config with an invalid backend for mainnet validators
@@ -364,4 +471,56 @@ mod tests {
         .unwrap_err();
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
+
+    #[test]
+    fn test_sanitize_unauthenticated_remote_service() {
+        // Create a node config with an unauthenticated service on another host
+        let node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                safety_rules: SafetyRulesConfig {
+                    service: SafetyRulesService::Process(RemoteService {
+                        server_address: "/ip4/10.0.0.2/tcp/6191".parse().unwrap(),
+                        authentication: None,
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config sanitizer fails
+        let error =
+            SafetyRulesConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_sanitize_authenticated_remote_service_on_mainnet() {
+        // Create a node config with an authenticated service on another host
+        let node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                safety_rules: SafetyRulesConfig {
+                    backend: SecureBackend::OnDiskStorage(Default::default()),
+                    service: SafetyRulesService::Process(RemoteService {
+                        server_address: "/ip4/10.0.0.2/tcp/6191".parse().unwrap(),
+                        authentication: Some(RemoteServiceAuthentication {
+                            identity_key_path: PathBuf::from("/opt/aptos/safety-rules-key"),
+                            server_public_key: x25519::PublicKey::from([0; 32]),
+                            client_public_keys: HashSet::new(),
+                            client_lease_ms: 60_000,
+                            request_timeout_ms: 10_000,
+                        }),
+                    }),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that the config sanitizer passes
+        SafetyRulesConfig::sanitize(&node_config, NodeType::Validator, Some(ChainId::mainnet()))
+            .unwrap();
+    }
 }
@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::config::Error;
-use aptos_secure_storage::{InMemoryStorage, Namespaced, OnDiskStorage, Storage, VaultStorage};
+use aptos_secure_storage::{
+    InMemoryStorage, Namespaced, OnDiskStorage, Pkcs11Storage, Storage, VaultStorage,
+};
 use serde::{Deserialize, Serialize};
 use std::{
     fs::File,
@@ -20,15 +22,15 @@ pub enum SecureBackend {
     InMemoryStorage,
     Vault(VaultConfig),
     OnDiskStorage(OnDiskStorageConfig),
+    Pkcs11(Pkcs11Config),
 }
 
 impl SecureBackend {
     pub fn namespace(&self) -> Option<&str> {
         match self {
             SecureBackend::Vault(VaultConfig { namespace, .. })
-            | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. }) => {
-                namespace.as_deref()
-            },
+            | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. })
+            | SecureBackend::Pkcs11(Pkcs11Config { namespace, .. }) => namespace.as_deref(),
             SecureBackend::InMemoryStorage => None,
         }
     }
@@ -36,7 +38,8 @@ impl SecureBackend {
     pub fn clear_namespace(&mut self) {
         match self {
             SecureBackend::Vault(VaultConfig { namespace, .. })
-            | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. }) => {
+            | SecureBackend::OnDiskStorage(OnDiskStorageConfig { namespace, .. })
+            | SecureBackend::Pkcs11(Pkcs11Config { namespace, .. }) => {
                 *namespace = None;
             },
             SecureBackend::InMemoryStorage => {},
@@ -97,6 +100,23 @@ pub struct OnDiskStorageConfig {
     data_dir: PathBuf,
 }
 
+/// Keeps keys in a token accessed through a PKCS#11 module (e.g., a hardware security module), so
+/// that Ed25519 private keys never leave the token.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pkcs11Config {
+    /// Full path to the PKCS#11 module (shared library) of the token, e.g., the SoftHSM library.
+    pub library_path: PathBuf,
+    /// The label of the token holding the keys.
+    pub token_label: String,
+    /// The user PIN for logging into the token.
+    pub pin: Token,
+    /// A namespace is an optional prefix of the label of the objects stored within the token. For
+    /// example, a key, S, without a namespace would be labelled S, with a namespace, N, it would
+    /// be labelled N/S.
+    pub namespace: Option<String>,
+}
+
 /// Tokens can either be directly within this config or stored somewhere on disk.
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "snake_case")]
@@ -191,6 +211,21 @@ impl From<&SecureBackend> for Storage {
                     storage
                 }
             },
+            SecureBackend::Pkcs11(config) => {
+                let storage = Storage::from(
+                    Pkcs11Storage::new(
+                        &config.library_path,
+                        &config.token_label,
+                        config.pin.read_token().expect("Unable to read pin"),
+                    )
+                    .expect("Unable to open PKCS#11 token"),
+                );
+                if let Some(namespace) = &config.namespace {
+                    Storage::from(Namespaced::new(namespace, Box::new(storage)))
+                } else {
+                    storage
+                }
+            },
         }
     }
 }
@@ -290,6 +325,31 @@ vault:
         serde_yaml::to_string(&from_disk).unwrap();
     }
 
+    #[test]
+    fn test_pkcs11_parsing() {
+        let text = r#"
+type: pkcs11
+library_path: "/usr/lib/softhsm/libsofthsm2.so"
+token_label: "validator"
+pin:
+    from_disk: "/opt/aptos/genesis/pin"
+namespace: "consensus"
+        "#;
+
+        let backend: SecureBackend = serde_yaml::from_str(text).unwrap();
+        assert_eq!(
+            backend,
+            SecureBackend::Pkcs11(Pkcs11Config {
+                library_path: PathBuf::from("/usr/lib/softhsm/libsofthsm2.so"),
+                token_label: "validator".to_string(),
+                pin: Token::FromDisk(PathBuf::from("/opt/aptos/genesis/pin")),
+                namespace: Some("consensus".to_string()),
+            })
+        );
+        assert_eq!(backend.namespace(), Some("consensus"));
+        assert!(!backend.is_in_memory());
+    }
+
     #[test]
     fn test_token_reading() {
         let temppath = aptos_temppath::TempPath::new();
@@ -44,6 +44,10 @@ pub enum BootstrappingMode {
     ExecuteTransactionsFromGenesis,
     /// Executes transactions or applies outputs from genesis (whichever is faster)
     ExecuteOrApplyFromGenesis,
+    /// Restores the latest state snapshot and transactions from a backup storage
+    /// (see `BackupRestoreConfig`), and then executes transactions or applies
+    /// outputs for the rest (whichever is faster).
+    RestoreFromBackup,
 }
 
 impl BootstrappingMode {
@@ -57,6 +61,7 @@ impl BootstrappingMode {
                 "execute_transactions_from_genesis"
             },
             BootstrappingMode::ExecuteOrApplyFromGenesis => "execute_or_apply_from_genesis",
+            BootstrappingMode::RestoreFromBackup => "restore_from_backup",
         }
     }
 
@@ -64,6 +69,11 @@ impl BootstrappingMode {
     pub fn is_fast_sync(&self) -> bool {
         *self == BootstrappingMode::DownloadLatestStates
     }
+
+    /// Returns true iff the bootstrapping mode restores from a backup
+    pub fn is_restore_from_backup(&self) -> bool {
+        *self == BootstrappingMode::RestoreFromBackup
+    }
 }
 
 /// The continuous syncing mode determines how the node will stay up-to-date
@@ -483,6 +493,24 @@ impl ConfigSanitizer for StateSyncDriverConfig {
             ));
         }
 
+        // Verify that a backup storage is configured for nodes
+        // that are restoring from a backup.
+        let restore_from_backup = state_sync_driver_config
+            .bootstrapping_mode
+            .is_restore_from_backup();
+        if restore_from_backup
+            && !node_config
+                .storage
+                .backup_restore_config
+                .has_backup_storage()
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "A backup storage must be configured for nodes that are restoring from a backup!"
+                    .to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -788,6 +816,49 @@ mod tests {
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
 
+    #[test]
+    fn test_sanitize_restore_from_backup_storage() {
+        // Create a node config that restores from a backup (without a backup storage)
+        let mut node_config = NodeConfig {
+            state_sync: StateSyncConfig {
+                state_sync_driver: StateSyncDriverConfig {
+                    bootstrapping_mode: BootstrappingMode::RestoreFromBackup,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Verify that sanitization fails
+        let error = StateSyncConfig::sanitize(
+            &node_config,
+            NodeType::PublicFullnode,
+            Some(ChainId::test()),
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+
+        // Configure a backup storage and verify that sanitization succeeds
+        node_config.storage.backup_restore_config.local_fs_dir = Some("backups".into());
+        StateSyncConfig::sanitize(
+            &node_config,
+            NodeType::PublicFullnode,
+            Some(ChainId::test()),
+        )
+        .unwrap();
+
+        // Configure an S3 backup storage instead and verify that sanitization succeeds
+        node_config.storage.backup_restore_config.local_fs_dir = None;
+        node_config.storage.backup_restore_config.s3_config = Some("s3.yaml".into());
+        StateSyncConfig::sanitize(
+            &node_config,
+            NodeType::PublicFullnode,
+            Some(ChainId::test()),
+        )
+        .unwrap();
+    }
+
     /// Creates and returns a node config with the syncing modes set to execution
     fn create_execution_mode_config() -> NodeConfig {
         NodeConfig {
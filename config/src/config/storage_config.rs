@@ -24,6 +24,34 @@ pub const DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD: usize = 1 << 13;
 pub const BUFFERED_STATE_TARGET_ITEMS: usize = 100_000;
 pub const BUFFERED_STATE_TARGET_ITEMS_FOR_TEST: usize = 10;
 
+/// The location of the backups to restore the DB from, when bootstrapping
+/// from a backup (see `BootstrappingMode::RestoreFromBackup`).
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackupRestoreConfig {
+    /// The command adapter config file of the backup storage (e.g., for a cloud bucket)
+    pub command_adapter_config: Option<PathBuf>,
+    /// The local directory of the backup storage (used mainly for tests)
+    pub local_fs_dir: Option<PathBuf>,
+    /// The S3 config file of the backup storage (talks to S3 or an S3-compatible store directly)
+    pub s3_config: Option<PathBuf>,
+    /// The file holding the key to decrypt encrypted backup files with (plaintext ones are read as is)
+    pub encryption_key_file: Option<PathBuf>,
+    /// The directory to cache the backup metadata in (defaults to a temporary directory)
+    pub metadata_cache_dir: Option<PathBuf>,
+    /// The number of concurrent downloads from the backup storage (defaults to the number of CPUs)
+    pub concurrent_downloads: Option<usize>,
+}
+
+impl BackupRestoreConfig {
+    /// Returns true iff a backup storage location has been configured
+    pub fn has_backup_storage(&self) -> bool {
+        self.command_adapter_config.is_some()
+            || self.local_fs_dir.is_some()
+            || self.s3_config.is_some()
+    }
+}
+
 #[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct DbPathConfig {
@@ -170,6 +198,8 @@ impl Default for RocksdbConfigs {
 #[serde(default, deny_unknown_fields)]
 pub struct StorageConfig {
     pub backup_service_address: SocketAddr,
+    /// The backups to restore the DB from (only used when bootstrapping from a backup)
+    pub backup_restore_config: BackupRestoreConfig,
     /// Top level directory to store the RocksDB
     pub dir: PathBuf,
     /// Storage pruning configuration
@@ -216,6 +246,7 @@ pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
         prune_window: 0,
         batch_size: 0,
     },
+    enable_archive_mode: false,
 };
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -278,6 +309,11 @@ pub struct PrunerConfig {
     pub ledger_pruner_config: LedgerPrunerConfig,
     pub state_merkle_pruner_config: StateMerklePrunerConfig,
     pub epoch_snapshot_pruner_config: EpochSnapshotPrunerConfig,
+    /// Boolean to enable/disable archive mode. In archive mode, the state value history (the state
+    /// kv db) is never pruned, while the state Merkle tree and the rest of the ledger are still
+    /// pruned according to their own configs. This allows serving historical state reads at any
+    /// version, without Merkle proofs for versions older than the state merkle prune window.
+    pub enable_archive_mode: bool,
 }
 
 impl Default for LedgerPrunerConfig {
@@ -332,6 +368,7 @@ impl Default for StorageConfig {
     fn default() -> StorageConfig {
         StorageConfig {
             backup_service_address: SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 6186),
+            backup_restore_config: BackupRestoreConfig::default(),
             dir: PathBuf::from("db"),
             // The prune window must at least out live a RPC request because its sub requests are
             // to return a consistent view of the DB at exactly same version. Considering a few
@@ -528,6 +565,14 @@ impl ConfigSanitizer for StorageConfig {
         if epoch_snapshot_prune_window < 50_000_000 {
             warn!("Epoch snapshot prune_window is too small, harming network data availability.");
         }
+        if config.storage_pruner_config.enable_archive_mode
+            && !config
+                .storage_pruner_config
+                .state_merkle_pruner_config
+                .enable
+        {
+            warn!("Archive mode is enabled without the state Merkle pruner, which keeps the full state history anyway.");
+        }
         if user_pruning_window_offset > 1_000_000 {
             return Err(Error::ConfigSanitizerFailed(
                 sanitizer_name,
@@ -608,6 +653,7 @@ mod test {
         assert!(config.ledger_pruner_config.prune_window >= 50_000_000);
         assert!(config.state_merkle_pruner_config.prune_window >= 100_000);
         assert!(config.epoch_snapshot_pruner_config.prune_window > 50_000_000);
+        assert!(!config.enable_archive_mode);
     }
 
     #[test]
@@ -0,0 +1,197 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    block::Block,
+    common::{Author, Round},
+    vote::Vote,
+};
+use anyhow::{ensure, format_err, Context};
+use aptos_crypto::hash::CryptoHash;
+use aptos_types::{
+    equivocation::{EquivocationEvidence, EquivocationKind},
+    validator_txn::ValidatorTransaction,
+    validator_verifier::ValidatorVerifier,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Display, Formatter};
+
+/// Two conflicting consensus messages signed by the same validator for the same round.
+#[derive(Clone, Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub enum Equivocation {
+    /// Two votes for different ledger infos.
+    Votes(Box<Vote>, Box<Vote>),
+    /// Two proposals of different blocks.
+    Proposals(Box<Block>, Box<Block>),
+}
+
+impl Display for Equivocation {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "[{:?} equivocation of {} at epoch {} round {}]",
+            self.kind(),
+            self.author(),
+            self.epoch(),
+            self.round()
+        )
+    }
+}
+
+impl Equivocation {
+    /// Returns the evidence of the equivocation of the author of the two votes, if they conflict.
+    pub fn from_votes(first: Vote, second: Vote) -> anyhow::Result<Self> {
+        let equivocation = Self::Votes(Box::new(first), Box::new(second));
+        equivocation.verify_conflict()?;
+        Ok(equivocation)
+    }
+
+    /// Returns the evidence of the equivocation of the author of the two proposals, if they
+    /// conflict.
+    pub fn from_proposals(first: Block, second: Block) -> anyhow::Result<Self> {
+        let equivocation = Self::Proposals(Box::new(first), Box::new(second));
+        equivocation.verify_conflict()?;
+        Ok(equivocation)
+    }
+
+    pub fn kind(&self) -> EquivocationKind {
+        match self {
+            Equivocation::Votes(..) => EquivocationKind::Vote,
+            Equivocation::Proposals(..) => EquivocationKind::Proposal,
+        }
+    }
+
+    pub fn author(&self) -> Author {
+        match self {
+            Equivocation::Votes(first, _) => first.author(),
+            // Proposals without author are rejected when the evidence is created.
+            Equivocation::Proposals(first, _) => first.author().unwrap_or(Author::ZERO),
+        }
+    }
+
+    pub fn epoch(&self) -> u64 {
+        match self {
+            Equivocation::Votes(first, _) => first.epoch(),
+            Equivocation::Proposals(first, _) => first.epoch(),
+        }
+    }
+
+    pub fn round(&self) -> Round {
+        match self {
+            Equivocation::Votes(first, _) => first.vote_data().proposed().round(),
+            Equivocation::Proposals(first, _) => first.round(),
+        }
+    }
+
+    /// Verifies that the two messages conflict and that both are signed by their author.
+    pub fn verify(&self, validator: &ValidatorVerifier) -> anyhow::Result<()> {
+        self.verify_conflict()?;
+        match self {
+            Equivocation::Votes(first, second) => {
+                for vote in [first, second] {
+                    ensure!(
+                        vote.ledger_info().consensus_data_hash() == vote.vote_data().hash(),
+                        "Vote's hash mismatch with LedgerInfo"
+                    );
+                    validator
+                        .verify(vote.author(), vote.ledger_info(), vote.signature())
+                        .context("Failed to verify Vote")?;
+                }
+            },
+            Equivocation::Proposals(first, second) => {
+                for block in [first, second] {
+                    block.validate_signature(validator)?;
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Ensures the two messages are from the same author for the same round, but differ.
+    fn verify_conflict(&self) -> anyhow::Result<()> {
+        match self {
+            Equivocation::Votes(first, second) => {
+                ensure!(
+                    first.author() == second.author(),
+                    "Votes from different authors"
+                );
+                ensure!(
+                    (first.epoch(), first.vote_data().proposed().round())
+                        == (second.epoch(), second.vote_data().proposed().round()),
+                    "Votes for different rounds"
+                );
+                ensure!(
+                    first.ledger_info().hash() != second.ledger_info().hash(),
+                    "Votes for the same ledger info"
+                );
+            },
+            Equivocation::Proposals(first, second) => {
+                let author = first
+                    .author()
+                    .ok_or_else(|| format_err!("Proposal without author"))?;
+                ensure!(
+                    second.author() == Some(author),
+                    "Proposals from different authors"
+                );
+                ensure!(
+                    (first.epoch(), first.round()) == (second.epoch(), second.round()),
+                    "Proposals for different rounds"
+                );
+                ensure!(first.id() != second.id(), "Proposals of the same block");
+            },
+        }
+        Ok(())
+    }
+
+    /// Packages the evidence to be proposed as a validator transaction.
+    pub fn into_validator_txn(self) -> anyhow::Result<ValidatorTransaction> {
+        Ok(ValidatorTransaction::EquivocationEvidence(
+            EquivocationEvidence::try_from(self)?,
+        ))
+    }
+}
+
+impl TryFrom<Equivocation> for EquivocationEvidence {
+    type Error = anyhow::Error;
+
+    fn try_from(equivocation: Equivocation) -> anyhow::Result<Self> {
+        let (first_message, second_message) = match &equivocation {
+            Equivocation::Votes(first, second) => (bcs::to_bytes(first)?, bcs::to_bytes(second)?),
+            Equivocation::Proposals(first, second) => {
+                (bcs::to_bytes(first)?, bcs::to_bytes(second)?)
+            },
+        };
+        Ok(Self {
+            author: equivocation.author(),
+            epoch: equivocation.epoch(),
+            round: equivocation.round(),
+            kind: equivocation.kind(),
+            first_message,
+            second_message,
+        })
+    }
+}
+
+impl TryFrom<&EquivocationEvidence> for Equivocation {
+    type Error = anyhow::Error;
+
+    fn try_from(evidence: &EquivocationEvidence) -> anyhow::Result<Self> {
+        let equivocation = match evidence.kind {
+            EquivocationKind::Vote => Self::from_votes(
+                bcs::from_bytes(&evidence.first_message)?,
+                bcs::from_bytes(&evidence.second_message)?,
+            )?,
+            EquivocationKind::Proposal => Self::from_proposals(
+                bcs::from_bytes(&evidence.first_message)?,
+                bcs::from_bytes(&evidence.second_message)?,
+            )?,
+        };
+        ensure!(
+            equivocation.author() == evidence.author
+                && equivocation.epoch() == evidence.epoch
+                && equivocation.round() == evidence.round,
+            "Equivocation evidence doesn't match its messages"
+        );
+        Ok(equivocation)
+    }
+}
@@ -61,6 +61,8 @@ pub enum Error {
     InvalidTimeout(String),
     #[error("Incorrect 1-chain Quorum Certificate provided for signing order votes. Quorum Certificate: {0}, block id: {1}")]
     InvalidOneChainQuorumCertificate(HashValue, HashValue),
+    #[error("SafetyRules service did not answer within {0} ms")]
+    RequestTimeout(u64),
 }
 
 impl From<serde_json::Error> for Error {
@@ -4,11 +4,16 @@
 
 use crate::{
     persistent_safety_storage::PersistentSafetyStorage,
-    remote_service::{self, RemoteService},
+    remote_service::{self, RemoteService, SERVICE_NAME},
     safety_rules_manager,
 };
-use aptos_config::config::{SafetyRulesConfig, SafetyRulesService};
-use std::net::SocketAddr;
+use aptos_config::config::{RemoteServiceAuthentication, SafetyRulesConfig, SafetyRulesService};
+use aptos_crypto::x25519;
+use aptos_secure_net::{
+    noise::{NoiseClientConfig, NoiseServerConfig},
+    NetworkClient, NetworkServer,
+};
+use std::{net::SocketAddr, time::Duration};
 
 pub struct Process {
     data: Option<ProcessData>,
@@ -29,13 +34,40 @@ impl Process {
                 server_addr,
                 storage,
                 network_timeout: config.network_timeout_ms,
+                authentication: service.authentication.clone(),
             }),
         }
     }
 
     pub fn start(&mut self) {
         let data = self.data.take().expect("Unable to retrieve ProcessData");
-        remote_service::execute(data.storage, data.server_addr, data.network_timeout);
+        let network_server = match &data.authentication {
+            Some(authentication) => {
+                let identity_key = identity_key(authentication);
+                assert_eq!(
+                    identity_key.public_key(),
+                    authentication.server_public_key,
+                    "The identity key does not match the server public key"
+                );
+                let noise = NoiseServerConfig::new(
+                    identity_key,
+                    authentication.client_public_keys.clone(),
+                    Duration::from_millis(authentication.client_lease_ms),
+                );
+                NetworkServer::new_authenticated(
+                    SERVICE_NAME.to_string(),
+                    data.server_addr,
+                    data.network_timeout,
+                    noise,
+                )
+            },
+            None => NetworkServer::new(
+                SERVICE_NAME.to_string(),
+                data.server_addr,
+                data.network_timeout,
+            ),
+        };
+        remote_service::execute(data.storage, network_server);
     }
 }
 
@@ -44,23 +76,61 @@ struct ProcessData {
     storage: PersistentSafetyStorage,
     // Timeout in Seconds for network operations
     network_timeout: u64,
+    authentication: Option<RemoteServiceAuthentication>,
 }
 
 pub struct ProcessService {
     server_addr: SocketAddr,
     network_timeout_ms: u64,
+    authentication: Option<RemoteServiceAuthentication>,
+    /// Identifies the clients of this consensus instance to the server, which only serves a single
+    /// consensus instance at a time.
+    instance_id: u64,
 }
 
 impl ProcessService {
-    pub fn new(server_addr: SocketAddr, network_timeout: u64) -> Self {
+    pub fn new(
+        server_addr: SocketAddr,
+        network_timeout: u64,
+        authentication: Option<RemoteServiceAuthentication>,
+    ) -> Self {
+        // Fail early on an unusable identity key, rather than on the first client
+        if let Some(authentication) = &authentication {
+            identity_key(authentication);
+        }
         Self {
             server_addr,
             network_timeout_ms: network_timeout,
+            authentication,
+            instance_id: rand::random(),
         }
     }
 }
 
 impl RemoteService for ProcessService {
+    fn network_client(&self) -> NetworkClient {
+        match &self.authentication {
+            Some(authentication) => {
+                let noise = NoiseClientConfig::new(
+                    identity_key(authentication),
+                    authentication.server_public_key,
+                    self.instance_id,
+                );
+                NetworkClient::new_authenticated(
+                    SERVICE_NAME.to_string(),
+                    self.server_addr,
+                    self.network_timeout_ms,
+                    noise,
+                )
+            },
+            None => NetworkClient::new(
+                SERVICE_NAME.to_string(),
+                self.server_addr,
+                self.network_timeout_ms,
+            ),
+        }
+    }
+
     fn server_address(&self) -> SocketAddr {
         self.server_addr
     }
@@ -68,4 +138,19 @@ impl RemoteService for ProcessService {
     fn network_timeout_ms(&self) -> u64 {
         self.network_timeout_ms
     }
+
+    fn request_timeout(&self) -> Option<Duration> {
+        self.authentication
+            .as_ref()
+            .map(|authentication| Duration::from_millis(authentication.request_timeout_ms))
+    }
+}
+
+fn identity_key(authentication: &RemoteServiceAuthentication) -> x25519::PrivateKey {
+    authentication.identity_key().unwrap_or_else(|error| {
+        panic!(
+            "Unable to read the identity key at {:?}: {}",
+            authentication.identity_key_path, error
+        )
+    })
 }
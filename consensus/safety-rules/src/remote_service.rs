@@ -9,34 +9,53 @@ use crate::{
 };
 use aptos_logger::warn;
 use aptos_secure_net::{NetworkClient, NetworkServer};
-use std::net::SocketAddr;
+use std::{
+    net::SocketAddr,
+    thread,
+    time::{Duration, Instant},
+};
+
+pub const SERVICE_NAME: &str = "safety-rules";
+
+/// Delay between two attempts to send a request that has a timeout
+const RETRY_DELAY: Duration = Duration::from_millis(100);
 
 pub trait RemoteService {
     fn client(&self) -> SerializerClient {
-        let network_client = NetworkClient::new(
-            "safety-rules".to_string(),
+        let service = Box::new(RemoteClient::new(
+            self.network_client(),
+            self.request_timeout(),
+        ));
+        SerializerClient::new_client(service)
+    }
+
+    fn network_client(&self) -> NetworkClient {
+        NetworkClient::new(
+            SERVICE_NAME.to_string(),
             self.server_address(),
             self.network_timeout_ms(),
-        );
-        let service = Box::new(RemoteClient::new(network_client));
-        SerializerClient::new_client(service)
+        )
     }
 
     fn server_address(&self) -> SocketAddr;
 
     /// Network Timeout in milliseconds.
     fn network_timeout_ms(&self) -> u64;
+
+    /// Time to wait for the answer to a request, across reconnections. Requests are retried
+    /// forever if not set.
+    fn request_timeout(&self) -> Option<Duration> {
+        None
+    }
 }
 
-pub fn execute(storage: PersistentSafetyStorage, listen_addr: SocketAddr, network_timeout_ms: u64) {
+pub fn execute(storage: PersistentSafetyStorage, mut network_server: NetworkServer) {
     let mut safety_rules = SafetyRules::new(storage);
     if let Err(e) = safety_rules.consensus_state() {
         warn!("Unable to print consensus state: {}", e);
     }
 
     let mut serializer_service = SerializerService::new(safety_rules);
-    let mut network_server =
-        NetworkServer::new("safety-rules".to_string(), listen_addr, network_timeout_ms);
 
     loop {
         if let Err(e) = process_one_message(&mut network_server, &mut serializer_service) {
@@ -57,11 +76,15 @@ fn process_one_message(
 
 struct RemoteClient {
     network_client: NetworkClient,
+    request_timeout: Option<Duration>,
 }
 
 impl RemoteClient {
-    pub fn new(network_client: NetworkClient) -> Self {
-        Self { network_client }
+    pub fn new(network_client: NetworkClient, request_timeout: Option<Duration>) -> Self {
+        Self {
+            network_client,
+            request_timeout,
+        }
     }
 
     fn process_one_message(&mut self, input: &[u8]) -> Result<Vec<u8>, Error> {
@@ -73,11 +96,18 @@ impl RemoteClient {
 impl TSerializerClient for RemoteClient {
     fn request(&mut self, input: SafetyRulesInput) -> Result<Vec<u8>, Error> {
         let input_message = serde_json::to_vec(&input)?;
+        let start = Instant::now();
         loop {
             match self.process_one_message(&input_message) {
                 Err(err) => warn!("Failed to communicate with SafetyRules service: {}", err),
                 Ok(value) => return Ok(value),
             }
+            if let Some(request_timeout) = self.request_timeout {
+                if start.elapsed() >= request_timeout {
+                    return Err(Error::RequestTimeout(request_timeout.as_millis() as u64));
+                }
+                thread::sleep(RETRY_DELAY);
+            }
         }
     }
 }
@@ -11,7 +11,9 @@ use crate::{
     thread::ThreadService,
     SafetyRules, TSafetyRules,
 };
-use aptos_config::config::{InitialSafetyRulesConfig, SafetyRulesConfig, SafetyRulesService};
+use aptos_config::config::{
+    InitialSafetyRulesConfig, RemoteServiceAuthentication, SafetyRulesConfig, SafetyRulesService,
+};
 use aptos_crypto::bls12381::PublicKey;
 use aptos_global_constants::CONSENSUS_KEY;
 use aptos_infallible::RwLock;
@@ -117,7 +119,14 @@ pub struct SafetyRulesManager {
 impl SafetyRulesManager {
     pub fn new(config: &SafetyRulesConfig) -> Self {
         if let SafetyRulesService::Process(conf) = &config.service {
-            return Self::new_process(conf.server_address(), config.network_timeout_ms);
+            return match &conf.authentication {
+                Some(authentication) => Self::new_authenticated_process(
+                    conf.server_address(),
+                    config.network_timeout_ms,
+                    authentication.clone(),
+                ),
+                None => Self::new_process(conf.server_address(), config.network_timeout_ms),
+            };
         }
 
         let storage = storage(config);
@@ -137,7 +146,19 @@ impl SafetyRulesManager {
     }
 
     pub fn new_process(server_addr: SocketAddr, timeout_ms: u64) -> Self {
-        let process_service = ProcessService::new(server_addr, timeout_ms);
+        let process_service = ProcessService::new(server_addr, timeout_ms, None);
+        Self {
+            internal_safety_rules: SafetyRulesWrapper::Process(process_service),
+        }
+    }
+
+    /// Uses a remote SafetyRules server over a mutually authenticated connection
+    pub fn new_authenticated_process(
+        server_addr: SocketAddr,
+        timeout_ms: u64,
+        authentication: RemoteServiceAuthentication,
+    ) -> Self {
+        let process_service = ProcessService::new(server_addr, timeout_ms, Some(authentication));
         Self {
             internal_safety_rules: SafetyRulesWrapper::Process(process_service),
         }
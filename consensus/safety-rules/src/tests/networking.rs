@@ -2,8 +2,21 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{test_utils, SafetyRulesManager};
+use crate::{
+    remote_service::{self, SERVICE_NAME},
+    test_utils, Error, SafetyRulesManager,
+};
+use aptos_config::{config::RemoteServiceAuthentication, utils};
+use aptos_crypto::{x25519, Uniform};
+use aptos_secure_net::{noise::NoiseServerConfig, NetworkServer};
 use aptos_types::validator_signer::ValidatorSigner;
+use rand::rngs::OsRng;
+use std::{
+    io::Write,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    thread,
+    time::Duration,
+};
 
 #[test]
 fn test_reconnect() {
@@ -18,3 +31,66 @@ fn test_reconnect() {
     let state1 = safety_rules_manager.client().consensus_state().unwrap();
     assert_eq!(state0, state1);
 }
+
+#[test]
+fn test_authenticated_process() {
+    let signer = ValidatorSigner::from_int(0);
+    let storage = test_utils::test_storage(&signer);
+    // test value for network timeout, in milliseconds.
+    let network_timeout = 5_000;
+
+    let server_key = x25519::PrivateKey::generate(&mut OsRng);
+    let client_key_bytes: [u8; x25519::PRIVATE_KEY_SIZE] = rand::random();
+    let mut client_key_file = tempfile::NamedTempFile::new().unwrap();
+    client_key_file.write_all(&client_key_bytes).unwrap();
+    let authentication = RemoteServiceAuthentication {
+        identity_key_path: client_key_file.path().to_path_buf(),
+        server_public_key: server_key.public_key(),
+        client_public_keys: [x25519::PrivateKey::from(client_key_bytes).public_key()]
+            .into_iter()
+            .collect(),
+        client_lease_ms: 60_000,
+        request_timeout_ms: 5_000,
+    };
+
+    let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port());
+    let noise = NoiseServerConfig::new(
+        server_key,
+        authentication.client_public_keys.clone(),
+        Duration::from_millis(authentication.client_lease_ms),
+    );
+    let network_server = NetworkServer::new_authenticated(
+        SERVICE_NAME.to_string(),
+        server_addr,
+        network_timeout,
+        noise,
+    );
+    thread::spawn(move || remote_service::execute(storage, network_server));
+
+    // Verify that the clients of a consensus instance can reconnect
+    let safety_rules_manager = SafetyRulesManager::new_authenticated_process(
+        server_addr,
+        network_timeout,
+        authentication.clone(),
+    );
+    let state0 = safety_rules_manager.client().consensus_state().unwrap();
+    let state1 = safety_rules_manager.client().consensus_state().unwrap();
+    assert_eq!(state0, state1);
+
+    // Verify that another consensus instance is refused while the first one holds the lease
+    let other_safety_rules_manager = SafetyRulesManager::new_authenticated_process(
+        server_addr,
+        network_timeout,
+        RemoteServiceAuthentication {
+            request_timeout_ms: 500,
+            ..authentication
+        },
+    );
+    assert_eq!(
+        other_safety_rules_manager
+            .client()
+            .consensus_state()
+            .unwrap_err(),
+        Error::RequestTimeout(500)
+    );
+}
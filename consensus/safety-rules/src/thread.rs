@@ -10,9 +10,10 @@
 
 use crate::{
     persistent_safety_storage::PersistentSafetyStorage,
-    remote_service::{self, RemoteService},
+    remote_service::{self, RemoteService, SERVICE_NAME},
 };
 use aptos_config::utils;
+use aptos_secure_net::NetworkServer;
 use std::{
     net::{IpAddr, Ipv4Addr, SocketAddr},
     thread::{self, JoinHandle},
@@ -32,7 +33,8 @@ impl ThreadService {
         let listen_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), listen_port);
         let server_addr = listen_addr;
 
-        let child = thread::spawn(move || remote_service::execute(storage, listen_addr, timeout));
+        let network_server = NetworkServer::new(SERVICE_NAME.to_string(), listen_addr, timeout);
+        let child = thread::spawn(move || remote_service::execute(storage, network_server));
 
         Self {
             _child: child,
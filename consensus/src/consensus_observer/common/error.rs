@@ -33,6 +33,9 @@ pub enum Error {
     #[error("Subscription timeout: {0}")]
     SubscriptionTimeout(String),
 
+    #[error("Subscription unreliable: {0}")]
+    SubscriptionUnreliable(String),
+
     #[error("Unexpected error encountered: {0}")]
     UnexpectedError(String),
 }
@@ -50,6 +53,7 @@ impl Error {
             Self::SubscriptionsReset(_) => "subscriptions_reset",
             Self::SubscriptionSuboptimal(_) => "subscription_suboptimal",
             Self::SubscriptionTimeout(_) => "subscription_timeout",
+            Self::SubscriptionUnreliable(_) => "subscription_unreliable",
             Self::UnexpectedError(_) => "unexpected_error",
         }
     }
@@ -63,6 +63,16 @@ pub static OBSERVER_REJECTED_MESSAGES: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Histogram for tracking the delivery lags of publishers behind the first publisher to deliver each message
+pub static OBSERVER_MESSAGE_DELIVERY_LAGS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "consensus_observer_message_delivery_lags",
+        "Histogram related to the delivery lags (in seconds) of the publishers for the consensus observer",
+        &["message_type", "network_id"]
+    )
+    .unwrap()
+});
+
 /// Gauge for tracking the number of active subscriptions for the consensus observer
 pub static OBSERVER_NUM_ACTIVE_SUBSCRIPTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
@@ -73,6 +83,14 @@ pub static OBSERVER_NUM_ACTIVE_SUBSCRIPTIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking the number of times the primary subscription changed for the consensus observer
+pub static OBSERVER_PRIMARY_SUBSCRIPTION_CHANGES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "consensus_observer_primary_subscription_changes",
+        "Counter for tracking the number of times the primary subscription changed for the consensus observer",
+    ).unwrap()
+});
+
 /// Gauge for tracking the number of processed blocks by the consensus observer
 pub static OBSERVER_NUM_PROCESSED_BLOCKS: Lazy<IntGaugeVec> = Lazy::new(|| {
     register_int_gauge_vec!(
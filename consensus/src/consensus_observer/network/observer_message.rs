@@ -140,6 +140,22 @@ impl ConsensusObserverDirectSend {
             ConsensusObserverDirectSend::BlockPayload(_) => "block_payload",
         }
     }
+
+    /// Returns the epoch and round of the block identified by the direct send
+    pub fn get_epoch_and_round(&self) -> (u64, Round) {
+        match self {
+            ConsensusObserverDirectSend::OrderedBlock(ordered_block) => {
+                let proof_block_info = ordered_block.proof_block_info();
+                (proof_block_info.epoch(), proof_block_info.round())
+            },
+            ConsensusObserverDirectSend::CommitDecision(commit_decision) => {
+                (commit_decision.epoch(), commit_decision.round())
+            },
+            ConsensusObserverDirectSend::BlockPayload(block_payload) => {
+                (block_payload.epoch(), block_payload.round())
+            },
+        }
+    }
 }
 
 impl Display for ConsensusObserverDirectSend {
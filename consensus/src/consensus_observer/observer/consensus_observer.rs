@@ -394,6 +394,8 @@ impl ConsensusObserver {
                     error
                 ))
             );
+            self.subscription_manager
+                .record_invalid_message(peer_network_id);
             return;
         }
 
@@ -408,9 +410,19 @@ impl ConsensusObserver {
                         block_payload.block(), error
                     ))
                 );
+                self.subscription_manager
+                    .record_invalid_message(peer_network_id);
                 return;
             }
 
+            // Mark the payload as verified (to drop the duplicates from other publishers)
+            self.subscription_manager.record_verified_message(
+                peer_network_id,
+                metrics::BLOCK_PAYLOAD_LABEL,
+                block_epoch,
+                block_round,
+            );
+
             true // We have successfully verified the signatures
         } else {
             false // We can't verify the signatures yet
@@ -462,9 +474,19 @@ impl ConsensusObserver {
                         error
                     ))
                 );
+                self.subscription_manager
+                    .record_invalid_message(peer_network_id);
                 return;
             }
 
+            // Mark the commit decision as verified (to drop the duplicates from other publishers)
+            self.subscription_manager.record_verified_message(
+                peer_network_id,
+                metrics::COMMIT_DECISION_LABEL,
+                commit_epoch,
+                commit_round,
+            );
+
             // Update the pending blocks with the commit decision
             if self.process_commit_decision_for_pending_block(&commit_decision) {
                 return; // The commit decision was successfully processed
@@ -585,6 +607,23 @@ impl ConsensusObserver {
             &peer_network_id,
         );
 
+        // Record the message delivery (to score the publisher), and drop the
+        // message if it duplicates a message already verified from another publisher.
+        let (epoch, round) = message.get_epoch_and_round();
+        if self.subscription_manager.record_message_delivery(
+            peer_network_id,
+            message.get_label(),
+            epoch,
+            round,
+        ) {
+            metrics::increment_counter(
+                &metrics::OBSERVER_DROPPED_MESSAGES,
+                message.get_label(),
+                &peer_network_id,
+            );
+            return;
+        }
+
         // Process the message based on the type
         match message {
             ConsensusObserverDirectSend::OrderedBlock(ordered_block) => {
@@ -619,6 +658,8 @@ impl ConsensusObserver {
                     error
                 ))
             );
+            self.subscription_manager
+                .record_invalid_message(peer_network_id);
             return;
         };
 
@@ -672,6 +713,15 @@ impl ConsensusObserver {
                 );
                 return;
             }
+
+            // Mark the ordered block as verified (to drop the duplicates from other publishers)
+            let proof_block_info = ordered_block.proof_block_info();
+            self.subscription_manager.record_verified_message(
+                peer_network_id,
+                metrics::ORDERED_BLOCK_LABEL,
+                proof_block_info.epoch(),
+                proof_block_info.round(),
+            );
         } else {
             // Drop the block and log an error (the block should always be for the current epoch)
             error!(
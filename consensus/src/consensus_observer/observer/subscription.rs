@@ -12,6 +12,67 @@ use std::{
     time::{Duration, Instant},
 };
 
+// The weight of the latest delivery lag in the moving average of the publisher delivery lag
+const DELIVERY_LAG_SMOOTHING_FACTOR: f64 = 0.1;
+
+/// The score of a subscription publisher, based on the latency
+/// and correctness of the messages it delivers.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PublisherScore {
+    // The moving average of the delay (in milliseconds) behind the first verified
+    // delivery of each message (if the publisher delivered any message yet).
+    average_delivery_lag_ms: Option<f64>,
+
+    // The number of invalid messages sent by the publisher
+    num_invalid_messages: u64,
+}
+
+impl PublisherScore {
+    /// Returns the average delivery lag (in milliseconds) of the publisher
+    pub fn get_average_delivery_lag_ms(&self) -> Option<f64> {
+        self.average_delivery_lag_ms
+    }
+
+    /// Returns the number of invalid messages sent by the publisher
+    pub fn get_num_invalid_messages(&self) -> u64 {
+        self.num_invalid_messages
+    }
+
+    /// Returns true iff the publisher is more reliable than the other publisher,
+    /// i.e., it sent fewer invalid messages, or it delivered messages faster.
+    pub fn is_better_than(&self, other: &PublisherScore) -> bool {
+        if self.num_invalid_messages != other.num_invalid_messages {
+            return self.num_invalid_messages < other.num_invalid_messages;
+        }
+
+        match (self.average_delivery_lag_ms, other.average_delivery_lag_ms) {
+            (Some(delivery_lag_ms), Some(other_delivery_lag_ms)) => {
+                delivery_lag_ms < other_delivery_lag_ms
+            },
+            (Some(_), None) => true, // Publishers that delivered messages are preferred
+            _ => false,
+        }
+    }
+
+    /// Updates the average delivery lag with the lag of the latest delivery
+    fn record_delivery_lag(&mut self, delivery_lag: Duration) {
+        let delivery_lag_ms = delivery_lag.as_secs_f64() * 1000.0;
+        let average_delivery_lag_ms = match self.average_delivery_lag_ms {
+            Some(average_delivery_lag_ms) => {
+                average_delivery_lag_ms * (1.0 - DELIVERY_LAG_SMOOTHING_FACTOR)
+                    + delivery_lag_ms * DELIVERY_LAG_SMOOTHING_FACTOR
+            },
+            None => delivery_lag_ms,
+        };
+        self.average_delivery_lag_ms = Some(average_delivery_lag_ms);
+    }
+
+    /// Increments the number of invalid messages sent by the publisher
+    fn record_invalid_message(&mut self) {
+        self.num_invalid_messages += 1;
+    }
+}
+
 /// A single consensus observer subscription
 pub struct ConsensusObserverSubscription {
     // The configuration of the consensus observer
@@ -32,6 +93,9 @@ pub struct ConsensusObserverSubscription {
     // The highest synced version we've seen from storage, along with the time at which it was seen
     highest_synced_version_and_time: (u64, Instant),
 
+    // The score of the subscription publisher
+    publisher_score: PublisherScore,
+
     // The time service (used to check the last message receive time)
     time_service: TimeService,
 }
@@ -54,15 +118,19 @@ impl ConsensusObserverSubscription {
             last_message_receive_time: time_now,
             last_optimality_check_time_and_peers: (time_now, HashSet::new()),
             highest_synced_version_and_time: (0, time_now),
+            publisher_score: PublisherScore::default(),
             time_service,
         }
     }
 
     /// Checks if the subscription is still healthy. If not, an error
     /// is returned indicating the reason for the subscription failure.
+    /// Syncing progress is only checked for the primary subscription, as
+    /// other subscriptions can take over if the primary publisher stalls.
     pub fn check_subscription_health(
         &mut self,
         connected_peers_and_metadata: &HashMap<PeerNetworkId, PeerMetadata>,
+        is_primary: bool,
     ) -> Result<(), Error> {
         // Verify the subscription peer is still connected
         let peer_network_id = self.get_peer_network_id();
@@ -76,8 +144,13 @@ impl ConsensusObserverSubscription {
         // Verify the subscription has not timed out
         self.check_subscription_timeout()?;
 
+        // Verify that the publisher is still reliable
+        self.check_publisher_reliability(is_primary)?;
+
         // Verify that the DB is continuing to sync and commit new data
-        self.check_syncing_progress()?;
+        if is_primary {
+            self.check_syncing_progress()?;
+        }
 
         // Verify that the subscription peer is still optimal
         self.check_subscription_peer_optimality(connected_peers_and_metadata)?;
@@ -146,6 +219,36 @@ impl ConsensusObserverSubscription {
         Ok(())
     }
 
+    /// Verifies that the publisher has not sent too many invalid messages, and
+    /// (if it is not the primary) that it is not lagging too far behind.
+    fn check_publisher_reliability(&self, is_primary: bool) -> Result<(), Error> {
+        // Check if the publisher has sent too many invalid messages
+        let num_invalid_messages = self.publisher_score.get_num_invalid_messages();
+        let max_invalid_messages = self
+            .consensus_observer_config
+            .max_publisher_invalid_messages;
+        if num_invalid_messages > max_invalid_messages {
+            return Err(Error::SubscriptionUnreliable(format!(
+                "Subscription to peer: {} sent too many invalid messages: {}",
+                self.peer_network_id, num_invalid_messages
+            )));
+        }
+
+        // Check if the publisher is lagging too far behind the others
+        if let Some(average_delivery_lag_ms) = self.publisher_score.get_average_delivery_lag_ms() {
+            let max_delivery_lag_ms =
+                self.consensus_observer_config.max_publisher_delivery_lag_ms as f64;
+            if !is_primary && average_delivery_lag_ms > max_delivery_lag_ms {
+                return Err(Error::SubscriptionSuboptimal(format!(
+                    "Subscription to peer: {} is lagging behind! Average delivery lag: {:.0} ms",
+                    self.peer_network_id, average_delivery_lag_ms
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
     /// Verifies that the subscription has not timed out based
     /// on the last received message time.
     fn check_subscription_timeout(&self) -> Result<(), Error> {
@@ -211,6 +314,27 @@ impl ConsensusObserverSubscription {
         self.peer_network_id
     }
 
+    /// Returns the score of the subscription publisher
+    pub fn get_publisher_score(&self) -> PublisherScore {
+        self.publisher_score
+    }
+
+    /// Records the delay of a message delivery behind the first verified delivery
+    pub fn record_delivery_lag(&mut self, delivery_lag: Duration) {
+        self.publisher_score.record_delivery_lag(delivery_lag);
+    }
+
+    /// Records an invalid message sent by the publisher
+    pub fn record_invalid_message(&mut self) {
+        self.publisher_score.record_invalid_message();
+    }
+
+    /// Resets the time at which the highest synced version was seen to the current
+    /// time (e.g., when the subscription becomes the primary subscription).
+    pub fn reset_syncing_progress_time(&mut self) {
+        self.highest_synced_version_and_time.1 = self.time_service.now();
+    }
+
     /// Updates the last message receive time to the current time
     pub fn update_last_message_receive_time(&mut self) {
         self.last_message_receive_time = self.time_service.now();
@@ -261,7 +385,7 @@ mod test {
 
         // Verify that the subscription is unhealthy (the peer is not connected)
         assert_matches!(
-            subscription.check_subscription_health(&HashMap::new()),
+            subscription.check_subscription_health(&HashMap::new(), true),
             Err(Error::SubscriptionDisconnected(_))
         );
 
@@ -277,7 +401,7 @@ mod test {
 
         // Verify that the subscription has timed out
         assert_matches!(
-            subscription.check_subscription_health(&peers_and_metadata),
+            subscription.check_subscription_health(&peers_and_metadata, true),
             Err(Error::SubscriptionTimeout(_))
         );
     }
@@ -376,7 +500,7 @@ mod test {
 
         // Verify that the subscription is healthy
         assert!(subscription
-            .check_subscription_health(&peers_and_metadata)
+            .check_subscription_health(&peers_and_metadata, true)
             .is_ok());
 
         // Add a more optimal peer to the set of peers
@@ -391,11 +515,61 @@ mod test {
 
         // Verify that the subscription is no longer optimal
         assert_matches!(
-            subscription.check_subscription_health(&peers_and_metadata),
+            subscription.check_subscription_health(&peers_and_metadata, true),
             Err(Error::SubscriptionSuboptimal(_))
         );
     }
 
+    #[test]
+    fn test_check_subscription_health_reliability() {
+        // Create a consensus observer config with a large timeout
+        let consensus_observer_config = ConsensusObserverConfig {
+            max_subscription_sync_timeout_ms: 100_000_000, // Use a large value so that we don't get DB progress errors
+            ..ConsensusObserverConfig::default()
+        };
+
+        // Create a new observer subscription
+        let peer_network_id = PeerNetworkId::random();
+        let mut subscription = ConsensusObserverSubscription::new(
+            consensus_observer_config,
+            Arc::new(MockDatabaseReader::new()),
+            peer_network_id,
+            TimeService::mock(),
+        );
+
+        // Create a peers and metadata map for the subscription
+        let mut peers_and_metadata = HashMap::new();
+        add_metadata_for_peer(&mut peers_and_metadata, peer_network_id, true, false);
+
+        // Record a delivery lag that exceeds the maximum lag
+        let max_delivery_lag_ms = consensus_observer_config.max_publisher_delivery_lag_ms;
+        subscription.record_delivery_lag(Duration::from_millis(max_delivery_lag_ms + 1));
+
+        // Verify that the lag is only tolerated for the primary subscription
+        assert!(subscription
+            .check_subscription_health(&peers_and_metadata, true)
+            .is_ok());
+        assert_matches!(
+            subscription.check_subscription_health(&peers_and_metadata, false),
+            Err(Error::SubscriptionSuboptimal(_))
+        );
+
+        // Record the maximum number of invalid messages and verify the subscription is healthy
+        for _ in 0..consensus_observer_config.max_publisher_invalid_messages {
+            subscription.record_invalid_message();
+        }
+        assert!(subscription
+            .check_subscription_health(&peers_and_metadata, true)
+            .is_ok());
+
+        // Record another invalid message and verify the subscription is unreliable
+        subscription.record_invalid_message();
+        assert_matches!(
+            subscription.check_subscription_health(&peers_and_metadata, true),
+            Err(Error::SubscriptionUnreliable(_))
+        );
+    }
+
     #[test]
     fn test_check_subscription_peer_optimality_single() {
         // Create a consensus observer config with a maximum of 1 subscription
@@ -777,6 +951,34 @@ mod test {
         assert_eq!(subscription.get_peer_network_id(), peer_network_id);
     }
 
+    #[test]
+    fn test_publisher_score() {
+        // Create two publisher scores and verify that neither is better
+        let mut publisher_score_1 = PublisherScore::default();
+        let mut publisher_score_2 = PublisherScore::default();
+        assert!(!publisher_score_1.is_better_than(&publisher_score_2));
+        assert!(!publisher_score_2.is_better_than(&publisher_score_1));
+
+        // Record a delivery for the first publisher and verify it is better
+        publisher_score_1.record_delivery_lag(Duration::from_millis(100));
+        assert_eq!(publisher_score_1.get_average_delivery_lag_ms(), Some(100.0));
+        assert!(publisher_score_1.is_better_than(&publisher_score_2));
+
+        // Record a faster delivery for the second publisher and verify it is better
+        publisher_score_2.record_delivery_lag(Duration::from_millis(0));
+        assert!(publisher_score_2.is_better_than(&publisher_score_1));
+
+        // Record a slow delivery for the second publisher and verify the average is smoothed
+        publisher_score_2.record_delivery_lag(Duration::from_millis(500));
+        assert_eq!(publisher_score_2.get_average_delivery_lag_ms(), Some(50.0));
+        assert!(publisher_score_2.is_better_than(&publisher_score_1));
+
+        // Record an invalid message for the second publisher and verify the first is better
+        publisher_score_2.record_invalid_message();
+        assert_eq!(publisher_score_2.get_num_invalid_messages(), 1);
+        assert!(publisher_score_1.is_better_than(&publisher_score_2));
+    }
+
     #[test]
     fn test_update_last_message_receive_time() {
         // Create a new observer subscription
@@ -17,15 +17,35 @@ use crate::consensus_observer::{
     publisher::consensus_publisher::ConsensusPublisher,
 };
 use aptos_config::{config::ConsensusObserverConfig, network_id::PeerNetworkId};
+use aptos_consensus_types::common::Round;
 use aptos_infallible::Mutex;
 use aptos_logger::{info, warn};
 use aptos_network::application::{interface::NetworkClient, metadata::PeerMetadata};
 use aptos_storage_interface::DbReader;
-use aptos_time_service::TimeService;
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use itertools::Itertools;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::task::JoinHandle;
 
+/// The deliveries of a message (identified by its
+/// type, epoch and round) across all subscriptions.
+struct MessageDelivery {
+    // The time at which the message was first delivered
+    first_delivery_time: Instant,
+
+    // The delivery time of the first copy of the message that was verified
+    first_verified_delivery_time: Option<Instant>,
+
+    // The deliveries (peers and times) made before the message was verified.
+    // These are only scored once a copy is verified, so that a publisher
+    // sending invalid messages early can't make the others look slow.
+    unscored_deliveries: Vec<(PeerNetworkId, Instant)>,
+}
+
 /// The manager for consensus observer subscriptions
 pub struct SubscriptionManager {
     // The currently active set of consensus observer subscriptions
@@ -48,6 +68,13 @@ pub struct SubscriptionManager {
     // A handle to storage (used to read the latest state and check progress)
     db_reader: Arc<dyn DbReader>,
 
+    // The first deliveries of the recent messages (used to de-duplicate
+    // messages across subscriptions and score the publishers).
+    message_deliveries: BTreeMap<(u64, Round, &'static str), MessageDelivery>,
+
+    // The primary subscription, i.e., the one with the best publisher score
+    primary_subscription_peer: Option<PeerNetworkId>,
+
     // The time service (used to check progress)
     time_service: TimeService,
 }
@@ -69,6 +96,8 @@ impl SubscriptionManager {
             consensus_observer_config,
             consensus_publisher,
             db_reader,
+            message_deliveries: BTreeMap::new(),
+            primary_subscription_peer: None,
             time_service,
         }
     }
@@ -80,15 +109,21 @@ impl SubscriptionManager {
         connected_peers_and_metadata: &HashMap<PeerNetworkId, PeerMetadata>,
         peer_network_id: PeerNetworkId,
     ) -> Result<(), Error> {
+        // Determine if the subscription is the primary subscription. Note: the primary
+        // is only switched between health checks, to give the new primary time to sync.
+        let primary_subscription_peer = self
+            .primary_subscription_peer
+            .or_else(|| self.get_primary_subscription_peer());
+        let is_primary = primary_subscription_peer == Some(peer_network_id);
+
         // Get the active subscription for the peer
         let mut active_observer_subscriptions = self.active_observer_subscriptions.lock();
         let active_subscription = active_observer_subscriptions.get_mut(&peer_network_id);
 
         // Check the health of the subscription
         match active_subscription {
-            Some(active_subscription) => {
-                active_subscription.check_subscription_health(connected_peers_and_metadata)
-            },
+            Some(active_subscription) => active_subscription
+                .check_subscription_health(connected_peers_and_metadata, is_primary),
             None => Err(Error::UnexpectedError(format!(
                 "The subscription to peer: {:?} is not active!",
                 peer_network_id
@@ -104,10 +139,18 @@ impl SubscriptionManager {
         let initial_subscription_peers = self.get_active_subscription_peers();
         let connected_peers_and_metadata = self.get_connected_peers_and_metadata();
 
-        // Terminate any unhealthy subscriptions
+        // Remove the message deliveries that are too old to score the publishers
+        self.garbage_collect_message_deliveries();
+
+        // Update the primary subscription, and terminate any unhealthy subscriptions
+        self.update_primary_subscription();
         let terminated_subscriptions =
             self.terminate_unhealthy_subscriptions(&connected_peers_and_metadata);
 
+        // Switch the primary subscription (if it was terminated) without resetting
+        // the block state, as long as other subscriptions remain healthy.
+        self.update_primary_subscription();
+
         // Check if all subscriptions were terminated
         let num_terminated_subscriptions = terminated_subscriptions.len();
         let all_subscriptions_terminated = num_terminated_subscriptions > 0
@@ -143,6 +186,182 @@ impl SubscriptionManager {
         }
     }
 
+    /// Removes the message deliveries that are older than the subscription timeout
+    /// (later deliveries would have timed out the subscriptions anyway).
+    fn garbage_collect_message_deliveries(&mut self) {
+        let time_now = self.time_service.now();
+        let max_delivery_age =
+            Duration::from_millis(self.consensus_observer_config.max_subscription_timeout_ms);
+        self.message_deliveries.retain(|_, message_delivery| {
+            time_now.duration_since(message_delivery.first_delivery_time) <= max_delivery_age
+        });
+    }
+
+    /// Returns the primary subscription peer, i.e., the current primary (if it is still
+    /// active), unless another subscription has a better publisher score.
+    fn get_primary_subscription_peer(&self) -> Option<PeerNetworkId> {
+        let active_observer_subscriptions = self.active_observer_subscriptions.lock();
+        let mut primary_subscription = self
+            .primary_subscription_peer
+            .and_then(|peer_network_id| active_observer_subscriptions.get(&peer_network_id));
+        for subscription in active_observer_subscriptions.values() {
+            let is_better = match primary_subscription {
+                Some(primary_subscription) => subscription
+                    .get_publisher_score()
+                    .is_better_than(&primary_subscription.get_publisher_score()),
+                None => true,
+            };
+            if is_better {
+                primary_subscription = Some(subscription);
+            }
+        }
+
+        primary_subscription.map(|subscription| subscription.get_peer_network_id())
+    }
+
+    /// Updates the primary subscription to the subscription with the best publisher score
+    fn update_primary_subscription(&mut self) {
+        let primary_subscription_peer = self.get_primary_subscription_peer();
+        if primary_subscription_peer != self.primary_subscription_peer {
+            info!(
+                LogSchema::new(LogEntry::ConsensusObserver).message(&format!(
+                    "Switching the primary subscription from peer: {:?} to peer: {:?}!",
+                    self.primary_subscription_peer, primary_subscription_peer
+                ))
+            );
+            metrics::increment_counter_without_labels(
+                &metrics::OBSERVER_PRIMARY_SUBSCRIPTION_CHANGES,
+            );
+            self.primary_subscription_peer = primary_subscription_peer;
+
+            // Give the new primary subscription time to make syncing progress. Note: the
+            // failovers can't delay the fallback to state sync beyond the fallback sync
+            // threshold, as the observer checks its syncing progress independently.
+            if let Some(primary_subscription_peer) = primary_subscription_peer {
+                if let Some(active_subscription) = self
+                    .active_observer_subscriptions
+                    .lock()
+                    .get_mut(&primary_subscription_peer)
+                {
+                    active_subscription.reset_syncing_progress_time();
+                }
+            }
+        }
+    }
+
+    /// Records the delivery of the message (identified by its type, epoch and round) by the
+    /// given subscription peer. If the message was already verified, the publisher is scored
+    /// based on the delay behind the first verified delivery. Otherwise, the delivery is scored
+    /// once a copy of the message is verified. Returns true iff the message is a duplicate
+    /// of an already verified message.
+    pub fn record_message_delivery(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        message_label: &'static str,
+        epoch: u64,
+        round: Round,
+    ) -> bool {
+        // Get the deliveries of the message (or record this one as the first)
+        let time_now = self.time_service.now();
+        let message_delivery = self
+            .message_deliveries
+            .entry((epoch, round, message_label))
+            .or_insert(MessageDelivery {
+                first_delivery_time: time_now,
+                first_verified_delivery_time: None,
+                unscored_deliveries: vec![],
+            });
+
+        // If the message hasn't been verified yet, score the delivery later
+        let Some(first_verified_delivery_time) = message_delivery.first_verified_delivery_time
+        else {
+            message_delivery
+                .unscored_deliveries
+                .push((peer_network_id, time_now));
+            return false;
+        };
+
+        // Otherwise, score the delivery and drop the duplicate
+        let delivery_lag = time_now.duration_since(first_verified_delivery_time);
+        self.record_delivery_lag(peer_network_id, message_label, delivery_lag);
+        true
+    }
+
+    /// Marks the message (identified by its type, epoch and round) delivered by the given
+    /// subscription peer as verified, so that the copies delivered by other subscriptions
+    /// can be dropped. The deliveries made before are scored against the verified delivery.
+    pub fn record_verified_message(
+        &mut self,
+        peer_network_id: PeerNetworkId,
+        message_label: &'static str,
+        epoch: u64,
+        round: Round,
+    ) {
+        // Get the deliveries of the message (if it hasn't been verified yet)
+        let message_key = (epoch, round, message_label);
+        let Some(message_delivery) = self.message_deliveries.get_mut(&message_key) else {
+            return;
+        };
+        if message_delivery.first_verified_delivery_time.is_some() {
+            return;
+        }
+
+        // The verified copy is the latest delivery by the peer
+        let Some(first_verified_delivery_time) = message_delivery
+            .unscored_deliveries
+            .iter()
+            .rev()
+            .find(|(delivery_peer, _)| *delivery_peer == peer_network_id)
+            .map(|(_, delivery_time)| *delivery_time)
+        else {
+            return;
+        };
+        message_delivery.first_verified_delivery_time = Some(first_verified_delivery_time);
+
+        // Score the deliveries made before the message was verified (copies delivered
+        // before the verified copy, e.g., that couldn't be verified yet, have no lag).
+        let unscored_deliveries = std::mem::take(&mut message_delivery.unscored_deliveries);
+        for (delivery_peer, delivery_time) in unscored_deliveries {
+            let delivery_lag =
+                delivery_time.saturating_duration_since(first_verified_delivery_time);
+            self.record_delivery_lag(delivery_peer, message_label, delivery_lag);
+        }
+    }
+
+    /// Scores the publisher of the given subscription peer with the delay
+    /// of a message delivery behind the first verified delivery.
+    fn record_delivery_lag(
+        &self,
+        peer_network_id: PeerNetworkId,
+        message_label: &'static str,
+        delivery_lag: Duration,
+    ) {
+        if let Some(active_subscription) = self
+            .active_observer_subscriptions
+            .lock()
+            .get_mut(&peer_network_id)
+        {
+            active_subscription.record_delivery_lag(delivery_lag);
+        }
+        metrics::observe_value_with_label(
+            &metrics::OBSERVER_MESSAGE_DELIVERY_LAGS,
+            message_label,
+            &peer_network_id,
+            delivery_lag.as_secs_f64(),
+        );
+    }
+
+    /// Records an invalid message sent by the given subscription peer
+    pub fn record_invalid_message(&mut self, peer_network_id: PeerNetworkId) {
+        if let Some(active_subscription) = self
+            .active_observer_subscriptions
+            .lock()
+            .get_mut(&peer_network_id)
+        {
+            active_subscription.record_invalid_message();
+        }
+    }
+
     /// Returns the currently active subscription peers
     fn get_active_subscription_peers(&self) -> Vec<PeerNetworkId> {
         let active_observer_subscriptions = self.active_observer_subscriptions.lock();
@@ -766,6 +985,233 @@ mod test {
         verify_active_subscription_peers(&subscription_manager, vec![]);
     }
 
+    #[tokio::test]
+    async fn test_primary_subscription_failover() {
+        // Create a consensus observer client
+        let network_id = NetworkId::Public;
+        let (peers_and_metadata, consensus_observer_client) =
+            create_consensus_observer_client(&[network_id]);
+
+        // Create a new subscription manager with a short sync timeout
+        let consensus_observer_config = ConsensusObserverConfig {
+            max_subscription_sync_timeout_ms: 1_000,
+            ..ConsensusObserverConfig::default()
+        };
+        let db_reader = create_mock_db_reader();
+        let time_service = TimeService::mock();
+        let mut subscription_manager = SubscriptionManager::new(
+            consensus_observer_client,
+            consensus_observer_config,
+            None,
+            db_reader.clone(),
+            time_service.clone(),
+        );
+
+        // Create two new subscriptions
+        let subscription_peer_1 =
+            create_peer_and_connection(network_id, peers_and_metadata.clone(), 1, None, true);
+        let subscription_peer_2 =
+            create_peer_and_connection(network_id, peers_and_metadata.clone(), 1, None, true);
+        for peer in &[subscription_peer_1, subscription_peer_2] {
+            create_observer_subscription(
+                &mut subscription_manager,
+                consensus_observer_config,
+                db_reader.clone(),
+                *peer,
+                time_service.clone(),
+            );
+        }
+
+        // Select the primary subscription
+        subscription_manager.update_primary_subscription();
+        let primary_peer = subscription_manager.primary_subscription_peer.unwrap();
+        let secondary_peer = if primary_peer == subscription_peer_1 {
+            subscription_peer_2
+        } else {
+            subscription_peer_1
+        };
+
+        // Elapse enough time for the DB to stop making sync progress
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.max_subscription_sync_timeout_ms + 1,
+        ));
+
+        // Verify that only the primary subscription is terminated
+        verify_terminated_unhealthy_subscriptions(&mut subscription_manager, vec![primary_peer]);
+        verify_active_subscription_peers(&subscription_manager, vec![secondary_peer]);
+
+        // Verify that the remaining subscription becomes the primary
+        subscription_manager.update_primary_subscription();
+        assert_eq!(
+            subscription_manager.primary_subscription_peer,
+            Some(secondary_peer)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_primary_subscription_scoring() {
+        // Create a consensus observer client
+        let network_id = NetworkId::Public;
+        let (peers_and_metadata, consensus_observer_client) =
+            create_consensus_observer_client(&[network_id]);
+
+        // Create a new subscription manager
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let db_reader = create_mock_db_reader();
+        let time_service = TimeService::mock();
+        let mut subscription_manager = SubscriptionManager::new(
+            consensus_observer_client,
+            consensus_observer_config,
+            None,
+            db_reader.clone(),
+            time_service.clone(),
+        );
+
+        // Create two new subscriptions
+        let subscription_peer_1 =
+            create_peer_and_connection(network_id, peers_and_metadata.clone(), 1, None, true);
+        let subscription_peer_2 =
+            create_peer_and_connection(network_id, peers_and_metadata.clone(), 1, None, true);
+        for peer in &[subscription_peer_1, subscription_peer_2] {
+            create_observer_subscription(
+                &mut subscription_manager,
+                consensus_observer_config,
+                db_reader.clone(),
+                *peer,
+                time_service.clone(),
+            );
+        }
+
+        // Deliver an (unverified) message from the first peer, and then a verified copy
+        // from the second peer. Verify that neither peer is scored as lagging behind.
+        let mock_time_service = time_service.into_mock();
+        let message_label = metrics::ORDERED_BLOCK_LABEL;
+        for peer in [subscription_peer_1, subscription_peer_2] {
+            let duplicate_message =
+                subscription_manager.record_message_delivery(peer, message_label, 0, 10);
+            assert!(!duplicate_message);
+            mock_time_service.advance(Duration::from_millis(100));
+        }
+        subscription_manager.record_verified_message(subscription_peer_2, message_label, 0, 10);
+        for peer in [subscription_peer_1, subscription_peer_2] {
+            verify_average_delivery_lag_ms(&subscription_manager, peer, Some(0.0));
+        }
+
+        // Deliver a verified message from the first peer, and then from the second peer
+        let duplicate_message =
+            subscription_manager.record_message_delivery(subscription_peer_1, message_label, 0, 11);
+        assert!(!duplicate_message);
+        subscription_manager.record_verified_message(subscription_peer_1, message_label, 0, 11);
+        mock_time_service.advance(Duration::from_millis(100));
+        let duplicate_message =
+            subscription_manager.record_message_delivery(subscription_peer_2, message_label, 0, 11);
+        assert!(duplicate_message);
+
+        // Verify that only the second peer is scored as lagging behind
+        verify_average_delivery_lag_ms(&subscription_manager, subscription_peer_1, Some(0.0));
+        verify_average_delivery_lag_ms(&subscription_manager, subscription_peer_2, Some(10.0));
+
+        // Verify that the first peer (the fastest publisher) is the primary
+        subscription_manager.update_primary_subscription();
+        assert_eq!(
+            subscription_manager.primary_subscription_peer,
+            Some(subscription_peer_1)
+        );
+
+        // Record an invalid message for the first peer and verify the second peer is the primary
+        subscription_manager.record_invalid_message(subscription_peer_1);
+        subscription_manager.update_primary_subscription();
+        assert_eq!(
+            subscription_manager.primary_subscription_peer,
+            Some(subscription_peer_2)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_record_message_delivery() {
+        // Create a consensus observer client
+        let network_id = NetworkId::Public;
+        let (peers_and_metadata, consensus_observer_client) =
+            create_consensus_observer_client(&[network_id]);
+
+        // Create a new subscription manager
+        let consensus_observer_config = ConsensusObserverConfig::default();
+        let db_reader = create_mock_db_reader();
+        let time_service = TimeService::mock();
+        let mut subscription_manager = SubscriptionManager::new(
+            consensus_observer_client,
+            consensus_observer_config,
+            None,
+            db_reader.clone(),
+            time_service.clone(),
+        );
+
+        // Create two new subscriptions
+        let subscription_peer_1 =
+            create_peer_and_connection(network_id, peers_and_metadata.clone(), 1, None, true);
+        let subscription_peer_2 =
+            create_peer_and_connection(network_id, peers_and_metadata.clone(), 1, None, true);
+        for peer in &[subscription_peer_1, subscription_peer_2] {
+            create_observer_subscription(
+                &mut subscription_manager,
+                consensus_observer_config,
+                db_reader.clone(),
+                *peer,
+                time_service.clone(),
+            );
+        }
+
+        // Deliver an unverified message from both peers and verify they are not duplicates
+        let (epoch, round) = (1, 5);
+        let message_label = metrics::COMMIT_DECISION_LABEL;
+        for peer in [subscription_peer_1, subscription_peer_2] {
+            let duplicate_message =
+                subscription_manager.record_message_delivery(peer, message_label, epoch, round);
+            assert!(!duplicate_message);
+        }
+
+        // Verify the message and check that further deliveries are duplicates
+        subscription_manager.record_verified_message(
+            subscription_peer_1,
+            message_label,
+            epoch,
+            round,
+        );
+        let duplicate_message = subscription_manager.record_message_delivery(
+            subscription_peer_2,
+            message_label,
+            epoch,
+            round,
+        );
+        assert!(duplicate_message);
+
+        // Verify that other messages for the same round are not duplicates
+        let duplicate_message = subscription_manager.record_message_delivery(
+            subscription_peer_2,
+            metrics::BLOCK_PAYLOAD_LABEL,
+            epoch,
+            round,
+        );
+        assert!(!duplicate_message);
+
+        // Elapse enough time to garbage collect the message deliveries
+        let mock_time_service = time_service.into_mock();
+        mock_time_service.advance(Duration::from_millis(
+            consensus_observer_config.max_subscription_timeout_ms + 1,
+        ));
+        subscription_manager.garbage_collect_message_deliveries();
+
+        // Verify that the message is no longer considered a duplicate
+        let duplicate_message = subscription_manager.record_message_delivery(
+            subscription_peer_1,
+            message_label,
+            epoch,
+            round,
+        );
+        assert!(!duplicate_message);
+    }
+
     #[tokio::test]
     #[allow(clippy::await_holding_lock)] // Required to wait on the subscription creation task
     async fn test_spawn_subscription_creation_task() {
@@ -1295,6 +1741,24 @@ mod test {
         assert_eq!(active_peers.len(), expected_active_peers.len());
     }
 
+    /// Verifies the average delivery lag of the publisher of the given subscription peer
+    fn verify_average_delivery_lag_ms(
+        subscription_manager: &SubscriptionManager,
+        peer_network_id: PeerNetworkId,
+        expected_average_delivery_lag_ms: Option<f64>,
+    ) {
+        let publisher_score = subscription_manager
+            .active_observer_subscriptions
+            .lock()
+            .get(&peer_network_id)
+            .unwrap()
+            .get_publisher_score();
+        assert_eq!(
+            publisher_score.get_average_delivery_lag_ms(),
+            expected_average_delivery_lag_ms
+        );
+    }
+
     /// Verifies the status of the active subscription creation task
     fn verify_subscription_creation_task(
         subscription_manager: &SubscriptionManager,
@@ -13,6 +13,7 @@ use crate::{
     },
     counters,
     epoch_manager::EpochManager,
+    liveness::leader_reputation::LeaderReputationHandle,
     network::NetworkTask,
     network_interface::{ConsensusMsg, ConsensusNetworkClient},
     persistent_liveness_storage::StorageWriteProxy,
@@ -54,7 +55,12 @@ pub fn start_consensus(
     reconfig_events: ReconfigNotificationListener<DbBackedOnChainConfig>,
     vtxn_pool: VTxnPoolState,
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
-) -> (Runtime, Arc<StorageWriteProxy>, Arc<QuorumStoreDB>) {
+) -> (
+    Runtime,
+    Arc<StorageWriteProxy>,
+    Arc<QuorumStoreDB>,
+    LeaderReputationHandle,
+) {
     let runtime = aptos_runtimes::spawn_named_runtime("consensus".into(), None);
     let storage = Arc::new(StorageWriteProxy::new(node_config, aptos_db.reader.clone()));
     let quorum_store_db = Arc::new(QuorumStoreDB::new(node_config.storage.dir()));
@@ -115,6 +121,7 @@ pub fn start_consensus(
         rand_storage,
         consensus_publisher,
     );
+    let leader_reputation = epoch_mgr.leader_reputation();
 
     let (network_task, network_receiver) = NetworkTask::new(network_service_events, self_receiver);
 
@@ -122,7 +129,7 @@ pub fn start_consensus(
     runtime.spawn(epoch_mgr.start(timeout_receiver, network_receiver));
 
     debug!("Consensus started.");
-    (runtime, storage, quorum_store_db)
+    (runtime, storage, quorum_store_db, leader_reputation)
 }
 
 /// A helper function to start the consensus observer
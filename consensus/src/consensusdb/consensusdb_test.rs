@@ -8,10 +8,15 @@ use crate::dag::{CertifiedNode, Extensions, Node, Vote};
 use aptos_consensus_types::{
     block::block_test_utils::certificate_for_genesis,
     common::{Author, Payload},
+    vote::Vote as ConsensusVote,
+    vote_data::VoteData,
 };
-use aptos_crypto::bls12381::Signature;
+use aptos_crypto::{bls12381::Signature, hash::CryptoHash};
 use aptos_temppath::TempPath;
-use aptos_types::aggregate_signature::AggregateSignature;
+use aptos_types::{
+    aggregate_signature::AggregateSignature, block_info::BlockInfo, ledger_info::LedgerInfo,
+    validator_signer::ValidatorSigner,
+};
 use std::{collections::HashMap, hash::Hash};
 
 #[test]
@@ -79,6 +84,36 @@ fn test_delete_block_and_qc() {
     assert_eq!(db.get_all::<QCSchema>().unwrap().len(), 0);
 }
 
+fn equivocation(epoch: u64, signer: &ValidatorSigner) -> Equivocation {
+    let vote = || {
+        let proposed = BlockInfo::new(epoch, 1, HashValue::random(), HashValue::zero(), 0, 0, None);
+        let vote_data = VoteData::new(proposed, BlockInfo::random(0));
+        let ledger_info = LedgerInfo::new(BlockInfo::empty(), vote_data.hash());
+        ConsensusVote::new(vote_data, signer.author(), ledger_info, signer).unwrap()
+    };
+    Equivocation::from_votes(vote(), vote()).unwrap()
+}
+
+#[test]
+fn test_prune_equivocations() {
+    let tmp_dir = TempPath::new();
+    let db = ConsensusDB::new(&tmp_dir);
+    let signer = ValidatorSigner::random(None);
+
+    let equivocations: Vec<_> = (1..=3).map(|epoch| equivocation(epoch, &signer)).collect();
+    for equivocation in &equivocations {
+        db.save_equivocation(equivocation).unwrap();
+    }
+    assert_eq!(db.get_equivocations().unwrap(), equivocations);
+
+    db.prune_equivocations(2).unwrap();
+    assert_eq!(db.get_equivocations().unwrap(), equivocations[1..].to_vec());
+
+    // Nothing to prune
+    db.prune_equivocations(2).unwrap();
+    assert_eq!(db.get_equivocations().unwrap(), equivocations[1..].to_vec());
+}
+
 fn test_dag_type<S: Schema<Key = K>, K: Eq + Hash>(key: S::Key, value: S::Value, db: &ConsensusDB) {
     db.put::<S>(&key, &value).unwrap();
     let mut from_db: HashMap<K, S::Value> = db.get_all::<S>().unwrap().into_iter().collect();
@@ -8,7 +8,7 @@ mod schema;
 
 use crate::error::DbError;
 use anyhow::Result;
-use aptos_consensus_types::{block::Block, quorum_cert::QuorumCert};
+use aptos_consensus_types::{block::Block, equivocation::Equivocation, quorum_cert::QuorumCert};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
 use aptos_schemadb::{schema::Schema, Options, SchemaBatch, DB, DEFAULT_COLUMN_FAMILY_NAME};
@@ -16,12 +16,13 @@ use aptos_storage_interface::AptosDbError;
 pub use schema::{
     block::BlockSchema,
     dag::{CertifiedNodeSchema, DagVoteSchema, NodeSchema},
+    equivocation::EquivocationSchema,
     quorum_certificate::QCSchema,
 };
 use schema::{
     single_entry::{SingleEntryKey, SingleEntrySchema},
-    BLOCK_CF_NAME, CERTIFIED_NODE_CF_NAME, DAG_VOTE_CF_NAME, NODE_CF_NAME, QC_CF_NAME,
-    SINGLE_ENTRY_CF_NAME,
+    BLOCK_CF_NAME, CERTIFIED_NODE_CF_NAME, DAG_VOTE_CF_NAME, EQUIVOCATION_CF_NAME, NODE_CF_NAME,
+    QC_CF_NAME, SINGLE_ENTRY_CF_NAME,
 };
 use std::{iter::Iterator, path::Path, time::Instant};
 
@@ -59,6 +60,7 @@ impl ConsensusDB {
             CERTIFIED_NODE_CF_NAME,
             DAG_VOTE_CF_NAME,
             "ordered_anchor_id", // deprecated CF
+            EQUIVOCATION_CF_NAME,
         ];
 
         let path = db_root_path.as_ref().join(CONSENSUS_DB_NAME);
@@ -186,6 +188,42 @@ impl ConsensusDB {
         Ok(())
     }
 
+    /// Persists the evidence of an equivocation, replacing any evidence of the same equivocation.
+    pub fn save_equivocation(&self, equivocation: &Equivocation) -> Result<(), DbError> {
+        let key = (
+            equivocation.epoch(),
+            equivocation.round(),
+            equivocation.author(),
+            equivocation.kind(),
+        );
+        self.put::<EquivocationSchema>(&key, equivocation)
+    }
+
+    /// Returns the evidence of all the recorded equivocations, ordered by epoch and round.
+    pub fn get_equivocations(&self) -> Result<Vec<Equivocation>, DbError> {
+        Ok(self
+            .get_all::<EquivocationSchema>()?
+            .into_iter()
+            .map(|(_, equivocation)| equivocation)
+            .collect())
+    }
+
+    /// Deletes the evidence of the equivocations of the epochs before `min_epoch`.
+    pub fn prune_equivocations(&self, min_epoch: u64) -> Result<(), DbError> {
+        let mut iter = self.db.iter::<EquivocationSchema>()?;
+        iter.seek_to_first();
+        let batch = SchemaBatch::new();
+        for item in iter {
+            let (key, _) = item?;
+            // Keys start with the big endian epoch, so the evidence is ordered by epoch.
+            if key.0 >= min_epoch {
+                break;
+            }
+            batch.delete::<EquivocationSchema>(&key)?;
+        }
+        self.commit(batch)
+    }
+
     pub fn put<S: Schema>(&self, key: &S::Key, value: &S::Value) -> Result<(), DbError> {
         let batch = SchemaBatch::new();
         batch.put::<S>(key, value)?;
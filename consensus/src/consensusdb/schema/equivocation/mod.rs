@@ -0,0 +1,80 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for the evidence of equivocations by validators.
+//!
+//! Serialized equivocation evidence identified by the epoch, round, author and kind of messages of
+//! the equivocation. The epoch and round are big endian, so that the evidence is ordered by round.
+//! ```text
+//! |<-----------------key---------------->|<----value---->|
+//! | epoch | round | author | message kind |  equivocation |
+//! ```
+
+use super::ensure_slice_len_eq;
+use crate::define_schema;
+use anyhow::{bail, Result};
+use aptos_consensus_types::{
+    common::{Author, Round},
+    equivocation::Equivocation,
+};
+use aptos_schemadb::{
+    schema::{KeyCodec, ValueCodec},
+    ColumnFamilyName,
+};
+use aptos_types::equivocation::EquivocationKind;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use std::mem::size_of;
+
+pub const EQUIVOCATION_CF_NAME: ColumnFamilyName = "equivocation";
+
+pub type EquivocationKey = (u64, Round, Author, EquivocationKind);
+
+define_schema!(
+    EquivocationSchema,
+    EquivocationKey,
+    Equivocation,
+    EQUIVOCATION_CF_NAME
+);
+
+const KEY_LEN: usize = 2 * size_of::<u64>() + Author::LENGTH + size_of::<u8>();
+
+impl KeyCodec<EquivocationSchema> for EquivocationKey {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (epoch, round, author, kind) = self;
+        let mut encoded = Vec::with_capacity(KEY_LEN);
+        encoded.write_u64::<BigEndian>(*epoch)?;
+        encoded.write_u64::<BigEndian>(*round)?;
+        encoded.extend_from_slice(author.as_ref());
+        encoded.write_u8(match kind {
+            EquivocationKind::Vote => 0,
+            EquivocationKind::Proposal => 1,
+        })?;
+        Ok(encoded)
+    }
+
+    fn decode_key(mut data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, KEY_LEN)?;
+        let epoch = data.read_u64::<BigEndian>()?;
+        let round = data.read_u64::<BigEndian>()?;
+        let author = Author::from_bytes(&data[..Author::LENGTH])?;
+        let kind = match data[Author::LENGTH] {
+            0 => EquivocationKind::Vote,
+            1 => EquivocationKind::Proposal,
+            kind => bail!("Unknown equivocation kind {}", kind),
+        };
+        Ok((epoch, round, author, kind))
+    }
+}
+
+impl ValueCodec<EquivocationSchema> for Equivocation {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(bcs::to_bytes(self)?)
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        Ok(bcs::from_bytes(data)?)
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,33 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use aptos_consensus_types::{vote::Vote, vote_data::VoteData};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_schemadb::{schema::fuzzing::assert_encode_decode, test_no_panic_decoding};
+use aptos_types::{
+    block_info::BlockInfo, ledger_info::LedgerInfo, validator_signer::ValidatorSigner,
+};
+
+fn random_vote(signer: &ValidatorSigner) -> Vote {
+    let proposed = BlockInfo::new(1, 1, HashValue::random(), HashValue::zero(), 0, 0, None);
+    let vote_data = VoteData::new(proposed, BlockInfo::random(0));
+    let ledger_info = LedgerInfo::new(BlockInfo::empty(), vote_data.hash());
+    Vote::new(vote_data, signer.author(), ledger_info, signer).unwrap()
+}
+
+#[test]
+fn test_encode_decode() {
+    let signer = ValidatorSigner::random(None);
+    let equivocation =
+        Equivocation::from_votes(random_vote(&signer), random_vote(&signer)).unwrap();
+    let key = (
+        equivocation.epoch(),
+        equivocation.round(),
+        equivocation.author(),
+        equivocation.kind(),
+    );
+    assert_encode_decode::<EquivocationSchema>(&key, &equivocation);
+}
+
+test_no_panic_decoding!(EquivocationSchema);
@@ -126,6 +126,16 @@ pub static PROPOSAL_VOTE_BROADCASTED: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of the equivocations of other validators whose evidence was recorded, by kind of message
+pub static EQUIVOCATIONS_RECORDED: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_consensus_equivocations_recorded",
+        "Count of the equivocations whose evidence was recorded, by kind of message",
+        &["kind"]
+    )
+    .unwrap()
+});
+
 //////////////////////
 // PROPOSAL ELECTION
 //////////////////////
@@ -15,7 +15,7 @@ use crate::{
     liveness::{
         cached_proposer_election::CachedProposerElection,
         leader_reputation::{
-            extract_epoch_to_proposers, AptosDBBackend, LeaderReputation,
+            extract_epoch_to_proposers, AptosDBBackend, LeaderReputation, LeaderReputationHandle,
             ProposerAndVoterHeuristic, ReputationHeuristic,
         },
         proposal_generator::{
@@ -171,6 +171,8 @@ pub struct EpochManager<P: OnChainConfigProvider> {
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
     pending_blocks: Arc<Mutex<PendingBlocks>>,
     key_storage: PersistentSafetyStorage,
+    // The leader reputation of the current epoch, if it is used for proposer election
+    leader_reputation: LeaderReputationHandle,
 }
 
 impl<P: OnChainConfigProvider> EpochManager<P> {
@@ -242,9 +244,16 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             consensus_publisher,
             pending_blocks: Arc::new(Mutex::new(PendingBlocks::new())),
             key_storage,
+            leader_reputation: LeaderReputationHandle::default(),
         }
     }
 
+    /// Returns the handle to the leader reputation of the current epoch, e.g., to explain its
+    /// proposer elections.
+    pub(crate) fn leader_reputation(&self) -> LeaderReputationHandle {
+        self.leader_reputation.clone()
+    }
+
     fn epoch_state(&self) -> &EpochState {
         self.epoch_state
             .as_ref()
@@ -274,6 +283,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         epoch_state: &EpochState,
         onchain_config: &OnChainConsensusConfig,
     ) -> Arc<dyn ProposerElection + Send + Sync> {
+        *self.leader_reputation.write() = None;
         let proposers = epoch_state
             .verifier
             .get_ordered_account_addresses_iter()
@@ -360,7 +370,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                         .collect::<Vec<_>>()
                 );
 
-                let proposer_election = Box::new(LeaderReputation::new(
+                let proposer_election = Arc::new(LeaderReputation::new(
                     epoch_state.epoch,
                     epoch_to_proposers,
                     voting_powers,
@@ -370,10 +380,11 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                     leader_reputation_type.use_root_hash_for_seed(),
                     self.config.window_for_chain_health,
                 ));
+                *self.leader_reputation.write() = Some(proposer_election.clone());
                 // LeaderReputation is not cheap, so we can cache the amount of rounds round_manager needs.
                 Arc::new(CachedProposerElection::new(
                     epoch_state.epoch,
-                    proposer_election,
+                    Box::new(proposer_election),
                     onchain_config.max_failed_authors_to_store()
                         + PROPOSER_ELECTION_CACHING_WINDOW_ADDITION,
                 ))
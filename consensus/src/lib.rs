@@ -39,6 +39,8 @@ pub mod quorum_store;
 mod rand;
 mod recovery_manager;
 mod round_manager;
+#[cfg(test)]
+mod simulator;
 mod state_computer;
 #[cfg(test)]
 mod state_computer_tests;
@@ -69,6 +71,11 @@ use aptos_metrics_core::IntGauge;
 pub use consensusdb::create_checkpoint;
 /// Required by the smoke tests
 pub use consensusdb::CONSENSUS_DB_NAME;
+/// Required by the admin service
+pub use liveness::leader_reputation::{
+    CandidateReputation, LeaderReputation, LeaderReputationExplanation, LeaderReputationHandle,
+    Reputations,
+};
 pub use quorum_store::quorum_store_db::QUORUM_STORE_DB_NAME;
 #[cfg(feature = "fuzzing")]
 pub use round_manager::round_manager_fuzzing;
@@ -13,16 +13,17 @@ use crate::{
     },
     liveness::proposer_election::{choose_index, ProposerElection},
 };
-use anyhow::{ensure, Result};
+use anyhow::{bail, ensure, format_err, Result};
 use aptos_bitvec::BitVec;
 use aptos_consensus_types::common::{Author, Round};
 use aptos_crypto::HashValue;
-use aptos_infallible::{Mutex, MutexGuard};
+use aptos_infallible::{Mutex, MutexGuard, RwLock};
 use aptos_logger::prelude::*;
 use aptos_storage_interface::DbReader;
 use aptos_types::{
     account_config::NewBlockEvent, epoch_change::EpochChangeProof, epoch_state::EpochState,
 };
+use serde::{Deserialize, Serialize};
 use std::{
     cmp::max,
     collections::{HashMap, HashSet},
@@ -32,6 +33,9 @@ use std::{
 
 pub type VotingPowerRatio = f64;
 
+/// Shared handle to the LeaderReputation of the current epoch, if it is used for proposer election.
+pub type LeaderReputationHandle = Arc<RwLock<Option<Arc<LeaderReputation>>>>;
+
 /// Interface to query committed NewBlockEvent.
 pub trait MetadataBackend: Send + Sync {
     /// Return a contiguous NewBlockEvent window in which last one is at target_round or
@@ -41,6 +45,16 @@ pub trait MetadataBackend: Send + Sync {
         target_epoch: u64,
         target_round: Round,
     ) -> (Vec<NewBlockEvent>, HashValue);
+
+    /// Return the same window as get_block_metadata, but only from the NewBlockEvents fetched
+    /// so far, without looking for newer ones. Fail if they don't cover the whole window.
+    fn get_cached_block_metadata(
+        &self,
+        _target_epoch: u64,
+        _target_round: Round,
+    ) -> Result<(Vec<NewBlockEvent>, HashValue)> {
+        bail!("Block metadata is not cached by this backend")
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -213,6 +227,44 @@ impl MetadataBackend for AptosDBBackend {
             self.get_from_db_result(target_epoch, target_round, events, hit_end)
         }
     }
+
+    fn get_cached_block_metadata(
+        &self,
+        target_epoch: u64,
+        target_round: Round,
+    ) -> Result<(Vec<NewBlockEvent>, HashValue)> {
+        let locked = self.db_result.lock();
+        let (events, version, hit_end) = locked
+            .as_ref()
+            .ok_or_else(|| format_err!("No block metadata has been fetched yet"))?;
+        let latest = events
+            .first()
+            .map_or((0, 0), |e| (e.event.epoch(), e.event.round()));
+        // Same check as in get_block_metadata, which would fetch newer events
+        let latest_db_version = self.aptos_db.get_latest_ledger_info_version().unwrap_or(0);
+        ensure!(
+            latest >= (target_epoch, target_round) || *version >= latest_db_version,
+            "Round {} of epoch {} is newer than the fetched block metadata, up to {:?}",
+            target_round,
+            target_epoch,
+            latest
+        );
+        let window_len = events
+            .iter()
+            .filter(|e| (e.event.epoch(), e.event.round()) <= (target_epoch, target_round))
+            .take(self.window_size)
+            .count();
+        ensure!(
+            window_len == self.window_size || *hit_end,
+            "Round {} of epoch {} is older than the fetched block metadata, from {:?}",
+            target_round,
+            target_epoch,
+            events
+                .last()
+                .map_or((0, 0), |e| (e.event.epoch(), e.event.round()))
+        );
+        Ok(self.get_from_db_result(target_epoch, target_round, events, *hit_end))
+    }
 }
 
 /// Interface to calculate weights for proposers based on history.
@@ -224,6 +276,34 @@ pub trait ReputationHeuristic: Send + Sync {
         epoch_to_candidates: &HashMap<u64, Vec<Author>>,
         history: &[NewBlockEvent],
     ) -> Vec<u64>;
+
+    /// Return the reputations of all candidates based on the history, i.e., their weights along
+    /// with the history windows and metrics they are computed from.
+    fn get_reputations(
+        &self,
+        epoch: u64,
+        epoch_to_candidates: &HashMap<u64, Vec<Author>>,
+        history: &[NewBlockEvent],
+    ) -> Reputations;
+}
+
+/// The reputation of a candidate, as computed by a ReputationHeuristic.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct CandidateReputation {
+    pub author: Author,
+    pub votes: u32,
+    pub proposals: u32,
+    pub failed_proposals: u32,
+    pub weight: u64,
+}
+
+/// The reputations of all candidates, along with the (epoch, round) of the committed blocks of
+/// the history windows they are computed from, latest first.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct Reputations {
+    pub voter_window: Vec<(u64, Round)>,
+    pub proposer_window: Vec<(u64, Round)>,
+    pub candidates: Vec<CandidateReputation>,
 }
 
 pub struct NewBlockEventAggregation {
@@ -326,6 +406,38 @@ impl NewBlockEventAggregation {
             .filter(move |&meta| epoch_to_candidates.contains_key(&meta.epoch()))
     }
 
+    /// Return the (epoch, round) of the blocks in the voter window.
+    pub fn voter_window(
+        &self,
+        epoch_to_candidates: &HashMap<u64, Vec<Author>>,
+        history: &[NewBlockEvent],
+    ) -> Vec<(u64, Round)> {
+        Self::history_iter(
+            history,
+            epoch_to_candidates,
+            self.voter_window_size,
+            self.reputation_window_from_stale_end,
+        )
+        .map(|meta| (meta.epoch(), meta.round()))
+        .collect()
+    }
+
+    /// Return the (epoch, round) of the blocks in the proposer window.
+    pub fn proposer_window(
+        &self,
+        epoch_to_candidates: &HashMap<u64, Vec<Author>>,
+        history: &[NewBlockEvent],
+    ) -> Vec<(u64, Round)> {
+        Self::history_iter(
+            history,
+            epoch_to_candidates,
+            self.proposer_window_size,
+            self.reputation_window_from_stale_end,
+        )
+        .map(|meta| (meta.epoch(), meta.round()))
+        .collect()
+    }
+
     pub fn get_aggregated_metrics(
         &self,
         epoch_to_candidates: &HashMap<u64, Vec<Author>>,
@@ -519,13 +631,13 @@ impl ProposerAndVoterHeuristic {
     }
 }
 
-impl ReputationHeuristic for ProposerAndVoterHeuristic {
-    fn get_weights(
+impl ProposerAndVoterHeuristic {
+    fn get_candidate_reputations(
         &self,
         epoch: u64,
         epoch_to_candidates: &HashMap<u64, Vec<Author>>,
         history: &[NewBlockEvent],
-    ) -> Vec<u64> {
+    ) -> Vec<CandidateReputation> {
         assert!(epoch_to_candidates.contains_key(&epoch));
 
         let (votes, proposals, failed_proposals) =
@@ -539,7 +651,7 @@ impl ReputationHeuristic for ProposerAndVoterHeuristic {
                 let cur_proposals = *proposals.get(author).unwrap_or(&0);
                 let cur_failed_proposals = *failed_proposals.get(author).unwrap_or(&0);
 
-                if cur_failed_proposals * 100
+                let weight = if cur_failed_proposals * 100
                     > (cur_proposals + cur_failed_proposals) * self.failure_threshold_percent
                 {
                     self.failed_weight
@@ -547,12 +659,66 @@ impl ReputationHeuristic for ProposerAndVoterHeuristic {
                     self.active_weight
                 } else {
                     self.inactive_weight
+                };
+                CandidateReputation {
+                    author: *author,
+                    votes: cur_votes,
+                    proposals: cur_proposals,
+                    failed_proposals: cur_failed_proposals,
+                    weight,
                 }
             })
             .collect()
     }
 }
 
+impl ReputationHeuristic for ProposerAndVoterHeuristic {
+    fn get_weights(
+        &self,
+        epoch: u64,
+        epoch_to_candidates: &HashMap<u64, Vec<Author>>,
+        history: &[NewBlockEvent],
+    ) -> Vec<u64> {
+        self.get_candidate_reputations(epoch, epoch_to_candidates, history)
+            .into_iter()
+            .map(|reputation| reputation.weight)
+            .collect()
+    }
+
+    fn get_reputations(
+        &self,
+        epoch: u64,
+        epoch_to_candidates: &HashMap<u64, Vec<Author>>,
+        history: &[NewBlockEvent],
+    ) -> Reputations {
+        Reputations {
+            voter_window: self.aggregation.voter_window(epoch_to_candidates, history),
+            proposer_window: self
+                .aggregation
+                .proposer_window(epoch_to_candidates, history),
+            candidates: self.get_candidate_reputations(epoch, epoch_to_candidates, history),
+        }
+    }
+}
+
+/// The proposer election of a round by LeaderReputation, with the inputs it is computed from.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct LeaderReputationExplanation {
+    pub epoch: u64,
+    pub round: Round,
+    /// The round of the latest committed block of the history, exclude_round before round.
+    pub target_round: Round,
+    pub reputations: Reputations,
+    /// The voting powers of the candidates, in the same order as the reputations.
+    pub voting_powers: Vec<u64>,
+    /// The weights of the candidates multiplied by their voting powers.
+    pub stake_weights: Vec<u128>,
+    /// The seed of the random choice of the proposer among the stake weights.
+    pub seed: Vec<u8>,
+    pub proposer: Author,
+    pub voting_power_participation_ratio: VotingPowerRatio,
+}
+
 /// Committed history based proposer election implementation that could help bias towards
 /// successful leaders to help improve performance.
 pub struct LeaderReputation {
@@ -592,6 +758,74 @@ impl LeaderReputation {
         }
     }
 
+    fn candidates(&self) -> &Vec<Author> {
+        self.epoch_to_proposers
+            .get(&self.epoch)
+            .expect("Epoch should always map to proposers")
+    }
+
+    // use f64, as total voting power is u128
+    fn total_voting_power(&self) -> f64 {
+        self.voting_powers.iter().map(|v| *v as f64).sum()
+    }
+
+    // Return the candidates that voted or proposed in the last window_size blocks of the
+    // history, along with their voting power.
+    fn participation(
+        &self,
+        history: &[NewBlockEvent],
+        window_size: usize,
+    ) -> (HashSet<Author>, f64) {
+        let participants: HashSet<_> = NewBlockEventAggregation::count_votes_custom(
+            &self.epoch_to_proposers,
+            history,
+            window_size,
+            false,
+        )
+        .into_keys()
+        .chain(
+            NewBlockEventAggregation::count_proposals_custom(
+                &self.epoch_to_proposers,
+                history,
+                window_size,
+                false,
+            )
+            .into_keys(),
+        )
+        .collect();
+
+        let participating_voting_power = self
+            .candidates()
+            .iter()
+            .zip(self.voting_powers.iter())
+            .filter(|(c, _vp)| participants.contains(c))
+            .map(|(_c, vp)| *vp as f64)
+            .sum();
+        (participants, participating_voting_power)
+    }
+
+    // Return participating voting power percentage for the window_for_chain_health, given the
+    // voting power participating in that window.
+    fn voting_power_participation_ratio(
+        &self,
+        history: &[NewBlockEvent],
+        participating_voting_power: f64,
+    ) -> VotingPowerRatio {
+        let total_voting_power = self.total_voting_power();
+        // do not treat chain as unhealthy, if chain just started, and we don't have enough history to decide.
+        if history.len() < self.window_for_chain_health && self.epoch <= 2 {
+            1.0
+        } else if total_voting_power >= 1.0 {
+            participating_voting_power / total_voting_power
+        } else {
+            error!(
+                "Total voting power is {}, should never happen",
+                total_voting_power
+            );
+            1.0
+        }
+    }
+
     // Compute chain health metrics, and
     // - return participating voting power percentage for the window_for_chain_health
     // - update metric counters for different windows
@@ -600,13 +834,8 @@ impl LeaderReputation {
         history: &[NewBlockEvent],
         round: Round,
     ) -> VotingPowerRatio {
-        let candidates = self
-            .epoch_to_proposers
-            .get(&self.epoch)
-            .expect("Epoch should always map to proposers");
-        // use f64 counter, as total voting power is u128
-        let total_voting_power = self.voting_powers.iter().map(|v| *v as f64).sum();
-        CHAIN_HEALTH_TOTAL_VOTING_POWER.set(total_voting_power);
+        let candidates = self.candidates();
+        CHAIN_HEALTH_TOTAL_VOTING_POWER.set(self.total_voting_power());
         CHAIN_HEALTH_TOTAL_NUM_VALIDATORS.set(candidates.len() as i64);
 
         let mut result = None;
@@ -618,30 +847,8 @@ impl LeaderReputation {
             let sample_fraction = participants_window_size / 10;
             // Sample longer durations
             if chosen || sample_fraction <= 1 || (round % sample_fraction as u64) == 1 {
-                let participants: HashSet<_> = NewBlockEventAggregation::count_votes_custom(
-                    &self.epoch_to_proposers,
-                    history,
-                    *participants_window_size,
-                    false,
-                )
-                .into_keys()
-                .chain(
-                    NewBlockEventAggregation::count_proposals_custom(
-                        &self.epoch_to_proposers,
-                        history,
-                        *participants_window_size,
-                        false,
-                    )
-                    .into_keys(),
-                )
-                .collect();
-
-                let participating_voting_power = candidates
-                    .iter()
-                    .zip(self.voting_powers.iter())
-                    .filter(|(c, _vp)| participants.contains(c))
-                    .map(|(_c, vp)| *vp as f64)
-                    .sum();
+                let (participants, participating_voting_power) =
+                    self.participation(history, *participants_window_size);
 
                 if counter_index == max(CHAIN_HEALTH_WINDOW_SIZES.len() - 2, 0) {
                     // Only emit this for one window value. Currently defaults to 100
@@ -664,19 +871,8 @@ impl LeaderReputation {
                     .set(participants.len() as i64);
 
                 if chosen {
-                    // do not treat chain as unhealthy, if chain just started, and we don't have enough history to decide.
-                    let voting_power_participation_ratio: VotingPowerRatio =
-                        if history.len() < *participants_window_size && self.epoch <= 2 {
-                            1.0
-                        } else if total_voting_power >= 1.0 {
-                            participating_voting_power / total_voting_power
-                        } else {
-                            error!(
-                                "Total voting power is {}, should never happen",
-                                total_voting_power
-                            );
-                            1.0
-                        };
+                    let voting_power_participation_ratio =
+                        self.voting_power_participation_ratio(history, participating_voting_power);
                     CHAIN_HEALTH_REPUTATION_PARTICIPATING_VOTING_POWER_FRACTION
                         .set(voting_power_participation_ratio);
                     result = Some(voting_power_participation_ratio);
@@ -691,26 +887,21 @@ impl LeaderReputation {
             )
         })
     }
-}
 
-impl ProposerElection for LeaderReputation {
-    fn get_valid_proposer_and_voting_power_participation_ratio(
+    // Choose the proposer of the round given the weights of the candidates, and return it along
+    // with the stake weights and seed it is chosen from.
+    fn choose_proposer(
         &self,
         round: Round,
-    ) -> (Author, VotingPowerRatio) {
-        let target_round = round.saturating_sub(self.exclude_round);
-        let (sliding_window, root_hash) = self.backend.get_block_metadata(self.epoch, target_round);
-        let voting_power_participation_ratio =
-            self.compute_chain_health_and_add_metrics(&sliding_window, round);
-        let mut weights =
-            self.heuristic
-                .get_weights(self.epoch, &self.epoch_to_proposers, &sliding_window);
+        weights: &[u64],
+        root_hash: HashValue,
+    ) -> (Author, Vec<u128>, Vec<u8>) {
         let proposers = &self.epoch_to_proposers[&self.epoch];
         assert_eq!(weights.len(), proposers.len());
 
         // Multiply weights by voting power:
         let stake_weights: Vec<u128> = weights
-            .iter_mut()
+            .iter()
             .enumerate()
             .map(|(i, w)| *w as u128 * self.voting_powers[i] as u128)
             .collect();
@@ -730,8 +921,63 @@ impl ProposerElection for LeaderReputation {
             .concat()
         };
 
-        let chosen_index = choose_index(stake_weights, state);
-        (proposers[chosen_index], voting_power_participation_ratio)
+        let chosen_index = choose_index(stake_weights.clone(), state.clone());
+        (proposers[chosen_index], stake_weights, state)
+    }
+
+    /// Explain the proposer election of the round, going through the same computation as
+    /// get_valid_proposer_and_voting_power_participation_ratio, without updating the chain
+    /// health metrics. Only rounds whose history window was already fetched can be explained.
+    pub fn explain(&self, round: Round) -> Result<LeaderReputationExplanation> {
+        let target_round = round.saturating_sub(self.exclude_round);
+        let (sliding_window, root_hash) = self
+            .backend
+            .get_cached_block_metadata(self.epoch, target_round)?;
+        let (_, participating_voting_power) =
+            self.participation(&sliding_window, self.window_for_chain_health);
+        let voting_power_participation_ratio =
+            self.voting_power_participation_ratio(&sliding_window, participating_voting_power);
+        let reputations =
+            self.heuristic
+                .get_reputations(self.epoch, &self.epoch_to_proposers, &sliding_window);
+        let weights: Vec<u64> = reputations
+            .candidates
+            .iter()
+            .map(|reputation| reputation.weight)
+            .collect();
+        let (proposer, stake_weights, seed) = self.choose_proposer(round, &weights, root_hash);
+        Ok(LeaderReputationExplanation {
+            epoch: self.epoch,
+            round,
+            target_round,
+            reputations,
+            voting_powers: self.voting_powers.clone(),
+            stake_weights,
+            seed,
+            proposer,
+            voting_power_participation_ratio,
+        })
+    }
+
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+}
+
+impl ProposerElection for LeaderReputation {
+    fn get_valid_proposer_and_voting_power_participation_ratio(
+        &self,
+        round: Round,
+    ) -> (Author, VotingPowerRatio) {
+        let target_round = round.saturating_sub(self.exclude_round);
+        let (sliding_window, root_hash) = self.backend.get_block_metadata(self.epoch, target_round);
+        let voting_power_participation_ratio =
+            self.compute_chain_health_and_add_metrics(&sliding_window, round);
+        let weights =
+            self.heuristic
+                .get_weights(self.epoch, &self.epoch_to_proposers, &sliding_window);
+        let (proposer, _, _) = self.choose_proposer(round, &weights, root_hash);
+        (proposer, voting_power_participation_ratio)
     }
 
     fn get_valid_proposer(&self, round: Round) -> Author {
@@ -387,6 +387,100 @@ fn test_api(use_root_hash: bool) {
     }
 }
 
+#[test]
+fn test_explain() {
+    let proposers: Vec<AccountAddress> =
+        (0..3).map(|_| AccountAddress::random()).sorted().collect();
+    let voting_powers = vec![1, 2, 3];
+    let epoch = 1;
+    let round = 42u64;
+
+    let aptos_db = Arc::new(MockDbReader::new());
+    aptos_db.new_epoch();
+    aptos_db.add_event_with_data(proposers[0], vec![1], vec![2]);
+    aptos_db.add_event_with_data(proposers[1], vec![0], vec![]);
+    let backend = Arc::new(AptosDBBackend::new(2, 4, aptos_db.clone()));
+    let leader_reputation = LeaderReputation::new(
+        epoch,
+        HashMap::from([(epoch, proposers.clone())]),
+        voting_powers.clone(),
+        backend,
+        Box::new(ProposerAndVoterHeuristic::new(
+            proposers[0],
+            100,
+            10,
+            1,
+            10,
+            2,
+            2,
+            false,
+        )),
+        4,
+        true,
+        30,
+    );
+
+    // Nothing to explain until the history is fetched by an election.
+    assert!(leader_reputation.explain(round).is_err());
+    let (proposer, voting_power_participation_ratio) =
+        leader_reputation.get_valid_proposer_and_voting_power_participation_ratio(round);
+
+    let explanation = leader_reputation.explain(round).unwrap();
+    let explanation_of_round_6 = leader_reputation.explain(6).unwrap();
+    assert_eq!(explanation.epoch, epoch);
+    assert_eq!(explanation.round, round);
+    assert_eq!(explanation.target_round, round - 4);
+    let expected_window = vec![(1, 3), (1, 2)];
+    assert_eq!(explanation.reputations.voter_window, expected_window);
+    assert_eq!(explanation.reputations.proposer_window, expected_window);
+
+    let reputations: Vec<_> = explanation
+        .reputations
+        .candidates
+        .iter()
+        .map(|reputation| {
+            (
+                reputation.author,
+                reputation.votes,
+                reputation.proposals,
+                reputation.failed_proposals,
+                reputation.weight,
+            )
+        })
+        .collect();
+    let expected_reputations = vec![
+        (proposers[0], 1, 1, 0, 100),
+        (proposers[1], 1, 1, 0, 100),
+        (proposers[2], 0, 0, 1, 1),
+    ];
+    assert_eq!(reputations, expected_reputations);
+    assert_eq!(explanation.voting_powers, voting_powers);
+    assert_eq!(explanation.stake_weights, vec![100, 200, 3]);
+
+    let state = [
+        aptos_db.get_accumulator_root_hash(2).unwrap().to_vec(),
+        epoch.to_le_bytes().to_vec(),
+        round.to_le_bytes().to_vec(),
+    ]
+    .concat();
+    assert_eq!(explanation.seed, state);
+
+    // The explanation matches the election.
+    assert_eq!(explanation.proposer, proposer);
+    assert_eq!(
+        explanation.voting_power_participation_ratio,
+        voting_power_participation_ratio
+    );
+
+    // Once newer blocks are committed, the fetched history can't tell about later rounds.
+    aptos_db.add_event_with_data(proposers[2], vec![2], vec![]);
+    assert!(leader_reputation.explain(round).is_err());
+    assert_eq!(
+        leader_reputation.explain(6).unwrap(),
+        explanation_of_round_6
+    );
+}
+
 struct MockDbReader {
     events: Mutex<Vec<EventWithVersion>>,
     random_address: Author,
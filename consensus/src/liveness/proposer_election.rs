@@ -5,7 +5,7 @@
 use aptos_consensus_types::common::{Author, Round};
 use aptos_fallible::copy_from_slice::copy_slice_to_vec;
 use num_traits::CheckedAdd;
-use std::cmp::Ordering;
+use std::{cmp::Ordering, sync::Arc};
 
 /// ProposerElection incorporates the logic of choosing a leader among multiple candidates.
 pub trait ProposerElection {
@@ -36,6 +36,28 @@ pub trait ProposerElection {
     }
 }
 
+impl<T: ProposerElection + ?Sized> ProposerElection for Arc<T> {
+    fn is_valid_proposer(&self, author: Author, round: Round) -> bool {
+        self.as_ref().is_valid_proposer(author, round)
+    }
+
+    fn get_valid_proposer(&self, round: Round) -> Author {
+        self.as_ref().get_valid_proposer(round)
+    }
+
+    fn get_voting_power_participation_ratio(&self, round: Round) -> f64 {
+        self.as_ref().get_voting_power_participation_ratio(round)
+    }
+
+    fn get_valid_proposer_and_voting_power_participation_ratio(
+        &self,
+        round: Round,
+    ) -> (Author, f64) {
+        self.as_ref()
+            .get_valid_proposer_and_voting_power_participation_ratio(round)
+    }
+}
+
 // next consumes seed and returns random deterministic u64 value in [0, max) range
 fn next_in_range(state: Vec<u8>, max: u128) -> u128 {
     // hash = SHA-3-256(state)
@@ -85,4 +85,11 @@ impl UnequivocalProposerElection {
             }
         })
     }
+
+    // Return the id of the valid proposal of a given round, if it is the latest round with a
+    // valid proposal. Only the id is kept, the block itself is looked up when needed as evidence.
+    pub fn already_proposed(&self, round: Round) -> Option<HashValue> {
+        let already_proposed = self.already_proposed.lock();
+        (already_proposed.0 == round && round > 0).then_some(already_proposed.1)
+    }
 }
@@ -104,3 +104,34 @@ fn test_is_valid_proposal() {
     // Proposal from previous round is not valid any more:
     assert!(!pe.is_valid_proposal(&good_proposal));
 }
+
+#[test]
+fn test_already_proposed() {
+    let validator_signer = ValidatorSigner::random([0u8; 32]);
+    let quorum_cert = certificate_for_genesis();
+    let proposal = |timestamp| {
+        Block::new_proposal(
+            Payload::empty(false, true),
+            1,
+            timestamp,
+            quorum_cert.clone(),
+            &validator_signer,
+            Vec::new(),
+        )
+        .unwrap()
+    };
+    let (first_proposal, second_proposal) = (proposal(1), proposal(2));
+
+    let proposers = HashMap::from([(1, validator_signer.author())]);
+    let pe = UnequivocalProposerElection::new(Arc::new(MockProposerElection::new(proposers)));
+
+    // nothing before the first proposal
+    assert_eq!(pe.already_proposed(1), None);
+    assert!(pe.is_valid_proposal(&first_proposal));
+    assert_eq!(pe.already_proposed(1), Some(first_proposal.id()));
+    assert_eq!(pe.already_proposed(2), None);
+
+    // the second proposal is rejected, the first one stays the valid proposal of the round
+    assert!(!pe.is_valid_proposal(&second_proposal));
+    assert_eq!(pe.already_proposed(1), Some(first_proposal.id()));
+}
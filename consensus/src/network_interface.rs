@@ -130,6 +130,7 @@ pub struct ConsensusNetworkClient<NetworkClient> {
 
 /// Supported protocols in preferred order (from highest priority to lowest).
 pub const RPC: &[ProtocolId] = &[
+    ProtocolId::ConsensusRpcCompressedZstd,
     ProtocolId::ConsensusRpcCompressed,
     ProtocolId::ConsensusRpcBcs,
     ProtocolId::ConsensusRpcJson,
@@ -137,6 +138,7 @@ pub const RPC: &[ProtocolId] = &[
 
 /// Supported protocols in preferred order (from highest priority to lowest).
 pub const DIRECT_SEND: &[ProtocolId] = &[
+    ProtocolId::ConsensusDirectSendCompressedZstd,
     ProtocolId::ConsensusDirectSendCompressed,
     ProtocolId::ConsensusDirectSendBcs,
     ProtocolId::ConsensusDirectSendJson,
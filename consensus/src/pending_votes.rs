@@ -37,7 +37,8 @@ pub enum VoteReceptionResult {
     /// The very same vote message has been processed in past.
     DuplicateVote,
     /// The very same author has already voted for another proposal in this round (equivocation).
-    EquivocateVote,
+    /// Returns the previous vote of the author.
+    EquivocateVote(Box<Vote>),
     /// This block has just been certified after adding the vote.
     NewQuorumCertificate(Arc<QuorumCert>),
     /// The vote completes a new TwoChainTimeoutCertificate
@@ -305,7 +306,7 @@ impl PendingVotes {
                     previous_vote = previously_seen_vote
                 );
 
-                return VoteReceptionResult::EquivocateVote;
+                return VoteReceptionResult::EquivocateVote(Box::new(previously_seen_vote.clone()));
             }
         }
 
@@ -625,7 +626,7 @@ mod tests {
         vote_data_2_author_0.set_verified();
         assert_eq!(
             pending_votes.insert_vote(&vote_data_2_author_0, &validator_verifier),
-            VoteReceptionResult::EquivocateVote
+            VoteReceptionResult::EquivocateVote(Box::new(vote_data_1_author_0.clone()))
         );
 
         // a different author voting for a different result -> VoteAdded
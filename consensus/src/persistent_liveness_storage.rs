@@ -6,8 +6,9 @@ use crate::{consensusdb::ConsensusDB, epoch_manager::LivenessStorageData, error:
 use anyhow::{format_err, Context, Result};
 use aptos_config::config::NodeConfig;
 use aptos_consensus_types::{
-    block::Block, quorum_cert::QuorumCert, timeout_2chain::TwoChainTimeoutCertificate, vote::Vote,
-    vote_data::VoteData, wrapped_ledger_info::WrappedLedgerInfo,
+    block::Block, equivocation::Equivocation, quorum_cert::QuorumCert,
+    timeout_2chain::TwoChainTimeoutCertificate, vote::Vote, vote_data::VoteData,
+    wrapped_ledger_info::WrappedLedgerInfo,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
@@ -33,6 +34,9 @@ pub trait PersistentLivenessStorage: Send + Sync {
     /// Persist consensus' state
     fn save_vote(&self, vote: &Vote) -> Result<()>;
 
+    /// Persist the evidence of an equivocation by another validator
+    fn save_equivocation(&self, equivocation: &Equivocation) -> Result<()>;
+
     /// Construct data that can be recovered from ledger
     fn recover_from_ledger(&self) -> LedgerRecoveryData;
 
@@ -331,6 +335,9 @@ impl RecoveryData {
     }
 }
 
+/// Number of epochs, up to the current one, whose evidence of equivocations is kept.
+const EQUIVOCATION_EPOCHS_TO_KEEP: u64 = 2;
+
 /// The proxy we use to persist data in db storage service via grpc.
 pub struct StorageWriteProxy {
     db: Arc<ConsensusDB>,
@@ -363,6 +370,10 @@ impl PersistentLivenessStorage for StorageWriteProxy {
         Ok(self.db.save_vote(bcs::to_bytes(vote)?)?)
     }
 
+    fn save_equivocation(&self, equivocation: &Equivocation) -> Result<()> {
+        Ok(self.db.save_equivocation(equivocation)?)
+    }
+
     fn recover_from_ledger(&self) -> LedgerRecoveryData {
         let latest_ledger_info = self
             .aptos_db
@@ -409,6 +420,14 @@ impl PersistentLivenessStorage for StorageWriteProxy {
             .aptos_db
             .get_accumulator_summary(latest_ledger_info.ledger_info().version())
             .expect("Failed to get accumulator summary.");
+        // Only keep the evidence of equivocations in the current and previous epochs.
+        let min_equivocation_epoch = latest_ledger_info
+            .ledger_info()
+            .next_block_epoch()
+            .saturating_sub(EQUIVOCATION_EPOCHS_TO_KEEP - 1);
+        if let Err(e) = self.db.prune_equivocations(min_equivocation_epoch) {
+            warn!(error = ?e, "Failed to prune the evidence of equivocations");
+        }
         let ledger_recovery_data = LedgerRecoveryData::new(latest_ledger_info);
 
         match RecoveryData::new(
@@ -8,8 +8,8 @@ use crate::{
         BlockReader, BlockRetriever, BlockStore, NeedFetchResult,
     },
     counters::{
-        self, ORDER_CERT_CREATED_WITHOUT_BLOCK_IN_BLOCK_STORE, ORDER_VOTE_ADDED,
-        ORDER_VOTE_BROADCASTED, ORDER_VOTE_NOT_IN_RANGE, ORDER_VOTE_OTHER_ERRORS,
+        self, EQUIVOCATIONS_RECORDED, ORDER_CERT_CREATED_WITHOUT_BLOCK_IN_BLOCK_STORE,
+        ORDER_VOTE_ADDED, ORDER_VOTE_BROADCASTED, ORDER_VOTE_NOT_IN_RANGE, ORDER_VOTE_OTHER_ERRORS,
         PROPOSAL_VOTE_ADDED, PROPOSAL_VOTE_BROADCASTED, PROPOSED_VTXN_BYTES, PROPOSED_VTXN_COUNT,
         QC_AGGREGATED_FROM_VOTES, SYNC_INFO_RECEIVED_WITH_NEWER_CERT,
     },
@@ -40,6 +40,7 @@ use aptos_consensus_types::{
     block::Block,
     block_data::BlockType,
     common::{Author, Round},
+    equivocation::Equivocation,
     order_vote::OrderVote,
     order_vote_msg::OrderVoteMsg,
     pipelined_block::PipelinedBlock,
@@ -64,6 +65,7 @@ use aptos_short_hex_str::AsShortHexStr;
 use aptos_types::{
     block_info::BlockInfo,
     epoch_state::EpochState,
+    equivocation::EquivocationKind,
     on_chain_config::{
         OnChainConsensusConfig, OnChainJWKConsensusConfig, OnChainRandomnessConfig,
         ValidatorTxnConfig,
@@ -955,12 +957,16 @@ impl RoundManager {
             self.local_config.max_receiving_block_bytes,
         );
 
-        ensure!(
-            self.proposer_election.is_valid_proposal(&proposal),
-            "[RoundManager] Proposer {} for block {} is not a valid proposer for this round or created duplicate proposal",
-            author,
-            proposal,
-        );
+        if !self.proposer_election.is_valid_proposal(&proposal) {
+            if let Some(equivocation) = self.proposal_equivocation(&proposal) {
+                self.record_equivocation(equivocation);
+            }
+            bail!(
+                "[RoundManager] Proposer {} for block {} is not a valid proposer for this round or created duplicate proposal",
+                author,
+                proposal,
+            );
+        }
 
         // Validate that failed_authors list is correctly specified in the block.
         let expected_failed_authors = self.proposal_generator.compute_failed_authors(
@@ -1462,10 +1468,46 @@ impl RoundManager {
                 Ok(())
             },
             VoteReceptionResult::EchoTimeout(_) | VoteReceptionResult::DuplicateVote => Ok(()),
+            VoteReceptionResult::EquivocateVote(previous_vote) => {
+                match Equivocation::from_votes(*previous_vote, vote.clone()) {
+                    Ok(equivocation) => self.record_equivocation(equivocation),
+                    Err(e) => warn!("Unable to create the evidence of equivocation: {:?}", e),
+                }
+                bail!("[RoundManager] Equivocating vote {}", vote)
+            },
             e => Err(anyhow::anyhow!("{:?}", e)),
         }
     }
 
+    /// Returns the evidence of the equivocation of the leader, if the proposal conflicts with the
+    /// valid proposal of its round, as found in the block store.
+    fn proposal_equivocation(&self, proposal: &Block) -> Option<Equivocation> {
+        let proposed_id = self.proposer_election.already_proposed(proposal.round())?;
+        let proposed_block = self.block_store.get_block(proposed_id)?;
+        Equivocation::from_proposals(proposed_block.block().clone(), proposal.clone()).ok()
+    }
+
+    /// Persists the evidence of an equivocation by another validator, so that it can be inspected
+    /// and packaged as a validator transaction later on.
+    fn record_equivocation(&self, equivocation: Equivocation) {
+        // The messages may have been verified optimistically, only keep properly signed evidence.
+        if let Err(e) = equivocation.verify(&self.epoch_state.verifier) {
+            warn!("Invalid evidence of {}: {:?}", equivocation, e);
+            return;
+        }
+        let kind = match equivocation.kind() {
+            EquivocationKind::Vote => "vote",
+            EquivocationKind::Proposal => "proposal",
+        };
+        EQUIVOCATIONS_RECORDED.with_label_values(&[kind]).inc();
+        if let Err(e) = self.storage.save_equivocation(&equivocation) {
+            error!(
+                "Failed to persist the evidence of {}: {:?}",
+                equivocation, e
+            );
+        }
+    }
+
     async fn process_timeout_reception_result(
         &mut self,
         timeout: &RoundTimeout,
@@ -40,6 +40,7 @@ use aptos_consensus_types::{
     },
     block_retrieval::{BlockRetrievalRequest, BlockRetrievalStatus},
     common::{Author, Payload, Round},
+    equivocation::Equivocation,
     pipeline::commit_decision::CommitDecision,
     proposal_msg::ProposalMsg,
     round_timeout::RoundTimeoutMsg,
@@ -1094,6 +1095,66 @@ fn no_vote_on_invalid_proposer() {
     });
 }
 
+#[test]
+/// Two different proposals of the leader for the same round are recorded as evidence of
+/// equivocation.
+fn record_equivocating_proposal() {
+    let runtime = consensus_runtime();
+    let mut playground = NetworkPlayground::new(runtime.handle().clone());
+    let mut node = NodeSetup::create_nodes(
+        &mut playground,
+        runtime.handle().clone(),
+        1,
+        None,
+        None,
+        None,
+        None,
+        None,
+    )
+    .pop()
+    .unwrap();
+    let genesis_qc = certificate_for_genesis();
+    let first_block = Block::new_proposal(
+        Payload::empty(false, true),
+        1,
+        1,
+        genesis_qc.clone(),
+        &node.signer,
+        Vec::new(),
+    )
+    .unwrap();
+    let second_block = Block::new_proposal(
+        Payload::empty(false, true),
+        1,
+        2,
+        genesis_qc.clone(),
+        &node.signer,
+        Vec::new(),
+    )
+    .unwrap();
+    let sync_info = SyncInfo::new(
+        genesis_qc.clone(),
+        genesis_qc.into_wrapped_ledger_info(),
+        None,
+    );
+    timed_block_on(&runtime, async {
+        node.round_manager
+            .process_proposal_msg(ProposalMsg::new(first_block.clone(), sync_info.clone()))
+            .await
+            .unwrap();
+        assert!(node
+            .round_manager
+            .process_proposal_msg(ProposalMsg::new(second_block.clone(), sync_info))
+            .await
+            .is_err());
+    });
+    let equivocation = Equivocation::Proposals(Box::new(first_block), Box::new(second_block));
+    assert_eq!(
+        *node.storage.shared_storage.equivocations.lock(),
+        vec![equivocation]
+    );
+}
+
 #[test]
 /// We allow to 'skip' round if proposal carries timeout certificate for next round
 fn new_round_on_timeout_certificate() {
@@ -0,0 +1,183 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_crypto::HashValue;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use std::{
+    collections::{BTreeMap, HashMap},
+    time::Duration,
+};
+
+/// Identifies a message sent between the simulated nodes: its sender, its recipient and
+/// its index among all the messages sent by the sender. As the nodes are deterministic,
+/// the same message gets the same id in every run with the same faults.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MessageId {
+    pub src: usize,
+    pub dst: usize,
+    pub seq: u64,
+}
+
+/// A fault injected into the delivery of a message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fault {
+    /// The message is never delivered.
+    Drop,
+    /// The message is delivered late (possibly after messages sent after it).
+    Delay(Duration),
+    /// The message is delivered twice, the copy after the given delay.
+    Duplicate(Duration),
+}
+
+/// The probabilities of the faults drawn by a `FaultSchedule::Random`
+#[derive(Clone, Copy, Debug)]
+pub struct FaultConfig {
+    pub drop_probability: f64,
+    pub duplicate_probability: f64,
+    pub delay_probability: f64,
+    /// The maximum delay of delayed messages and duplicate copies
+    pub max_delay: Duration,
+}
+
+impl Default for FaultConfig {
+    fn default() -> Self {
+        Self {
+            drop_probability: 0.05,
+            duplicate_probability: 0.05,
+            delay_probability: 0.2,
+            max_delay: Duration::from_millis(500),
+        }
+    }
+}
+
+/// The faults injected by a `MessageScheduler`
+#[derive(Clone, Debug)]
+pub enum FaultSchedule {
+    /// No faults are injected.
+    None,
+    /// Faults are drawn independently for each message, from the seed and the message id.
+    Random { seed: u64, config: FaultConfig },
+    /// The given faults are injected (e.g., the faults recorded in a failing run, being shrunk).
+    Explicit(BTreeMap<MessageId, Fault>),
+}
+
+impl FaultSchedule {
+    /// Returns the fault to inject into the delivery of the given message (if any)
+    fn get_fault(&self, message_id: &MessageId) -> Option<Fault> {
+        match self {
+            FaultSchedule::None => None,
+            FaultSchedule::Random { seed, config } => {
+                // Derive the randomness from the message id (and not from the order in
+                // which the messages are scheduled), so a fault only depends on its message.
+                let message_seed = HashValue::sha3_256_of(
+                    &bcs::to_bytes(&(seed, message_id.src, message_id.dst, message_id.seq))
+                        .expect("Message ids must serialize"),
+                );
+                let mut rng = StdRng::from_seed(*message_seed);
+                let max_delay_ms = config.max_delay.as_millis() as u64;
+                if rng.gen_bool(config.drop_probability) {
+                    Some(Fault::Drop)
+                } else if rng.gen_bool(config.duplicate_probability) {
+                    let delay_ms = rng.gen_range(0, max_delay_ms + 1);
+                    Some(Fault::Duplicate(Duration::from_millis(delay_ms)))
+                } else if rng.gen_bool(config.delay_probability) {
+                    let delay_ms = rng.gen_range(0, max_delay_ms + 1);
+                    Some(Fault::Delay(Duration::from_millis(delay_ms)))
+                } else {
+                    None
+                }
+            },
+            FaultSchedule::Explicit(faults) => faults.get(message_id).copied(),
+        }
+    }
+}
+
+/// `MessageScheduler` decides when (and if) the messages sent between the simulated nodes are
+/// delivered. Every message is delivered after the base latency, unless a fault is injected.
+/// Messages due at the same time are delivered in the order of their ids.
+pub struct MessageScheduler<T> {
+    /// The latency of the messages without faults
+    base_latency: Duration,
+    /// The faults to inject
+    fault_schedule: FaultSchedule,
+    /// The index of the next message sent by each node
+    next_seqs: HashMap<usize, u64>,
+    /// The messages to deliver, by delivery time (and copy index, for duplicates)
+    pending: BTreeMap<(Duration, MessageId, u8), T>,
+    /// The faults injected so far
+    injected_faults: BTreeMap<MessageId, Fault>,
+}
+
+impl<T: Clone> MessageScheduler<T> {
+    pub fn new(base_latency: Duration, fault_schedule: FaultSchedule) -> Self {
+        Self {
+            base_latency,
+            fault_schedule,
+            next_seqs: HashMap::new(),
+            pending: BTreeMap::new(),
+            injected_faults: BTreeMap::new(),
+        }
+    }
+
+    /// Schedules the delivery of a message sent from `src` to `dst` at time `now`,
+    /// and returns the id of the message.
+    pub fn schedule(&mut self, now: Duration, src: usize, dst: usize, message: T) -> MessageId {
+        let next_seq = self.next_seqs.entry(src).or_insert(0);
+        let message_id = MessageId {
+            src,
+            dst,
+            seq: *next_seq,
+        };
+        *next_seq += 1;
+
+        let delivery_time = now + self.base_latency;
+        let fault = self.fault_schedule.get_fault(&message_id);
+        match fault {
+            None => {
+                self.pending.insert((delivery_time, message_id, 0), message);
+            },
+            Some(Fault::Drop) => {},
+            Some(Fault::Delay(delay)) => {
+                self.pending
+                    .insert((delivery_time + delay, message_id, 0), message);
+            },
+            Some(Fault::Duplicate(delay)) => {
+                self.pending
+                    .insert((delivery_time, message_id, 0), message.clone());
+                self.pending
+                    .insert((delivery_time + delay, message_id, 1), message);
+            },
+        }
+        if let Some(fault) = fault {
+            self.injected_faults.insert(message_id, fault);
+        }
+
+        message_id
+    }
+
+    /// Removes and returns the messages due at time `now`, in delivery order
+    pub fn pop_ready_messages(&mut self, now: Duration) -> Vec<(MessageId, T)> {
+        let mut ready_messages = vec![];
+        while let Some(entry) = self.pending.first_entry() {
+            let (delivery_time, message_id, _) = *entry.key();
+            if delivery_time > now {
+                break;
+            }
+            ready_messages.push((message_id, entry.remove()));
+        }
+        ready_messages
+    }
+
+    /// Returns the delivery time of the next pending message (if any)
+    pub fn next_delivery_time(&self) -> Option<Duration> {
+        self.pending
+            .keys()
+            .next()
+            .map(|(delivery_time, _, _)| *delivery_time)
+    }
+
+    /// Returns the faults injected so far
+    pub fn injected_faults(&self) -> &BTreeMap<MessageId, Fault> {
+        &self.injected_faults
+    }
+}
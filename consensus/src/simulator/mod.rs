@@ -0,0 +1,20 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A deterministic simulator for consensus.
+//!
+//! The simulator runs several validators (each with its own `EpochManager` and `RoundManager`)
+//! on a single-threaded runtime, with a mock clock shared by all the nodes. All the messages
+//! between the nodes go through a seeded `MessageScheduler` that can delay (and thus reorder),
+//! drop and duplicate them. Time only advances when the nodes are idle, so a run is entirely
+//! determined by its configuration and fault schedule: a failing seed reproduces exactly, and
+//! the faults it injected can be shrunk to a minimal failing set with `shrink_faults`.
+
+mod message_scheduler;
+mod simulated_clock;
+mod simulated_node;
+mod simulation;
+mod simulator_test;
+
+pub use message_scheduler::{Fault, FaultConfig, FaultSchedule, MessageId, MessageScheduler};
+pub use simulation::{shrink_faults, Simulation, SimulationConfig, SimulationResult};
@@ -0,0 +1,51 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::util::time_service::{ScheduledTask, TimeService};
+use aptos_time_service::TimeServiceTrait;
+use async_trait::async_trait;
+use futures::future::{AbortHandle, Abortable};
+use std::time::Duration;
+
+/// The timestamp of the start of the simulation (after the genesis timestamp, so that
+/// the first proposals have strictly increasing timestamps).
+const SIMULATION_START_TIME: Duration = Duration::from_secs(1);
+
+/// `SimulatedClock` implements the consensus `TimeService` on top of a mock
+/// `aptos_time_service::TimeService`, so the round timeouts and block timestamps
+/// of all the simulated nodes follow the simulation time.
+#[derive(Clone)]
+pub struct SimulatedClock {
+    time_service: aptos_time_service::TimeService,
+}
+
+impl SimulatedClock {
+    pub fn new(time_service: aptos_time_service::TimeService) -> Self {
+        Self { time_service }
+    }
+}
+
+#[async_trait]
+impl TimeService for SimulatedClock {
+    fn run_after(&self, timeout: Duration, mut task: Box<dyn ScheduledTask>) -> AbortHandle {
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+        let time_service = self.time_service.clone();
+        let task = Abortable::new(
+            async move {
+                time_service.sleep(timeout).await;
+                task.run().await;
+            },
+            abort_registration,
+        );
+        tokio::spawn(task);
+        abort_handle
+    }
+
+    fn get_current_timestamp(&self) -> Duration {
+        SIMULATION_START_TIME + self.time_service.now_unix_time()
+    }
+
+    async fn sleep(&self, t: Duration) {
+        self.time_service.sleep(t).await
+    }
+}
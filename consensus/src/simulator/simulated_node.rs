@@ -0,0 +1,198 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    counters,
+    epoch_manager::EpochManager,
+    network::NetworkTask,
+    network_interface::{ConsensusNetworkClient, DIRECT_SEND, RPC},
+    pipeline::buffer_manager::OrderedBlocks,
+    quorum_store::quorum_store_db::MockQuorumStoreDB,
+    rand::rand_gen::storage::in_memory::InMemRandDb,
+    simulator::simulated_clock::SimulatedClock,
+    test_utils::{mock_execution_client::MockExecutionClient, MockStorage},
+};
+use aptos_bounded_executor::BoundedExecutor;
+use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
+use aptos_config::{
+    config::{NodeConfig, WaypointConfig},
+    network_id::NetworkId,
+};
+use aptos_consensus_types::common::Author;
+use aptos_event_notifications::{ReconfigNotification, ReconfigNotificationListener};
+use aptos_infallible::Mutex;
+use aptos_mempool::mocks::MockSharedMempool;
+use aptos_network::{
+    application::{
+        interface::{NetworkClient, NetworkServiceEvents},
+        storage::PeersAndMetadata,
+    },
+    peer_manager::{ConnectionRequestSender, PeerManagerRequest, PeerManagerRequestSender},
+    protocols::network::{
+        self, NetworkEvents, NewNetworkEvents, NewNetworkSender, ReceivedMessage,
+    },
+    ProtocolId,
+};
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::{
+        InMemoryOnChainConfig, OnChainConfig, OnChainConfigPayload, OnChainConsensusConfig,
+        ValidatorSet,
+    },
+    transaction::SignedTransaction,
+    waypoint::Waypoint,
+    PeerId,
+};
+use aptos_validator_transaction_pool::VTxnPoolState;
+use futures::{channel::mpsc, StreamExt};
+use maplit::hashmap;
+use std::{collections::HashMap, sync::Arc};
+
+/// The capacity of the network queues of the simulated nodes (per peer and protocol). The
+/// queues are drained after every step of the simulation, so they should never fill up.
+const NETWORK_QUEUE_SIZE: usize = 1_024;
+
+/// A consensus node run by the simulator. The node is started on the current (single-threaded)
+/// runtime, and its network requests are routed through the simulator's `MessageScheduler`.
+pub struct SimulatedNode {
+    pub author: Author,
+    /// The ledger infos committed by the node, in commit order
+    pub commits: Arc<Mutex<Vec<LedgerInfoWithSignatures>>>,
+    /// The sender of the inbound network messages of the node
+    pub consensus_tx: aptos_channel::Sender<(PeerId, ProtocolId), ReceivedMessage>,
+    /// The receiver of the outbound network requests of the node
+    pub network_reqs_rx: aptos_channel::Receiver<(PeerId, ProtocolId), PeerManagerRequest>,
+    _shared_mempool: MockSharedMempool,
+    _state_sync: mpsc::UnboundedReceiver<Vec<SignedTransaction>>,
+}
+
+impl SimulatedNode {
+    /// Starts a node with the given config. Must be called from within the simulation runtime.
+    pub fn start(
+        mut config: NodeConfig,
+        consensus_config: OnChainConsensusConfig,
+        validator_set: ValidatorSet,
+        peers_and_metadata: Arc<PeersAndMetadata>,
+        time_service: aptos_time_service::TimeService,
+    ) -> Self {
+        let author = config.validator_network.as_ref().unwrap().peer_id();
+        let (_, storage) = MockStorage::start_for_testing(validator_set);
+
+        // Set the waypoint of the node to the genesis
+        let waypoint = Waypoint::new_epoch_boundary(&storage.get_ledger_info())
+            .expect("Unable to produce waypoint with the provided LedgerInfo");
+        config
+            .consensus
+            .safety_rules
+            .test
+            .as_mut()
+            .unwrap()
+            .waypoint = Some(waypoint);
+        config.base.waypoint = WaypointConfig::FromConfig(waypoint);
+
+        // Setup the network of the node
+        let (network_reqs_tx, network_reqs_rx) =
+            aptos_channel::new(QueueStyle::FIFO, NETWORK_QUEUE_SIZE, None);
+        let (connection_reqs_tx, _) = aptos_channel::new(QueueStyle::FIFO, 8, None);
+        let (consensus_tx, consensus_rx) =
+            aptos_channel::new(QueueStyle::FIFO, NETWORK_QUEUE_SIZE, None);
+        let network_sender = network::NetworkSender::new(
+            PeerManagerRequestSender::new(network_reqs_tx),
+            ConnectionRequestSender::new(connection_reqs_tx),
+        );
+        let network_client = NetworkClient::new(
+            DIRECT_SEND.into(),
+            RPC.into(),
+            hashmap! {NetworkId::Validator => network_sender},
+            peers_and_metadata,
+        );
+        let consensus_network_client = ConsensusNetworkClient::new(network_client);
+        let network_events = NetworkEvents::new(consensus_rx, None, true);
+        let network_service_events =
+            NetworkServiceEvents::new(hashmap! {NetworkId::Validator => network_events});
+
+        // Setup the (mock) execution of the node
+        let (state_sync_client, state_sync) = mpsc::unbounded();
+        let (ordered_blocks_tx, mut ordered_blocks_events) = mpsc::unbounded::<OrderedBlocks>();
+        let shared_mempool = MockSharedMempool::new();
+        let (quorum_store_to_mempool_sender, _) = mpsc::channel(1_024);
+        let execution_client = Arc::new(MockExecutionClient::new(
+            state_sync_client,
+            ordered_blocks_tx,
+            Arc::clone(&storage),
+        ));
+
+        // Notify the node of the genesis configs
+        let (reconfig_sender, reconfig_events) = aptos_channel::new(QueueStyle::LIFO, 1, None);
+        let reconfig_listener = ReconfigNotificationListener {
+            notification_receiver: reconfig_events,
+        };
+        let mut configs = HashMap::new();
+        configs.insert(
+            ValidatorSet::CONFIG_ID,
+            bcs::to_bytes(storage.get_validator_set()).unwrap(),
+        );
+        configs.insert(
+            OnChainConsensusConfig::CONFIG_ID,
+            // Requires double serialization, check deserialize_into_config for more details
+            bcs::to_bytes(&bcs::to_bytes(&consensus_config).unwrap()).unwrap(),
+        );
+        let payload = OnChainConfigPayload::new(1, InMemoryOnChainConfig::new(configs));
+        let reconfig_notification = ReconfigNotification {
+            version: 1,
+            on_chain_configs: payload,
+        };
+        reconfig_sender.push((), reconfig_notification).unwrap();
+
+        // Start the epoch manager with the simulated clock
+        let (timeout_sender, timeout_receiver) =
+            aptos_channels::new(1_024, &counters::PENDING_ROUND_TIMEOUTS);
+        let (self_sender, self_receiver) =
+            aptos_channels::new_unbounded(&counters::PENDING_SELF_MESSAGES);
+        let bounded_executor = BoundedExecutor::new(2, tokio::runtime::Handle::current());
+        let epoch_mgr = EpochManager::new(
+            &config,
+            Arc::new(SimulatedClock::new(time_service.clone())),
+            self_sender,
+            consensus_network_client,
+            timeout_sender,
+            quorum_store_to_mempool_sender,
+            execution_client.clone(),
+            storage.clone(),
+            Arc::new(MockQuorumStoreDB::new()),
+            reconfig_listener,
+            bounded_executor,
+            time_service,
+            VTxnPoolState::default(),
+            Arc::new(InMemRandDb::new()),
+            None,
+        );
+        let (network_task, network_receiver) =
+            NetworkTask::new(network_service_events, self_receiver);
+        tokio::spawn(network_task.start());
+        tokio::spawn(epoch_mgr.start(timeout_receiver, network_receiver));
+
+        // Commit the ordered blocks to storage, and record the commits
+        let commits = Arc::new(Mutex::new(vec![]));
+        let recorded_commits = commits.clone();
+        tokio::spawn(async move {
+            while let Some(ordered_blocks) = ordered_blocks_events.next().await {
+                let commit = ordered_blocks.ordered_proof.clone();
+                execution_client
+                    .commit_to_storage(ordered_blocks)
+                    .await
+                    .unwrap();
+                recorded_commits.lock().push(commit);
+            }
+        });
+
+        Self {
+            author,
+            commits,
+            consensus_tx,
+            network_reqs_rx,
+            _shared_mempool: shared_mempool,
+            _state_sync: state_sync,
+        }
+    }
+}
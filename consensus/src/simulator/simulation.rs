@@ -0,0 +1,424 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::simulator::{
+    message_scheduler::{Fault, FaultSchedule, MessageId, MessageScheduler},
+    simulated_node::SimulatedNode,
+};
+use anyhow::ensure;
+use aptos_config::{
+    generator::{self, ValidatorSwarm},
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_consensus_types::common::{Author, Round};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_network::{
+    application::storage::PeersAndMetadata,
+    peer_manager::PeerManagerRequest,
+    protocols::{
+        network::ReceivedMessage,
+        wire::{
+            handshake::v1::ProtocolIdSet,
+            messaging::v1::{DirectSendMsg, NetworkMessage, RpcRequest},
+        },
+    },
+    transport::ConnectionMetadata,
+    ProtocolId,
+};
+use aptos_time_service::{MockTimeService, TimeService, TimeServiceTrait};
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    on_chain_config::{
+        ConsensusConfigV1, OnChainConsensusConfig, ProposerElectionType, ValidatorSet,
+    },
+    validator_info::ValidatorInfo,
+};
+use futures::{FutureExt, StreamExt};
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::runtime::Runtime;
+
+/// The number of times the simulation yields to the runtime in every settling round
+const SETTLE_YIELDS: usize = 64;
+/// The number of consecutive settling rounds without any new message after which the nodes
+/// are considered idle.
+const SETTLE_IDLE_ROUNDS: usize = 3;
+
+/// The configuration of a simulation
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+    /// The number of validators
+    pub num_nodes: usize,
+    /// The proposer election of the validators
+    pub proposer_type: ProposerElectionType,
+    /// The simulated duration of the run
+    pub duration: Duration,
+    /// The latency of the messages without faults
+    pub base_latency: Duration,
+    /// The maximum simulated time to advance at once while messages are in flight
+    pub tick: Duration,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            num_nodes: 4,
+            proposer_type: ProposerElectionType::RotatingProposer(1),
+            duration: Duration::from_secs(30),
+            base_latency: Duration::from_millis(10),
+            tick: Duration::from_millis(10),
+        }
+    }
+}
+
+/// The outcome of a simulation
+#[derive(Clone, Debug)]
+pub struct SimulationResult {
+    /// The ledger infos committed by each node, in commit order
+    pub commits: Vec<Vec<LedgerInfoWithSignatures>>,
+    /// The faults injected during the run
+    pub injected_faults: BTreeMap<MessageId, Fault>,
+}
+
+impl SimulationResult {
+    /// Verifies that no two nodes committed different blocks for the same epoch and round
+    pub fn check_safety(&self) -> anyhow::Result<()> {
+        let mut committed_blocks = HashMap::new();
+        for (node_index, commits) in self.commits.iter().enumerate() {
+            for commit in commits {
+                let block_info = commit.ledger_info().commit_info();
+                let block_key = (block_info.epoch(), block_info.round());
+                let committed_block = committed_blocks
+                    .entry(block_key)
+                    .or_insert((node_index, block_info.id()));
+                ensure!(
+                    committed_block.1 == block_info.id(),
+                    "Nodes {} and {} committed different blocks at epoch {} round {}",
+                    committed_block.0,
+                    node_index,
+                    block_key.0,
+                    block_key.1
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the highest round committed by every node
+    pub fn min_committed_round(&self) -> Round {
+        self.commits
+            .iter()
+            .map(|commits| {
+                commits
+                    .last()
+                    .map_or(0, |commit| commit.ledger_info().commit_info().round())
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Returns a digest of the commits of all the nodes (to compare runs)
+    pub fn commits_digest(&self) -> Vec<Vec<HashValue>> {
+        self.commits
+            .iter()
+            .map(|commits| {
+                commits
+                    .iter()
+                    .map(|commit| commit.ledger_info().hash())
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// `Simulation` runs the validators of a `SimulationConfig` on a single-threaded runtime. The
+/// simulation alternates between letting the nodes run until they are idle, delivering the
+/// messages that are due, and advancing the mock clock to the next delivery or timer.
+pub struct Simulation {
+    config: SimulationConfig,
+    runtime: Option<Runtime>,
+    mock_time_service: MockTimeService,
+    nodes: Vec<SimulatedNode>,
+    author_to_index: HashMap<Author, usize>,
+    scheduler: MessageScheduler<ReceivedMessage>,
+}
+
+impl Simulation {
+    pub fn new(config: SimulationConfig, fault_schedule: FaultSchedule) -> Self {
+        // The blocking tasks of the nodes (e.g., message deserialization) run on a single thread,
+        // so they complete in the order they are spawned.
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .max_blocking_threads(1)
+            .enable_all()
+            .build()
+            .expect("Failed to create the simulation runtime");
+        let mock_time_service = MockTimeService::new();
+
+        // Generate the validators (with a fixed seed, so the validator set is always the same)
+        let ValidatorSwarm {
+            nodes: node_configs,
+        } = generator::validator_swarm_for_testing(config.num_nodes);
+        let validator_set = ValidatorSet::new(
+            node_configs
+                .iter()
+                .enumerate()
+                .map(|(index, node_config)| {
+                    let sr_test_config = node_config.consensus.safety_rules.test.as_ref().unwrap();
+                    ValidatorInfo::new_with_test_network_keys(
+                        sr_test_config.author,
+                        sr_test_config.consensus_key.as_ref().unwrap().public_key(),
+                        1,
+                        index as u64,
+                    )
+                })
+                .collect(),
+        );
+        let consensus_config = OnChainConsensusConfig::V1(ConsensusConfigV1 {
+            proposer_election_type: config.proposer_type.clone(),
+            ..ConsensusConfigV1::default()
+        });
+
+        // Connect all the validators
+        let peers_and_metadata = PeersAndMetadata::new(&[NetworkId::Validator]);
+        for node_config in &node_configs {
+            let peer_id = node_config.validator_network.as_ref().unwrap().peer_id();
+            let mut connection_metadata = ConnectionMetadata::mock(peer_id);
+            connection_metadata.application_protocols = ProtocolIdSet::from_iter([
+                ProtocolId::ConsensusDirectSendJson,
+                ProtocolId::ConsensusDirectSendBcs,
+                ProtocolId::ConsensusRpcBcs,
+            ]);
+            peers_and_metadata
+                .insert_connection_metadata(
+                    PeerNetworkId::new(NetworkId::Validator, peer_id),
+                    connection_metadata,
+                )
+                .unwrap();
+        }
+
+        // Start the validators on the simulation runtime
+        let nodes: Vec<_> = {
+            let _entered_runtime = runtime.enter();
+            node_configs
+                .into_iter()
+                .map(|node_config| {
+                    SimulatedNode::start(
+                        node_config,
+                        consensus_config.clone(),
+                        validator_set.clone(),
+                        peers_and_metadata.clone(),
+                        TimeService::from_mock(mock_time_service.clone()),
+                    )
+                })
+                .collect()
+        };
+        let author_to_index = nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (node.author, index))
+            .collect();
+        let scheduler = MessageScheduler::new(config.base_latency, fault_schedule);
+
+        Self {
+            config,
+            runtime: Some(runtime),
+            mock_time_service,
+            nodes,
+            author_to_index,
+            scheduler,
+        }
+    }
+
+    /// Runs the simulation for the configured duration and returns its outcome
+    pub fn run(mut self) -> SimulationResult {
+        let runtime = self
+            .runtime
+            .take()
+            .expect("The simulation runtime must exist");
+        runtime.block_on(async {
+            loop {
+                // Let the nodes run until they are idle
+                self.settle().await;
+
+                // Stop once the simulated duration has elapsed
+                let now = self.mock_time_service.now_unix_time();
+                if now >= self.config.duration {
+                    break;
+                }
+
+                // Deliver the messages that are due
+                let ready_messages = self.scheduler.pop_ready_messages(now);
+                if !ready_messages.is_empty() {
+                    for (message_id, message) in ready_messages {
+                        self.deliver_message(message_id, message);
+                    }
+                    continue;
+                }
+
+                // Otherwise, advance the time to the next delivery (or the next timer)
+                match self.scheduler.next_delivery_time() {
+                    Some(delivery_time) => {
+                        let time_to_advance = (delivery_time - now).min(self.config.tick);
+                        self.mock_time_service.advance(time_to_advance);
+                    },
+                    None => {
+                        if self.mock_time_service.advance_next().is_none() {
+                            break; // Nothing will ever happen again
+                        }
+                    },
+                }
+            }
+        });
+
+        let result = SimulationResult {
+            commits: self
+                .nodes
+                .iter()
+                .map(|node| node.commits.lock().clone())
+                .collect(),
+            injected_faults: self.scheduler.injected_faults().clone(),
+        };
+
+        // Stop all the tasks of the nodes
+        runtime.shutdown_background();
+        result
+    }
+
+    /// Lets the nodes run until they are idle, scheduling the messages they send
+    async fn settle(&mut self) {
+        let mut idle_rounds = 0;
+        while idle_rounds < SETTLE_IDLE_ROUNDS {
+            for _ in 0..SETTLE_YIELDS {
+                tokio::task::yield_now().await;
+                Self::wait_for_blocking_tasks();
+            }
+            if self.schedule_outbound_messages() > 0 {
+                idle_rounds = 0;
+            } else {
+                idle_rounds += 1;
+            }
+        }
+    }
+
+    /// Blocks until the blocking tasks spawned by the nodes are done, so their results are
+    /// observed by the nodes at the same point of every run (instead of whenever they complete).
+    fn wait_for_blocking_tasks() {
+        let metrics = tokio::runtime::Handle::current().metrics();
+        while metrics.blocking_queue_depth() > 0
+            || metrics.num_blocking_threads() > metrics.num_idle_blocking_threads()
+        {
+            std::thread::yield_now();
+        }
+    }
+
+    /// Schedules the messages sent by the nodes (in node order), and returns their number
+    fn schedule_outbound_messages(&mut self) -> usize {
+        let now = self.mock_time_service.now_unix_time();
+        let mut num_messages = 0;
+        for src in 0..self.nodes.len() {
+            let src_author = self.nodes[src].author;
+            let network_reqs_rx = &mut self.nodes[src].network_reqs_rx;
+            while let Some(Some(network_request)) = network_reqs_rx.next().now_or_never() {
+                // Convert the request into the message received by the recipient
+                let (dst_author, message, rpc_replier) = match network_request {
+                    PeerManagerRequest::SendDirectSend(dst_author, message) => {
+                        let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
+                            protocol_id: message.protocol_id,
+                            priority: 0,
+                            raw_msg: message.mdata.into(),
+                        });
+                        (dst_author, message, None)
+                    },
+                    PeerManagerRequest::SendRpc(dst_author, outbound_request) => {
+                        // Rpc responses are sent back to the requester directly (they are not
+                        // subject to faults). Dropping a request cancels the rpc.
+                        let message = NetworkMessage::RpcRequest(RpcRequest {
+                            protocol_id: outbound_request.protocol_id,
+                            request_id: 0,
+                            priority: 0,
+                            raw_request: outbound_request.data.into(),
+                        });
+                        (dst_author, message, Some(Arc::new(outbound_request.res_tx)))
+                    },
+                };
+                let received_message = ReceivedMessage {
+                    message,
+                    sender: PeerNetworkId::new(NetworkId::Validator, src_author),
+                    receive_timestamp_micros: 0,
+                    rpc_replier,
+                };
+
+                // Schedule the delivery of the message
+                if let Some(dst) = self.author_to_index.get(&dst_author) {
+                    self.scheduler.schedule(now, src, *dst, received_message);
+                    num_messages += 1;
+                }
+            }
+        }
+        num_messages
+    }
+
+    /// Delivers the message to its recipient
+    fn deliver_message(&self, message_id: MessageId, message: ReceivedMessage) {
+        let protocol_id = match &message.message {
+            NetworkMessage::DirectSendMsg(direct_send) => direct_send.protocol_id,
+            NetworkMessage::RpcRequest(rpc_request) => rpc_request.protocol_id,
+            _ => ProtocolId::ConsensusDirectSendBcs,
+        };
+        let src_author = self.nodes[message_id.src].author;
+        let _ = self.nodes[message_id.dst]
+            .consensus_tx
+            .push((src_author, protocol_id), message);
+    }
+}
+
+/// Shrinks the faults of a failing run to a smaller set of faults with which the run still
+/// fails (according to `fails`). Faults are removed by chunks of decreasing size, as in
+/// delta debugging, and every candidate set of faults is checked by a new run.
+pub fn shrink_faults<F>(
+    config: &SimulationConfig,
+    faults: BTreeMap<MessageId, Fault>,
+    fails: F,
+) -> BTreeMap<MessageId, Fault>
+where
+    F: Fn(&SimulationResult) -> bool,
+{
+    let mut faults: Vec<_> = faults.into_iter().collect();
+    let mut chunk_size = (faults.len() / 2).max(1);
+    while !faults.is_empty() {
+        // Try to remove every chunk of faults
+        let mut removed_faults = false;
+        let mut chunk_start = 0;
+        while chunk_start < faults.len() {
+            let chunk_end = (chunk_start + chunk_size).min(faults.len());
+            let candidate_faults: BTreeMap<_, _> = faults[..chunk_start]
+                .iter()
+                .chain(&faults[chunk_end..])
+                .copied()
+                .collect();
+            let result = Simulation::new(
+                config.clone(),
+                FaultSchedule::Explicit(candidate_faults.clone()),
+            )
+            .run();
+            if fails(&result) {
+                faults = candidate_faults.into_iter().collect();
+                removed_faults = true;
+            } else {
+                chunk_start = chunk_end;
+            }
+        }
+
+        // Move on to smaller chunks once no chunk can be removed
+        if !removed_faults {
+            if chunk_size == 1 {
+                break;
+            }
+            chunk_size /= 2;
+        }
+    }
+    faults.into_iter().collect()
+}
@@ -0,0 +1,143 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::simulator::{
+    shrink_faults, Fault, FaultConfig, FaultSchedule, MessageId, MessageScheduler, Simulation,
+    SimulationConfig, SimulationResult,
+};
+use std::{collections::BTreeMap, time::Duration};
+
+#[test]
+/// This test checks that the scheduler delivers the messages after the base latency,
+/// in the order of their ids, and that it injects the explicit faults.
+fn message_scheduler_test() {
+    let base_latency = Duration::from_millis(10);
+    let delayed_message = MessageId {
+        src: 0,
+        dst: 1,
+        seq: 0,
+    };
+    let dropped_message = MessageId {
+        src: 0,
+        dst: 2,
+        seq: 1,
+    };
+    let duplicated_message = MessageId {
+        src: 1,
+        dst: 0,
+        seq: 0,
+    };
+    let (delay, duplicate_delay) = (Duration::from_millis(5), Duration::from_millis(20));
+    let faults = BTreeMap::from([
+        (delayed_message, Fault::Delay(delay)),
+        (dropped_message, Fault::Drop),
+        (duplicated_message, Fault::Duplicate(duplicate_delay)),
+    ]);
+    let mut scheduler = MessageScheduler::new(base_latency, FaultSchedule::Explicit(faults));
+
+    // Schedule the messages
+    let now = Duration::ZERO;
+    assert_eq!(scheduler.schedule(now, 0, 1, "delayed"), delayed_message);
+    assert_eq!(scheduler.schedule(now, 0, 2, "dropped"), dropped_message);
+    let message_id = scheduler.schedule(now, 1, 0, "duplicated");
+    assert_eq!(message_id, duplicated_message);
+    scheduler.schedule(now, 1, 2, "on time");
+    assert_eq!(scheduler.injected_faults().len(), 3);
+
+    // Verify that nothing is delivered before the base latency
+    let early_messages = scheduler.pop_ready_messages(Duration::from_millis(9));
+    assert!(early_messages.is_empty());
+    assert_eq!(scheduler.next_delivery_time(), Some(base_latency));
+
+    // Verify the deliveries at the base latency
+    let messages: Vec<_> = scheduler
+        .pop_ready_messages(base_latency)
+        .into_iter()
+        .map(|(_, message)| message)
+        .collect();
+    assert_eq!(messages, vec!["duplicated", "on time"]);
+
+    // Verify the delayed delivery, and the delivery of the duplicate
+    let messages: Vec<_> = scheduler
+        .pop_ready_messages(Duration::from_millis(30))
+        .into_iter()
+        .map(|(_, message)| message)
+        .collect();
+    assert_eq!(messages, vec!["delayed", "duplicated"]);
+    assert_eq!(scheduler.next_delivery_time(), None);
+}
+
+#[test]
+/// This test checks that the validators commit (the same blocks) without faults.
+///
+/// Run the test:
+/// cargo xtest -p consensus simulation_without_faults_test -- --nocapture
+fn simulation_without_faults_test() {
+    let result = Simulation::new(SimulationConfig::default(), FaultSchedule::None).run();
+
+    result.check_safety().unwrap();
+    assert!(result.min_committed_round() > 0);
+    assert!(result.injected_faults.is_empty());
+}
+
+#[test]
+/// This test checks that a run with faults is exactly reproduced by its seed, and that
+/// the validators keep committing (the same blocks) despite the faults.
+///
+/// Run the test:
+/// cargo xtest -p consensus simulation_determinism_test -- --nocapture
+fn simulation_determinism_test() {
+    let fault_schedule = FaultSchedule::Random {
+        seed: 42,
+        config: FaultConfig::default(),
+    };
+    let first_result = Simulation::new(SimulationConfig::default(), fault_schedule.clone()).run();
+    let second_result = Simulation::new(SimulationConfig::default(), fault_schedule).run();
+
+    first_result.check_safety().unwrap();
+    assert!(first_result.min_committed_round() > 0);
+    assert!(!first_result.injected_faults.is_empty());
+    assert_eq!(first_result.injected_faults, second_result.injected_faults);
+    assert_eq!(
+        first_result.commits_digest(),
+        second_result.commits_digest()
+    );
+}
+
+#[test]
+/// This test checks that shrinking the faults of a failing run keeps only the faults
+/// required for the failure (here, a failure is the injection of a given fault).
+///
+/// Run the test:
+/// cargo xtest -p consensus shrink_faults_test -- --nocapture
+fn shrink_faults_test() {
+    // Keep the run short and the faults few, as every shrinking step is a new run
+    let config = SimulationConfig {
+        duration: Duration::from_secs(1),
+        ..SimulationConfig::default()
+    };
+    let fault_schedule = FaultSchedule::Random {
+        seed: 7,
+        config: FaultConfig {
+            drop_probability: 0.0,
+            duplicate_probability: 0.02,
+            delay_probability: 0.0,
+            ..FaultConfig::default()
+        },
+    };
+    let result = Simulation::new(config.clone(), fault_schedule).run();
+
+    // Pick the first injected fault as the cause of the failure
+    let (message_id, fault) = result
+        .injected_faults
+        .iter()
+        .next()
+        .map(|(message_id, fault)| (*message_id, *fault))
+        .expect("Some faults should have been injected");
+    let fails = |result: &SimulationResult| result.injected_faults.get(&message_id) == Some(&fault);
+    assert!(fails(&result));
+
+    // Shrink the faults and verify that only the cause of the failure is left
+    let shrunk_faults = shrink_faults(&config, result.injected_faults, fails);
+    assert_eq!(shrunk_faults, BTreeMap::from([(message_id, fault)]));
+}
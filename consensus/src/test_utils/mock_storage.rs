@@ -10,7 +10,8 @@ use crate::{
 };
 use anyhow::Result;
 use aptos_consensus_types::{
-    block::Block, quorum_cert::QuorumCert, timeout_2chain::TwoChainTimeoutCertificate, vote::Vote,
+    block::Block, equivocation::Equivocation, quorum_cert::QuorumCert,
+    timeout_2chain::TwoChainTimeoutCertificate, vote::Vote,
 };
 use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
@@ -29,6 +30,7 @@ pub struct MockSharedStorage {
     pub qc: Mutex<HashMap<HashValue, QuorumCert>>,
     pub lis: Mutex<HashMap<u64, LedgerInfoWithSignatures>>,
     pub last_vote: Mutex<Option<Vote>>,
+    pub equivocations: Mutex<Vec<Equivocation>>,
 
     // Liveness state
     pub highest_2chain_timeout_certificate: Mutex<Option<TwoChainTimeoutCertificate>>,
@@ -42,6 +44,7 @@ impl MockSharedStorage {
             qc: Mutex::new(HashMap::new()),
             lis: Mutex::new(HashMap::new()),
             last_vote: Mutex::new(None),
+            equivocations: Mutex::new(vec![]),
             highest_2chain_timeout_certificate: Mutex::new(None),
             validator_set,
         }
@@ -199,6 +202,14 @@ impl PersistentLivenessStorage for MockStorage {
         Ok(())
     }
 
+    fn save_equivocation(&self, equivocation: &Equivocation) -> Result<()> {
+        self.shared_storage
+            .equivocations
+            .lock()
+            .push(equivocation.clone());
+        Ok(())
+    }
+
     fn recover_from_ledger(&self) -> LedgerRecoveryData {
         self.get_ledger_recovery_data()
     }
@@ -272,6 +283,10 @@ impl PersistentLivenessStorage for EmptyStorage {
         Ok(())
     }
 
+    fn save_equivocation(&self, _: &Equivocation) -> Result<()> {
+        Ok(())
+    }
+
     fn recover_from_ledger(&self) -> LedgerRecoveryData {
         LedgerRecoveryData::new(LedgerInfoWithSignatures::new(
             LedgerInfo::mock_genesis(None),
@@ -20,5 +20,7 @@ pub fn is_vtxn_expected(
     match vtxn {
         ValidatorTransaction::DKGResult(_) => randomness_config.randomness_enabled(),
         ValidatorTransaction::ObservedJWKUpdate(_) => jwk_consensus_config.jwk_consensus_enabled(),
+        // Equivocation evidence is not processed on-chain yet.
+        ValidatorTransaction::EquivocationEvidence(_) => false,
     }
 }
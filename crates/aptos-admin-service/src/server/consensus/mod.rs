@@ -1,15 +1,16 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use anyhow::{bail, Error};
+use anyhow::{bail, ensure, Error};
 use aptos_consensus::{
     persistent_liveness_storage::PersistentLivenessStorage,
     quorum_store::quorum_store_db::QuorumStoreStorage, util::db_tool::extract_txns_from_block,
+    LeaderReputation, LeaderReputationExplanation,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::info;
 use aptos_system_utils::utils::{reply_with, reply_with_status, spawn_blocking};
-use aptos_types::transaction::Transaction;
+use aptos_types::{transaction::Transaction, validator_txn::ValidatorTransaction};
 use http::header::{HeaderValue, CONTENT_LENGTH};
 use hyper::{Body, Request, Response, StatusCode};
 use std::{collections::HashMap, sync::Arc};
@@ -127,6 +128,132 @@ pub async fn handle_dump_block_request(
     }
 }
 
+pub async fn handle_dump_equivocations_request(
+    req: Request<Body>,
+    consensus_db: Arc<dyn PersistentLivenessStorage>,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    // With bcs, the evidence is packaged as validator transactions.
+    let bcs: bool = match query_pairs.get("bcs") {
+        Some(val) => match val.parse() {
+            Ok(val) => val,
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => false,
+    };
+
+    info!("Dumping equivocations.");
+
+    match spawn_blocking(move || {
+        if bcs {
+            dump_equivocations_bcs(consensus_db.as_ref()).map(Into::<Body>::into)
+        } else {
+            dump_equivocations(consensus_db.as_ref()).map(Into::into)
+        }
+    })
+    .await
+    {
+        Ok(result) => {
+            info!("Finished dumping equivocations.");
+            Ok(reply_with(vec![], result))
+        },
+        Err(e) => {
+            info!("Failed to dump equivocations: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+pub async fn handle_explain_leader_reputation_request(
+    req: Request<Body>,
+    leader_reputation: Arc<LeaderReputation>,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let epoch: Option<u64> = match query_pairs.get("epoch") {
+        Some(val) => match val.parse() {
+            Ok(val) => Some(val),
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => None,
+    };
+
+    let round: u64 = match query_pairs.get("round") {
+        Some(val) => match val.parse() {
+            Ok(val) => val,
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "Missing round parameter.",
+            ))
+        },
+    };
+
+    let bcs: bool = match query_pairs.get("bcs") {
+        Some(val) => match val.parse() {
+            Ok(val) => val,
+            Err(err) => return Ok(reply_with_status(StatusCode::BAD_REQUEST, err.to_string())),
+        },
+        None => false,
+    };
+
+    // Only the leader reputation of the current epoch is kept around.
+    if let Some(epoch) = epoch {
+        if epoch != leader_reputation.epoch() {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                format!(
+                    "Only the current epoch ({}) can be explained.",
+                    leader_reputation.epoch()
+                ),
+            ));
+        }
+    }
+
+    info!("Explaining leader reputation for round {round}.");
+
+    // Rounds whose history isn't at hand can't be explained.
+    let explanation = match spawn_blocking(move || leader_reputation.explain(round)).await {
+        Ok(explanation) => explanation,
+        Err(e) => {
+            info!("Unable to explain leader reputation: {e:?}");
+            return Ok(reply_with_status(StatusCode::BAD_REQUEST, e.to_string()));
+        },
+    };
+
+    match spawn_blocking(move || {
+        if bcs {
+            bcs::to_bytes(&explanation)
+                .map_err(Error::msg)
+                .map(Into::<Body>::into)
+        } else {
+            explain_leader_reputation(&explanation).map(Into::into)
+        }
+    })
+    .await
+    {
+        Ok(result) => {
+            info!("Finished explaining leader reputation.");
+            Ok(reply_with(vec![], result))
+        },
+        Err(e) => {
+            info!("Failed to explain leader reputation: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
 fn dump_consensus_db(consensus_db: &dyn PersistentLivenessStorage) -> anyhow::Result<String> {
     let mut body = String::new();
 
@@ -238,3 +365,81 @@ fn dump_blocks_bcs(
 
     bcs::to_bytes(&all_txns).map_err(Error::msg)
 }
+
+fn dump_equivocations(consensus_db: &dyn PersistentLivenessStorage) -> anyhow::Result<String> {
+    let mut body = String::new();
+
+    for equivocation in consensus_db.consensus_db().get_equivocations()? {
+        body.push_str(&format!("{equivocation}:\n{equivocation:?}\n\n"));
+    }
+
+    if body.is_empty() {
+        body.push_str("Done, no equivocation is found.");
+    }
+
+    Ok(body)
+}
+
+fn dump_equivocations_bcs(consensus_db: &dyn PersistentLivenessStorage) -> anyhow::Result<Vec<u8>> {
+    let txns = consensus_db
+        .consensus_db()
+        .get_equivocations()?
+        .into_iter()
+        .map(|equivocation| equivocation.into_validator_txn())
+        .collect::<anyhow::Result<Vec<ValidatorTransaction>>>()?;
+
+    bcs::to_bytes(&txns).map_err(Error::msg)
+}
+
+fn explain_leader_reputation(explanation: &LeaderReputationExplanation) -> anyhow::Result<String> {
+    let mut body = String::new();
+
+    let reputations = &explanation.reputations;
+    ensure!(
+        reputations.candidates.len() == explanation.stake_weights.len(),
+        "Candidates don't match their stake weights"
+    );
+
+    body.push_str(&format!(
+        "Epoch: {}, round: {}, history up to round: {}\n",
+        explanation.epoch, explanation.round, explanation.target_round
+    ));
+    body.push_str(&format!("Proposer: {}\n", explanation.proposer));
+    body.push_str(&format!("Seed: {}\n", hex::encode(&explanation.seed)));
+    body.push_str(&format!(
+        "Voting power participation ratio: {}\n",
+        explanation.voting_power_participation_ratio
+    ));
+    for (name, window) in [
+        ("Voter", &reputations.voter_window),
+        ("Proposer", &reputations.proposer_window),
+    ] {
+        body.push_str(&format!(
+            "{name} window: {} blocks, (epoch, round) from {:?} to {:?}\n",
+            window.len(),
+            window.last(),
+            window.first(),
+        ));
+    }
+
+    body.push_str("\nCandidates: \n");
+    for ((candidate, voting_power), stake_weight) in reputations
+        .candidates
+        .iter()
+        .zip(explanation.voting_powers.iter())
+        .zip(explanation.stake_weights.iter())
+    {
+        body.push_str(&format!(
+            "[author: {}, votes: {}, proposals: {}, failed_proposals: {}, weight: {}, voting_power: {}, stake_weight: {}]\n",
+            candidate.author,
+            candidate.votes,
+            candidate.proposals,
+            candidate.failed_proposals,
+            candidate.weight,
+            voting_power,
+            stake_weight,
+        ));
+    }
+
+    Ok(body)
+}
@@ -4,6 +4,7 @@
 use aptos_config::config::{AuthenticationConfig, NodeConfig};
 use aptos_consensus::{
     persistent_liveness_storage::StorageWriteProxy, quorum_store::quorum_store_db::QuorumStoreDB,
+    LeaderReputationHandle,
 };
 use aptos_infallible::RwLock;
 use aptos_logger::info;
@@ -36,6 +37,7 @@ pub struct Context {
     aptos_db: RwLock<Option<Arc<DbReaderWriter>>>,
     consensus_db: RwLock<Option<Arc<StorageWriteProxy>>>,
     quorum_store_db: RwLock<Option<Arc<QuorumStoreDB>>>,
+    leader_reputation: RwLock<Option<LeaderReputationHandle>>,
     mempool_client_sender: RwLock<Option<MempoolClientSender>>,
 }
 
@@ -53,6 +55,10 @@ impl Context {
         *self.quorum_store_db.write() = Some(quorum_store_db);
     }
 
+    fn set_leader_reputation(&self, leader_reputation: LeaderReputationHandle) {
+        *self.leader_reputation.write() = Some(leader_reputation);
+    }
+
     fn set_mempool_client_sender(&self, mempool_client_sender: MempoolClientSender) {
         *self.mempool_client_sender.write() = Some(mempool_client_sender);
     }
@@ -114,6 +120,10 @@ impl AdminService {
             .set_consensus_dbs(consensus_db, quorum_store_db)
     }
 
+    pub fn set_leader_reputation(&self, leader_reputation: LeaderReputationHandle) {
+        self.context.set_leader_reputation(leader_reputation)
+    }
+
     pub fn set_mempool_client_sender(&self, mempool_client_sender: MempoolClientSender) {
         self.context
             .set_mempool_client_sender(mempool_client_sender)
@@ -222,6 +232,33 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::GET, "/debug/consensus/equivocations") => {
+                let consensus_db = context.consensus_db.read().clone();
+                if let Some(consensus_db) = consensus_db {
+                    consensus::handle_dump_equivocations_request(req, consensus_db).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Consensus db is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::GET, "/debug/consensus/leader_reputation") => {
+                let leader_reputation = context
+                    .leader_reputation
+                    .read()
+                    .as_ref()
+                    .and_then(|leader_reputation| leader_reputation.read().clone());
+                if let Some(leader_reputation) = leader_reputation {
+                    consensus::handle_explain_leader_reputation_request(req, leader_reputation)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Leader reputation is not available.",
+                    ))
+                }
+            },
             (hyper::Method::GET, "/debug/mempool/parking-lot/addresses") => {
                 let mempool_client_sender = context.mempool_client_sender.read().clone();
                 if mempool_client_sender.is_some() {
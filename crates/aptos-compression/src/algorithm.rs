@@ -0,0 +1,37 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+/// The compression algorithms offered by the crate. When data is compressed
+/// with a header (see `compress_with_header`), the algorithm is identified by
+/// the first byte of the compressed data, so these values must never change.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+#[repr(u8)]
+pub enum CompressionAlgorithm {
+    #[default]
+    Lz4 = 0,
+    Zstd = 1,
+}
+
+impl CompressionAlgorithm {
+    /// Returns a summary label for the algorithm
+    pub fn get_label(&self) -> &'static str {
+        match self {
+            Self::Lz4 => "lz4",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Returns the header byte identifying the algorithm
+    pub fn to_header_byte(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the algorithm identified by the given header byte (if any)
+    pub fn from_header_byte(header_byte: u8) -> Option<Self> {
+        match header_byte {
+            0 => Some(Self::Lz4),
+            1 => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    algorithm::CompressionAlgorithm,
     client::CompressionClient,
     Error::{CompressionError, DecompressionError},
 };
@@ -13,12 +14,15 @@ use thiserror::Error;
 /// This crate provides a simple library interface for data compression.
 /// It is useful for compressing large data chunks that are
 /// sent across the network (e.g., by state sync and consensus).
-/// Internally, it uses LZ4 in fast mode to compress the data.
+/// By default, it uses LZ4 in fast mode to compress the data.
 /// See <https://github.com/10xGenomics/lz4-rs> for more information.
+/// Zstd is also offered, for data that is worth a better compression
+/// ratio (see `compress_with_header` and `decompress_with_header`).
 ///
 /// Note: the crate also exposes some basic compression metrics
 /// that can be used to track the cumulative compression ratio
 /// and compression/decompression durations during the runtime.
+pub mod algorithm;
 pub mod client;
 mod metrics;
 #[cfg(test)]
@@ -28,6 +32,13 @@ mod tests;
 /// This was determined anecdotally.
 const ACCELERATION_PARAMETER: i32 = 1;
 
+/// The compression level to use for zstd. Low levels are close
+/// to LZ4 in speed, while still offering a better compression ratio.
+const ZSTD_COMPRESSION_LEVEL: i32 = 1;
+
+/// The length of the size prefix of the compressed data
+const SIZE_PREFIX_LENGTH: usize = 4;
+
 /// A useful wrapper for representing compressed data
 pub type CompressedData = Vec<u8>;
 
@@ -40,11 +51,42 @@ pub enum Error {
     DecompressionError(String),
 }
 
-/// Compresses the raw data stream
+/// Compresses the raw data stream using LZ4. The compressed data does not
+/// identify the algorithm, and must be decompressed using `decompress`.
 pub fn compress(
     raw_data: Vec<u8>,
     client: CompressionClient,
     max_bytes: usize,
+) -> Result<CompressedData, Error> {
+    compress_data(
+        raw_data,
+        client,
+        CompressionAlgorithm::Lz4,
+        false,
+        max_bytes,
+    )
+}
+
+/// Compresses the raw data stream using the given algorithm. The compressed
+/// data is prefixed with a header identifying the algorithm, and must be
+/// decompressed using `decompress_with_header`.
+pub fn compress_with_header(
+    raw_data: Vec<u8>,
+    client: CompressionClient,
+    algorithm: CompressionAlgorithm,
+    max_bytes: usize,
+) -> Result<CompressedData, Error> {
+    compress_data(raw_data, client, algorithm, true, max_bytes)
+}
+
+/// Compresses the raw data stream using the given algorithm (and prepends
+/// the algorithm header, if required).
+fn compress_data(
+    raw_data: Vec<u8>,
+    client: CompressionClient,
+    algorithm: CompressionAlgorithm,
+    include_header: bool,
+    max_bytes: usize,
 ) -> Result<CompressedData, Error> {
     // Start the compression timer
     let start_time = Instant::now();
@@ -60,11 +102,19 @@ pub fn compress(
     }
 
     // Compress the data
-    let compression_mode = CompressionMode::FAST(ACCELERATION_PARAMETER);
-    let compressed_data = match lz4::block::compress(&raw_data, Some(compression_mode), true) {
+    let header = if include_header {
+        vec![algorithm.to_header_byte()]
+    } else {
+        vec![]
+    };
+    let compressed_data = match compress_with_algorithm(&raw_data, algorithm, header) {
         Ok(compressed_data) => compressed_data,
         Err(error) => {
-            let error_string = format!("Failed to compress the data: {}", error);
+            let error_string = format!(
+                "Failed to compress the data with {}: {}",
+                algorithm.get_label(),
+                error
+            );
             return create_compression_error(&client, error_string);
         },
     };
@@ -88,16 +138,84 @@ pub fn compress(
     Ok(compressed_data)
 }
 
-/// Decompresses the compressed data stream
+/// Compresses the raw data using the given algorithm, and appends it to the
+/// given header. The compressed data is prefixed with the raw data size.
+fn compress_with_algorithm(
+    raw_data: &[u8],
+    algorithm: CompressionAlgorithm,
+    mut header: Vec<u8>,
+) -> Result<CompressedData, String> {
+    match algorithm {
+        CompressionAlgorithm::Lz4 => {
+            // LZ4 prepends the raw data size itself
+            let compression_mode = CompressionMode::FAST(ACCELERATION_PARAMETER);
+            let compressed_data = lz4::block::compress(raw_data, Some(compression_mode), true)
+                .map_err(|error| error.to_string())?;
+            if header.is_empty() {
+                return Ok(compressed_data);
+            }
+            header.extend_from_slice(&compressed_data);
+            Ok(header)
+        },
+        CompressionAlgorithm::Zstd => {
+            // Prepend the raw data size, in the same format as LZ4
+            let raw_data_size = i32::try_from(raw_data.len())
+                .map_err(|_| format!("Raw data is too large: {}", raw_data.len()))?;
+            let compressed_data = zstd::bulk::compress(raw_data, ZSTD_COMPRESSION_LEVEL)
+                .map_err(|error| error.to_string())?;
+            header.extend_from_slice(&raw_data_size.to_le_bytes());
+            header.extend_from_slice(&compressed_data);
+            Ok(header)
+        },
+    }
+}
+
+/// Decompresses the compressed data stream (compressed using `compress`)
 pub fn decompress(
     compressed_data: &CompressedData,
     client: CompressionClient,
     max_size: usize,
+) -> Result<Vec<u8>, Error> {
+    decompress_data(compressed_data, client, CompressionAlgorithm::Lz4, max_size)
+}
+
+/// Decompresses the compressed data stream (compressed using `compress_with_header`)
+/// using the algorithm identified by the header.
+pub fn decompress_with_header(
+    compressed_data: &CompressedData,
+    client: CompressionClient,
+    max_size: usize,
+) -> Result<Vec<u8>, Error> {
+    // Parse the algorithm header
+    let (header_byte, compressed_data) = match compressed_data.split_first() {
+        Some((header_byte, compressed_data)) => (*header_byte, compressed_data),
+        None => {
+            let error_string = "Compressed data is missing the algorithm header!".into();
+            return create_decompression_error(&client, error_string);
+        },
+    };
+    let algorithm = match CompressionAlgorithm::from_header_byte(header_byte) {
+        Some(algorithm) => algorithm,
+        None => {
+            let error_string = format!("Unknown compression algorithm header: {}", header_byte);
+            return create_decompression_error(&client, error_string);
+        },
+    };
+
+    decompress_data(compressed_data, client, algorithm, max_size)
+}
+
+/// Decompresses the compressed data stream using the given algorithm
+fn decompress_data(
+    compressed_data: &[u8],
+    client: CompressionClient,
+    algorithm: CompressionAlgorithm,
+    max_size: usize,
 ) -> Result<Vec<u8>, Error> {
     // Start the decompression timer
     let start_time = Instant::now();
 
-    // Check size of the data and initialize raw_data
+    // Check size of the data
     let decompressed_size = match get_decompressed_size(compressed_data, max_size) {
         Ok(size) => size,
         Err(error) => {
@@ -105,12 +223,18 @@ pub fn decompress(
             return create_decompression_error(&client, error_string);
         },
     };
-    let mut raw_data = vec![0u8; decompressed_size];
 
     // Decompress the data
-    if let Err(error) = lz4::block::decompress_to_buffer(compressed_data, None, &mut raw_data) {
-        let error_string = format!("Failed to decompress the data: {}", error);
-        return create_decompression_error(&client, error_string);
+    let raw_data = match decompress_with_algorithm(compressed_data, algorithm, decompressed_size) {
+        Ok(raw_data) => raw_data,
+        Err(error) => {
+            let error_string = format!(
+                "Failed to decompress the data with {}: {}",
+                algorithm.get_label(),
+                error
+            );
+            return create_decompression_error(&client, error_string);
+        },
     };
 
     // Stop the timer and update the metrics
@@ -120,6 +244,36 @@ pub fn decompress(
     Ok(raw_data)
 }
 
+/// Decompresses the compressed data (prefixed with the given raw data size)
+/// using the given algorithm.
+fn decompress_with_algorithm(
+    compressed_data: &[u8],
+    algorithm: CompressionAlgorithm,
+    decompressed_size: usize,
+) -> Result<Vec<u8>, String> {
+    match algorithm {
+        CompressionAlgorithm::Lz4 => {
+            let mut raw_data = vec![0u8; decompressed_size];
+            lz4::block::decompress_to_buffer(compressed_data, None, &mut raw_data)
+                .map_err(|error| error.to_string())?;
+            Ok(raw_data)
+        },
+        CompressionAlgorithm::Zstd => {
+            let raw_data =
+                zstd::bulk::decompress(&compressed_data[SIZE_PREFIX_LENGTH..], decompressed_size)
+                    .map_err(|error| error.to_string())?;
+            if raw_data.len() != decompressed_size {
+                return Err(format!(
+                    "Decompressed size does not match the size prefix: {}, expected: {}",
+                    raw_data.len(),
+                    decompressed_size
+                ));
+            }
+            Ok(raw_data)
+        },
+    }
+}
+
 /// A simple utility function that wraps the given error string in a compression error
 fn create_compression_error(
     client: &CompressionClient,
@@ -147,12 +301,9 @@ fn create_decompression_error(
 /// Derived from the lz4-rs crate, which prepends the compressed payload
 /// with the original data size as i32.
 /// See: https://github.com/10XGenomics/lz4-rs/blob/0abc0a52af1f6010f9a57640b1dc8eb8d2d697aa/src/block/mod.rs#L162
-fn get_decompressed_size(
-    compressed_data: &CompressedData,
-    max_size: usize,
-) -> Result<usize, Error> {
+fn get_decompressed_size(compressed_data: &[u8], max_size: usize) -> Result<usize, Error> {
     // Ensure that the compressed data is at least 4 bytes long
-    if compressed_data.len() < 4 {
+    if compressed_data.len() < SIZE_PREFIX_LENGTH {
         return Err(DecompressionError(format!(
             "Compressed data must be at least 4 bytes long! Got: {}",
             compressed_data.len()
@@ -193,11 +344,11 @@ mod test {
         let max_compression_size = 100;
 
         // Verify that an error is returned when the compressed data length is zero
-        let result = get_decompressed_size(&vec![0u8; 0], max_compression_size);
+        let result = get_decompressed_size(&[0u8; 0], max_compression_size);
         assert!(result.is_err());
 
         // Verify that an error is returned when the compressed data length is too small
-        let result = get_decompressed_size(&vec![0u8; 3], max_compression_size);
+        let result = get_decompressed_size(&[0u8; 3], max_compression_size);
         assert!(result.is_err());
 
         // Verify that an error is returned when the compressed data length is too large
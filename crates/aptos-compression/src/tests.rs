@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::CompressionClient;
+use crate::{algorithm::CompressionAlgorithm, CompressionClient};
 use aptos_crypto::{ed25519::Ed25519PrivateKey, hash::HashValue, PrivateKey, SigningKey, Uniform};
 use aptos_types::{
     account_address::AccountAddress,
@@ -74,12 +74,51 @@ fn test_compression_limits() {
     assert!(maybe_decompressed_bytes.is_err());
 }
 
+#[test]
+fn test_compression_with_header() {
+    // Create test data
+    let transactions_with_proof = create_transaction_list_with_proof(1000, 1999, 1999, true);
+    let bcs_encoded_bytes = bcs::to_bytes(&transactions_with_proof).unwrap();
+
+    // Verify that the algorithm is identified by the header
+    for algorithm in [CompressionAlgorithm::Lz4, CompressionAlgorithm::Zstd] {
+        let compressed_bytes = crate::compress_with_header(
+            bcs_encoded_bytes.clone(),
+            CompressionClient::StateSync,
+            algorithm,
+            MAX_COMPRESSION_SIZE,
+        )
+        .unwrap();
+        assert_eq!(compressed_bytes[0], algorithm.to_header_byte());
+
+        // Verify that data with a corrupted header fails to decompress
+        let mut corrupted_bytes = compressed_bytes.clone();
+        corrupted_bytes[0] = u8::MAX;
+        let maybe_decompressed_bytes = crate::decompress_with_header(
+            &corrupted_bytes,
+            CompressionClient::StateSync,
+            MAX_COMPRESSION_SIZE,
+        );
+        assert!(maybe_decompressed_bytes.is_err());
+
+        // Verify that the decompression limit is enforced
+        let maybe_decompressed_bytes =
+            crate::decompress_with_header(&compressed_bytes, CompressionClient::StateSync, 1);
+        assert!(maybe_decompressed_bytes.is_err());
+    }
+
+    // Verify that data without a header fails to decompress
+    let maybe_decompressed_bytes =
+        crate::decompress_with_header(&vec![], CompressionClient::StateSync, MAX_COMPRESSION_SIZE);
+    assert!(maybe_decompressed_bytes.is_err());
+}
+
 /// Ensures that the given object can be compressed and decompressed successfully
-/// when BCS encoded.
+/// when BCS encoded (using both the legacy format and each algorithm with a header).
 fn test_compress_and_decompress<T: Debug + DeserializeOwned + PartialEq + Serialize>(object: T) {
     let bcs_encoded_bytes = bcs::to_bytes(&object).unwrap();
     let compressed_bytes = crate::compress(
-        bcs_encoded_bytes,
+        bcs_encoded_bytes.clone(),
         CompressionClient::StateSync,
         MAX_COMPRESSION_SIZE,
     )
@@ -91,8 +130,25 @@ fn test_compress_and_decompress<T: Debug + DeserializeOwned + PartialEq + Serial
     )
     .unwrap();
     let decoded_object = bcs::from_bytes::<T>(&decompressed_bytes).unwrap();
-
     assert_eq!(object, decoded_object);
+
+    for algorithm in [CompressionAlgorithm::Lz4, CompressionAlgorithm::Zstd] {
+        let compressed_bytes = crate::compress_with_header(
+            bcs_encoded_bytes.clone(),
+            CompressionClient::StateSync,
+            algorithm,
+            MAX_COMPRESSION_SIZE,
+        )
+        .unwrap();
+        let decompressed_bytes = crate::decompress_with_header(
+            &compressed_bytes,
+            CompressionClient::StateSync,
+            MAX_COMPRESSION_SIZE,
+        )
+        .unwrap();
+        let decoded_object = bcs::from_bytes::<T>(&decompressed_bytes).unwrap();
+        assert_eq!(object, decoded_object);
+    }
 }
 
 /// Creates a test epoch change proof
@@ -23,9 +23,9 @@ pub use aptos_api_types::{
 use aptos_api_types::{
     deserialize_from_string,
     mime_types::{BCS, BCS_SIGNED_TRANSACTION, BCS_VIEW_FUNCTION, JSON},
-    AptosError, BcsBlock, Block, GasEstimation, HexEncodedBytes, IndexResponse, MoveModuleId,
-    TransactionData, TransactionOnChainData, TransactionsBatchSubmissionResult, UserTransaction,
-    VersionedEvent, ViewFunction, ViewRequest,
+    AccountMempoolTransactions, AptosError, BcsBlock, Block, GasEstimation, HexEncodedBytes,
+    IndexResponse, MoveModuleId, TransactionData, TransactionOnChainData,
+    TransactionsBatchSubmissionResult, UserTransaction, VersionedEvent, ViewFunction, ViewRequest,
 };
 use aptos_crypto::HashValue;
 use aptos_logger::{debug, info, sample, sample::SampleRate};
@@ -953,6 +953,19 @@ impl Client {
         self.json(response).await
     }
 
+    /// Lists the transactions of an account in the mempool of the node, and whether each one
+    /// is ready or parked behind a missing sequence number.
+    pub async fn get_account_mempool_transactions(
+        &self,
+        address: AccountAddress,
+    ) -> AptosResult<Response<AccountMempoolTransactions>> {
+        let url = self.build_path(&format!(
+            "accounts/{}/mempool_transactions",
+            address.to_hex()
+        ))?;
+        self.get(url).await
+    }
+
     pub async fn get_account_transactions_bcs(
         &self,
         address: AccountAddress,
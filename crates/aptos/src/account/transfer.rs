@@ -51,21 +51,30 @@ impl CliCommand<TransferSummary> for TransferCoins {
 const SUPPORTED_COINS: [&str; 1] = ["0x1::coin::CoinStore<0x1::aptos_coin::AptosCoin>"];
 
 /// A shortened transaction output
+///
+/// The outcome of the transfer is only known once it's committed, not when it's saved with
+/// `--output-unsigned-transaction`.
 #[derive(Clone, Debug, Serialize)]
 pub struct TransferSummary {
     pub gas_unit_price: u64,
-    pub gas_used: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_used: Option<u64>,
     pub balance_changes: BTreeMap<AccountAddress, serde_json::Value>,
     pub sender: AccountAddress,
-    pub success: bool,
-    pub version: u64,
-    pub vm_status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pending: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vm_status: Option<String>,
     pub transaction_hash: HashValue,
 }
 
 impl TransferSummary {
     pub fn octa_spent(&self) -> u64 {
-        self.gas_unit_price * self.gas_used
+        self.gas_unit_price * self.gas_used.unwrap_or_default()
     }
 }
 
@@ -100,16 +109,29 @@ impl From<Transaction> for TransferSummary {
 
             TransferSummary {
                 gas_unit_price,
-                gas_used,
+                gas_used: Some(gas_used),
                 balance_changes,
                 sender,
-                success,
-                version,
-                vm_status,
+                pending: None,
+                success: Some(success),
+                version: Some(version),
+                vm_status: Some(vm_status),
                 transaction_hash,
             }
+        } else if let Transaction::PendingTransaction(txn) = transaction {
+            TransferSummary {
+                gas_unit_price: txn.request.gas_unit_price.0,
+                gas_used: None,
+                balance_changes: BTreeMap::new(),
+                sender: *txn.request.sender.inner(),
+                pending: Some(true),
+                success: None,
+                version: None,
+                vm_status: None,
+                transaction_hash: txn.hash,
+            }
         } else {
-            panic!("Can't call From<Transaction> for a non UserTransaction")
+            panic!("Can't call From<Transaction> for a non user transaction")
         }
     }
 }
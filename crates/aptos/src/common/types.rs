@@ -1,7 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use super::utils::{explorer_transaction_link, fund_account};
+use super::utils::fund_account;
 use crate::{
     common::{
         init::Network,
@@ -9,14 +9,15 @@ use crate::{
         utils::{
             check_if_file_exists, create_dir_if_not_exist, dir_default_to_current,
             get_account_with_state, get_auth_key, get_sequence_number, parse_json_file,
-            prompt_yes_with_override, read_from_file, start_logger, to_common_result,
-            to_common_success_result, write_to_file, write_to_file_with_opts,
+            prompt_yes_with_override, read_from_file, start_logger, submit_signed_transaction,
+            to_common_result, to_common_success_result, write_to_file, write_to_file_with_opts,
             write_to_user_only_file,
         },
     },
     config::GlobalConfig,
     genesis::git::from_yaml,
     move_tool::{ArgWithType, FunctionArgType, MemberId},
+    transaction::UnsignedTransaction,
 };
 use anyhow::{bail, Context};
 use aptos_api_types::ViewFunction;
@@ -64,6 +65,7 @@ use std::{
     fs::OpenOptions,
     path::PathBuf,
     str::FromStr,
+    sync::atomic::{AtomicU64, Ordering},
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 use thiserror::Error;
@@ -1637,6 +1639,40 @@ pub struct TransactionOptions {
     /// flamegraphs that reflect the gas usage.
     #[clap(long)]
     pub(crate) profile_gas: bool,
+
+    /// Save the transaction unsigned to this file, instead of signing and submitting it
+    ///
+    /// The transaction is saved as BCS, along with a JSON copy for review at `<file>.json`.
+    /// It can then be signed offline with `aptos transaction sign`, and submitted with
+    /// `aptos transaction submit`. Set `--expiration-secs` to leave enough time to collect
+    /// the signatures.
+    ///
+    /// Commands submitting several transactions save them to `<file>`, `<file>.1`, `<file>.2`,
+    /// ... with increasing sequence numbers, to be submitted in that order. Set `--max-gas` if
+    /// they depend on each other, as each one is simulated without the ones before it.
+    #[clap(long, value_parser)]
+    pub(crate) output_unsigned_transaction: Option<PathBuf>,
+
+    /// Secondary signers of the unsigned transaction, for a multi-agent transaction
+    #[clap(
+        long,
+        num_args = 0..,
+        value_parser = crate::common::types::load_account_arg,
+        requires = "output_unsigned_transaction"
+    )]
+    pub(crate) secondary_signer_addresses: Vec<AccountAddress>,
+
+    /// Account paying the gas fee of the unsigned transaction instead of the sender
+    #[clap(
+        long,
+        value_parser = crate::common::types::load_account_arg,
+        requires = "output_unsigned_transaction"
+    )]
+    pub(crate) fee_payer_address: Option<AccountAddress>,
+
+    /// Number of transactions saved with `--output-unsigned-transaction` so far
+    #[clap(skip)]
+    pub(crate) num_unsigned_transactions_saved: AtomicU64,
 }
 
 impl TransactionOptions {
@@ -1725,6 +1761,17 @@ impl TransactionOptions {
         let client = self.rest_client()?;
         let (sender_public_key, sender_address) = self.get_public_key_and_address()?;
 
+        // Simulation is only signed by the sender, and charges it the gas fee, so it can't
+        // estimate gas for the other signers
+        if (!self.secondary_signer_addresses.is_empty() || self.fee_payer_address.is_some())
+            && self.gas_options.max_gas.is_none()
+        {
+            return Err(CliError::CommandArgumentError(
+                "--max-gas is required for a transaction with secondary signers or a fee payer"
+                    .to_string(),
+            ));
+        }
+
         // Ask to confirm price if the gas unit price is estimated above the lowest value when
         // it is automatically estimated
         let ask_to_confirm_price;
@@ -1819,6 +1866,27 @@ impl TransactionOptions {
             .with_max_gas_amount(max_gas)
             .with_transaction_expiration_time(self.gas_options.expiration_secs);
 
+        // Save it to be signed offline instead. The transactions saved before it by the same
+        // command aren't on chain yet, so its sequence number follows theirs.
+        if let Some(output_file) = &self.output_unsigned_transaction {
+            let index = self
+                .num_unsigned_transactions_saved
+                .fetch_add(1, Ordering::Relaxed);
+            let output_file = UnsignedTransaction::output_file(output_file, index);
+            let unsigned_transaction = UnsignedTransaction {
+                raw_txn: transaction_factory
+                    .payload(payload)
+                    .sender(sender_address)
+                    .sequence_number(sequence_number + index)
+                    .build(),
+                secondary_signers: self.secondary_signer_addresses.clone(),
+                fee_payer_address: self.fee_payer_address,
+            };
+            unsigned_transaction.save(&output_file, self.prompt_options)?;
+            eprintln!("Unsigned transaction saved: {}", output_file.display());
+            return unsigned_transaction.to_pending_transaction();
+        }
+
         // Sign it with the appropriate signer
         let transaction = match self.get_transaction_account_type() {
             Ok(AccountType::Local) => {
@@ -1845,26 +1913,12 @@ impl TransactionOptions {
         };
 
         // Submit the transaction, printing out a useful transaction link
-        client
-            .submit_bcs(&transaction)
-            .await
-            .map_err(|err| CliError::ApiError(err.to_string()))?;
-        let transaction_hash = transaction.clone().committed_hash();
         let network = self
             .profile_options
             .profile()
             .ok()
             .and_then(|profile| profile.network);
-        eprintln!(
-            "Transaction submitted: {}",
-            explorer_transaction_link(transaction_hash, network)
-        );
-        let response = client
-            .wait_for_signed_transaction(&transaction)
-            .await
-            .map_err(|err| CliError::ApiError(err.to_string()))?;
-
-        Ok(response.into_inner())
+        submit_signed_transaction(&client, &transaction, network).await
     }
 
     /// Simulates a transaction locally, using the debugger to fetch required data from remote.
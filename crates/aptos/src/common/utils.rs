@@ -16,13 +16,15 @@ use aptos_build_info::build_information;
 use aptos_crypto::ed25519::{Ed25519PrivateKey, Ed25519PublicKey};
 use aptos_keygen::KeyGen;
 use aptos_logger::{debug, Level};
-use aptos_rest_client::{aptos_api_types::HashValue, Account, Client, FaucetClient, State};
+use aptos_rest_client::{
+    aptos_api_types::HashValue, Account, Client, FaucetClient, State, Transaction,
+};
 use aptos_telemetry::service::telemetry_is_disabled;
 use aptos_types::{
     account_address::create_multisig_account_address,
     chain_id::ChainId,
     on_chain_config::{FeatureFlag, Features},
-    transaction::{authenticator::AuthenticationKey, TransactionPayload},
+    transaction::{authenticator::AuthenticationKey, SignedTransaction, TransactionPayload},
 };
 use itertools::Itertools;
 use move_core_types::{account_address::AccountAddress, language_storage::CORE_CODE_ADDRESS};
@@ -579,6 +581,30 @@ pub fn explorer_account_link(hash: AccountAddress, network: Option<Network>) ->
     }
 }
 
+/// Submits a signed transaction, printing out a link to it in the explorer, and waits for it to
+/// be committed
+pub async fn submit_signed_transaction(
+    client: &Client,
+    transaction: &SignedTransaction,
+    network: Option<Network>,
+) -> CliTypedResult<Transaction> {
+    client
+        .submit_bcs(transaction)
+        .await
+        .map_err(|err| CliError::ApiError(err.to_string()))?;
+    let transaction_hash = transaction.clone().committed_hash();
+    eprintln!(
+        "Transaction submitted: {}",
+        explorer_transaction_link(transaction_hash, network)
+    );
+    let response = client
+        .wait_for_signed_transaction(transaction)
+        .await
+        .map_err(|err| CliError::ApiError(err.to_string()))?;
+
+    Ok(response.into_inner())
+}
+
 pub fn explorer_transaction_link(
     hash: aptos_crypto::HashValue,
     network: Option<Network>,
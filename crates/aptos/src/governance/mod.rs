@@ -461,6 +461,10 @@ fn extract_proposal_id(txn: &Transaction) -> CliTypedResult<Option<u64>> {
 
         return Ok(proposal_id);
     }
+    // The transaction was saved unsigned, so it has no output yet
+    if let Transaction::PendingTransaction(_) = txn {
+        return Ok(None);
+    }
     Err(CliError::UnexpectedError(
         "Unable to find parse proposal transaction output".to_string(),
     ))
@@ -1545,6 +1545,15 @@ async fn submit_chunked_publish_transactions(
     let payloads_length = payloads.len() as u64;
     let mut tx_hashes = vec![];
 
+    // Saved chunks aren't staged on chain, so the publishing transaction would fail simulation
+    if txn_options.output_unsigned_transaction.is_some()
+        && txn_options.gas_options.max_gas.is_none()
+    {
+        return Err(CliError::CommandArgumentError(
+            "--max-gas is required to save the transactions of a chunked publish".to_string(),
+        ));
+    }
+
     let account_address = txn_options.profile_options.account_address()?;
 
     if !is_staging_area_empty(txn_options).await? {
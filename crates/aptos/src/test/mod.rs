@@ -45,8 +45,13 @@ use crate::{
         AddStake, IncreaseLockup, InitializeStakeOwner, SetDelegatedVoter, SetOperator,
         UnlockStake, WithdrawStake,
     },
+    transaction::{
+        sign::{SignSummary, SignTransaction},
+        submit::SubmitTransaction,
+    },
     CliCommand,
 };
+use aptos_cached_packages::aptos_stdlib;
 use aptos_config::config::Peer;
 use aptos_crypto::{
     bls12381,
@@ -345,6 +350,72 @@ impl CliTestFramework {
         .await
     }
 
+    /// Saves transfers unsigned, rather than submitting them, with a single set of transaction
+    /// options as a command submitting several transactions does. The first one is saved to
+    /// `output_file`, the following ones to `<output_file>.1`, `<output_file>.2`, ...
+    pub async fn save_unsigned_transfers(
+        &self,
+        sender_index: usize,
+        receiver_index: usize,
+        amounts: &[u64],
+        fee_payer_index: Option<usize>,
+        gas_options: Option<GasOptions>,
+        output_file: PathBuf,
+    ) -> CliTypedResult<Vec<TransactionSummary>> {
+        let txn_options = TransactionOptions {
+            output_unsigned_transaction: Some(output_file),
+            fee_payer_address: fee_payer_index.map(|index| self.account_id(index)),
+            ..self.transaction_options(sender_index, gas_options)
+        };
+        let mut summaries = vec![];
+        for amount in amounts {
+            let transaction = txn_options
+                .submit_transaction(aptos_stdlib::aptos_account_transfer(
+                    self.account_id(receiver_index),
+                    *amount,
+                ))
+                .await?;
+            summaries.push(TransactionSummary::from(transaction));
+        }
+        Ok(summaries)
+    }
+
+    pub async fn sign_transaction(
+        &self,
+        index: usize,
+        unsigned_transaction_file: PathBuf,
+        output_file: PathBuf,
+    ) -> CliTypedResult<SignSummary> {
+        SignTransaction {
+            unsigned_transaction_file,
+            signer_account: Some(self.account_id(index)),
+            private_key_options: PrivateKeyInputOptions::from_private_key(self.private_key(index))?,
+            encoding_options: Default::default(),
+            profile_options: Default::default(),
+            save_file: SaveFile {
+                output_file,
+                prompt_options: PromptOptions::yes(),
+            },
+        }
+        .execute()
+        .await
+    }
+
+    pub async fn submit_signed_transaction(
+        &self,
+        unsigned_transaction_file: PathBuf,
+        signature_files: Vec<PathBuf>,
+    ) -> CliTypedResult<TransactionSummary> {
+        SubmitTransaction {
+            unsigned_transaction_file,
+            signature_files,
+            rest_options: self.rest_options(),
+            profile_options: Default::default(),
+        }
+        .execute()
+        .await
+    }
+
     pub async fn show_validator_config(
         &self,
         pool_index: usize,
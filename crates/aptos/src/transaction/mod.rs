@@ -0,0 +1,386 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::common::{
+    types::{CliCommand, CliError, CliResult, CliTypedResult, PromptOptions},
+    utils::{check_if_file_exists, read_from_file, write_to_file},
+};
+use aptos_crypto::{ed25519::Ed25519PrivateKey, hash::CryptoHash, PrivateKey, SigningKey};
+use aptos_rest_client::{
+    aptos_api_types::{
+        EntryFunctionId, EntryFunctionPayload, HexEncodedBytes, MoveScriptBytecode,
+        MultisigPayload, MultisigTransactionPayload, PendingTransaction, ScriptPayload,
+        TransactionPayload, UserTransactionRequest,
+    },
+    Transaction,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{
+        self, authenticator::AccountAuthenticator, EntryFunction, RawTransaction,
+        RawTransactionWithData, SignedTransaction,
+    },
+};
+use clap::Subcommand;
+use move_core_types::transaction_argument::convert_txn_args;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+pub mod sign;
+pub mod submit;
+
+/// Tool for signing and submitting transactions offline
+///
+/// Any command submitting a transaction can save it unsigned instead with
+/// `--output-unsigned-transaction`. The saved transaction is signed by each of its signers with
+/// `aptos transaction sign`, which doesn't need network access, and the signatures are then
+/// submitted together with `aptos transaction submit`.
+#[derive(Debug, Subcommand)]
+pub enum TransactionTool {
+    Sign(sign::SignTransaction),
+    Submit(submit::SubmitTransaction),
+}
+
+impl TransactionTool {
+    pub async fn execute(self) -> CliResult {
+        match self {
+            TransactionTool::Sign(tool) => tool.execute_serialized().await,
+            TransactionTool::Submit(tool) => tool.execute_serialized().await,
+        }
+    }
+}
+
+/// A transaction saved with `--output-unsigned-transaction`, along with the accounts that have to
+/// sign it besides its sender
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UnsignedTransaction {
+    pub raw_txn: RawTransaction,
+    pub secondary_signers: Vec<AccountAddress>,
+    pub fee_payer_address: Option<AccountAddress>,
+}
+
+impl UnsignedTransaction {
+    /// Loads an unsigned transaction from its BCS file
+    pub fn load(path: &Path) -> CliTypedResult<Self> {
+        bcs::from_bytes(&read_from_file(path)?)
+            .map_err(|err| CliError::BCS("unsigned transaction", err))
+    }
+
+    /// Saves the unsigned transaction as BCS to `path`, and as JSON to `<path>.json` for review
+    pub fn save(&self, path: &Path, prompt_options: PromptOptions) -> CliTypedResult<()> {
+        let json_path = Self::json_path(path);
+        check_if_file_exists(path, prompt_options)?;
+        check_if_file_exists(&json_path, prompt_options)?;
+
+        let bytes =
+            bcs::to_bytes(self).map_err(|err| CliError::BCS("unsigned transaction", err))?;
+        write_to_file(path, "Unsigned transaction", &bytes)?;
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        write_to_file(&json_path, "Unsigned transaction JSON", json.as_bytes())
+    }
+
+    fn json_path(path: &Path) -> PathBuf {
+        let mut json_path = path.as_os_str().to_owned();
+        json_path.push(".json");
+        json_path.into()
+    }
+
+    /// File of the `index`-th transaction saved by a command: commands submitting several
+    /// transactions save the first one to `path`, and the following ones to `<path>.1`,
+    /// `<path>.2`, ...
+    pub fn output_file(path: &Path, index: u64) -> PathBuf {
+        if index == 0 {
+            return path.to_path_buf();
+        }
+        let mut output_file = path.as_os_str().to_owned();
+        output_file.push(format!(".{}", index));
+        output_file.into()
+    }
+
+    /// The transaction as it's reported to the user after it was saved, pending and without a
+    /// signature. As the hash of the submitted transaction depends on its signatures, the hash of
+    /// the `RawTransaction` is reported instead.
+    pub fn to_pending_transaction(&self) -> CliTypedResult<Transaction> {
+        let raw_txn = &self.raw_txn;
+        Ok(Transaction::PendingTransaction(PendingTransaction {
+            hash: raw_txn.hash().into(),
+            request: UserTransactionRequest {
+                sender: raw_txn.sender().into(),
+                sequence_number: raw_txn.sequence_number().into(),
+                max_gas_amount: raw_txn.max_gas_amount().into(),
+                gas_unit_price: raw_txn.gas_unit_price().into(),
+                expiration_timestamp_secs: raw_txn.expiration_timestamp_secs().into(),
+                payload: api_payload(raw_txn.payload().clone())?,
+                signature: None,
+            },
+        }))
+    }
+
+    /// All accounts that have to sign the transaction, starting with its sender
+    pub fn signers(&self) -> Vec<AccountAddress> {
+        let mut signers = vec![self.raw_txn.sender()];
+        signers.extend(self.secondary_signers.iter().copied());
+        signers.extend(self.fee_payer_address);
+        signers
+    }
+
+    /// Signs the transaction as one of its signers. The sender, the secondary signers and the fee
+    /// payer all sign the same message, so the signature is valid for every role of the signer.
+    pub fn sign(&self, private_key: &Ed25519PrivateKey) -> CliTypedResult<AccountAuthenticator> {
+        let raw_txn = self.raw_txn.clone();
+        let secondary_signers = self.secondary_signers.clone();
+        let signature = match self.fee_payer_address {
+            Some(fee_payer_address) => private_key.sign(&RawTransactionWithData::new_fee_payer(
+                raw_txn,
+                secondary_signers,
+                fee_payer_address,
+            )),
+            None if secondary_signers.is_empty() => private_key.sign(&raw_txn),
+            None => private_key.sign(&RawTransactionWithData::new_multi_agent(
+                raw_txn,
+                secondary_signers,
+            )),
+        }
+        .map_err(|err| CliError::UnexpectedError(format!("Failed to sign transaction: {}", err)))?;
+
+        Ok(AccountAuthenticator::ed25519(
+            private_key.public_key(),
+            signature,
+        ))
+    }
+
+    /// Assembles the signed transaction from the signatures of all of its signers, checking them
+    pub fn into_signed_transaction(
+        self,
+        signatures: Vec<SignerSignature>,
+    ) -> CliTypedResult<SignedTransaction> {
+        let signers = self.signers();
+        let mut authenticators = BTreeMap::new();
+        for SignerSignature {
+            signer,
+            authenticator,
+        } in signatures
+        {
+            if !signers.contains(&signer) {
+                return Err(CliError::CommandArgumentError(format!(
+                    "Account {} is not a signer of the transaction",
+                    signer
+                )));
+            }
+            authenticators.insert(signer, authenticator);
+        }
+        let authenticator_of = |signer: &AccountAddress| {
+            authenticators.get(signer).cloned().ok_or_else(|| {
+                CliError::CommandArgumentError(format!("Missing signature of account {}", signer))
+            })
+        };
+
+        let sender = authenticator_of(&self.raw_txn.sender())?;
+        let secondary_authenticators = self
+            .secondary_signers
+            .iter()
+            .map(authenticator_of)
+            .collect::<CliTypedResult<Vec<_>>>()?;
+        let transaction = match self.fee_payer_address {
+            Some(fee_payer_address) => SignedTransaction::new_fee_payer(
+                self.raw_txn,
+                sender,
+                self.secondary_signers,
+                secondary_authenticators,
+                fee_payer_address,
+                authenticator_of(&fee_payer_address)?,
+            ),
+            None if self.secondary_signers.is_empty() => match sender {
+                AccountAuthenticator::Ed25519 {
+                    public_key,
+                    signature,
+                } => SignedTransaction::new(self.raw_txn, public_key, signature),
+                sender => SignedTransaction::new_single_sender(self.raw_txn, sender),
+            },
+            None => SignedTransaction::new_multi_agent(
+                self.raw_txn,
+                sender,
+                self.secondary_signers,
+                secondary_authenticators,
+            ),
+        };
+
+        transaction
+            .check_signature()
+            .map(|transaction| transaction.into_inner())
+            .map_err(|err| {
+                CliError::CommandArgumentError(format!("Invalid transaction signatures: {}", err))
+            })
+    }
+}
+
+/// Converts a payload for display, without the ABIs needed to decode the arguments: they are shown
+/// as BCS bytes
+fn api_payload(payload: transaction::TransactionPayload) -> CliTypedResult<TransactionPayload> {
+    Ok(match payload {
+        transaction::TransactionPayload::Script(script) => {
+            let (code, ty_args, args) = script.into_inner();
+            TransactionPayload::ScriptPayload(ScriptPayload {
+                code: MoveScriptBytecode::new(code),
+                type_arguments: ty_args.iter().map(Into::into).collect(),
+                arguments: json_args(convert_txn_args(&args))?,
+            })
+        },
+        transaction::TransactionPayload::EntryFunction(entry_function) => {
+            TransactionPayload::EntryFunctionPayload(api_entry_function(entry_function)?)
+        },
+        transaction::TransactionPayload::Multisig(multisig) => {
+            let transaction_payload = match multisig.transaction_payload {
+                Some(transaction::MultisigTransactionPayload::EntryFunction(entry_function)) => {
+                    Some(MultisigTransactionPayload::EntryFunctionPayload(
+                        api_entry_function(entry_function)?,
+                    ))
+                },
+                None => None,
+            };
+            TransactionPayload::MultisigPayload(MultisigPayload {
+                multisig_address: multisig.multisig_address.into(),
+                transaction_payload,
+            })
+        },
+        transaction::TransactionPayload::ModuleBundle(_) => {
+            return Err(CliError::UnexpectedError(
+                "Module bundle payload has been removed".to_string(),
+            ))
+        },
+    })
+}
+
+fn api_entry_function(entry_function: EntryFunction) -> CliTypedResult<EntryFunctionPayload> {
+    let (module, function, ty_args, args) = entry_function.into_inner();
+    Ok(EntryFunctionPayload {
+        function: EntryFunctionId {
+            module: module.into(),
+            name: function.into(),
+        },
+        type_arguments: ty_args.iter().map(Into::into).collect(),
+        arguments: json_args(args)?,
+    })
+}
+
+fn json_args(args: Vec<Vec<u8>>) -> CliTypedResult<Vec<serde_json::Value>> {
+    args.into_iter()
+        .map(|arg| HexEncodedBytes::from(arg).json())
+        .collect::<anyhow::Result<_>>()
+        .map_err(|err| CliError::UnexpectedError(err.to_string()))
+}
+
+/// The signature of one of the signers of an [`UnsignedTransaction`], saved by
+/// `aptos transaction sign`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerSignature {
+    pub signer: AccountAddress,
+    pub authenticator: AccountAuthenticator,
+}
+
+impl SignerSignature {
+    /// Loads a signature from its BCS file
+    pub fn load(path: &Path) -> CliTypedResult<Self> {
+        bcs::from_bytes(&read_from_file(path)?).map_err(|err| CliError::BCS("signature", err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_cached_packages::aptos_stdlib;
+    use aptos_keygen::KeyGen;
+    use aptos_types::{chain_id::ChainId, transaction::authenticator::AuthenticationKey};
+
+    #[test]
+    fn test_sign_fee_payer_multi_agent_transaction() {
+        let mut keygen = KeyGen::from_seed([9; 32]);
+        let keys: Vec<_> = (0..3)
+            .map(|_| keygen.generate_ed25519_private_key())
+            .collect();
+        let addresses: Vec<_> = keys
+            .iter()
+            .map(|key| AuthenticationKey::ed25519(&key.public_key()).account_address())
+            .collect();
+        let unsigned_transaction = UnsignedTransaction {
+            raw_txn: RawTransaction::new(
+                addresses[0],
+                0,
+                aptos_stdlib::aptos_account_transfer(addresses[1], 1),
+                1_000,
+                100,
+                u64::MAX,
+                ChainId::test(),
+            ),
+            secondary_signers: vec![addresses[1]],
+            fee_payer_address: Some(addresses[2]),
+        };
+        let signatures: Vec<_> = keys
+            .iter()
+            .zip(addresses.iter())
+            .map(|(key, signer)| SignerSignature {
+                signer: *signer,
+                authenticator: unsigned_transaction.sign(key).unwrap(),
+            })
+            .collect();
+
+        // Every signer is needed
+        unsigned_transaction
+            .clone()
+            .into_signed_transaction(signatures[..2].to_vec())
+            .unwrap_err();
+        // Signatures of other accounts are rejected
+        let mut wrong_signer = signatures.clone();
+        wrong_signer[2].signer = AccountAddress::ONE;
+        unsigned_transaction
+            .clone()
+            .into_signed_transaction(wrong_signer)
+            .unwrap_err();
+        // Signatures of a different transaction are rejected
+        let mut other_transaction = unsigned_transaction.clone();
+        other_transaction.fee_payer_address = None;
+        let mut wrong_signature = signatures.clone();
+        wrong_signature[1].authenticator = other_transaction.sign(&keys[1]).unwrap();
+        unsigned_transaction
+            .clone()
+            .into_signed_transaction(wrong_signature)
+            .unwrap_err();
+
+        let transaction = unsigned_transaction
+            .clone()
+            .into_signed_transaction(signatures.into_iter().rev().collect())
+            .unwrap();
+        assert_eq!(
+            transaction.raw_transaction_ref(),
+            &unsigned_transaction.raw_txn
+        );
+
+        // It's reported as pending and unsigned once saved
+        match unsigned_transaction.to_pending_transaction().unwrap() {
+            Transaction::PendingTransaction(pending) => {
+                assert_eq!(*pending.request.sender.inner(), addresses[0]);
+                assert_eq!(pending.request.max_gas_amount.0, 1_000);
+                assert!(pending.request.signature.is_none());
+            },
+            transaction => panic!("Unexpected transaction {:?}", transaction),
+        }
+    }
+
+    #[test]
+    fn test_output_files() {
+        let path = Path::new("transfer.bcs");
+        assert_eq!(UnsignedTransaction::output_file(path, 0), path);
+        assert_eq!(
+            UnsignedTransaction::output_file(path, 2),
+            Path::new("transfer.bcs.2")
+        );
+        assert_eq!(
+            UnsignedTransaction::json_path(&UnsignedTransaction::output_file(path, 1)),
+            Path::new("transfer.bcs.1.json")
+        );
+    }
+}
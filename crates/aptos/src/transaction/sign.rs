@@ -0,0 +1,98 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::{
+        types::{
+            CliCommand, CliError, CliTypedResult, EncodingOptions, PrivateKeyInputOptions,
+            ProfileOptions, SaveFile,
+        },
+        utils::prompt_yes_with_override,
+    },
+    transaction::{SignerSignature, UnsignedTransaction},
+};
+use aptos_types::account_address::AccountAddress;
+use async_trait::async_trait;
+use clap::Parser;
+use serde::Serialize;
+use std::path::PathBuf;
+
+/// Sign a transaction saved with `--output-unsigned-transaction`
+///
+/// The transaction can be signed by its sender, any of its secondary signers, or its fee payer.
+/// This doesn't need network access, so it can run on an offline machine holding the private key.
+/// The signature is saved to the output file, to be submitted with `aptos transaction submit`.
+#[derive(Debug, Parser)]
+pub struct SignTransaction {
+    /// Unsigned transaction file, in BCS
+    #[clap(long, value_parser)]
+    pub(crate) unsigned_transaction_file: PathBuf,
+
+    /// Signer account address
+    ///
+    /// This allows you to override the account address from the derived account address
+    /// in the event that the authentication key was rotated or for a resource account
+    #[clap(long, value_parser = crate::common::types::load_account_arg)]
+    pub(crate) signer_account: Option<AccountAddress>,
+
+    #[clap(flatten)]
+    pub(crate) private_key_options: PrivateKeyInputOptions,
+    #[clap(flatten)]
+    pub(crate) encoding_options: EncodingOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+    #[clap(flatten)]
+    pub(crate) save_file: SaveFile,
+}
+
+/// Output of signing a transaction
+#[derive(Clone, Debug, Serialize)]
+pub struct SignSummary {
+    pub signer: AccountAddress,
+    pub signature_file: PathBuf,
+}
+
+#[async_trait]
+impl CliCommand<SignSummary> for SignTransaction {
+    fn command_name(&self) -> &'static str {
+        "SignTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<SignSummary> {
+        let unsigned_transaction = UnsignedTransaction::load(&self.unsigned_transaction_file)?;
+        let (private_key, signer) = self.private_key_options.extract_private_key_and_address(
+            self.encoding_options.encoding,
+            &self.profile_options,
+            self.signer_account,
+        )?;
+        if !unsigned_transaction.signers().contains(&signer) {
+            return Err(CliError::CommandArgumentError(format!(
+                "Account {} is not a signer of the transaction, signers are {:?}",
+                signer,
+                unsigned_transaction.signers()
+            )));
+        }
+        self.save_file.check_file()?;
+
+        // The transaction is signed offline, so show it in full before signing
+        let json = serde_json::to_string_pretty(&unsigned_transaction)
+            .map_err(|err| CliError::UnexpectedError(err.to_string()))?;
+        eprintln!("{}", json);
+        prompt_yes_with_override(
+            &format!("Do you want to sign this transaction as {}?", signer),
+            self.save_file.prompt_options,
+        )?;
+
+        let signature = SignerSignature {
+            signer,
+            authenticator: unsigned_transaction.sign(&private_key)?,
+        };
+        let bytes = bcs::to_bytes(&signature).map_err(|err| CliError::BCS("signature", err))?;
+        self.save_file.save_to_file("Signature", &bytes)?;
+
+        Ok(SignSummary {
+            signer,
+            signature_file: self.save_file.output_file,
+        })
+    }
+}
@@ -0,0 +1,60 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    common::{
+        types::{CliCommand, CliTypedResult, ProfileOptions, RestOptions, TransactionSummary},
+        utils::submit_signed_transaction,
+    },
+    transaction::{SignerSignature, UnsignedTransaction},
+};
+use async_trait::async_trait;
+use clap::Parser;
+use std::path::PathBuf;
+
+/// Submit a transaction saved with `--output-unsigned-transaction` along with its signatures
+///
+/// A signature from `aptos transaction sign` is needed for the sender, and for each of the
+/// secondary signers and the fee payer of the transaction if it has any.
+#[derive(Debug, Parser)]
+pub struct SubmitTransaction {
+    /// Unsigned transaction file, in BCS
+    #[clap(long, value_parser)]
+    pub(crate) unsigned_transaction_file: PathBuf,
+
+    /// Signature files of the signers of the transaction, in any order
+    #[clap(long, num_args = 1.., required = true, value_parser)]
+    pub(crate) signature_files: Vec<PathBuf>,
+
+    #[clap(flatten)]
+    pub(crate) rest_options: RestOptions,
+    #[clap(flatten)]
+    pub(crate) profile_options: ProfileOptions,
+}
+
+#[async_trait]
+impl CliCommand<TransactionSummary> for SubmitTransaction {
+    fn command_name(&self) -> &'static str {
+        "SubmitTransaction"
+    }
+
+    async fn execute(self) -> CliTypedResult<TransactionSummary> {
+        let unsigned_transaction = UnsignedTransaction::load(&self.unsigned_transaction_file)?;
+        let signatures = self
+            .signature_files
+            .iter()
+            .map(|path| SignerSignature::load(path))
+            .collect::<CliTypedResult<Vec<_>>>()?;
+        let transaction = unsigned_transaction.into_signed_transaction(signatures)?;
+
+        let client = self.rest_options.client(&self.profile_options)?;
+        let network = self
+            .profile_options
+            .profile()
+            .ok()
+            .and_then(|profile| profile.network);
+        submit_signed_transaction(&client, &transaction, network)
+            .await
+            .map(TransactionSummary::from)
+    }
+}
@@ -948,6 +948,8 @@ fn convert_validator_transaction(
                     )
                 )
             },
+            // Equivocation evidence has no protobuf representation yet.
+            ApiValidatorTransactionEnum::EquivocationEvidence(_) => None,
         },
         events: convert_events(api_validator_txn.events()),
     })
@@ -51,7 +51,8 @@ impl InternalIndexerDBService {
                 .expect("Failed to open internal indexer db"),
         );
 
-        let internal_indexer_db_config = InternalIndexerDBConfig::new(false, false, true, 10_000);
+        let internal_indexer_db_config =
+            InternalIndexerDBConfig::new(false, false, true, false, false, 10_000);
         Some(InternalIndexerDB::new(arc_db, internal_indexer_db_config))
     }
 
@@ -137,6 +138,33 @@ impl InternalIndexerDBService {
             }
         }
 
+        if node_config.indexer_db_config.enable_event_by_type() {
+            let event_by_type_start_version = self
+                .db_indexer
+                .indexer_db
+                .get_event_by_type_version()?
+                .map_or(0, |v| v + 1);
+            if start_version != event_by_type_start_version {
+                panic!("Cannot start event by type indexer because the progress doesn't match.");
+            }
+        }
+
+        if node_config
+            .indexer_db_config
+            .enable_transaction_by_entry_function()
+        {
+            let entry_function_start_version = self
+                .db_indexer
+                .indexer_db
+                .get_transaction_by_entry_function_version()?
+                .map_or(0, |v| v + 1);
+            if start_version != entry_function_start_version {
+                panic!(
+                    "Cannot start transaction by entry function indexer because the progress doesn't match."
+                );
+            }
+        }
+
         Ok(start_version)
     }
 
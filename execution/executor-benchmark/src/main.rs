@@ -94,6 +94,7 @@ impl PrunerOpt {
                 batch_size: self.ledger_pruning_batch_size,
                 user_pruning_window_offset: 0,
             },
+            enable_archive_mode: false,
         }
     }
 }
@@ -14,7 +14,8 @@ use crate::{
     logging::{LogEntry, LogSchema, TxnsLog},
     network::BroadcastPeerPriority,
     shared_mempool::types::{
-        MempoolSenderBucket, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
+        MempoolSenderBucket, MultiBucketTimelineIndexIds, SenderTransactions,
+        TimelineIndexIdentifier,
     },
 };
 use aptos_config::config::NodeConfig;
@@ -270,6 +271,10 @@ impl Mempool {
         self.transactions.get_by_hash(hash)
     }
 
+    pub(crate) fn get_transactions_by_sender(&self, sender: &AccountAddress) -> SenderTransactions {
+        self.transactions.get_transactions_by_sender(sender)
+    }
+
     /// Used to add a transaction to the Mempool.
     /// Performs basic validation: checks account's sequence number.
     pub(crate) fn add_txn(
@@ -15,7 +15,8 @@ use crate::{
     logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
     network::BroadcastPeerPriority,
     shared_mempool::types::{
-        MempoolSenderBucket, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
+        MempoolSenderBucket, MultiBucketTimelineIndexIds, SenderTransaction,
+        SenderTransactionStatus, SenderTransactions, TimelineIndexIdentifier,
     },
 };
 use aptos_config::config::MempoolConfig;
@@ -47,6 +48,13 @@ pub fn sender_bucket(
     address.as_ref()[address.as_ref().len() - 1] as MempoolSenderBucket % num_sender_buckets
 }
 
+/// The minimum gas unit price for a transaction to replace a pending one with the given gas unit
+/// price. The replacement always has to pay strictly more, even with a zero bump.
+fn min_replacement_gas_price(gas_unit_price: u64, bump_pct: u64) -> u64 {
+    let bump = (gas_unit_price as u128 * bump_pct as u128).div_ceil(100);
+    gas_unit_price.saturating_add(bump.clamp(1, u64::MAX as u128) as u64)
+}
+
 /// TransactionStore is in-memory storage for all transactions in mempool.
 pub struct TransactionStore {
     // main DS
@@ -85,6 +93,7 @@ pub struct TransactionStore {
     capacity_bytes: usize,
     capacity_per_user: usize,
     max_batch_bytes: u64,
+    replacement_min_gas_price_bump_pct: Option<u64>,
 
     // eager expiration
     eager_expire_threshold: Option<Duration>,
@@ -123,6 +132,7 @@ impl TransactionStore {
             capacity_bytes: config.capacity_bytes,
             capacity_per_user: config.capacity_per_user,
             max_batch_bytes: config.shared_mempool_max_batch_bytes,
+            replacement_min_gas_price_bump_pct: config.replacement_min_gas_price_bump_pct,
 
             // eager expiration
             eager_expire_threshold: config.eager_expire_threshold_ms.map(Duration::from_millis),
@@ -228,12 +238,39 @@ impl TransactionStore {
 
         // If the transaction is already in Mempool, we only allow the user to
         // increase the gas unit price to speed up a transaction, but not the max gas.
+        // If replacement is enabled, a transaction with a different payload, expiration or
+        // max gas replaces the pending one as long as its gas unit price is bumped enough.
         //
         // Transactions with all the same inputs (but possibly signed differently) are idempotent
         // since the raw transaction is the same
+        let replacement_min_gas_price_bump_pct = self.replacement_min_gas_price_bump_pct;
         if let Some(txns) = self.transactions.get_mut(&address) {
             if let Some(current_version) = txns.get_mut(&txn_seq_num) {
-                if current_version.txn.payload() != txn.txn.payload() {
+                let is_different_txn = current_version.txn.payload() != txn.txn.payload()
+                    || current_version.txn.expiration_timestamp_secs()
+                        != txn.txn.expiration_timestamp_secs()
+                    || current_version.txn.max_gas_amount() != txn.txn.max_gas_amount();
+                let replacement_bump_pct =
+                    replacement_min_gas_price_bump_pct.filter(|_| is_different_txn);
+                if let Some(bump_pct) = replacement_bump_pct {
+                    let min_gas_price =
+                        min_replacement_gas_price(current_version.get_gas_price(), bump_pct);
+                    if txn.get_gas_price() < min_gas_price {
+                        return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
+                            format!(
+                                "Transaction already in mempool with a different payload, expiration timestamp or max gas amount. \
+                                 Replacing it requires a gas unit price of at least {}",
+                                min_gas_price
+                            ),
+                        );
+                    }
+                    // Remove the pending txn from all indexes, the replacement is inserted below
+                    // as a new txn, so it gets broadcast again once it's ready
+                    if let Some(txn) = txns.remove(&txn_seq_num) {
+                        self.index_remove(&txn);
+                    };
+                    counters::CORE_MEMPOOL_REPLACED_TXNS.inc();
+                } else if current_version.txn.payload() != txn.txn.payload() {
                     return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
                         "Transaction already in mempool with a different payload".to_string(),
                     );
@@ -920,4 +957,53 @@ impl TransactionStore {
     pub(crate) fn get_parking_lot_addresses(&self) -> Vec<(AccountAddress, u64)> {
         self.parking_lot_index.get_addresses()
     }
+
+    /// Returns the transactions of the sender. Like in `process_ready_transactions`, those
+    /// following the first sequence number missing after the account sequence number are parked.
+    /// Transactions below the account sequence number are already committed, so they're skipped.
+    pub(crate) fn get_transactions_by_sender(
+        &self,
+        address: &AccountAddress,
+    ) -> SenderTransactions {
+        let account_sequence_number = self.sequence_numbers.get(address).copied();
+        let txns = match self.transactions.get(address) {
+            Some(txns) => txns,
+            None => {
+                return SenderTransactions {
+                    account_sequence_number,
+                    transactions: vec![],
+                }
+            },
+        };
+
+        let mut next_seq = account_sequence_number.or_else(|| txns.keys().next().copied());
+        let mut missing_seq = None;
+        let transactions = txns
+            .range(account_sequence_number.unwrap_or(0)..)
+            .map(|(seq, txn)| {
+                if let Some(next) = next_seq {
+                    if *seq > next {
+                        missing_seq = Some(next);
+                        next_seq = None;
+                    } else {
+                        next_seq = Some(seq + 1);
+                    }
+                }
+                SenderTransaction {
+                    txn: txn.txn.clone(),
+                    insertion_time: txn.insertion_info.insertion_time,
+                    status: match missing_seq {
+                        None => SenderTransactionStatus::Ready,
+                        Some(missing_sequence_number) => SenderTransactionStatus::Parked {
+                            missing_sequence_number,
+                        },
+                    },
+                }
+            })
+            .collect();
+        SenderTransactions {
+            account_sequence_number,
+            transactions,
+        }
+    }
 }
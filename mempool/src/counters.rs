@@ -196,6 +196,15 @@ pub static CORE_MEMPOOL_GAS_UPGRADED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter tracking number of txns received that replace a pending txn for the same sequence number
+pub static CORE_MEMPOOL_REPLACED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_core_mempool_replaced_txns_count",
+        "Number of txns received that replace a pending txn for the same sequence number"
+    )
+    .unwrap()
+});
+
 pub fn core_mempool_txn_commit_latency(
     stage: &'static str,
     submitted_by: &'static str,
@@ -63,7 +63,8 @@ pub use shared_mempool::{
     network::MempoolSyncMsg,
     types::{
         MempoolClientRequest, MempoolClientSender, MempoolEventsReceiver, QuorumStoreRequest,
-        QuorumStoreResponse, SubmissionStatus,
+        QuorumStoreResponse, SenderTransaction, SenderTransactionStatus, SenderTransactions,
+        SubmissionStatus,
     },
 };
 #[cfg(any(test, feature = "fuzzing"))]
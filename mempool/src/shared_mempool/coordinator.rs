@@ -222,6 +222,15 @@ async fn handle_client_request<NetworkClient, TransactionValidator>(
                 .spawn(tasks::process_parking_lot_addresses(smp.clone(), callback))
                 .await;
         },
+        MempoolClientRequest::GetTransactionsBySender(sender, callback) => {
+            bounded_executor
+                .spawn(tasks::process_client_get_transactions_by_sender(
+                    smp.clone(),
+                    sender,
+                    callback,
+                ))
+                .await;
+        },
     }
 }
 
@@ -11,8 +11,8 @@ use crate::{
     network::{BroadcastError, BroadcastPeerPriority, MempoolSyncMsg},
     shared_mempool::{
         types::{
-            notify_subscribers, ScheduledBroadcast, SharedMempool, SharedMempoolNotification,
-            SubmissionStatusBundle,
+            notify_subscribers, ScheduledBroadcast, SenderTransactions, SharedMempool,
+            SharedMempoolNotification, SubmissionStatusBundle,
         },
         use_case_history::UseCaseHistory,
     },
@@ -171,6 +171,26 @@ pub(crate) async fn process_parking_lot_addresses<NetworkClient, TransactionVali
     }
 }
 
+/// Processes get transactions by sender request by client.
+pub(crate) async fn process_client_get_transactions_by_sender<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    sender: AccountAddress,
+    callback: oneshot::Sender<SenderTransactions>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation,
+{
+    let transactions = smp.mempool.lock().get_transactions_by_sender(&sender);
+
+    if callback.send(transactions).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::GetTransaction,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes get transaction by hash request by client.
 pub(crate) async fn process_client_get_transaction<NetworkClient, TransactionValidator>(
     smp: SharedMempool<NetworkClient, TransactionValidator>,
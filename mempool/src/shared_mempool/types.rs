@@ -235,6 +235,32 @@ pub type SubmissionStatus = (MempoolStatus, Option<DiscardedVMStatus>);
 
 pub type SubmissionStatusBundle = (SignedTransaction, SubmissionStatus);
 
+/// Transactions of a single sender in mempool
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SenderTransactions {
+    /// Sequence number of the account as last seen by mempool, if it has seen the account
+    pub account_sequence_number: Option<u64>,
+    /// Transactions of the sender, ordered by sequence number
+    pub transactions: Vec<SenderTransaction>,
+}
+
+/// A transaction in mempool, and whether it can be included in a block
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SenderTransaction {
+    pub txn: SignedTransaction,
+    pub insertion_time: SystemTime,
+    pub status: SenderTransactionStatus,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SenderTransactionStatus {
+    /// The transaction can be included in a block
+    Ready,
+    /// The transaction is parked until the transaction with the given sequence number, which is
+    /// missing from mempool, arrives
+    Parked { missing_sequence_number: u64 },
+}
+
 pub enum MempoolClientRequest {
     /// Submits a transaction to the mempool and returns its submission status
     SubmitTransaction(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>),
@@ -243,6 +269,8 @@ pub enum MempoolClientRequest {
     /// Retrieves all addresses with transactions in the mempool's parking lot and
     /// the number of transactions for each address
     GetAddressesFromParkingLot(oneshot::Sender<Vec<(AccountAddress, u64)>>),
+    /// Retrieves all transactions of a sender in the mempool, ready or parked
+    GetTransactionsBySender(AccountAddress, oneshot::Sender<SenderTransactions>),
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
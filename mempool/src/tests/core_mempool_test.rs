@@ -5,9 +5,10 @@
 use crate::{
     core_mempool::{sender_bucket, CoreMempool, MempoolTransaction, SubmittedBy, TimelineState},
     network::BroadcastPeerPriority,
+    shared_mempool::types::{SenderTransactionStatus, SenderTransactions},
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, setup_mempool,
-        setup_mempool_with_broadcast_buckets, txn_bytes_len, TestTransaction,
+        setup_mempool_with_broadcast_buckets, txn_bytes_len, ConsensusMock, TestTransaction,
     },
 };
 use aptos_config::config::{MempoolConfig, NodeConfig};
@@ -181,6 +182,64 @@ fn test_update_invalid_transaction_in_mempool() {
     assert_eq!(next_tnx[0].gas_unit_price(), 1);
 }
 
+#[test]
+fn test_replace_transaction_in_mempool() {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.broadcast_buckets = vec![0];
+    config.mempool.replacement_min_gas_price_bump_pct = Some(10);
+    let mut pool = CoreMempool::new(&config);
+    let mut consensus = ConsensusMock::new();
+
+    let txn = add_txn(&mut pool, TestTransaction::new(0, 0, 100)).unwrap();
+    let sender_bucket = sender_bucket(&txn.sender(), config.mempool.num_sender_buckets);
+    let (timeline, timeline_id) = pool.read_timeline(
+        sender_bucket,
+        &vec![0].into(),
+        10,
+        None,
+        BroadcastPeerPriority::Primary,
+    );
+    assert_eq!(view(timeline), vec![0]);
+
+    // A different payload without a large enough gas price bump is rejected
+    let replacement = TestTransaction::new_with_large_script(0, 0, 109);
+    assert!(add_signed_txn(&mut pool, replacement.make_signed_transaction()).is_err());
+
+    // Bumping the gas price by 10% replaces the pending txn in every index
+    let replacement = TestTransaction::new_with_large_script(0, 0, 110).make_signed_transaction();
+    add_signed_txn(&mut pool, replacement.clone()).unwrap();
+    assert!(pool.get_by_hash(txn.committed_hash()).is_none());
+    assert_eq!(
+        pool.get_by_hash(replacement.committed_hash()),
+        Some(replacement.clone())
+    );
+    assert_eq!(pool.get_parking_lot_size(), 0);
+
+    // The replacement is broadcast again
+    let (timeline, _) = pool.read_timeline(
+        sender_bucket,
+        &timeline_id,
+        10,
+        None,
+        BroadcastPeerPriority::Primary,
+    );
+    assert_eq!(view(timeline), vec![0]);
+
+    assert_eq!(consensus.get_block(&mut pool, 10, 1024 * 1024), vec![
+        replacement
+    ]);
+}
+
+#[test]
+fn test_replace_transaction_disabled() {
+    let (mut pool, mut consensus) = setup_mempool();
+    let txn = add_txn(&mut pool, TestTransaction::new(0, 0, 100)).unwrap();
+
+    let replacement = TestTransaction::new_with_large_script(0, 0, 1000);
+    assert!(add_signed_txn(&mut pool, replacement.make_signed_transaction()).is_err());
+    assert_eq!(consensus.get_block(&mut pool, 10, 1024 * 1024), vec![txn]);
+}
+
 #[test]
 fn test_commit_transaction() {
     let (mut pool, mut consensus) = setup_mempool();
@@ -208,6 +267,55 @@ fn test_commit_transaction() {
     );
 }
 
+#[test]
+fn test_get_transactions_by_sender() {
+    let (mut pool, _) = setup_mempool();
+
+    // Sequence number 2 is missing.
+    let txns = add_txns_to_mempool(&mut pool, vec![
+        TestTransaction::new(0, 0, 1),
+        TestTransaction::new(0, 1, 1),
+        TestTransaction::new(0, 3, 1),
+        TestTransaction::new(0, 4, 1),
+        TestTransaction::new(1, 0, 1),
+    ]);
+    let sender = txns[0].sender();
+
+    let sender_txns = pool.get_transactions_by_sender(&sender);
+    assert_eq!(sender_txns.account_sequence_number, Some(0));
+    assert_eq!(
+        sender_txns
+            .transactions
+            .iter()
+            .map(|txn| (txn.txn.clone(), txn.status))
+            .collect::<Vec<_>>(),
+        vec![
+            (txns[0].clone(), SenderTransactionStatus::Ready),
+            (txns[1].clone(), SenderTransactionStatus::Ready),
+            (txns[2].clone(), SenderTransactionStatus::Parked {
+                missing_sequence_number: 2
+            }),
+            (txns[3].clone(), SenderTransactionStatus::Parked {
+                missing_sequence_number: 2
+            }),
+        ]
+    );
+
+    // Filling the gap makes all transactions ready.
+    add_txn(&mut pool, TestTransaction::new(0, 2, 1)).unwrap();
+    assert!(pool
+        .get_transactions_by_sender(&sender)
+        .transactions
+        .iter()
+        .all(|txn| txn.status == SenderTransactionStatus::Ready));
+
+    // Nothing from an unknown sender.
+    assert_eq!(
+        pool.get_transactions_by_sender(&TestTransaction::get_address(2)),
+        SenderTransactions::default()
+    );
+}
+
 #[test]
 fn test_reject_transaction() {
     let (mut pool, _) = setup_mempool();
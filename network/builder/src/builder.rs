@@ -20,7 +20,7 @@ use aptos_config::{
 };
 use aptos_event_notifications::{DbBackedOnChainConfig, EventSubscriptionService};
 use aptos_logger::prelude::*;
-use aptos_netcore::transport::tcp::TCPBufferCfg;
+use aptos_netcore::transport::{fault_injection::FaultInjector, tcp::TCPBufferCfg};
 use aptos_network::{
     application::storage::PeersAndMetadata,
     connectivity_manager::{builder::ConnectivityManagerBuilder, ConnectivityRequest},
@@ -38,10 +38,10 @@ use aptos_network::{
         },
     },
 };
-use aptos_network_discovery::DiscoveryChangeListener;
+use aptos_network_discovery::{DiscoveryChangeListener, SystemDnsResolver};
 use aptos_time_service::TimeService;
 use aptos_types::{chain_id::ChainId, network_address::NetworkAddress};
-use std::{clone::Clone, collections::HashSet, sync::Arc, time::Duration};
+use std::{clone::Clone, collections::HashSet, path::PathBuf, sync::Arc, time::Duration};
 use tokio::runtime::Handle;
 
 #[derive(Debug, PartialEq, PartialOrd)]
@@ -197,6 +197,10 @@ impl NetworkBuilder {
             ),
         );
 
+        network_builder
+            .peer_manager_builder
+            .set_peer_quota_config(config.peer_quota_config.clone());
+
         network_builder.add_connection_monitoring(
             config.ping_interval_ms,
             config.ping_timeout_ms,
@@ -204,6 +208,10 @@ impl NetworkBuilder {
             config.max_parallel_deserialization_tasks,
         );
 
+        if let Some(fault_injection_config_path) = &config.fault_injection_config_path {
+            network_builder.add_fault_injection(fault_injection_config_path.clone());
+        }
+
         // Always add a connectivity manager to keep track of known peers
         let seeds = merge_seeds(config);
 
@@ -384,6 +392,28 @@ impl NetworkBuilder {
                     Duration::from_secs(rest_discovery.interval_secs),
                     self.time_service.clone(),
                 ),
+                DiscoveryMethod::Dns(dns_discovery) => {
+                    let resolver = match SystemDnsResolver::new() {
+                        Ok(resolver) => resolver,
+                        Err(error) => {
+                            error!(
+                                NetworkSchema::new(&self.network_context),
+                                "{} DNS discovery is unable to create a DNS resolver, skipping it: {:?}",
+                                self.network_context,
+                                error
+                            );
+                            continue;
+                        },
+                    };
+                    DiscoveryChangeListener::dns(
+                        self.network_context,
+                        conn_mgr_reqs_tx.clone(),
+                        dns_discovery.name.clone(),
+                        Arc::new(resolver),
+                        Duration::from_secs(dns_discovery.interval_secs),
+                        self.time_service.clone(),
+                    )
+                },
                 DiscoveryMethod::None => {
                     continue;
                 },
@@ -426,6 +456,18 @@ impl NetworkBuilder {
         self
     }
 
+    /// Inject the network faults described in the given file into the connections this node
+    /// dials. Only meant for testing.
+    fn add_fault_injection(&mut self, fault_injection_config_path: PathBuf) -> &mut Self {
+        self.peer_manager_builder
+            .set_fault_injector(FaultInjector::new(fault_injection_config_path));
+        warn!(
+            NetworkSchema::new(&self.network_context),
+            "{} Network fault injection is enabled", self.network_context
+        );
+        self
+    }
+
     /// Register a new client and service application with the network. Return
     /// the client interface for sending messages and the service interface
     /// for handling network requests.
@@ -0,0 +1,373 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::DiscoveryError;
+use aptos_config::{
+    config::{Peer, PeerRole, PeerSet, HANDSHAKE_VERSION},
+    network_id::NetworkContext,
+};
+use aptos_crypto::{x25519, ValidCryptoMaterialStringExt};
+use aptos_logger::warn;
+use aptos_network::logging::NetworkSchema;
+use aptos_time_service::{Interval, TimeService, TimeServiceTrait};
+use aptos_types::{account_address::from_identity_public_key, network_address::NetworkAddress};
+use async_trait::async_trait;
+use futures::{future::BoxFuture, Future, Stream};
+use hickory_resolver::{error::ResolveErrorKind, TokioAsyncResolver};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+/// The prefix of the TXT records holding the x25519 key of an SRV target
+const X25519_KEY_PREFIX: &str = "x25519=";
+
+/// The DNS lookups required by `DnsStream`. This allows the resolver to be stubbed in tests.
+#[async_trait]
+pub trait DnsResolver: Send + Sync {
+    /// Returns the TXT records of the given name (empty if the name has none)
+    async fn lookup_txt(&self, name: &str) -> Result<Vec<String>, DiscoveryError>;
+
+    /// Returns the targets and ports of the SRV records of the given name
+    /// (empty if the name has none)
+    async fn lookup_srv(&self, name: &str) -> Result<Vec<(String, u16)>, DiscoveryError>;
+}
+
+/// A `DnsResolver` that uses the resolver configuration of the system
+pub struct SystemDnsResolver {
+    resolver: TokioAsyncResolver,
+}
+
+impl SystemDnsResolver {
+    pub fn new() -> Result<Self, DiscoveryError> {
+        let resolver = TokioAsyncResolver::tokio_from_system_conf()
+            .map_err(|error| DiscoveryError::Dns(error.to_string()))?;
+        Ok(Self { resolver })
+    }
+}
+
+#[async_trait]
+impl DnsResolver for SystemDnsResolver {
+    async fn lookup_txt(&self, name: &str) -> Result<Vec<String>, DiscoveryError> {
+        match self.resolver.txt_lookup(name).await {
+            Ok(lookup) => Ok(lookup
+                .iter()
+                .map(|txt| {
+                    // Long records are split into several character strings
+                    txt.iter()
+                        .map(|data| String::from_utf8_lossy(data))
+                        .collect::<String>()
+                })
+                .collect()),
+            Err(error) => match error.kind() {
+                ResolveErrorKind::NoRecordsFound { .. } => Ok(vec![]),
+                _ => Err(DiscoveryError::Dns(error.to_string())),
+            },
+        }
+    }
+
+    async fn lookup_srv(&self, name: &str) -> Result<Vec<(String, u16)>, DiscoveryError> {
+        match self.resolver.srv_lookup(name).await {
+            Ok(lookup) => Ok(lookup
+                .iter()
+                .map(|srv| {
+                    let target = srv.target().to_utf8();
+                    (target.trim_end_matches('.').to_string(), srv.port())
+                })
+                .collect()),
+            Err(error) => match error.kind() {
+                ResolveErrorKind::NoRecordsFound { .. } => Ok(vec![]),
+                _ => Err(DiscoveryError::Dns(error.to_string())),
+            },
+        }
+    }
+}
+
+/// A discovery stream that periodically resolves the seed peers listed in the DNS records
+/// of a name (see `DnsDiscovery` for the format of the records). Useful for fullnodes to
+/// bootstrap without a static list of seeds.
+pub struct DnsStream {
+    network_context: NetworkContext,
+    name: String,
+    resolver: Arc<dyn DnsResolver>,
+    interval: Pin<Box<Interval>>,
+    pending_lookup: Option<BoxFuture<'static, Result<PeerSet, DiscoveryError>>>,
+}
+
+impl DnsStream {
+    pub(crate) fn new(
+        network_context: NetworkContext,
+        name: String,
+        resolver: Arc<dyn DnsResolver>,
+        interval_duration: Duration,
+        time_service: TimeService,
+    ) -> Self {
+        DnsStream {
+            network_context,
+            name,
+            resolver,
+            interval: Box::pin(time_service.interval(interval_duration)),
+            pending_lookup: None,
+        }
+    }
+}
+
+impl Stream for DnsStream {
+    type Item = Result<PeerSet, DiscoveryError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        // Wait for delay, or add the delay for next call
+        if self.pending_lookup.is_none() {
+            futures::ready!(self.interval.as_mut().poll_next(cx));
+            let lookup = lookup_peers(
+                self.network_context,
+                self.resolver.clone(),
+                self.name.clone(),
+            );
+            self.pending_lookup = Some(Box::pin(lookup));
+        }
+
+        // Wait for the lookup to complete
+        let pending_lookup = self
+            .pending_lookup
+            .as_mut()
+            .expect("Lookup must be pending");
+        let result = futures::ready!(pending_lookup.as_mut().poll(cx));
+        self.pending_lookup = None;
+        Poll::Ready(Some(result))
+    }
+}
+
+/// Resolves the seed peers listed in the TXT and SRV records of the given name.
+/// Malformed records are skipped, so that they don't hide the other seeds.
+async fn lookup_peers(
+    network_context: NetworkContext,
+    resolver: Arc<dyn DnsResolver>,
+    name: String,
+) -> Result<PeerSet, DiscoveryError> {
+    let mut addresses = vec![];
+    for record in resolver.lookup_txt(&name).await? {
+        match parse_address_record(&record) {
+            Ok(address) => addresses.push(address),
+            Err(error) => warn!(
+                NetworkSchema::new(&network_context),
+                "{} Skipping TXT record {} of {}: {:?}", network_context, record, name, error
+            ),
+        }
+    }
+    for (target, port) in resolver.lookup_srv(&name).await? {
+        match lookup_srv_target_address(resolver.as_ref(), &target, port).await {
+            Ok(address) => addresses.push(address),
+            Err(error) => warn!(
+                NetworkSchema::new(&network_context),
+                "{} Skipping SRV target {}:{} of {}: {:?}",
+                network_context,
+                target,
+                port,
+                name,
+                error
+            ),
+        }
+    }
+    Ok(addresses_to_peer_set(addresses))
+}
+
+/// Parses a TXT record holding the network address of a seed peer
+fn parse_address_record(record: &str) -> Result<NetworkAddress, DiscoveryError> {
+    let address = NetworkAddress::from_str(record.trim())
+        .map_err(|error| DiscoveryError::Parsing(error.to_string()))?;
+    if !address.is_aptosnet_addr() {
+        return Err(DiscoveryError::Parsing(format!(
+            "Address {} is missing its x25519 key or handshake version",
+            address
+        )));
+    }
+    Ok(address)
+}
+
+/// Builds the network address of an SRV target from the x25519 key in its TXT records
+async fn lookup_srv_target_address(
+    resolver: &dyn DnsResolver,
+    target: &str,
+    port: u16,
+) -> Result<NetworkAddress, DiscoveryError> {
+    let key = resolver
+        .lookup_txt(target)
+        .await?
+        .iter()
+        .find_map(|record| {
+            record
+                .trim()
+                .strip_prefix(X25519_KEY_PREFIX)
+                .map(str::to_string)
+        })
+        .ok_or_else(|| DiscoveryError::Parsing(format!("No x25519 key found for {}", target)))?;
+    let key = x25519::PublicKey::from_encoded_string(&key)
+        .map_err(|error| DiscoveryError::Parsing(error.to_string()))?;
+    let address = NetworkAddress::from_str(&format!("/dns/{}/tcp/{}", target, port))
+        .map_err(|error| DiscoveryError::Parsing(error.to_string()))?;
+    Ok(address.append_prod_protos(key, HANDSHAKE_VERSION))
+}
+
+/// Groups the addresses by peer. Seed peers are identified by their x25519 keys.
+fn addresses_to_peer_set(addresses: Vec<NetworkAddress>) -> PeerSet {
+    let mut peer_addresses: HashMap<_, Vec<NetworkAddress>> = HashMap::new();
+    for address in addresses {
+        if let Some(key) = address.find_noise_proto() {
+            peer_addresses
+                .entry(from_identity_public_key(key))
+                .or_default()
+                .push(address);
+        }
+    }
+    peer_addresses
+        .into_iter()
+        .map(|(peer_id, addresses)| (peer_id, Peer::from_addrs(PeerRole::Upstream, addresses)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DiscoveryChangeListener;
+    use aptos_event_notifications::DbBackedOnChainConfig;
+    use aptos_infallible::Mutex;
+    use aptos_network::connectivity_manager::{ConnectivityRequest, DiscoverySource};
+    use futures::StreamExt;
+
+    const SEED_ADDRESS: &str = "/dns/seed0.example.com/tcp/6182/noise-ik/080e287879c918794170e258bfaddd75acac5b3e350419044655e4983a487120/handshake/0";
+    const SRV_TARGET_KEY: &str =
+        "0x5ad5d1c8f0b7cef8b9ac1e3fd0dbf4e8a6d52bc19c5dcb8a7ed0e45c2aa7e56d";
+
+    /// A `DnsResolver` that serves the records of a local zone
+    #[derive(Default)]
+    struct StubDnsResolver {
+        txt_records: Mutex<HashMap<String, Vec<String>>>,
+        srv_records: Mutex<HashMap<String, Vec<(String, u16)>>>,
+    }
+
+    impl StubDnsResolver {
+        fn add_txt_record(&self, name: &str, record: &str) {
+            self.txt_records
+                .lock()
+                .entry(name.to_string())
+                .or_default()
+                .push(record.to_string());
+        }
+
+        fn add_srv_record(&self, name: &str, target: &str, port: u16) {
+            self.srv_records
+                .lock()
+                .entry(name.to_string())
+                .or_default()
+                .push((target.to_string(), port));
+        }
+    }
+
+    #[async_trait]
+    impl DnsResolver for StubDnsResolver {
+        async fn lookup_txt(&self, name: &str) -> Result<Vec<String>, DiscoveryError> {
+            Ok(self
+                .txt_records
+                .lock()
+                .get(name)
+                .cloned()
+                .unwrap_or_default())
+        }
+
+        async fn lookup_srv(&self, name: &str) -> Result<Vec<(String, u16)>, DiscoveryError> {
+            Ok(self
+                .srv_records
+                .lock()
+                .get(name)
+                .cloned()
+                .unwrap_or_default())
+        }
+    }
+
+    async fn next_discovered_peers(
+        conn_mgr_reqs_rx: &mut aptos_channels::Receiver<ConnectivityRequest>,
+    ) -> PeerSet {
+        match conn_mgr_reqs_rx.next().await {
+            Some(ConnectivityRequest::UpdateDiscoveredPeers(DiscoverySource::Dns, peers)) => peers,
+            _ => panic!("No message sent by discovery"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dns_listener() {
+        let name = "seeds.example.com";
+        let resolver = Arc::new(StubDnsResolver::default());
+        let (conn_mgr_reqs_tx, mut conn_mgr_reqs_rx) = aptos_channels::new(
+            1,
+            &aptos_network::counters::PENDING_CONNECTIVITY_MANAGER_REQUESTS,
+        );
+        let listener = DiscoveryChangeListener::<DbBackedOnChainConfig>::dns(
+            NetworkContext::mock(),
+            conn_mgr_reqs_tx,
+            name.to_string(),
+            resolver.clone(),
+            Duration::from_millis(5),
+            TimeService::real(),
+        );
+        tokio::spawn(Box::pin(listener).run());
+
+        // Verify that no seeds are discovered without records
+        assert_eq!(
+            next_discovered_peers(&mut conn_mgr_reqs_rx).await,
+            PeerSet::new()
+        );
+
+        // Add a TXT record and an SRV record (and a malformed record, which is skipped)
+        resolver.add_txt_record(name, SEED_ADDRESS);
+        resolver.add_txt_record(name, "not an address");
+        resolver.add_srv_record(name, "seed1.example.com", 6182);
+        resolver.add_txt_record("seed1.example.com", &format!("x25519={}", SRV_TARGET_KEY));
+
+        // Verify that both seeds are eventually discovered
+        let txt_address = NetworkAddress::from_str(SEED_ADDRESS).unwrap();
+        let srv_key = x25519::PublicKey::from_encoded_string(SRV_TARGET_KEY).unwrap();
+        let srv_address = NetworkAddress::from_str("/dns/seed1.example.com/tcp/6182")
+            .unwrap()
+            .append_prod_protos(srv_key, HANDSHAKE_VERSION);
+        let mut expected_peers = PeerSet::new();
+        for address in [txt_address, srv_address] {
+            let peer_id = from_identity_public_key(address.find_noise_proto().unwrap());
+            let peer = Peer::from_addrs(PeerRole::Upstream, vec![address]);
+            expected_peers.insert(peer_id, peer);
+        }
+        loop {
+            let peers = next_discovered_peers(&mut conn_mgr_reqs_rx).await;
+            if !peers.is_empty() {
+                assert_eq!(peers, expected_peers);
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_address_record() {
+        // A full address is accepted
+        let address = parse_address_record(SEED_ADDRESS).unwrap();
+        assert_eq!(address, NetworkAddress::from_str(SEED_ADDRESS).unwrap());
+
+        // Addresses without a key (or malformed addresses) are rejected
+        parse_address_record("/dns/seed0.example.com/tcp/6182").unwrap_err();
+        parse_address_record("seed0.example.com:6182").unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn test_srv_target_without_key() {
+        let resolver = StubDnsResolver::default();
+        resolver.add_txt_record("seed1.example.com", "some other record");
+
+        // Verify that SRV targets without a key are rejected
+        lookup_srv_target_address(&resolver, "seed1.example.com", 6182)
+            .await
+            .unwrap_err();
+    }
+}
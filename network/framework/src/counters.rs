@@ -27,6 +27,8 @@ pub const SENT_LABEL: &str = "sent";
 pub const SUCCEEDED_LABEL: &str = "succeeded";
 pub const FAILED_LABEL: &str = "failed";
 pub const UNKNOWN_LABEL: &str = "unknown";
+pub const THROTTLED_LABEL: &str = "throttled";
+pub const DISCONNECTED_LABEL: &str = "disconnected";
 
 // Direction labels
 pub const INBOUND_LABEL: &str = "inbound";
@@ -333,6 +335,31 @@ pub fn direct_send_bytes(
     ])
 }
 
+/// Counter of the inbound messages dropped (or that caused a disconnect) for exceeding
+/// the quotas of their peer
+pub static APTOS_NETWORK_PEER_QUOTA_VIOLATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_network_peer_quota_violations",
+        "Number of inbound messages exceeding the quotas of their peer",
+        &["role_type", "network_id", "peer_id", "protocol_id", "state"]
+    )
+    .unwrap()
+});
+
+pub fn peer_quota_violations(
+    network_context: &NetworkContext,
+    protocol_id: ProtocolId,
+    state_label: &'static str,
+) -> IntCounter {
+    APTOS_NETWORK_PEER_QUOTA_VIOLATIONS.with_label_values(&[
+        network_context.role().as_str(),
+        network_context.network_id().as_str(),
+        network_context.peer_id().short_str().as_str(),
+        protocol_id.as_str(),
+        state_label,
+    ])
+}
+
 /// Counters(queued,dequeued,dropped) related to inbound network notifications for RPCs and
 /// DirectSends.
 pub static PENDING_NETWORK_NOTIFICATIONS: Lazy<IntCounterVec> = Lazy::new(|| {
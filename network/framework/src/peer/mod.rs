@@ -18,9 +18,11 @@
 use crate::{
     counters::{
         self, network_application_inbound_traffic, network_application_outbound_traffic,
-        DECLINED_LABEL, FAILED_LABEL, RECEIVED_LABEL, SENT_LABEL, UNKNOWN_LABEL,
+        DECLINED_LABEL, DISCONNECTED_LABEL, FAILED_LABEL, RECEIVED_LABEL, SENT_LABEL,
+        THROTTLED_LABEL, UNKNOWN_LABEL,
     },
     logging::NetworkSchema,
+    peer::quotas::{PeerQuotas, QuotaCheck},
     peer_manager::{PeerManagerError, TransportNotification},
     protocols::{
         direct_send::Message,
@@ -36,7 +38,10 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
-use aptos_config::network_id::{NetworkContext, PeerNetworkId};
+use aptos_config::{
+    config::PeerQuotaConfig,
+    network_id::{NetworkContext, PeerNetworkId},
+};
 use aptos_logger::prelude::*;
 use aptos_short_hex_str::AsShortHexStr;
 use aptos_time_service::{TimeService, TimeServiceTrait};
@@ -61,6 +66,7 @@ mod test;
 
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod fuzzing;
+mod quotas;
 
 /// Requests [`Peer`] receives from the [`PeerManager`](crate::peer_manager::PeerManager).
 #[derive(Debug)]
@@ -76,11 +82,14 @@ pub enum PeerRequest {
 /// For example, if the remote peer closed the connection or the connection was
 /// lost, the disconnect reason will be `ConnectionLost`. In contrast, if the
 /// [`PeerManager`](crate::peer_manager::PeerManager) requested us to close this
-/// connection, then the disconnect reason will be `Requested`.
+/// connection, then the disconnect reason will be `Requested`. If the remote peer
+/// repeatedly exceeded its inbound message quotas, the disconnect reason will be
+/// `QuotaExceeded`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
 pub enum DisconnectReason {
     Requested,
     ConnectionLost,
+    QuotaExceeded,
 }
 
 impl fmt::Display for DisconnectReason {
@@ -88,6 +97,7 @@ impl fmt::Display for DisconnectReason {
         let s = match self {
             DisconnectReason::Requested => "Requested",
             DisconnectReason::ConnectionLost => "ConnectionLost",
+            DisconnectReason::QuotaExceeded => "QuotaExceeded",
         };
         write!(f, "{}", s)
     }
@@ -130,6 +140,8 @@ pub struct Peer<TSocket> {
     max_message_size: usize,
     /// Inbound stream buffer
     inbound_stream: InboundStreamBuffer,
+    /// The quotas on the inbound messages of the remote peer
+    quotas: PeerQuotas,
 }
 
 impl<TSocket> Peer<TSocket>
@@ -152,6 +164,7 @@ where
         max_concurrent_outbound_rpcs: u32,
         max_frame_size: usize,
         max_message_size: usize,
+        peer_quota_config: &PeerQuotaConfig,
     ) -> Self {
         let Connection {
             metadata: connection_metadata,
@@ -159,6 +172,11 @@ where
         } = connection;
         let remote_peer_id = connection_metadata.remote_peer_id;
         let max_fragments = max_message_size / max_frame_size;
+        let quotas = PeerQuotas::new(
+            peer_quota_config,
+            connection_metadata.role,
+            time_service.clone(),
+        );
         Self {
             network_context,
             executor,
@@ -185,6 +203,7 @@ where
             max_frame_size,
             max_message_size,
             inbound_stream: InboundStreamBuffer::new(max_fragments),
+            quotas,
         }
     }
 
@@ -440,6 +459,7 @@ where
     fn handle_inbound_network_message(
         &mut self,
         message: NetworkMessage,
+        write_reqs_tx: &mut aptos_channel::Sender<(), NetworkMessage>,
     ) -> Result<(), PeerManagerError> {
         match &message {
             NetworkMessage::DirectSendMsg(direct) => {
@@ -449,6 +469,9 @@ where
                     direct.protocol_id,
                     data_len as u64,
                 );
+                if !self.check_inbound_quotas(direct.protocol_id, data_len) {
+                    return Ok(());
+                }
                 match self.upstream_handlers.get(&direct.protocol_id) {
                     None => {
                         counters::direct_send_messages(&self.network_context, UNKNOWN_LABEL).inc();
@@ -484,6 +507,11 @@ where
                     },
                 }
             },
+            NetworkMessage::Error(ErrorCode::RpcThrottled(throttled)) => {
+                // The remote peer throttled one of our requests, so fail it immediately
+                self.outbound_rpcs
+                    .handle_throttled_request(throttled.request_id);
+            },
             NetworkMessage::Error(error_msg) => {
                 warn!(
                     NetworkSchema::new(&self.network_context)
@@ -496,6 +524,20 @@ where
                 );
             },
             NetworkMessage::RpcRequest(request) => {
+                if !self.check_inbound_quotas(request.protocol_id, request.raw_request.len()) {
+                    // Let the remote peer know, so that the request doesn't time out. Peers
+                    // that don't understand the error just see the request time out.
+                    if self
+                        .connection_metadata
+                        .application_protocols
+                        .contains(ProtocolId::RpcThrottledErrors)
+                    {
+                        let error_code =
+                            ErrorCode::rpc_throttled(request.protocol_id, request.request_id);
+                        write_reqs_tx.push((), NetworkMessage::Error(error_code))?;
+                    }
+                    return Ok(());
+                }
                 match self.upstream_handlers.get(&request.protocol_id) {
                     None => {
                         counters::direct_send_messages(&self.network_context, UNKNOWN_LABEL).inc();
@@ -533,9 +575,34 @@ where
         Ok(())
     }
 
+    /// Checks an inbound message against the quotas of the remote peer. Returns false
+    /// if the message must be dropped, in which case the connection is also shut down
+    /// if the peer keeps exceeding its quotas.
+    fn check_inbound_quotas(&mut self, protocol_id: ProtocolId, num_bytes: usize) -> bool {
+        let state_label = match self.quotas.check_inbound_message(protocol_id, num_bytes) {
+            QuotaCheck::Allowed => return true,
+            QuotaCheck::Throttled => THROTTLED_LABEL,
+            QuotaCheck::Disconnect => {
+                warn!(
+                    NetworkSchema::new(&self.network_context)
+                        .connection_metadata(&self.connection_metadata),
+                    "{} Disconnecting from peer {}, as it repeatedly exceeded its quotas (last protocol: {})",
+                    self.network_context,
+                    self.remote_peer_id().short_str(),
+                    protocol_id
+                );
+                self.shutdown(DisconnectReason::QuotaExceeded);
+                DISCONNECTED_LABEL
+            },
+        };
+        counters::peer_quota_violations(&self.network_context, protocol_id, state_label).inc();
+        false
+    }
+
     fn handle_inbound_stream_message(
         &mut self,
         message: StreamMessage,
+        write_reqs_tx: &mut aptos_channel::Sender<(), NetworkMessage>,
     ) -> Result<(), PeerManagerError> {
         match message {
             StreamMessage::Header(header) => {
@@ -543,7 +610,7 @@ where
             },
             StreamMessage::Fragment(fragment) => {
                 if let Some(message) = self.inbound_stream.append_fragment(fragment)? {
-                    self.handle_inbound_network_message(message)?;
+                    self.handle_inbound_network_message(message, write_reqs_tx)?;
                 }
             },
         }
@@ -587,8 +654,12 @@ where
         };
 
         match message {
-            MultiplexMessage::Message(message) => self.handle_inbound_network_message(message),
-            MultiplexMessage::Stream(message) => self.handle_inbound_stream_message(message),
+            MultiplexMessage::Message(message) => {
+                self.handle_inbound_network_message(message, write_reqs_tx)
+            },
+            MultiplexMessage::Stream(message) => {
+                self.handle_inbound_stream_message(message, write_reqs_tx)
+            },
         }
     }
 
@@ -0,0 +1,318 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::ProtocolId;
+use aptos_config::config::{PeerQuotaConfig, PeerRole, ProtocolQuota};
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// The result of checking an inbound message against the quotas of a peer
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotaCheck {
+    /// The message is within the quotas
+    Allowed,
+    /// The message exceeds the quota of its protocol, and should be dropped
+    Throttled,
+    /// The message exceeds the quota of its protocol, and the peer has exceeded its
+    /// quotas too often, so it should be disconnected.
+    Disconnect,
+}
+
+/// A token bucket that holds (at most) one second worth of tokens, and is
+/// refilled at the given rate. Time is read from the time service of the
+/// peer, so that the quotas can be tested using mock time.
+struct TokenBucket {
+    rate_per_sec: usize,
+    tokens: usize,
+    last_refill_time: Instant,
+    time_service: TimeService,
+}
+
+impl TokenBucket {
+    /// Creates a (full) token bucket with the given rate, or `None` if the rate is unlimited (0)
+    fn new(rate_per_sec: usize, time_service: TimeService) -> Option<Self> {
+        if rate_per_sec == 0 {
+            return None;
+        }
+        Some(Self {
+            rate_per_sec,
+            tokens: rate_per_sec,
+            last_refill_time: time_service.now(),
+            time_service,
+        })
+    }
+
+    /// Acquires the given number of tokens. Returns false (and acquires
+    /// nothing) if there are not enough tokens in the bucket.
+    fn acquire(&mut self, num_tokens: usize) -> bool {
+        self.refill();
+        if num_tokens > self.tokens {
+            return false;
+        }
+        self.tokens -= num_tokens;
+        true
+    }
+
+    /// Returns the given number of tokens to the bucket
+    fn return_tokens(&mut self, num_tokens: usize) {
+        self.tokens = self
+            .tokens
+            .saturating_add(num_tokens)
+            .min(self.rate_per_sec);
+    }
+
+    /// Adds the tokens accrued since the last refill to the bucket
+    fn refill(&mut self) {
+        let now = self.time_service.now();
+        let elapsed_secs = now.duration_since(self.last_refill_time).as_secs_f64();
+        let new_tokens = (elapsed_secs * self.rate_per_sec as f64) as usize;
+        if new_tokens == 0 {
+            return;
+        }
+
+        // Only advance the refill time by the time required to accrue the new
+        // tokens, so that fractional tokens aren't lost between refills.
+        self.tokens = self.tokens.saturating_add(new_tokens);
+        if self.tokens >= self.rate_per_sec {
+            self.tokens = self.rate_per_sec;
+            self.last_refill_time = now;
+        } else {
+            self.last_refill_time +=
+                Duration::from_secs_f64(new_tokens as f64 / self.rate_per_sec as f64);
+        }
+    }
+}
+
+/// The token buckets of a protocol quota
+struct ProtocolBuckets {
+    messages: Option<TokenBucket>,
+    bytes: Option<TokenBucket>,
+}
+
+impl ProtocolBuckets {
+    fn new(quota: &ProtocolQuota, time_service: &TimeService) -> Self {
+        Self {
+            messages: TokenBucket::new(quota.max_messages_per_sec, time_service.clone()),
+            bytes: TokenBucket::new(quota.max_bytes_per_sec, time_service.clone()),
+        }
+    }
+
+    /// Acquires the tokens of a message (of the given size) from the buckets.
+    /// Returns false (and acquires nothing) if the message exceeds the quota.
+    fn acquire(&mut self, num_bytes: usize) -> bool {
+        if let Some(messages) = self.messages.as_mut() {
+            if !messages.acquire(1) {
+                return false;
+            }
+        }
+        if let Some(bytes) = self.bytes.as_mut() {
+            if !bytes.acquire(num_bytes) {
+                // Return the message token, as the message is dropped
+                if let Some(messages) = self.messages.as_mut() {
+                    messages.return_tokens(1);
+                }
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// `PeerQuotas` enforces the `PeerQuotaConfig` on the inbound messages of a single peer
+pub struct PeerQuotas {
+    /// The quota buckets of each protocol with a quota (for the role of the peer)
+    protocol_buckets: HashMap<ProtocolId, ProtocolBuckets>,
+    /// The bucket of throttled messages, used to detect peers that repeatedly
+    /// exceed their quotas (`None` if peers are never disconnected).
+    throttled_messages: Option<TokenBucket>,
+}
+
+impl PeerQuotas {
+    pub fn new(config: &PeerQuotaConfig, peer_role: PeerRole, time_service: TimeService) -> Self {
+        // Identify the quota of each protocol (the first matching quota applies)
+        let mut protocol_buckets = HashMap::new();
+        for protocol_id in ProtocolId::all() {
+            let quota = config.protocol_quotas.iter().find(|quota| {
+                quota.protocol_id == protocol_id.as_str() && quota.applies_to(peer_role)
+            });
+            if let Some(quota) = quota {
+                protocol_buckets.insert(*protocol_id, ProtocolBuckets::new(quota, &time_service));
+            }
+        }
+
+        let throttled_messages =
+            TokenBucket::new(config.max_throttled_messages_per_sec, time_service);
+        Self {
+            protocol_buckets,
+            throttled_messages,
+        }
+    }
+
+    /// Checks an inbound message (of the given protocol and size) against the quotas
+    pub fn check_inbound_message(
+        &mut self,
+        protocol_id: ProtocolId,
+        num_bytes: usize,
+    ) -> QuotaCheck {
+        let buckets = match self.protocol_buckets.get_mut(&protocol_id) {
+            Some(buckets) => buckets,
+            None => return QuotaCheck::Allowed, // The protocol has no quota
+        };
+        if buckets.acquire(num_bytes) {
+            return QuotaCheck::Allowed;
+        }
+
+        // The message is throttled. Verify the peer isn't repeatedly exceeding its quotas.
+        match self.throttled_messages.as_mut() {
+            Some(throttled_messages) if !throttled_messages.acquire(1) => QuotaCheck::Disconnect,
+            _ => QuotaCheck::Throttled,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_quota(
+        protocol_id: ProtocolId,
+        peer_roles: Vec<PeerRole>,
+        max_messages_per_sec: usize,
+        max_bytes_per_sec: usize,
+    ) -> ProtocolQuota {
+        ProtocolQuota {
+            protocol_id: protocol_id.as_str().to_string(),
+            peer_roles,
+            max_messages_per_sec,
+            max_bytes_per_sec,
+        }
+    }
+
+    #[test]
+    fn test_message_and_byte_quotas() {
+        // Create quotas for mempool messages and storage service requests
+        let config = PeerQuotaConfig {
+            protocol_quotas: vec![
+                create_quota(ProtocolId::MempoolDirectSend, vec![], 3, 0),
+                create_quota(ProtocolId::StorageServiceRpc, vec![], 0, 100),
+            ],
+            max_throttled_messages_per_sec: 0,
+        };
+        let mut peer_quotas = PeerQuotas::new(&config, PeerRole::Unknown, TimeService::mock());
+
+        // Verify the message quota
+        for _ in 0..3 {
+            let quota_check = peer_quotas.check_inbound_message(ProtocolId::MempoolDirectSend, 10);
+            assert_eq!(quota_check, QuotaCheck::Allowed);
+        }
+        let quota_check = peer_quotas.check_inbound_message(ProtocolId::MempoolDirectSend, 10);
+        assert_eq!(quota_check, QuotaCheck::Throttled);
+
+        // Verify the byte quota
+        let quota_check = peer_quotas.check_inbound_message(ProtocolId::StorageServiceRpc, 60);
+        assert_eq!(quota_check, QuotaCheck::Allowed);
+        let quota_check = peer_quotas.check_inbound_message(ProtocolId::StorageServiceRpc, 60);
+        assert_eq!(quota_check, QuotaCheck::Throttled);
+        let quota_check = peer_quotas.check_inbound_message(ProtocolId::StorageServiceRpc, 40);
+        assert_eq!(quota_check, QuotaCheck::Allowed);
+
+        // Verify that protocols without quotas are never throttled
+        for _ in 0..100 {
+            let quota_check = peer_quotas.check_inbound_message(ProtocolId::ConsensusRpcBcs, 1000);
+            assert_eq!(quota_check, QuotaCheck::Allowed);
+        }
+    }
+
+    #[test]
+    fn test_quota_refill() {
+        // Create a quota of 10 messages and 100 bytes per second
+        let config = PeerQuotaConfig {
+            protocol_quotas: vec![create_quota(ProtocolId::MempoolDirectSend, vec![], 10, 100)],
+            max_throttled_messages_per_sec: 0,
+        };
+        let time_service = TimeService::mock();
+        let mut peer_quotas = PeerQuotas::new(&config, PeerRole::Unknown, time_service.clone());
+
+        // Exhaust the message quota
+        for _ in 0..10 {
+            let quota_check = peer_quotas.check_inbound_message(ProtocolId::MempoolDirectSend, 1);
+            assert_eq!(quota_check, QuotaCheck::Allowed);
+        }
+        let quota_check = peer_quotas.check_inbound_message(ProtocolId::MempoolDirectSend, 1);
+        assert_eq!(quota_check, QuotaCheck::Throttled);
+
+        // Elapse a tenth of a second and verify that a single message is allowed
+        let time_service = time_service.into_mock();
+        time_service.advance_ms(100);
+        let quota_check = peer_quotas.check_inbound_message(ProtocolId::MempoolDirectSend, 1);
+        assert_eq!(quota_check, QuotaCheck::Allowed);
+        let quota_check = peer_quotas.check_inbound_message(ProtocolId::MempoolDirectSend, 1);
+        assert_eq!(quota_check, QuotaCheck::Throttled);
+
+        // Elapse several seconds and verify the bucket holds at most a second of tokens
+        time_service.advance_secs(5);
+        for _ in 0..10 {
+            let quota_check = peer_quotas.check_inbound_message(ProtocolId::MempoolDirectSend, 1);
+            assert_eq!(quota_check, QuotaCheck::Allowed);
+        }
+        let quota_check = peer_quotas.check_inbound_message(ProtocolId::MempoolDirectSend, 1);
+        assert_eq!(quota_check, QuotaCheck::Throttled);
+    }
+
+    #[test]
+    fn test_peer_role_quotas() {
+        // Create a quota for public peers, and a more permissive default quota
+        let config = PeerQuotaConfig {
+            protocol_quotas: vec![
+                create_quota(ProtocolId::MempoolDirectSend, vec![PeerRole::Unknown], 1, 0),
+                create_quota(ProtocolId::MempoolDirectSend, vec![], 10, 0),
+            ],
+            max_throttled_messages_per_sec: 0,
+        };
+
+        // Verify that the first matching quota applies to each peer
+        let mut public_peer_quotas =
+            PeerQuotas::new(&config, PeerRole::Unknown, TimeService::mock());
+        let mut validator_quotas =
+            PeerQuotas::new(&config, PeerRole::Validator, TimeService::mock());
+        for i in 0..10 {
+            let quota_check =
+                public_peer_quotas.check_inbound_message(ProtocolId::MempoolDirectSend, 10);
+            let expected_check = if i == 0 {
+                QuotaCheck::Allowed
+            } else {
+                QuotaCheck::Throttled
+            };
+            assert_eq!(quota_check, expected_check);
+
+            let quota_check =
+                validator_quotas.check_inbound_message(ProtocolId::MempoolDirectSend, 10);
+            assert_eq!(quota_check, QuotaCheck::Allowed);
+        }
+    }
+
+    #[test]
+    fn test_repeat_offender() {
+        // Create a quota that disconnects peers after 2 throttled messages
+        let config = PeerQuotaConfig {
+            protocol_quotas: vec![create_quota(ProtocolId::MempoolDirectSend, vec![], 1, 0)],
+            max_throttled_messages_per_sec: 2,
+        };
+        let mut peer_quotas = PeerQuotas::new(&config, PeerRole::Unknown, TimeService::mock());
+
+        // Verify that the peer is disconnected once it exceeds the throttled messages
+        let expected_checks = [
+            QuotaCheck::Allowed,
+            QuotaCheck::Throttled,
+            QuotaCheck::Throttled,
+            QuotaCheck::Disconnect,
+        ];
+        for expected_check in expected_checks {
+            let quota_check = peer_quotas.check_inbound_message(ProtocolId::MempoolDirectSend, 10);
+            assert_eq!(quota_check, expected_check);
+        }
+    }
+}
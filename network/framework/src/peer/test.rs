@@ -16,8 +16,8 @@ use crate::{
         wire::{
             handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
             messaging::v1::{
-                DirectSendMsg, MultiplexMessage, MultiplexMessageSink, MultiplexMessageStream,
-                NetworkMessage, RpcRequest, RpcResponse,
+                DirectSendMsg, ErrorCode, MultiplexMessage, MultiplexMessageSink,
+                MultiplexMessageStream, NetworkMessage, RpcRequest, RpcResponse,
             },
         },
     },
@@ -25,7 +25,10 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::PeerRole, network_id::NetworkContext};
+use aptos_config::{
+    config::{PeerQuotaConfig, PeerRole, ProtocolQuota},
+    network_id::NetworkContext,
+};
 use aptos_logger::info;
 use aptos_memsocket::MemorySocket;
 use aptos_netcore::transport::ConnectionOrigin;
@@ -64,6 +67,31 @@ fn build_test_peer(
     PeerHandle,
     MemorySocket,
     aptos_channels::Receiver<TransportNotification<MemorySocket>>,
+) {
+    build_test_peer_with_quotas(
+        executor,
+        time_service,
+        origin,
+        upstream_handlers,
+        &PeerQuotaConfig::default(),
+        ProtocolIdSet::empty(),
+    )
+}
+
+fn build_test_peer_with_quotas(
+    executor: Handle,
+    time_service: TimeService,
+    origin: ConnectionOrigin,
+    upstream_handlers: Arc<
+        HashMap<ProtocolId, aptos_channel::Sender<(PeerId, ProtocolId), ReceivedMessage>>,
+    >,
+    peer_quota_config: &PeerQuotaConfig,
+    application_protocols: ProtocolIdSet,
+) -> (
+    Peer<MemorySocket>,
+    PeerHandle,
+    MemorySocket,
+    aptos_channels::Receiver<TransportNotification<MemorySocket>>,
 ) {
     let (a, b) = MemorySocket::new_pair();
     let peer_id = PeerId::random();
@@ -74,7 +102,7 @@ fn build_test_peer(
             NetworkAddress::from_str("/ip4/127.0.0.1/tcp/8081").unwrap(),
             origin,
             MessagingProtocolVersion::V1,
-            ProtocolIdSet::empty(),
+            application_protocols,
             PeerRole::Unknown,
         ),
         socket: a,
@@ -97,6 +125,7 @@ fn build_test_peer(
         MAX_CONCURRENT_OUTBOUND_RPCS,
         MAX_FRAME_SIZE,
         MAX_MESSAGE_SIZE,
+        peer_quota_config,
     );
     let peer_handle = PeerHandle(peer_reqs_tx);
 
@@ -306,6 +335,150 @@ fn peer_recv_message() {
     info!("done");
 }
 
+// Inbound messages exceeding the quotas of the remote peer should be dropped, and the
+// peer should be disconnected if it keeps exceeding its quotas.
+#[test]
+fn peer_recv_message_exceeding_quotas() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let (upstream_handlers, mut receiver) = test_upstream_handlers();
+    let peer_quota_config = PeerQuotaConfig {
+        protocol_quotas: vec![ProtocolQuota {
+            protocol_id: PROTOCOL.as_str().to_string(),
+            peer_roles: vec![],
+            max_messages_per_sec: 2,
+            max_bytes_per_sec: 0,
+        }],
+        max_throttled_messages_per_sec: 1,
+    };
+    let (peer, _peer_handle, connection, mut connection_notifs_rx) = build_test_peer_with_quotas(
+        rt.handle().clone(),
+        TimeService::mock(),
+        ConnectionOrigin::Inbound,
+        upstream_handlers,
+        &peer_quota_config,
+        ProtocolIdSet::empty(),
+    );
+    let remote_peer_id = peer.remote_peer_id();
+
+    let send_msg = MultiplexMessage::Message(NetworkMessage::DirectSendMsg(DirectSendMsg {
+        protocol_id: PROTOCOL,
+        priority: 0,
+        raw_msg: Vec::from("hello world"),
+    }));
+
+    let client = async move {
+        // The client sends 2 messages within the quota, 1 throttled message,
+        // and 1 message that exceeds the throttled messages of the peer.
+        let mut connection = MultiplexMessageSink::new(connection, MAX_FRAME_SIZE);
+        for _ in 0..4 {
+            connection.send(&send_msg).await.unwrap();
+        }
+        connection
+    };
+
+    let server = async move {
+        // Only the messages within the quota are received
+        for _ in 0..2 {
+            receiver.next().await.unwrap();
+        }
+
+        // The peer is disconnected, and the other messages are dropped
+        assert_disconnected_event(
+            remote_peer_id,
+            DisconnectReason::QuotaExceeded,
+            &mut connection_notifs_rx,
+        )
+        .await;
+        assert!(receiver.next().await.is_none());
+    };
+    rt.block_on(future::join3(peer.start(), server, client));
+}
+
+// Inbound rpc requests exceeding the quotas of the remote peer should be dropped,
+// and the remote peer should be notified (so that its requests fail immediately).
+#[test]
+fn peer_recv_rpc_exceeding_quotas() {
+    recv_rpc_exceeding_quotas(ProtocolIdSet::from_iter([ProtocolId::RpcThrottledErrors]));
+}
+
+// Remote peers that don't understand RpcThrottled errors should not be notified.
+#[test]
+fn peer_recv_rpc_exceeding_quotas_without_throttled_errors() {
+    recv_rpc_exceeding_quotas(ProtocolIdSet::empty());
+}
+
+fn recv_rpc_exceeding_quotas(application_protocols: ProtocolIdSet) {
+    ::aptos_logger::Logger::init_for_testing();
+    let expect_throttled_error = application_protocols.contains(ProtocolId::RpcThrottledErrors);
+    let rt = Runtime::new().unwrap();
+    let (upstream_handlers, mut prot_rx) = test_upstream_handlers();
+    let peer_quota_config = PeerQuotaConfig {
+        protocol_quotas: vec![ProtocolQuota {
+            protocol_id: PROTOCOL.as_str().to_string(),
+            peer_roles: vec![],
+            max_messages_per_sec: 1,
+            max_bytes_per_sec: 0,
+        }],
+        max_throttled_messages_per_sec: 0,
+    };
+    let (peer, _peer_handle, mut connection, _connection_notifs_rx) = build_test_peer_with_quotas(
+        rt.handle().clone(),
+        TimeService::mock(),
+        ConnectionOrigin::Inbound,
+        upstream_handlers,
+        &peer_quota_config,
+        application_protocols,
+    );
+    let (mut client_sink, mut client_stream) = build_network_sink_stream(&mut connection);
+
+    let client = async move {
+        // The client sends 1 request within the quota, and 1 throttled request
+        for request_id in [1, 2] {
+            let send_msg = MultiplexMessage::Message(NetworkMessage::RpcRequest(RpcRequest {
+                request_id,
+                protocol_id: PROTOCOL,
+                priority: 0,
+                raw_request: Vec::from("hello world"),
+            }));
+            client_sink.send(&send_msg).await.unwrap();
+        }
+
+        // The client receives the response to the first request, and an error for the second
+        // (if it understands such errors)
+        let mut received_messages = vec![];
+        for _ in 0..(1 + expect_throttled_error as usize) {
+            received_messages.push(client_stream.next().await.unwrap().unwrap());
+        }
+        let resp_msg = MultiplexMessage::Message(NetworkMessage::RpcResponse(RpcResponse {
+            request_id: 1,
+            priority: 0,
+            raw_response: Vec::from("goodbye world"),
+        }));
+        let error_msg =
+            MultiplexMessage::Message(NetworkMessage::Error(ErrorCode::rpc_throttled(PROTOCOL, 2)));
+        assert!(received_messages.contains(&resp_msg));
+        assert_eq!(
+            received_messages.contains(&error_msg),
+            expect_throttled_error
+        );
+
+        // Client then closes connection, and receives nothing else.
+        client_sink.close().await.unwrap();
+        assert!(client_stream.next().await.is_none());
+    };
+    let server = async move {
+        // Only the request within the quota is received
+        let received = prot_rx.next().await.unwrap();
+        let rpc_replier = Arc::into_inner(received.rpc_replier.expect("rpc without replier"))
+            .expect("Arc unpack fail");
+        rpc_replier
+            .send(Ok(Bytes::from("goodbye world")))
+            .expect("rpc reply send fail");
+    };
+    rt.block_on(future::join3(peer.start(), server, client));
+}
+
 // Two connected Peer actors should be able to send/recv a DirectSend from each
 // other and then shutdown gracefully.
 #[test]
@@ -691,6 +864,47 @@ fn peer_send_rpc() {
     rt.block_on(future::join3(peer.start(), server, client));
 }
 
+// Outbound rpc requests throttled by the remote peer should fail immediately
+#[test]
+fn peer_send_rpc_throttled() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let upstream_handlers = Arc::new(HashMap::new());
+    let (peer, mut peer_handle, mut connection, _connection_notifs_rx) = build_test_peer(
+        rt.handle().clone(),
+        TimeService::mock(),
+        ConnectionOrigin::Inbound,
+        upstream_handlers,
+    );
+    let (mut server_sink, mut server_stream) = build_network_sink_stream(&mut connection);
+    let timeout = Duration::from_millis(10_000);
+
+    let client = async move {
+        // Send RpcRequest to server and verify that the request is throttled
+        let error = peer_handle
+            .send_rpc_request(PROTOCOL, Bytes::from(&b"hello world"[..]), timeout)
+            .await
+            .unwrap_err();
+        assert!(matches!(error, RpcError::Throttled(protocol_id) if protocol_id == PROTOCOL));
+        // Client then closes connection.
+    };
+    let server = async move {
+        // Server should receive the rpc request, and then throttle it
+        let received = server_stream.next().await.unwrap().unwrap();
+        let received = match received {
+            MultiplexMessage::Message(NetworkMessage::RpcRequest(request)) => request,
+            _ => panic!("Expected RpcRequest; unexpected: {:?}", received),
+        };
+        let error = MultiplexMessage::Message(NetworkMessage::Error(ErrorCode::rpc_throttled(
+            received.protocol_id,
+            received.request_id,
+        )));
+        server_sink.send(&error).await.unwrap();
+        assert!(server_stream.next().await.is_none());
+    };
+    rt.block_on(future::join3(peer.start(), server, client));
+}
+
 #[test]
 fn peer_send_rpc_concurrent() {
     ::aptos_logger::Logger::init_for_testing();
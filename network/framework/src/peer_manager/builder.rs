@@ -18,12 +18,16 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
-use aptos_config::{config::HANDSHAKE_VERSION, network_id::NetworkContext};
+use aptos_config::{
+    config::{PeerQuotaConfig, HANDSHAKE_VERSION},
+    network_id::NetworkContext,
+};
 use aptos_crypto::x25519;
 use aptos_logger::prelude::*;
 #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
 use aptos_netcore::transport::memory::MemoryTransport;
 use aptos_netcore::transport::{
+    fault_injection::{FaultInjectingSocket, FaultInjectingTransport, FaultInjector},
     tcp::{TCPBufferCfg, TcpSocket, TcpTransport},
     Transport,
 };
@@ -52,6 +56,7 @@ struct TransportContext {
     authentication_mode: AuthenticationMode,
     peers_and_metadata: Arc<PeersAndMetadata>,
     enable_proxy_protocol: bool,
+    fault_injector: Option<FaultInjector>,
 }
 
 impl TransportContext {
@@ -78,6 +83,7 @@ struct PeerManagerContext {
     max_message_size: usize,
     inbound_connection_limit: usize,
     tcp_buffer_cfg: TCPBufferCfg,
+    peer_quota_config: PeerQuotaConfig,
 }
 
 impl PeerManagerContext {
@@ -116,6 +122,7 @@ impl PeerManagerContext {
             max_message_size,
             inbound_connection_limit,
             tcp_buffer_cfg,
+            peer_quota_config: PeerQuotaConfig::default(),
         }
     }
 
@@ -139,11 +146,16 @@ impl PeerManagerContext {
 type MemoryPeerManager =
     PeerManager<AptosNetTransport<MemoryTransport>, NoiseStream<aptos_memsocket::MemorySocket>>;
 type TcpPeerManager = PeerManager<AptosNetTransport<TcpTransport>, NoiseStream<TcpSocket>>;
+type FaultInjectingTcpPeerManager = PeerManager<
+    AptosNetTransport<FaultInjectingTransport<TcpTransport>>,
+    NoiseStream<FaultInjectingSocket<TcpSocket>>,
+>;
 
 enum TransportPeerManager {
     #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
     Memory(MemoryPeerManager),
     Tcp(TcpPeerManager),
+    FaultInjectingTcp(FaultInjectingTcpPeerManager),
 }
 
 pub struct PeerManagerBuilder {
@@ -193,6 +205,7 @@ impl PeerManagerBuilder {
                 authentication_mode,
                 peers_and_metadata: peers_and_metadata.clone(),
                 enable_proxy_protocol,
+                fault_injector: None,
             }),
             peer_manager_context: Some(PeerManagerContext::new(
                 pm_reqs_tx,
@@ -237,6 +250,27 @@ impl PeerManagerBuilder {
             .expect("Cannot get PeerManagerContext once PeerManager has been built")
     }
 
+    /// Inject the network faults of the given injector into the TCP connections this peer dials.
+    pub fn set_fault_injector(&mut self, fault_injector: FaultInjector) {
+        self.transport_context().fault_injector = Some(fault_injector);
+    }
+
+    /// Enforce the given quotas on the inbound messages of each peer.
+    pub fn set_peer_quota_config(&mut self, peer_quota_config: PeerQuotaConfig) {
+        for quota in &peer_quota_config.protocol_quotas {
+            let is_known_protocol = ProtocolId::all()
+                .iter()
+                .any(|protocol_id| protocol_id.as_str() == quota.protocol_id);
+            if !is_known_protocol {
+                warn!(
+                    "{} Ignoring the quota of unknown protocol: {}",
+                    self.network_context, quota.protocol_id
+                );
+            }
+        }
+        self.peer_manager_context().peer_quota_config = peer_quota_config;
+    }
+
     /// Create the configured transport and start PeerManager.
     /// Return the actual NetworkAddress over which this peer is listening.
     pub fn build(&mut self, executor: &Handle) -> &mut Self {
@@ -247,9 +281,13 @@ impl PeerManagerBuilder {
             .take()
             .expect("PeerManager can only be built once");
 
-        let protos = transport_context.supported_protocols;
+        // Also advertise that we understand RpcThrottled errors, so that peers only
+        // send them to us if we do (older peers can't deserialize them).
+        let mut protos = transport_context.supported_protocols;
+        protos.insert(ProtocolId::RpcThrottledErrors);
         let chain_id = transport_context.chain_id;
         let enable_proxy_protocol = transport_context.enable_proxy_protocol;
+        let fault_injector = transport_context.fault_injector;
 
         let (key, auth_mode) = match transport_context.authentication_mode {
             AuthenticationMode::MaybeMutual(key) => (
@@ -267,6 +305,26 @@ impl PeerManagerBuilder {
         aptos_tcp_transport.set_tcp_buffers(&tcp_cfg);
 
         self.peer_manager = match self.listen_address.as_slice() {
+            [Ip4(_), Tcp(_)] | [Ip6(_), Tcp(_)] if fault_injector.is_some() => {
+                let fault_injector = fault_injector.unwrap();
+                fault_injector.spawn_reloader(executor);
+                Some(TransportPeerManager::FaultInjectingTcp(
+                    self.build_with_transport(
+                        AptosNetTransport::new(
+                            FaultInjectingTransport::new(aptos_tcp_transport, fault_injector),
+                            self.network_context,
+                            self.time_service.clone(),
+                            key,
+                            auth_mode,
+                            HANDSHAKE_VERSION,
+                            chain_id,
+                            protos,
+                            enable_proxy_protocol,
+                        ),
+                        executor,
+                    ),
+                ))
+            },
             [Ip4(_), Tcp(_)] | [Ip6(_), Tcp(_)] => {
                 Some(TransportPeerManager::Tcp(self.build_with_transport(
                     AptosNetTransport::new(
@@ -340,6 +398,7 @@ impl PeerManagerBuilder {
             pm_context.max_frame_size,
             pm_context.max_message_size,
             pm_context.inbound_connection_limit,
+            pm_context.peer_quota_config,
         );
 
         // PeerManager constructor appends a public key to the listen_address.
@@ -370,6 +429,7 @@ impl PeerManagerBuilder {
             #[cfg(any(test, feature = "testing", feature = "fuzzing"))]
             TransportPeerManager::Memory(pm) => self.start_peer_manager(pm, executor),
             TransportPeerManager::Tcp(pm) => self.start_peer_manager(pm, executor),
+            TransportPeerManager::FaultInjectingTcp(pm) => self.start_peer_manager(pm, executor),
         }
     }
 
@@ -23,7 +23,10 @@ use crate::{
     ProtocolId,
 };
 use aptos_channels::{self, aptos_channel, message_queues::QueueStyle};
-use aptos_config::network_id::{NetworkContext, PeerNetworkId};
+use aptos_config::{
+    config::PeerQuotaConfig,
+    network_id::{NetworkContext, PeerNetworkId},
+};
 use aptos_logger::prelude::*;
 use aptos_netcore::transport::{ConnectionOrigin, Transport};
 use aptos_short_hex_str::AsShortHexStr;
@@ -117,6 +120,8 @@ where
     max_message_size: usize,
     /// Inbound connection limit separate of outbound connections
     inbound_connection_limit: usize,
+    /// The quotas on the inbound messages of each peer
+    peer_quota_config: PeerQuotaConfig,
 }
 
 impl<TTransport, TSocket> PeerManager<TTransport, TSocket>
@@ -144,6 +149,7 @@ where
         max_frame_size: usize,
         max_message_size: usize,
         inbound_connection_limit: usize,
+        peer_quota_config: PeerQuotaConfig,
     ) -> Self {
         let (transport_notifs_tx, transport_notifs_rx) = aptos_channels::new(
             channel_size,
@@ -185,6 +191,7 @@ where
             max_frame_size,
             max_message_size,
             inbound_connection_limit,
+            peer_quota_config,
         }
     }
 
@@ -661,6 +668,7 @@ where
             constants::MAX_CONCURRENT_OUTBOUND_RPCS,
             self.max_frame_size,
             self.max_message_size,
+            &self.peer_quota_config,
         );
         self.executor.spawn(peer.start());
 
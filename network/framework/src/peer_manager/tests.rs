@@ -24,7 +24,7 @@ use crate::{
 use anyhow::anyhow;
 use aptos_channels::{aptos_channel, message_queues::QueueStyle};
 use aptos_config::{
-    config::{PeerRole, MAX_INBOUND_CONNECTIONS},
+    config::{PeerQuotaConfig, PeerRole, MAX_INBOUND_CONNECTIONS},
     network_id::{NetworkContext, NetworkId},
 };
 use aptos_memsocket::MemorySocket;
@@ -116,6 +116,7 @@ fn build_test_peer_manager(
         constants::MAX_FRAME_SIZE,
         constants::MAX_MESSAGE_SIZE,
         MAX_INBOUND_CONNECTIONS,
+        PeerQuotaConfig::default(),
     );
 
     (
@@ -406,7 +406,8 @@ pub struct OutboundRpcs {
     /// Maps a `RequestId` into a handle to a task in the `outbound_rpc_tasks`
     /// completion queue. When a new `RpcResponse` message comes in, we will use
     /// this map to notify the corresponding task that its response has arrived.
-    pending_outbound_rpcs: HashMap<RequestId, (ProtocolId, oneshot::Sender<RpcResponse>)>,
+    pending_outbound_rpcs:
+        HashMap<RequestId, (ProtocolId, oneshot::Sender<Result<RpcResponse, RpcError>>)>,
     /// Only allow this many concurrent outbound rpcs at one time from this remote
     /// peer. New outbound requests exceeding this limit will be dropped.
     max_concurrent_outbound_rpcs: u32,
@@ -503,7 +504,7 @@ impl OutboundRpcs {
         self.update_outbound_rpc_request_metrics(protocol_id, req_len);
 
         // Create channel over which response is delivered to outbound_rpc_task.
-        let (response_tx, response_rx) = oneshot::channel::<RpcResponse>();
+        let (response_tx, response_rx) = oneshot::channel::<Result<RpcResponse, RpcError>>();
 
         // Store send-side in the pending map so we can notify outbound_rpc_task
         // when the rpc response has arrived.
@@ -519,7 +520,8 @@ impl OutboundRpcs {
             .map(|result| {
                 // Flatten errors.
                 match result {
-                    Ok(Ok(response)) => Ok(Bytes::from(response.raw_response)),
+                    Ok(Ok(Ok(response))) => Ok(Bytes::from(response.raw_response)),
+                    Ok(Ok(Err(error))) => Err(error),
                     Ok(Err(oneshot::Canceled)) => Err(RpcError::UnexpectedResponseChannelCancel),
                     Err(timeout::Elapsed) => Err(RpcError::TimedOut),
                 }
@@ -698,7 +700,7 @@ impl OutboundRpcs {
                 protocol_id,
                 response.raw_response.len() as u64,
             );
-            response_tx.send(response).is_err()
+            response_tx.send(Ok(response)).is_err()
         } else {
             true
         };
@@ -731,6 +733,24 @@ impl OutboundRpcs {
         }
     }
 
+    /// Handle an inbound `RpcThrottled` error, i.e., the remote peer dropped one of
+    /// our requests because it exceeded the peer's inbound quotas. If the request
+    /// is still pending, it is failed immediately (instead of timing out).
+    pub fn handle_throttled_request(&mut self, request_id: RequestId) {
+        if let Some((protocol_id, response_tx)) = self.pending_outbound_rpcs.remove(&request_id) {
+            debug!(
+                NetworkSchema::new(&self.network_context).remote_peer(&self.remote_peer_id),
+                request_id = request_id,
+                "{} Request_id {} was throttled by {} (protocol: {})",
+                self.network_context,
+                request_id,
+                self.remote_peer_id.short_str(),
+                protocol_id,
+            );
+            let _ = response_tx.send(Err(RpcError::Throttled(protocol_id)));
+        }
+    }
+
     /// Updates the inbound RPC response metrics (e.g., messages and bytes received)
     fn update_inbound_rpc_response_metrics(&self, protocol_id: ProtocolId, data_len: u64) {
         // Update the metrics for the new RPC response
@@ -15,7 +15,7 @@
 
 use crate::counters::{start_serialization_timer, DESERIALIZATION_LABEL, SERIALIZATION_LABEL};
 use anyhow::anyhow;
-use aptos_compression::client::CompressionClient;
+use aptos_compression::{algorithm::CompressionAlgorithm, client::CompressionClient};
 use aptos_config::{config::MAX_APPLICATION_MESSAGE_SIZE, network_id::NetworkId};
 use aptos_types::chain_id::ChainId;
 #[cfg(any(test, feature = "fuzzing"))]
@@ -73,12 +73,17 @@ pub enum ProtocolId {
     JWKConsensusRpcJson = 26,
     ConsensusObserver = 27,
     ConsensusObserverRpc = 28,
+    ConsensusRpcCompressedZstd = 29,
+    ConsensusDirectSendCompressedZstd = 30,
+    StorageServiceRpcZstd = 31, // Responses are compressed with zstd by the storage service
+    RpcThrottledErrors = 32, // Not an application protocol: the peer understands RpcThrottled errors
 }
 
 /// The encoding types for Protocols
 enum Encoding {
     Bcs(usize),
     CompressedBcs(usize),
+    CompressedBcsWithHeader(usize, CompressionAlgorithm), // The algorithm is encoded in the message
     Json,
 }
 
@@ -115,6 +120,10 @@ impl ProtocolId {
             JWKConsensusRpcJson => "JWKConsensusRpcJson",
             ConsensusObserver => "ConsensusObserver",
             ConsensusObserverRpc => "ConsensusObserverRpc",
+            ConsensusRpcCompressedZstd => "ConsensusRpcCompressedZstd",
+            ConsensusDirectSendCompressedZstd => "ConsensusDirectSendCompressedZstd",
+            StorageServiceRpcZstd => "StorageServiceRpcZstd",
+            RpcThrottledErrors => "RpcThrottledErrors",
         }
     }
 
@@ -150,6 +159,10 @@ impl ProtocolId {
             ProtocolId::JWKConsensusRpcJson,
             ProtocolId::ConsensusObserver,
             ProtocolId::ConsensusObserverRpc,
+            ProtocolId::ConsensusRpcCompressedZstd,
+            ProtocolId::ConsensusDirectSendCompressedZstd,
+            ProtocolId::StorageServiceRpcZstd,
+            ProtocolId::RpcThrottledErrors,
         ]
     }
 
@@ -160,6 +173,10 @@ impl ProtocolId {
             ProtocolId::ConsensusDirectSendCompressed | ProtocolId::ConsensusRpcCompressed => {
                 Encoding::CompressedBcs(RECURSION_LIMIT)
             },
+            ProtocolId::ConsensusDirectSendCompressedZstd
+            | ProtocolId::ConsensusRpcCompressedZstd => {
+                Encoding::CompressedBcsWithHeader(RECURSION_LIMIT, CompressionAlgorithm::Zstd)
+            },
             ProtocolId::ConsensusObserver => Encoding::CompressedBcs(RECURSION_LIMIT),
             ProtocolId::DKGDirectSendCompressed | ProtocolId::DKGRpcCompressed => {
                 Encoding::CompressedBcs(RECURSION_LIMIT)
@@ -175,9 +192,10 @@ impl ProtocolId {
     /// Returns the compression client label based on the current protocol id
     fn get_compression_client(self) -> CompressionClient {
         match self {
-            ProtocolId::ConsensusDirectSendCompressed | ProtocolId::ConsensusRpcCompressed => {
-                CompressionClient::Consensus
-            },
+            ProtocolId::ConsensusDirectSendCompressed
+            | ProtocolId::ConsensusRpcCompressed
+            | ProtocolId::ConsensusDirectSendCompressedZstd
+            | ProtocolId::ConsensusRpcCompressedZstd => CompressionClient::Consensus,
             ProtocolId::ConsensusObserver => CompressionClient::ConsensusObserver,
             ProtocolId::MempoolDirectSend => CompressionClient::Mempool,
             ProtocolId::DKGDirectSendCompressed | ProtocolId::DKGRpcCompressed => {
@@ -211,6 +229,17 @@ impl ProtocolId {
                 )
                 .map_err(|e| anyhow!("{:?}", e))
             },
+            Encoding::CompressedBcsWithHeader(limit, algorithm) => {
+                let compression_client = self.get_compression_client();
+                let bcs_bytes = self.bcs_encode(value, limit)?;
+                aptos_compression::compress_with_header(
+                    bcs_bytes,
+                    compression_client,
+                    algorithm,
+                    MAX_APPLICATION_MESSAGE_SIZE,
+                )
+                .map_err(|e| anyhow!("{:?}", e))
+            },
             Encoding::Json => serde_json::to_vec(value).map_err(|e| anyhow!("{:?}", e)),
         };
 
@@ -241,6 +270,16 @@ impl ProtocolId {
                 .map_err(|e| anyhow! {"{:?}", e})?;
                 self.bcs_decode(&raw_bytes, limit)
             },
+            Encoding::CompressedBcsWithHeader(limit, _) => {
+                let compression_client = self.get_compression_client();
+                let raw_bytes = aptos_compression::decompress_with_header(
+                    &bytes.to_vec(),
+                    compression_client,
+                    MAX_APPLICATION_MESSAGE_SIZE,
+                )
+                .map_err(|e| anyhow! {"{:?}", e})?;
+                self.bcs_decode(&raw_bytes, limit)
+            },
             Encoding::Json => serde_json::from_slice(bytes).map_err(|e| anyhow!("{:?}", e)),
         };
 
@@ -450,12 +489,15 @@ impl HandshakeMsg {
         }
 
         // find the greatest common MessagingProtocolVersion where we both support
-        // at least one common ProtocolId.
+        // at least one common application ProtocolId.
         for (our_handshake_version, our_protocols) in self.supported_protocols.iter().rev() {
             if let Some(their_protocols) = other.supported_protocols.get(our_handshake_version) {
                 let common_protocols = our_protocols.intersect(their_protocols);
 
-                if !common_protocols.is_empty() {
+                if common_protocols
+                    .iter()
+                    .any(|protocol| protocol != ProtocolId::RpcThrottledErrors)
+                {
                     return Ok((*our_handshake_version, common_protocols));
                 }
             }
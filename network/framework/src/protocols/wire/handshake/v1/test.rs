@@ -116,6 +116,27 @@ fn common_protocols() {
         h1.perform_handshake(&h2).unwrap_err(),
         HandshakeError::NoCommonProtocols,
     );
+
+    // Case 4: The only intersecting protocol isn't an application protocol.
+    let mut h1 = h1;
+    h1.supported_protocols
+        .get_mut(&MessagingProtocolVersion::V1)
+        .unwrap()
+        .insert(ProtocolId::RpcThrottledErrors);
+    let mut supported_protocols = BTreeMap::new();
+    supported_protocols.insert(
+        MessagingProtocolVersion::V1,
+        ProtocolIdSet::from_iter([ProtocolId::RpcThrottledErrors]),
+    );
+    let h2 = HandshakeMsg {
+        supported_protocols,
+        chain_id,
+        network_id,
+    };
+    assert_eq!(
+        h1.perform_handshake(&h2).unwrap_err(),
+        HandshakeError::NoCommonProtocols,
+    );
 }
 
 #[test]
@@ -183,3 +204,20 @@ fn ignore_unknown_protocols() {
         ProtocolIdSet::empty(),
     );
 }
+
+#[test]
+fn test_compressed_with_header_encoding() {
+    let message = vec![7u64; 1000];
+    for protocol_id in [
+        ProtocolId::ConsensusRpcCompressedZstd,
+        ProtocolId::ConsensusDirectSendCompressedZstd,
+    ] {
+        // Verify that the message is encoded with the zstd algorithm header
+        let bytes = protocol_id.to_bytes(&message).unwrap();
+        assert_eq!(bytes[0], CompressionAlgorithm::Zstd.to_header_byte());
+
+        // Verify that the message can be decoded
+        let decoded_message: Vec<u64> = protocol_id.from_bytes(&bytes).unwrap();
+        assert_eq!(decoded_message, message);
+    }
+}
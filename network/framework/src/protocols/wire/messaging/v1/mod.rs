@@ -71,12 +71,21 @@ pub enum ErrorCode {
     ParsingError(ParsingErrorType),
     /// A message was received for a protocol that is not supported over this connection.
     NotSupported(NotSupportedType),
+    /// An rpc request was dropped because it exceeded the inbound quotas of the receiver.
+    RpcThrottled(RpcThrottledType),
 }
 
 impl ErrorCode {
     pub fn parsing_error(message: u8, protocol: u8) -> Self {
         ErrorCode::ParsingError(ParsingErrorType { message, protocol })
     }
+
+    pub fn rpc_throttled(protocol_id: ProtocolId, request_id: RequestId) -> Self {
+        ErrorCode::RpcThrottled(RpcThrottledType {
+            protocol_id,
+            request_id,
+        })
+    }
 }
 
 /// Flags an invalid network message with as much header information as possible. This is a message
@@ -97,6 +106,15 @@ pub enum NotSupportedType {
     DirectSendMsg(ProtocolId),
 }
 
+/// Flags a throttled rpc request, so that the sender can fail the request
+/// immediately (instead of waiting for it to time out).
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
+pub struct RpcThrottledType {
+    pub protocol_id: ProtocolId,
+    pub request_id: RequestId,
+}
+
 /// Create alias RequestId for `u32`.
 pub type RequestId = u32;
 
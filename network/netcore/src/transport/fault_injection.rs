@@ -0,0 +1,548 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A transport that injects network faults (delays, packet loss, bandwidth limits and partitions)
+//! into the connections of an underlying transport.
+//!
+//! This is meant to emulate adverse network conditions between nodes running on a single machine
+//! (e.g., a local Forge swarm), where tools like Chaos Mesh aren't available. The faults are
+//! described per remote peer by a [`FaultInjectionConfig`], which is reloaded periodically from a
+//! file so that faults can be injected and removed while the node is running.
+//!
+//! Only the dialer of a connection knows the remote peer before the handshake, so the dialer
+//! applies the faults in both directions: `outbound` faults to the bytes it writes and `inbound`
+//! faults to the bytes it reads. Inbound connections are passed through unchanged.
+
+use crate::transport::Transport;
+use aptos_infallible::RwLock;
+use aptos_types::{network_address::NetworkAddress, PeerId};
+use bytes::{Buf, Bytes};
+use futures::{
+    future::{BoxFuture, Future, FutureExt, MapOk, TryFutureExt},
+    io::{AsyncRead, AsyncWrite},
+    ready,
+    stream::{self, TryStreamExt},
+};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fs, io,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    runtime::Handle,
+    time::{Instant, Sleep},
+};
+
+/// How often the fault injection config is reloaded from its file
+const RELOAD_INTERVAL: Duration = Duration::from_secs(1);
+/// The delay added to a chunk of bytes that is "lost". TCP recovers lost packets by
+/// retransmitting them, so on a stream loss shows up as added latency (the minimum TCP
+/// retransmission timeout on Linux).
+const RETRANSMISSION_DELAY: Duration = Duration::from_millis(200);
+/// The maximum number of bytes held back in each direction before applying backpressure
+const MAX_QUEUED_BYTES: usize = 4 * 1024 * 1024;
+/// The maximum number of bytes read from the underlying socket at once
+const READ_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Faults applied to the bytes sent in one direction of a connection
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LinkFault {
+    /// Delay added to every chunk of bytes
+    pub delay_ms: u64,
+    /// Maximum random variation of the delay, in both directions
+    pub jitter_ms: u64,
+    /// Percentage of chunks of bytes that are lost (and delayed by a retransmission)
+    pub loss_percentage: u64,
+    /// Percentage of chance that a chunk is lost if the previous one was
+    pub loss_correlation_percentage: u64,
+    /// Bandwidth limit, unlimited if not set
+    pub rate_bytes_per_sec: Option<u64>,
+}
+
+impl LinkFault {
+    fn is_noop(&self) -> bool {
+        *self == LinkFault::default()
+    }
+}
+
+/// Faults applied to the connections with a remote peer
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LinkFaults {
+    /// Whether the remote peer is unreachable. Dialing the peer fails and existing connections
+    /// to it are reset.
+    pub partitioned: bool,
+    /// Faults applied to the bytes sent to the remote peer
+    pub outbound: LinkFault,
+    /// Faults applied to the bytes received from the remote peer
+    pub inbound: LinkFault,
+}
+
+/// The faults to inject, per remote peer
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FaultInjectionConfig {
+    pub peers: BTreeMap<PeerId, LinkFaults>,
+}
+
+impl FaultInjectionConfig {
+    /// Loads the config from the given file. A missing file means no faults.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(contents) => serde_yaml::from_str(&contents)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error)),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error),
+        }
+    }
+
+    /// Saves the config to the given file. The file is replaced atomically, so a node reloading
+    /// it never sees a partially written config.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let contents = serde_yaml::to_string(self)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, contents)?;
+        fs::rename(tmp_path, path)
+    }
+}
+
+/// Shared handle to the faults currently injected, reloaded from a file
+#[derive(Clone, Debug)]
+pub struct FaultInjector {
+    path: PathBuf,
+    config: Arc<RwLock<FaultInjectionConfig>>,
+}
+
+impl FaultInjector {
+    pub fn new(path: PathBuf) -> Self {
+        let config = FaultInjectionConfig::load(&path).unwrap_or_default();
+        Self {
+            path,
+            config: Arc::new(RwLock::new(config)),
+        }
+    }
+
+    /// Reloads the faults from the file. On failure, the current faults are kept.
+    pub fn reload(&self) -> io::Result<()> {
+        let config = FaultInjectionConfig::load(&self.path)?;
+        *self.config.write() = config;
+        Ok(())
+    }
+
+    /// Spawns a task reloading the faults from the file periodically
+    pub fn spawn_reloader(&self, executor: &Handle) {
+        let fault_injector = self.clone();
+        executor.spawn(async move {
+            let mut interval = tokio::time::interval(RELOAD_INTERVAL);
+            loop {
+                interval.tick().await;
+                let _ = fault_injector.reload();
+            }
+        });
+    }
+
+    pub fn link_faults(&self, peer_id: &PeerId) -> LinkFaults {
+        self.config
+            .read()
+            .peers
+            .get(peer_id)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// A [`Transport`] injecting the faults of a [`FaultInjector`] into the connections it dials
+#[derive(Clone, Debug)]
+pub struct FaultInjectingTransport<T> {
+    transport: T,
+    fault_injector: FaultInjector,
+}
+
+impl<T> FaultInjectingTransport<T> {
+    pub fn new(transport: T, fault_injector: FaultInjector) -> Self {
+        Self {
+            transport,
+            fault_injector,
+        }
+    }
+}
+
+type WrapSocketFn<S> = fn(S) -> FaultInjectingSocket<S>;
+type FaultInjectingInbound<T> =
+    MapOk<<T as Transport>::Inbound, WrapSocketFn<<T as Transport>::Output>>;
+type WrapInboundFn<T> =
+    fn((<T as Transport>::Inbound, NetworkAddress)) -> (FaultInjectingInbound<T>, NetworkAddress);
+
+impl<T> Transport for FaultInjectingTransport<T>
+where
+    T: Transport<Error = io::Error>,
+    T::Output: AsyncRead + AsyncWrite + Send + Unpin + 'static,
+    T::Outbound: Send + 'static,
+{
+    type Error = io::Error;
+    type Inbound = FaultInjectingInbound<T>;
+    type Listener = stream::MapOk<T::Listener, WrapInboundFn<T>>;
+    type Outbound = BoxFuture<'static, io::Result<Self::Output>>;
+    type Output = FaultInjectingSocket<T::Output>;
+
+    fn listen_on(
+        &self,
+        addr: NetworkAddress,
+    ) -> Result<(Self::Listener, NetworkAddress), Self::Error> {
+        let (listener, addr) = self.transport.listen_on(addr)?;
+        let wrap_inbound: WrapInboundFn<T> = |(inbound, addr)| {
+            let wrap_socket: WrapSocketFn<T::Output> = FaultInjectingSocket::passthrough;
+            (inbound.map_ok(wrap_socket), addr)
+        };
+        Ok((listener.map_ok(wrap_inbound), addr))
+    }
+
+    fn dial(&self, peer_id: PeerId, addr: NetworkAddress) -> Result<Self::Outbound, Self::Error> {
+        if self.fault_injector.link_faults(&peer_id).partitioned {
+            return Err(partitioned_error(&peer_id));
+        }
+        let fault_injector = self.fault_injector.clone();
+        let outbound = self.transport.dial(peer_id, addr)?;
+        Ok(outbound
+            .map_ok(move |socket| FaultInjectingSocket::new(socket, fault_injector, peer_id))
+            .boxed())
+    }
+}
+
+fn partitioned_error(peer_id: &PeerId) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::ConnectionReset,
+        format!("Injected network partition with peer {}", peer_id),
+    )
+}
+
+/// Holds back chunks of bytes until their delivery time, as determined by a [`LinkFault`]
+#[derive(Debug, Default)]
+struct Shaper {
+    queue: VecDeque<(Instant, Bytes)>,
+    queued_bytes: usize,
+    // Delivery time of the last queued chunk. Chunks are delivered in order.
+    last_delivery: Option<Instant>,
+    last_lost: bool,
+    timer: Option<Pin<Box<Sleep>>>,
+}
+
+impl Shaper {
+    fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+
+    fn is_full(&self) -> bool {
+        self.queued_bytes >= MAX_QUEUED_BYTES
+    }
+
+    fn push(&mut self, fault: &LinkFault, bytes: Bytes) {
+        let mut rng = rand::thread_rng();
+        let now = Instant::now();
+
+        let jitter_ms = if fault.jitter_ms > 0 {
+            rng.gen_range(0, 2 * fault.jitter_ms + 1)
+        } else {
+            0
+        };
+        let mut delay =
+            Duration::from_millis((fault.delay_ms + jitter_ms).saturating_sub(fault.jitter_ms));
+
+        let loss_probability = if self.last_lost {
+            fault.loss_correlation_percentage.max(fault.loss_percentage)
+        } else {
+            fault.loss_percentage
+        };
+        self.last_lost = rng.gen_bool(loss_probability.min(100) as f64 / 100.0);
+        if self.last_lost {
+            delay += RETRANSMISSION_DELAY;
+        }
+
+        // Chunks can't overtake each other, and are sent one after the other at the limited rate
+        let mut delivery = now + delay;
+        if let Some(last_delivery) = self.last_delivery {
+            delivery = delivery.max(last_delivery);
+        }
+        if let Some(rate_bytes_per_sec) = fault.rate_bytes_per_sec {
+            delivery += Duration::from_secs_f64(bytes.len() as f64 / rate_bytes_per_sec as f64);
+        }
+
+        self.last_delivery = Some(delivery);
+        self.queued_bytes += bytes.len();
+        self.queue.push_back((delivery, bytes));
+    }
+
+    /// Returns the next chunk once its delivery time has come
+    fn poll_next_chunk(&mut self, context: &mut Context) -> Poll<Option<&mut Bytes>> {
+        let delivery = match self.queue.front() {
+            Some((delivery, _)) => *delivery,
+            None => return Poll::Ready(None),
+        };
+        if delivery > Instant::now() {
+            let timer = self
+                .timer
+                .get_or_insert_with(|| Box::pin(tokio::time::sleep_until(delivery)));
+            timer.as_mut().reset(delivery);
+            ready!(timer.as_mut().poll(context));
+        }
+        Poll::Ready(self.queue.front_mut().map(|(_, bytes)| bytes))
+    }
+
+    fn consume(&mut self, num_bytes: usize) {
+        if let Some((_, bytes)) = self.queue.front_mut() {
+            bytes.advance(num_bytes);
+            self.queued_bytes -= num_bytes;
+            if bytes.is_empty() {
+                self.queue.pop_front();
+            }
+        }
+    }
+}
+
+/// A socket applying the faults injected for its remote peer
+#[derive(Debug)]
+pub struct FaultInjectingSocket<S> {
+    socket: S,
+    faults: Option<(FaultInjector, PeerId)>,
+    // Bytes read from the socket, not yet delivered to the reader
+    reader: Shaper,
+    reader_eof: bool,
+    // Buffer the socket is read into (allocated on the first faulty read, and then reused)
+    read_chunk: Vec<u8>,
+    // Bytes written to the socket, not yet delivered to the underlying socket
+    writer: Shaper,
+}
+
+impl<S> FaultInjectingSocket<S> {
+    fn new(socket: S, fault_injector: FaultInjector, peer_id: PeerId) -> Self {
+        Self {
+            socket,
+            faults: Some((fault_injector, peer_id)),
+            reader: Shaper::default(),
+            reader_eof: false,
+            read_chunk: Vec::new(),
+            writer: Shaper::default(),
+        }
+    }
+
+    fn passthrough(socket: S) -> Self {
+        Self {
+            socket,
+            faults: None,
+            reader: Shaper::default(),
+            reader_eof: false,
+            read_chunk: Vec::new(),
+            writer: Shaper::default(),
+        }
+    }
+
+    fn link_faults(&self) -> io::Result<LinkFaults> {
+        match &self.faults {
+            Some((fault_injector, peer_id)) => {
+                let link_faults = fault_injector.link_faults(peer_id);
+                if link_faults.partitioned {
+                    return Err(partitioned_error(peer_id));
+                }
+                Ok(link_faults)
+            },
+            None => Ok(LinkFaults::default()),
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> FaultInjectingSocket<S> {
+    /// Writes the queued chunks whose delivery time has come to the underlying socket
+    fn poll_write_queued(&mut self, context: &mut Context) -> Poll<io::Result<()>> {
+        while let Some(bytes) = ready!(self.writer.poll_next_chunk(context)) {
+            let num_bytes = ready!(Pin::new(&mut self.socket).poll_write(context, bytes))?;
+            if num_bytes == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.writer.consume(num_bytes);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for FaultInjectingSocket<S> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        context: &mut Context,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let fault = this.link_faults()?.inbound;
+        if fault.is_noop() && this.reader.is_empty() {
+            return Pin::new(&mut this.socket).poll_read(context, buf);
+        }
+
+        // Queue everything the socket has available
+        this.read_chunk.resize(READ_CHUNK_SIZE, 0);
+        while !this.reader_eof && !this.reader.is_full() {
+            match Pin::new(&mut this.socket).poll_read(context, &mut this.read_chunk)? {
+                Poll::Ready(0) => this.reader_eof = true,
+                Poll::Ready(num_bytes) => {
+                    let bytes = Bytes::copy_from_slice(&this.read_chunk[..num_bytes]);
+                    this.reader.push(&fault, bytes);
+                },
+                Poll::Pending => break,
+            }
+        }
+
+        match ready!(this.reader.poll_next_chunk(context)) {
+            Some(bytes) => {
+                let num_bytes = bytes.len().min(buf.len());
+                buf[..num_bytes].copy_from_slice(&bytes[..num_bytes]);
+                this.reader.consume(num_bytes);
+                Poll::Ready(Ok(num_bytes))
+            },
+            None if this.reader_eof => Poll::Ready(Ok(0)),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for FaultInjectingSocket<S> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        context: &mut Context,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        let fault = this.link_faults()?.outbound;
+        if fault.is_noop() && this.writer.is_empty() {
+            return Pin::new(&mut this.socket).poll_write(context, buf);
+        }
+
+        if this.poll_write_queued(context)?.is_pending() && this.writer.is_full() {
+            return Poll::Pending;
+        }
+        this.writer.push(&fault, Bytes::copy_from_slice(buf));
+        // Register a wakeup for the delivery of the queued bytes
+        let _ = this.poll_write_queued(context)?;
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        this.link_faults()?;
+        ready!(this.poll_write_queued(context))?;
+        Pin::new(&mut this.socket).poll_flush(context)
+    }
+
+    fn poll_close(self: Pin<&mut Self>, context: &mut Context) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_queued(context))?;
+        Pin::new(&mut this.socket).poll_close(context)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::transport::memory::MemoryTransport;
+    use futures::{
+        future::join,
+        io::{AsyncReadExt, AsyncWriteExt},
+        stream::StreamExt,
+    };
+    use tempfile::TempDir;
+
+    fn setup(
+        peer_id: PeerId,
+        link_faults: LinkFaults,
+    ) -> (TempDir, FaultInjectingTransport<MemoryTransport>) {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("fault_injection.yaml");
+        FaultInjectionConfig {
+            peers: BTreeMap::from([(peer_id, link_faults)]),
+        }
+        .save(&path)
+        .unwrap();
+        let transport = FaultInjectingTransport::new(MemoryTransport, FaultInjector::new(path));
+        (dir, transport)
+    }
+
+    #[test]
+    fn config_roundtrip() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("fault_injection.yaml");
+        assert_eq!(
+            FaultInjectionConfig::load(&path).unwrap(),
+            FaultInjectionConfig::default()
+        );
+
+        let config = FaultInjectionConfig {
+            peers: BTreeMap::from([(PeerId::random(), LinkFaults {
+                partitioned: false,
+                outbound: LinkFault {
+                    delay_ms: 100,
+                    rate_bytes_per_sec: Some(1000),
+                    ..LinkFault::default()
+                },
+                inbound: LinkFault::default(),
+            })]),
+        };
+        config.save(&path).unwrap();
+        assert_eq!(FaultInjectionConfig::load(&path).unwrap(), config);
+    }
+
+    #[tokio::test]
+    async fn delays_both_directions() {
+        let peer_id = PeerId::random();
+        let delay = LinkFault {
+            delay_ms: 100,
+            ..LinkFault::default()
+        };
+        let (_dir, transport) = setup(peer_id, LinkFaults {
+            partitioned: false,
+            outbound: delay,
+            inbound: delay,
+        });
+
+        let (listener, addr) = transport.listen_on("/memory/0".parse().unwrap()).unwrap();
+        let start = Instant::now();
+        let listener_task = async move {
+            let (inbound, _) = listener.into_future().await.0.unwrap().unwrap();
+            let mut socket = inbound.await.unwrap();
+            let mut buf = [0; 4];
+            socket.read_exact(&mut buf).await.unwrap();
+            socket.write_all(&buf).await.unwrap();
+            socket.flush().await.unwrap();
+        };
+        let dialer_task = async move {
+            let mut socket = transport.dial(peer_id, addr).unwrap().await.unwrap();
+            socket.write_all(b"ping").await.unwrap();
+            socket.flush().await.unwrap();
+            let mut buf = [0; 4];
+            socket.read_exact(&mut buf).await.unwrap();
+            assert_eq!(&buf, b"ping");
+        };
+        join(listener_task, dialer_task).await;
+        assert!(start.elapsed() >= Duration::from_millis(200));
+    }
+
+    #[tokio::test]
+    async fn partition_fails_dial() {
+        let peer_id = PeerId::random();
+        let (_dir, transport) = setup(peer_id, LinkFaults {
+            partitioned: true,
+            ..LinkFaults::default()
+        });
+
+        let (_listener, addr) = transport.listen_on("/memory/0".parse().unwrap()).unwrap();
+        let error = transport.dial(peer_id, addr.clone()).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionReset);
+
+        // Other peers aren't affected
+        assert!(transport.dial(PeerId::random(), addr).is_ok());
+    }
+}
@@ -15,10 +15,16 @@
 //!
 //! Internally both the client and server leverage a NetworkStream that communications in blocks
 //! where a block is a length prefixed array of bytes.
+//!
+//! Clients and servers can optionally authenticate each other and encrypt their streams, see the
+//! [`noise`] module.
 
 pub mod grpc_network_service;
 pub mod network_controller;
+pub mod noise;
 
+use crate::noise::{NoiseClientConfig, NoiseServerConfig};
+use aptos_crypto::noise::{NoiseError, NoiseSession};
 use aptos_logger::{info, trace, warn, Schema};
 use aptos_metrics_core::{register_int_counter_vec, IntCounterVec};
 use once_cell::sync::Lazy;
@@ -137,14 +143,22 @@ pub enum Error {
     AlreadyShutdown,
     #[error("Found data that is too large to decode: {0}")]
     DataTooLarge(usize),
+    #[error("Client instance {0} refused while serving client instance {1}")]
+    ClientRefused(u64, u64),
+    #[error("Invalid handshake: {0}")]
+    InvalidHandshake(String),
     #[error("Internal network error:")]
     NetworkError(#[from] std::io::Error),
     #[error("No active stream")]
     NoActiveStream,
+    #[error("Noise error: {0}")]
+    NoiseError(#[from] NoiseError),
     #[error("Overflow error: {0}")]
     OverflowError(String),
     #[error("Remote stream cleanly closed")]
     RemoteStreamClosed,
+    #[error("Untrusted peer: {0}")]
+    UntrustedPeer(String),
 }
 
 pub struct NetworkClient {
@@ -153,6 +167,8 @@ pub struct NetworkClient {
     stream: Option<NetworkStream>,
     /// Read, Write, Connect timeout in milliseconds.
     timeout_ms: u64,
+    /// Authenticates the server and encrypts the stream, if set.
+    noise: Option<NoiseClientConfig>,
 }
 
 impl NetworkClient {
@@ -162,6 +178,22 @@ impl NetworkClient {
             server,
             stream: None,
             timeout_ms,
+            noise: None,
+        }
+    }
+
+    /// Creates a client that mutually authenticates with the server. Unlike other clients, it
+    /// makes a single connection attempt per read or write, so that callers can bound the time
+    /// spent waiting on the server.
+    pub fn new_authenticated(
+        service: String,
+        server: SocketAddr,
+        timeout_ms: u64,
+        noise: NoiseClientConfig,
+    ) -> Self {
+        Self {
+            noise: Some(noise),
+            ..Self::new(service, server, timeout_ms)
         }
     }
 
@@ -242,21 +274,39 @@ impl NetworkClient {
             let sleeptime = time::Duration::from_millis(100);
             while let Err(err) = stream {
                 self.increment_counter(Method::Connect, MethodResult::Failure);
+                let err = err.into();
                 warn!(SecureNetLogSchema::new(
                     &self.service,
                     NetworkMode::Client,
                     LogEvent::ConnectionFailed,
                 )
-                .error(&err.into())
+                .error(&err)
                 .remote_peer(&self.server));
 
+                if self.noise.is_some() {
+                    return Err(err);
+                }
                 thread::sleep(sleeptime);
                 stream = TcpStream::connect_timeout(&self.server, timeout);
             }
 
             let stream = stream?;
             stream.set_nodelay(true)?;
-            self.stream = Some(NetworkStream::new(stream, self.server, self.timeout_ms));
+            let mut stream = NetworkStream::new(stream, self.server, self.timeout_ms);
+            if let Some(noise) = &self.noise {
+                if let Err(err) = noise.handshake(&self.service, &mut stream) {
+                    self.increment_counter(Method::Connect, MethodResult::Failure);
+                    warn!(SecureNetLogSchema::new(
+                        &self.service,
+                        NetworkMode::Client,
+                        LogEvent::ConnectionFailed,
+                    )
+                    .error(&err)
+                    .remote_peer(&self.server));
+                    return Err(err);
+                }
+            }
+            self.stream = Some(stream);
             self.increment_counter(Method::Connect, MethodResult::Success);
             info!(SecureNetLogSchema::new(
                 &self.service,
@@ -276,6 +326,8 @@ pub struct NetworkServer {
     stream: Option<NetworkStream>,
     /// Read, Write, Connect timeout in milliseconds.
     timeout_ms: u64,
+    /// Authenticates the clients and encrypts the stream, if set.
+    noise: Option<NoiseServerConfig>,
 }
 
 impl NetworkServer {
@@ -286,6 +338,21 @@ impl NetworkServer {
             listener: Some(listener.unwrap()),
             stream: None,
             timeout_ms,
+            noise: None,
+        }
+    }
+
+    /// Creates a server that only serves the clients it authenticates, one client instance at a
+    /// time.
+    pub fn new_authenticated(
+        service: String,
+        listen: SocketAddr,
+        timeout_ms: u64,
+        noise: NoiseServerConfig,
+    ) -> Self {
+        Self {
+            noise: Some(noise),
+            ..Self::new(service, listen, timeout_ms)
         }
     }
 
@@ -316,6 +383,9 @@ impl NetworkServer {
             self.stream = None;
         } else {
             self.increment_counter(Method::Read, MethodResult::Success);
+            if let Some(noise) = &mut self.noise {
+                noise.client_active();
+            }
         }
 
         result.map_err(|err| err.1)
@@ -398,7 +468,21 @@ impl NetworkServer {
             .remote_peer(&stream_addr));
 
             stream.set_nodelay(true)?;
-            self.stream = Some(NetworkStream::new(stream, stream_addr, self.timeout_ms));
+            let mut stream = NetworkStream::new(stream, stream_addr, self.timeout_ms);
+            if let Some(noise) = &mut self.noise {
+                if let Err(err) = noise.handshake(&self.service, &mut stream) {
+                    self.increment_counter(Method::Connect, MethodResult::Failure);
+                    warn!(SecureNetLogSchema::new(
+                        &self.service,
+                        NetworkMode::Server,
+                        LogEvent::ConnectionFailed,
+                    )
+                    .error(&err)
+                    .remote_peer(&stream_addr));
+                    return Err(err);
+                }
+            }
+            self.stream = Some(stream);
         }
 
         self.stream.as_mut().ok_or(Error::NoActiveStream)
@@ -410,6 +494,8 @@ struct NetworkStream {
     remote: SocketAddr,
     buffer: Vec<u8>,
     temp_buffer: [u8; 1024],
+    /// Encrypts the messages once the stream is authenticated
+    session: Option<NoiseSession>,
 }
 
 impl NetworkStream {
@@ -424,11 +510,32 @@ impl NetworkStream {
             remote,
             buffer: Vec::new(),
             temp_buffer: [0; 1024],
+            session: None,
         }
     }
 
     /// Blocking read until able to successfully read an entire message
     pub fn read(&mut self) -> Result<Vec<u8>, Error> {
+        let data = self.read_block()?;
+        match &mut self.session {
+            Some(session) => noise::decrypt(session, data),
+            None => Ok(data),
+        }
+    }
+
+    /// Blocking write until able to successfully send an entire message
+    pub fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+        match &mut self.session {
+            Some(session) => {
+                let data = noise::encrypt(session, data)?;
+                self.write_block(&data)
+            },
+            None => self.write_block(data),
+        }
+    }
+
+    /// Blocking read until able to successfully read an entire block
+    fn read_block(&mut self) -> Result<Vec<u8>, Error> {
         let result = self.read_buffer();
         if !result.is_empty() {
             return Ok(result);
@@ -456,8 +563,8 @@ impl NetworkStream {
         Ok(self.stream.shutdown(Shutdown::Both)?)
     }
 
-    /// Blocking write until able to successfully send an entire message
-    pub fn write(&mut self, data: &[u8]) -> Result<(), Error> {
+    /// Blocking write until able to successfully send an entire block
+    fn write_block(&mut self, data: &[u8]) -> Result<(), Error> {
         let u32_max = u32::max_value() as usize;
         if u32_max <= data.len() {
             return Err(Error::DataTooLarge(data.len()));
@@ -517,7 +624,15 @@ impl NetworkStream {
 mod test {
     use super::*;
     use aptos_config::utils;
-    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use aptos_crypto::{
+        noise::{handshake_init_msg_len, NoiseConfig, MAX_SIZE_NOISE_MSG},
+        x25519, Uniform,
+    };
+    use rand::rngs::OsRng;
+    use std::{
+        net::{IpAddr, Ipv4Addr, SocketAddr},
+        time::Duration,
+    };
 
     /// Read, Write, Connect timeout in milliseconds.
     const TIMEOUT: u64 = 5_000;
@@ -655,4 +770,162 @@ mod test {
         let result2 = server2.read().unwrap();
         assert_eq!(data2, result2);
     }
+
+    #[test]
+    fn test_authenticated_ping() {
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let server_key = x25519::PrivateKey::generate(&mut OsRng);
+        let client_key = x25519::PrivateKey::generate(&mut OsRng);
+        let server_public_key = server_key.public_key();
+
+        let noise = NoiseServerConfig::new(
+            server_key,
+            [client_key.public_key()].into_iter().collect(),
+            Duration::from_secs(60),
+        );
+        let mut server =
+            NetworkServer::new_authenticated("test".to_string(), server_addr, TIMEOUT, noise);
+        let server_thread = thread::spawn(move || {
+            let data = server.read().unwrap();
+            server.write(&data).unwrap();
+        });
+
+        let noise = NoiseClientConfig::new(client_key, server_public_key, 0);
+        let mut client =
+            NetworkClient::new_authenticated("test".to_string(), server_addr, TIMEOUT, noise);
+
+        // Larger than a single noise message
+        let data = vec![7; 3 * MAX_SIZE_NOISE_MSG];
+        client.write(&data).unwrap();
+        assert_eq!(data, client.read().unwrap());
+        server_thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_untrusted_client() {
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let server_key = x25519::PrivateKey::generate(&mut OsRng);
+        let trusted_key = x25519::PrivateKey::generate(&mut OsRng);
+        let server_public_key = server_key.public_key();
+
+        let noise = NoiseServerConfig::new(
+            server_key,
+            [trusted_key.public_key()].into_iter().collect(),
+            Duration::from_secs(60),
+        );
+        let mut server =
+            NetworkServer::new_authenticated("test".to_string(), server_addr, TIMEOUT, noise);
+        let server_thread = thread::spawn(move || {
+            assert!(matches!(server.read(), Err(Error::UntrustedPeer(_))));
+        });
+
+        let untrusted_key = x25519::PrivateKey::generate(&mut OsRng);
+        let noise = NoiseClientConfig::new(untrusted_key, server_public_key, 0);
+        let mut client =
+            NetworkClient::new_authenticated("test".to_string(), server_addr, TIMEOUT, noise);
+        client.write(&[0, 1, 2, 3]).unwrap_err();
+        server_thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_single_client_instance() {
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let server_key = x25519::PrivateKey::generate(&mut OsRng);
+        let client_key_bytes: [u8; 32] = rand::random();
+        let server_public_key = server_key.public_key();
+        let data = vec![0, 1, 2, 3];
+
+        let noise = NoiseServerConfig::new(
+            server_key,
+            [x25519::PrivateKey::from(client_key_bytes).public_key()]
+                .into_iter()
+                .collect(),
+            Duration::from_secs(60),
+        );
+        let mut server =
+            NetworkServer::new_authenticated("test".to_string(), server_addr, TIMEOUT, noise);
+        let expected_data = data.clone();
+        let server_thread = thread::spawn(move || {
+            // The first client instance is served until it disconnects
+            assert_eq!(expected_data, server.read().unwrap());
+            server.read().unwrap_err();
+            // Another instance is refused while the first one holds the lease
+            assert!(matches!(server.read(), Err(Error::ClientRefused(2, 1))));
+            // The first instance can reconnect
+            assert_eq!(expected_data, server.read().unwrap());
+        });
+
+        let new_client = |instance_id| {
+            let client_key = x25519::PrivateKey::from(client_key_bytes);
+            let noise = NoiseClientConfig::new(client_key, server_public_key, instance_id);
+            NetworkClient::new_authenticated("test".to_string(), server_addr, TIMEOUT, noise)
+        };
+        let mut client = new_client(1);
+        client.write(&data).unwrap();
+        client.shutdown().unwrap();
+
+        new_client(2).write(&data).unwrap_err();
+        new_client(1).write(&data).unwrap();
+        server_thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_replayed_handshake() {
+        let server_port = utils::get_available_port();
+        let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), server_port);
+        let server_key = x25519::PrivateKey::generate(&mut OsRng);
+        let client_key_bytes: [u8; 32] = rand::random();
+        let server_public_key = server_key.public_key();
+        let data = vec![0, 1, 2, 3];
+
+        let noise = NoiseServerConfig::new(
+            server_key,
+            [x25519::PrivateKey::from(client_key_bytes).public_key()]
+                .into_iter()
+                .collect(),
+            Duration::from_secs(60),
+        );
+        let mut server =
+            NetworkServer::new_authenticated("test".to_string(), server_addr, TIMEOUT, noise);
+        let expected_data = data.clone();
+        let server_thread = thread::spawn(move || {
+            // The replayed handshake succeeds, but its requests can't be decrypted
+            server.read().unwrap_err();
+            // So it didn't take the lease from other client instances
+            assert_eq!(expected_data, server.read().unwrap());
+        });
+
+        // Replay a client init message of instance 1, which can't be followed by a request
+        // encrypted in the session, as the ephemeral keys of the client are unknown.
+        let payload = 1u64.to_le_bytes();
+        let mut init_message = vec![0; handshake_init_msg_len(payload.len())];
+        NoiseConfig::new(x25519::PrivateKey::from(client_key_bytes))
+            .initiate_connection(
+                &mut OsRng,
+                b"aptos-secure-net/test",
+                server_public_key,
+                Some(&payload),
+                &mut init_message,
+            )
+            .unwrap();
+        let mut stream = NetworkStream::new(
+            TcpStream::connect(server_addr).unwrap(),
+            server_addr,
+            TIMEOUT,
+        );
+        stream.write_block(&init_message).unwrap();
+        stream.read_block().unwrap();
+        stream.write_block(&[0; 32]).unwrap();
+
+        // Another client instance is then served
+        let client_key = x25519::PrivateKey::from(client_key_bytes);
+        let noise = NoiseClientConfig::new(client_key, server_public_key, 2);
+        let mut client =
+            NetworkClient::new_authenticated("test".to_string(), server_addr, TIMEOUT, noise);
+        client.write(&data).unwrap();
+        server_thread.join().unwrap();
+    }
 }
@@ -0,0 +1,184 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Mutual authentication and encryption of the network streams, for services that cannot trust the
+//! network between their client and server (e.g., when they run on different hosts).
+//!
+//! Upon connecting, the client and server perform a Noise IK handshake: the client has to know the
+//! static public key of the server, and the server only accepts the clients whose static public
+//! keys it trusts. All messages are then encrypted with the keys of the Noise session.
+//!
+//! The client also sends the identifier of its instance in the handshake. A server only serves a
+//! single client instance at a time: once a client instance has been served, other instances are
+//! refused until the current one has been inactive for the client lease. This prevents two clients
+//! from being served concurrently, e.g., when a client fails over to another host while the
+//! original one is still running.
+//!
+//! The handshake alone doesn't grant the lease, as a recorded client init message can be replayed
+//! by anyone who can reach the server. The lease is only granted to a client instance (and then
+//! extended) once a request it sent over the Noise session has been decrypted, which requires the
+//! ephemeral keys of the client.
+
+use crate::{Error, NetworkStream};
+use aptos_crypto::{
+    noise::{self, NoiseConfig, NoiseSession, AES_GCM_TAGLEN, MAX_SIZE_NOISE_MSG},
+    x25519,
+};
+use rand::rngs::OsRng;
+use std::{
+    collections::HashSet,
+    convert::TryInto,
+    time::{Duration, Instant},
+};
+
+/// The maximum size of the plaintext of a single Noise message. Larger messages of the stream are
+/// split into multiple Noise messages.
+const MAX_CHUNK_SIZE: usize = MAX_SIZE_NOISE_MSG - AES_GCM_TAGLEN;
+
+/// Encrypts a message of the stream
+pub(crate) fn encrypt(session: &mut NoiseSession, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut encrypted = Vec::with_capacity(noise::encrypted_len(data.len()));
+    for chunk in data.chunks(MAX_CHUNK_SIZE) {
+        let start = encrypted.len();
+        encrypted.extend_from_slice(chunk);
+        let tag = session.write_message_in_place(&mut encrypted[start..])?;
+        encrypted.extend_from_slice(&tag);
+    }
+    Ok(encrypted)
+}
+
+/// Decrypts a message of the stream
+pub(crate) fn decrypt(session: &mut NoiseSession, mut data: Vec<u8>) -> Result<Vec<u8>, Error> {
+    let mut decrypted = Vec::with_capacity(data.len());
+    for chunk in data.chunks_mut(MAX_SIZE_NOISE_MSG) {
+        decrypted.extend_from_slice(session.read_message_in_place(chunk)?);
+    }
+    Ok(decrypted)
+}
+
+/// The prologue of the handshakes, which binds the sessions to the service
+fn prologue(service: &str) -> Vec<u8> {
+    format!("aptos-secure-net/{}", service).into_bytes()
+}
+
+pub struct NoiseClientConfig {
+    config: NoiseConfig,
+    server_public_key: x25519::PublicKey,
+    instance_id: u64,
+}
+
+impl NoiseClientConfig {
+    /// Creates the configuration of a client identified by `identity_key`. Clients sharing an
+    /// `instance_id` are considered as the same client instance by the server.
+    pub fn new(
+        identity_key: x25519::PrivateKey,
+        server_public_key: x25519::PublicKey,
+        instance_id: u64,
+    ) -> Self {
+        Self {
+            config: NoiseConfig::new(identity_key),
+            server_public_key,
+            instance_id,
+        }
+    }
+
+    pub(crate) fn handshake(&self, service: &str, stream: &mut NetworkStream) -> Result<(), Error> {
+        let payload = self.instance_id.to_le_bytes();
+        let mut init_message = vec![0; noise::handshake_init_msg_len(payload.len())];
+        let state = self.config.initiate_connection(
+            &mut OsRng,
+            &prologue(service),
+            self.server_public_key,
+            Some(&payload),
+            &mut init_message,
+        )?;
+        stream.write_block(&init_message)?;
+
+        let response = stream.read_block()?;
+        let (_, session) = self.config.finalize_connection(state, &response)?;
+        stream.session = Some(session);
+        Ok(())
+    }
+}
+
+/// The client instance currently served
+struct ActiveClient {
+    instance_id: u64,
+    last_active: Instant,
+}
+
+pub struct NoiseServerConfig {
+    config: NoiseConfig,
+    trusted_clients: HashSet<x25519::PublicKey>,
+    client_lease: Duration,
+    active_client: Option<ActiveClient>,
+    /// The client instance of the last handshake, until it sends its first request
+    pending_instance_id: Option<u64>,
+}
+
+impl NoiseServerConfig {
+    /// Creates the configuration of a server identified by `identity_key`, serving the clients
+    /// identified by `trusted_clients`. Other client instances are refused until the served one has
+    /// been inactive for `client_lease`.
+    pub fn new(
+        identity_key: x25519::PrivateKey,
+        trusted_clients: HashSet<x25519::PublicKey>,
+        client_lease: Duration,
+    ) -> Self {
+        Self {
+            config: NoiseConfig::new(identity_key),
+            trusted_clients,
+            client_lease,
+            active_client: None,
+            pending_instance_id: None,
+        }
+    }
+
+    pub(crate) fn handshake(
+        &mut self,
+        service: &str,
+        stream: &mut NetworkStream,
+    ) -> Result<(), Error> {
+        let init_message = stream.read_block()?;
+        let (client_public_key, state, payload) = self
+            .config
+            .parse_client_init_message(&prologue(service), &init_message)?;
+        if !self.trusted_clients.contains(&client_public_key) {
+            return Err(Error::UntrustedPeer(client_public_key.to_string()));
+        }
+        let instance_id = payload
+            .as_slice()
+            .try_into()
+            .map(u64::from_le_bytes)
+            .map_err(|_| Error::InvalidHandshake("Missing client instance id".into()))?;
+        if let Some(active_client) = &self.active_client {
+            if active_client.instance_id != instance_id
+                && active_client.last_active.elapsed() < self.client_lease
+            {
+                return Err(Error::ClientRefused(instance_id, active_client.instance_id));
+            }
+        }
+
+        let mut response = vec![0; noise::handshake_resp_msg_len(0)];
+        let session = self
+            .config
+            .respond_to_client(&mut OsRng, state, None, &mut response)?;
+        stream.write_block(&response)?;
+        stream.session = Some(session);
+        self.pending_instance_id = Some(instance_id);
+        Ok(())
+    }
+
+    /// Grants the lease to the client instance of the current session upon its first request, or
+    /// extends the lease of the served client.
+    pub(crate) fn client_active(&mut self) {
+        if let Some(instance_id) = self.pending_instance_id.take() {
+            self.active_client = Some(ActiveClient {
+                instance_id,
+                last_active: Instant::now(),
+            });
+        } else if let Some(active_client) = &mut self.active_client {
+            active_client.last_active = Instant::now();
+        }
+    }
+}
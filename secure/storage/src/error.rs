@@ -36,6 +36,12 @@ impl From<chrono::format::ParseError> for Error {
     }
 }
 
+impl From<cryptoki::error::Error> for Error {
+    fn from(error: cryptoki::error::Error) -> Self {
+        Self::InternalError(format!("{}", error))
+    }
+}
+
 impl From<io::Error> for Error {
     fn from(error: io::Error) -> Self {
         Self::InternalError(format!("{}", error))
@@ -0,0 +1,490 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{CryptoStorage, Error, GetResponse, KVStorage, PublicKeyResponse};
+use aptos_crypto::{
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature},
+    hash::CryptoHash,
+    signing_message,
+};
+use aptos_infallible::Mutex;
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use cryptoki::{
+    context::{CInitializeArgs, Pkcs11},
+    error::RvError,
+    mechanism::Mechanism,
+    object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle},
+    session::{Session, UserType},
+    types::AuthPin,
+};
+use once_cell::sync::Lazy;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    path::{Path, PathBuf},
+};
+
+/// The CKA_APPLICATION of the data objects holding the key/value pairs
+const APPLICATION: &[u8] = b"aptos-secure-storage";
+/// DER encoding of the Ed25519 curve OID (1.3.101.112), used as the CKA_EC_PARAMS of keys
+const ED25519_EC_PARAMS: &[u8] = &[0x06, 0x03, 0x2b, 0x65, 0x70];
+/// DER tag of an OCTET STRING, which wraps the CKA_EC_POINT of Ed25519 public keys
+const DER_OCTET_STRING: u8 = 0x04;
+/// The CKA_LABEL of the AES key encrypting the key/value pairs
+const DATA_KEY_LABEL: &[u8] = b"aptos-secure-storage-data-key";
+/// The length (in bytes) of the AES key encrypting the key/value pairs
+const DATA_KEY_LENGTH: u64 = 32;
+/// The length (in bytes) of the AES-CBC initialization vector prefixing each encrypted value
+const AES_CBC_IV_LENGTH: usize = 16;
+
+/// The initialized PKCS#11 modules of this process, by library path. A module can only be
+/// initialized once per process, so every storage (and namespace) backed by the same module shares
+/// its context and opens its own session on it.
+static MODULES: Lazy<Mutex<HashMap<PathBuf, Pkcs11>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Pkcs11Storage keeps data in a token accessed through a PKCS#11 module, e.g., a hardware
+/// security module. Ed25519 key pairs are generated inside the token as sensitive,
+/// non-extractable objects: signing happens in the token using the key handle and private keys
+/// can never be exported. Key/value pairs (e.g., the BLS12-381 consensus key and the safety
+/// data of safety rules, which can't be used inside a token) are stored as private data objects
+/// on the token, encrypted by a non-extractable AES key generated in the token. They are only
+/// decrypted (by the token) when read.
+///
+/// Each named key pair is labelled with its name and its previous version (if any) is labelled
+/// with the name followed by `_previous`, matching the versioning of the other storage engines.
+/// The CKA_ID of both objects of a key pair holds its creation time, which also links the
+/// public and private key objects together.
+pub struct Pkcs11Storage {
+    session: Mutex<Session>,
+    time_service: TimeService,
+}
+
+impl Pkcs11Storage {
+    /// Loads the PKCS#11 module at `library_path` and logs into the token with the given label
+    pub fn new(library_path: &Path, token_label: &str, pin: String) -> Result<Self, Error> {
+        let pkcs11 = Self::module(library_path)?;
+
+        let mut slot = None;
+        for candidate in pkcs11.get_slots_with_token()? {
+            if pkcs11.get_token_info(candidate)?.label() == token_label {
+                slot = Some(candidate);
+                break;
+            }
+        }
+        let slot = slot.ok_or_else(|| {
+            Error::InternalError(format!("No PKCS#11 token labelled: {}", token_label))
+        })?;
+
+        let session = pkcs11.open_rw_session(slot)?;
+        session.login(UserType::User, Some(&AuthPin::new(pin)))?;
+        Ok(Self {
+            session: Mutex::new(session),
+            time_service: TimeService::real(),
+        })
+    }
+
+    /// Returns the initialized context of the module at `library_path`, loading and initializing
+    /// the module on first use.
+    fn module(library_path: &Path) -> Result<Pkcs11, Error> {
+        let mut modules = MODULES.lock();
+        if let Some(pkcs11) = modules.get(library_path) {
+            return Ok(pkcs11.clone());
+        }
+
+        let pkcs11 = Pkcs11::new(library_path)?;
+        match pkcs11.initialize(CInitializeArgs::OsThreads) {
+            // The module may have been initialized by another user of the library in this process
+            Ok(()) | Err(cryptoki::error::Error::Pkcs11(RvError::CryptokiAlreadyInitialized)) => {},
+            Err(error) => return Err(error.into()),
+        }
+        modules.insert(library_path.to_path_buf(), pkcs11.clone());
+        Ok(pkcs11)
+    }
+
+    fn find_data(session: &Session, key: &str) -> Result<Option<ObjectHandle>, Error> {
+        let objects = session.find_objects(&[
+            Attribute::Class(ObjectClass::DATA),
+            Attribute::Application(APPLICATION.to_vec()),
+            Attribute::Label(key.as_bytes().to_vec()),
+        ])?;
+        Ok(objects.into_iter().next())
+    }
+
+    fn find_data_key(session: &Session) -> Result<Option<ObjectHandle>, Error> {
+        let objects = session.find_objects(&[
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::AES),
+            Attribute::Label(DATA_KEY_LABEL.to_vec()),
+        ])?;
+        Ok(objects.into_iter().next())
+    }
+
+    /// Returns the AES key encrypting the key/value pairs, generating it in the token on first use
+    fn data_key(session: &Session) -> Result<ObjectHandle, Error> {
+        if let Some(data_key) = Self::find_data_key(session)? {
+            return Ok(data_key);
+        }
+        Ok(session.generate_key(
+            &Mechanism::AesKeyGen,
+            &[
+                Attribute::Class(ObjectClass::SECRET_KEY),
+                Attribute::KeyType(KeyType::AES),
+                Attribute::Token(true),
+                Attribute::Private(true),
+                Attribute::Sensitive(true),
+                Attribute::Extractable(false),
+                Attribute::Encrypt(true),
+                Attribute::Decrypt(true),
+                Attribute::ValueLen(DATA_KEY_LENGTH.into()),
+                Attribute::Label(DATA_KEY_LABEL.to_vec()),
+            ],
+        )?)
+    }
+
+    /// Encrypts the value inside the token, returning the initialization vector followed by the
+    /// ciphertext.
+    fn encrypt_value(session: &Session, value: &[u8]) -> Result<Vec<u8>, Error> {
+        let data_key = Self::data_key(session)?;
+        let mut iv = [0u8; AES_CBC_IV_LENGTH];
+        session.generate_random_slice(&mut iv)?;
+        let mut encrypted_value = iv.to_vec();
+        encrypted_value.extend(session.encrypt(&Mechanism::AesCbcPad(iv), data_key, value)?);
+        Ok(encrypted_value)
+    }
+
+    /// Decrypts (inside the token) a value encrypted by `encrypt_value`
+    fn decrypt_value(session: &Session, encrypted_value: &[u8]) -> Result<Vec<u8>, Error> {
+        let data_key = Self::find_data_key(session)?
+            .ok_or_else(|| Error::InternalError("Missing data key in PKCS#11 token".into()))?;
+        if encrypted_value.len() < AES_CBC_IV_LENGTH {
+            return Err(Error::InternalError(
+                "Encrypted value shorter than its initialization vector".into(),
+            ));
+        }
+        let (iv, ciphertext) = encrypted_value.split_at(AES_CBC_IV_LENGTH);
+        let iv = <[u8; AES_CBC_IV_LENGTH]>::try_from(iv).expect("Length was checked above");
+        Ok(session.decrypt(&Mechanism::AesCbcPad(iv), data_key, ciphertext)?)
+    }
+
+    fn find_key(
+        session: &Session,
+        class: ObjectClass,
+        label: &str,
+    ) -> Result<Option<ObjectHandle>, Error> {
+        let objects = session.find_objects(&[
+            Attribute::Class(class),
+            Attribute::KeyType(KeyType::EC_EDWARDS),
+            Attribute::Label(label.as_bytes().to_vec()),
+        ])?;
+        Ok(objects.into_iter().next())
+    }
+
+    /// Returns the public and private key objects labelled with `label`
+    fn find_key_pair(
+        session: &Session,
+        label: &str,
+    ) -> Result<Option<(ObjectHandle, ObjectHandle)>, Error> {
+        let public_key = Self::find_key(session, ObjectClass::PUBLIC_KEY, label)?;
+        let private_key = Self::find_key(session, ObjectClass::PRIVATE_KEY, label)?;
+        match (public_key, private_key) {
+            (Some(public_key), Some(private_key)) => Ok(Some((public_key, private_key))),
+            (None, None) => Ok(None),
+            _ => Err(Error::InternalError(format!(
+                "Incomplete key pair in PKCS#11 token: {}",
+                label
+            ))),
+        }
+    }
+
+    fn public_key(
+        session: &Session,
+        handle: ObjectHandle,
+    ) -> Result<(Ed25519PublicKey, u64), Error> {
+        let attributes =
+            session.get_attributes(handle, &[AttributeType::EcPoint, AttributeType::Id])?;
+        let mut public_key = None;
+        let mut last_update = 0;
+        for attribute in attributes {
+            match attribute {
+                Attribute::EcPoint(point) => {
+                    // Tokens either return the raw point or (as the standard mandates) the
+                    // point wrapped in a DER octet string.
+                    let bytes = match point.as_slice() {
+                        [DER_OCTET_STRING, 32, bytes @ ..] if bytes.len() == 32 => bytes,
+                        bytes => bytes,
+                    };
+                    public_key = Some(
+                        Ed25519PublicKey::try_from(bytes)
+                            .map_err(|e| Error::SerializationError(e.to_string()))?,
+                    );
+                },
+                Attribute::Id(id) => {
+                    last_update = <[u8; 8]>::try_from(id.as_slice()).map_or(0, u64::from_be_bytes);
+                },
+                _ => {},
+            }
+        }
+        let public_key =
+            public_key.ok_or_else(|| Error::InternalError("Public key without EC point".into()))?;
+        Ok((public_key, last_update))
+    }
+
+    fn key_pair_templates(&self, label: &str) -> (Vec<Attribute>, Vec<Attribute>) {
+        let label = label.as_bytes().to_vec();
+        let id = self.time_service.now_secs().to_be_bytes().to_vec();
+        let public_template = vec![
+            Attribute::Token(true),
+            Attribute::Private(false),
+            Attribute::Verify(true),
+            Attribute::EcParams(ED25519_EC_PARAMS.to_vec()),
+            Attribute::Label(label.clone()),
+            Attribute::Id(id.clone()),
+        ];
+        let private_template = vec![
+            Attribute::Token(true),
+            Attribute::Private(true),
+            Attribute::Sensitive(true),
+            Attribute::Extractable(false),
+            Attribute::Sign(true),
+            Attribute::Label(label),
+            Attribute::Id(id),
+        ];
+        (public_template, private_template)
+    }
+
+    /// Generates a new key pair in the token and returns its public and private key objects
+    fn generate_key_pair(
+        &self,
+        session: &Session,
+        label: &str,
+    ) -> Result<(ObjectHandle, ObjectHandle), Error> {
+        let (public_template, private_template) = self.key_pair_templates(label);
+        Ok(session.generate_key_pair(
+            &Mechanism::EccEdwardsKeyPairGen,
+            &public_template,
+            &private_template,
+        )?)
+    }
+
+    /// Returns the private key object of the named key pair with the given public key
+    fn private_key_for_version(
+        session: &Session,
+        name: &str,
+        version: &Ed25519PublicKey,
+    ) -> Result<ObjectHandle, Error> {
+        for label in [name.to_string(), get_previous_version_name(name)] {
+            if let Some((public_key, private_key)) = Self::find_key_pair(session, &label)? {
+                if &Self::public_key(session, public_key)?.0 == version {
+                    return Ok(private_key);
+                }
+            }
+        }
+        Err(Error::KeyVersionNotFound(name.into(), version.to_string()))
+    }
+
+    fn sign_with<T: CryptoHash + Serialize>(
+        session: &Session,
+        private_key: ObjectHandle,
+        message: &T,
+    ) -> Result<Ed25519Signature, Error> {
+        let bytes = signing_message(message).map_err(|e| {
+            Error::InternalError(format!(
+                "Serialization of signable material should not fail, yet returned Error:{}",
+                e
+            ))
+        })?;
+        let signature = session.sign(&Mechanism::Eddsa, private_key, &bytes)?;
+        Ed25519Signature::try_from(signature.as_slice())
+            .map_err(|e| Error::SerializationError(e.to_string()))
+    }
+}
+
+impl KVStorage for Pkcs11Storage {
+    fn available(&self) -> Result<(), Error> {
+        self.session.lock().get_session_info()?;
+        Ok(())
+    }
+
+    fn get<V: DeserializeOwned>(&self, key: &str) -> Result<GetResponse<V>, Error> {
+        let session = self.session.lock();
+        let handle =
+            Self::find_data(&session, key)?.ok_or_else(|| Error::KeyNotSet(key.to_string()))?;
+        for attribute in session.get_attributes(handle, &[AttributeType::Value])? {
+            if let Attribute::Value(value) = attribute {
+                let value = Self::decrypt_value(&session, &value)?;
+                return serde_json::from_slice(&value).map_err(|e| e.into());
+            }
+        }
+        Err(Error::KeyNotSet(key.to_string()))
+    }
+
+    fn set<V: Serialize>(&mut self, key: &str, value: V) -> Result<(), Error> {
+        let now = self.time_service.now_secs();
+        let value = serde_json::to_vec(&GetResponse::new(value, now))?;
+        let session = self.session.lock();
+        let value = Self::encrypt_value(&session, &value)?;
+        match Self::find_data(&session, key)? {
+            Some(handle) => session.update_attributes(handle, &[Attribute::Value(value)])?,
+            None => {
+                session.create_object(&[
+                    Attribute::Class(ObjectClass::DATA),
+                    Attribute::Token(true),
+                    Attribute::Private(true),
+                    Attribute::Modifiable(true),
+                    Attribute::Application(APPLICATION.to_vec()),
+                    Attribute::Label(key.as_bytes().to_vec()),
+                    Attribute::Value(value),
+                ])?;
+            },
+        }
+        Ok(())
+    }
+
+    /// Destroys all the key/value pairs, their AES key and the Ed25519 keys in the token, so this
+    /// should only be used against a token dedicated to testing.
+    #[cfg(any(test, feature = "testing"))]
+    fn reset_and_clear(&mut self) -> Result<(), Error> {
+        let session = self.session.lock();
+        let mut objects = session.find_objects(&[
+            Attribute::Class(ObjectClass::DATA),
+            Attribute::Application(APPLICATION.to_vec()),
+        ])?;
+        objects.extend(Self::find_data_key(&session)?);
+        objects.extend(session.find_objects(&[Attribute::KeyType(KeyType::EC_EDWARDS)])?);
+        for object in objects {
+            session.destroy_object(object)?;
+        }
+        Ok(())
+    }
+}
+
+impl CryptoStorage for Pkcs11Storage {
+    fn create_key(&mut self, name: &str) -> Result<Ed25519PublicKey, Error> {
+        let session = self.session.lock();
+        if Self::find_key_pair(&session, name)?.is_some() {
+            return Err(Error::KeyAlreadyExists(name.to_string()));
+        }
+        let (public_key, _) = self.generate_key_pair(&session, name)?;
+        Self::public_key(&session, public_key).map(|(public_key, _)| public_key)
+    }
+
+    /// Private keys never leave the token
+    fn export_private_key(&self, _name: &str) -> Result<Ed25519PrivateKey, Error> {
+        Err(Error::PermissionDenied)
+    }
+
+    /// Imports the key into the token, after which it can no longer be exported
+    fn import_private_key(&mut self, name: &str, key: Ed25519PrivateKey) -> Result<(), Error> {
+        let session = self.session.lock();
+        if Self::find_key_pair(&session, name)?.is_some() {
+            return Err(Error::KeyAlreadyExists(name.to_string()));
+        }
+
+        let public_key = Ed25519PublicKey::from(&key);
+        let mut point = vec![DER_OCTET_STRING, 32];
+        point.extend_from_slice(&public_key.to_bytes());
+
+        let (mut public_template, mut private_template) = self.key_pair_templates(name);
+        public_template.extend([
+            Attribute::Class(ObjectClass::PUBLIC_KEY),
+            Attribute::KeyType(KeyType::EC_EDWARDS),
+            Attribute::EcPoint(point),
+        ]);
+        private_template.extend([
+            Attribute::Class(ObjectClass::PRIVATE_KEY),
+            Attribute::KeyType(KeyType::EC_EDWARDS),
+            Attribute::EcParams(ED25519_EC_PARAMS.to_vec()),
+            Attribute::Value(key.to_bytes().to_vec()),
+        ]);
+        session.create_object(&public_template)?;
+        session.create_object(&private_template)?;
+        Ok(())
+    }
+
+    /// Private keys never leave the token
+    fn export_private_key_for_version(
+        &self,
+        _name: &str,
+        _version: Ed25519PublicKey,
+    ) -> Result<Ed25519PrivateKey, Error> {
+        Err(Error::PermissionDenied)
+    }
+
+    fn get_public_key(&self, name: &str) -> Result<PublicKeyResponse, Error> {
+        let session = self.session.lock();
+        let (public_key, _) = Self::find_key_pair(&session, name)?
+            .ok_or_else(|| Error::KeyNotSet(name.to_string()))?;
+        let (public_key, last_update) = Self::public_key(&session, public_key)?;
+        Ok(PublicKeyResponse {
+            last_update,
+            public_key,
+        })
+    }
+
+    fn get_public_key_previous_version(&self, name: &str) -> Result<Ed25519PublicKey, Error> {
+        let session = self.session.lock();
+        match Self::find_key_pair(&session, &get_previous_version_name(name))? {
+            Some((public_key, _)) => Self::public_key(&session, public_key).map(|(key, _)| key),
+            None => Err(Error::KeyVersionNotFound(
+                name.into(),
+                "previous version".into(),
+            )),
+        }
+    }
+
+    fn rotate_key(&mut self, name: &str) -> Result<Ed25519PublicKey, Error> {
+        let session = self.session.lock();
+        let (current_public, current_private) = Self::find_key_pair(&session, name)?
+            .ok_or_else(|| Error::KeyNotSet(name.to_string()))?;
+
+        // Generate the new key pair before touching the existing ones, so a failure leaves the
+        // current key pair in place.
+        let (new_public, new_private) =
+            self.generate_key_pair(&session, &format!("{}_pending", name))?;
+
+        let previous_name = get_previous_version_name(name);
+        if let Some((previous_public, previous_private)) =
+            Self::find_key_pair(&session, &previous_name)?
+        {
+            session.destroy_object(previous_public)?;
+            session.destroy_object(previous_private)?;
+        }
+        let previous_label = Attribute::Label(previous_name.into_bytes());
+        session.update_attributes(current_public, &[previous_label.clone()])?;
+        session.update_attributes(current_private, &[previous_label])?;
+        let label = Attribute::Label(name.as_bytes().to_vec());
+        session.update_attributes(new_public, &[label.clone()])?;
+        session.update_attributes(new_private, &[label])?;
+
+        Self::public_key(&session, new_public).map(|(public_key, _)| public_key)
+    }
+
+    fn sign<T: CryptoHash + Serialize>(
+        &self,
+        name: &str,
+        message: &T,
+    ) -> Result<Ed25519Signature, Error> {
+        let session = self.session.lock();
+        let private_key = Self::find_key(&session, ObjectClass::PRIVATE_KEY, name)?
+            .ok_or_else(|| Error::KeyNotSet(name.to_string()))?;
+        Self::sign_with(&session, private_key, message)
+    }
+
+    fn sign_using_version<T: CryptoHash + Serialize>(
+        &self,
+        name: &str,
+        version: Ed25519PublicKey,
+        message: &T,
+    ) -> Result<Ed25519Signature, Error> {
+        let session = self.session.lock();
+        let private_key = Self::private_key_for_version(&session, name, &version)?;
+        Self::sign_with(&session, private_key, message)
+    }
+}
+
+/// Private helper method to get the name of the previous version of the given key pair, as held in
+/// the token.
+fn get_previous_version_name(name: &str) -> String {
+    format!("{}_previous", name)
+}
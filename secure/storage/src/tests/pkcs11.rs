@@ -0,0 +1,156 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{tests::suite, CryptoStorage, Error, KVStorage, Pkcs11Storage, Storage};
+use aptos_crypto::{
+    bls12381, ed25519::Ed25519PrivateKey, test_utils::TestAptosCrypto, PrivateKey, Signature,
+    SigningKey, Uniform,
+};
+use std::path::PathBuf;
+
+/// Environment variables pointing the tests at a PKCS#11 token
+const PKCS11_MODULE: &str = "PKCS11_MODULE";
+const PKCS11_TOKEN_LABEL: &str = "PKCS11_TOKEN_LABEL";
+const PKCS11_PIN: &str = "PKCS11_PIN";
+
+/// Pkcs11Storage key names
+const CRYPTO_NAME: &str = "crypto_key";
+const IMPORTED_NAME: &str = "imported_key";
+const CONSENSUS_KEY: &str = "consensus_key";
+
+/// This holds the canonical list of PKCS#11 storage tests. As all tests share the same token,
+/// they are run sequentially and the token is reset after each test.
+const PKCS11_TESTS: &[fn(&mut Storage)] = &[
+    test_consensus_key,
+    test_create_existing_key,
+    test_export_denied,
+    test_import_key,
+    test_rotations,
+];
+
+/// A test for verifying Pkcs11Storage properly implements the SecureStorage API. This test
+/// depends on a token dedicated to testing (all its keys are destroyed), e.g., a SoftHSM token
+/// created with `softhsm2-util --init-token --free --label aptos-test --pin 1234 --so-pin 1234`
+/// and selected with `PKCS11_MODULE=/usr/lib/softhsm/libsofthsm2.so`.
+#[test]
+fn execute_storage_tests_pkcs11() {
+    let module = match std::env::var(PKCS11_MODULE) {
+        Ok(module) => PathBuf::from(module),
+        Err(_) => return,
+    };
+    let token_label = std::env::var(PKCS11_TOKEN_LABEL).unwrap_or_else(|_| "aptos-test".into());
+    let pin = std::env::var(PKCS11_PIN).unwrap_or_else(|_| "1234".into());
+    let mut storage =
+        Storage::from(Pkcs11Storage::new(&module, &token_label, pin.clone()).unwrap());
+
+    suite::execute_non_exporting_storage_tests(&mut storage);
+    for test in PKCS11_TESTS.iter() {
+        test(&mut storage);
+        storage.reset_and_clear().unwrap();
+    }
+
+    // Opening the token again (e.g., for another namespace) shares the initialized module
+    let public_key = storage.create_key(CRYPTO_NAME).unwrap();
+    let other_storage = Storage::from(Pkcs11Storage::new(&module, &token_label, pin).unwrap());
+    assert_eq!(
+        other_storage
+            .get_public_key(CRYPTO_NAME)
+            .unwrap()
+            .public_key,
+        public_key
+    );
+    storage.reset_and_clear().unwrap();
+}
+
+/// BLS12-381 consensus keys (as stored by safety rules) are kept encrypted in the token
+fn test_consensus_key(storage: &mut Storage) {
+    for _ in 0..2 {
+        let consensus_key = bls12381::PrivateKey::generate_for_testing();
+        storage.set(CONSENSUS_KEY, consensus_key.clone()).unwrap();
+        let stored_key: bls12381::PrivateKey = storage.get(CONSENSUS_KEY).unwrap().value;
+        assert_eq!(stored_key.public_key(), consensus_key.public_key());
+    }
+}
+
+/// Keys are created once per name, rotation is how they are replaced
+fn test_create_existing_key(storage: &mut Storage) {
+    storage.create_key(CRYPTO_NAME).unwrap();
+    assert_eq!(
+        storage.create_key(CRYPTO_NAME).unwrap_err(),
+        Error::KeyAlreadyExists(CRYPTO_NAME.into())
+    );
+}
+
+/// Private keys never leave the token
+fn test_export_denied(storage: &mut Storage) {
+    let public_key = storage.create_key(CRYPTO_NAME).unwrap();
+    assert_eq!(
+        storage.export_private_key(CRYPTO_NAME).unwrap_err(),
+        Error::PermissionDenied
+    );
+    assert_eq!(
+        storage
+            .export_private_key_for_version(CRYPTO_NAME, public_key)
+            .unwrap_err(),
+        Error::PermissionDenied
+    );
+}
+
+/// Imported keys sign like the original key but can no longer be exported
+fn test_import_key(storage: &mut Storage) {
+    let private_key = Ed25519PrivateKey::generate_for_testing();
+    storage
+        .import_private_key(IMPORTED_NAME, private_key.clone())
+        .unwrap();
+
+    let public_key = storage.get_public_key(IMPORTED_NAME).unwrap().public_key;
+    assert_eq!(public_key, private_key.public_key());
+
+    let message = TestAptosCrypto("Hello, World".to_string());
+    let signature = storage.sign(IMPORTED_NAME, &message).unwrap();
+    assert_eq!(signature, private_key.sign(&message).unwrap());
+    assert_eq!(
+        storage.export_private_key(IMPORTED_NAME).unwrap_err(),
+        Error::PermissionDenied
+    );
+}
+
+/// Rotations keep the previous version of the key around for signing
+fn test_rotations(storage: &mut Storage) {
+    let num_rotations = 3;
+    let message = TestAptosCrypto("Hello, World".to_string());
+
+    let mut public_key = storage.create_key(CRYPTO_NAME).unwrap();
+    assert!(storage
+        .get_public_key_previous_version(CRYPTO_NAME)
+        .is_err());
+
+    for _ in 0..num_rotations {
+        let new_public_key = storage.rotate_key(CRYPTO_NAME).unwrap();
+        assert_ne!(public_key, new_public_key);
+        assert_eq!(
+            storage.get_public_key(CRYPTO_NAME).unwrap().public_key,
+            new_public_key
+        );
+        assert_eq!(
+            storage
+                .get_public_key_previous_version(CRYPTO_NAME)
+                .unwrap(),
+            public_key
+        );
+
+        let signature = storage
+            .sign_using_version(CRYPTO_NAME, public_key.clone(), &message)
+            .unwrap();
+        signature.verify(&message, &public_key).unwrap();
+        let signature = storage.sign(CRYPTO_NAME, &message).unwrap();
+        signature.verify(&message, &new_public_key).unwrap();
+
+        public_key = new_public_key;
+    }
+
+    let unknown_version = Ed25519PrivateKey::generate_for_testing().public_key();
+    assert!(storage
+        .sign_using_version(CRYPTO_NAME, unknown_version, &message)
+        .is_err());
+}
@@ -0,0 +1,18 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::Result;
+use aptos_types::waypoint::Waypoint;
+use async_trait::async_trait;
+
+/// The backup restore interface required by state sync to bootstrap the node
+/// from a backup storage, instead of the network (see `RestoreFromBackup`).
+/// This is implemented outside of state sync, as restoring a backup requires
+/// direct access to the database.
+#[async_trait]
+pub trait BackupRestoreInterface: Send + Sync {
+    /// Restores the most recent state snapshot (and the transactions after it)
+    /// from the backup storage into the local storage. The epoch ending ledger
+    /// infos in the backup must be verified against the given waypoint.
+    async fn restore_from_backup(&self, waypoint: Waypoint) -> Result<()>;
+}
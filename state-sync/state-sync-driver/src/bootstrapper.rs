@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup_restore::BackupRestoreInterface,
     driver::DriverConfiguration,
     error::Error,
     logging::{LogEntry, LogSchema},
@@ -22,6 +23,7 @@ use aptos_data_streaming_service::{
 };
 use aptos_logger::{prelude::*, sample::SampleRate};
 use aptos_storage_interface::DbReader;
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use aptos_types::{
     epoch_change::Verifier,
     epoch_state::EpochState,
@@ -31,10 +33,17 @@ use aptos_types::{
     waypoint::Waypoint,
 };
 use futures::channel::oneshot;
-use std::{collections::BTreeMap, sync::Arc, time::Duration};
+use std::{
+    collections::BTreeMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::task::JoinHandle;
 
 // Useful bootstrapper constants
 const BOOTSTRAPPER_LOG_INTERVAL_SECS: u64 = 3;
+const RESTORE_RETRY_BASE_DELAY_SECS: u64 = 1; // The delay before retrying the first failed restore
+const RESTORE_RETRY_MAX_DELAY_SECS: u64 = 300; // The max delay between failed restores
 pub const GENESIS_TRANSACTION_VERSION: u64 = 0; // The expected version of the genesis transaction
 
 /// A simple container for verified epoch states and epoch ending ledger infos
@@ -291,6 +300,9 @@ pub struct Bootstrapper<MetadataStorage, StorageSyncer, StreamingClient> {
     // The currently active data stream (provided by the data streaming service)
     active_data_stream: Option<DataStreamListener>,
 
+    // The component used to restore the storage from a backup (if restoring from a backup)
+    backup_restorer: Option<Arc<dyn BackupRestoreInterface>>,
+
     // The channel used to notify a listener of successful bootstrapping
     bootstrap_notifier_channel: Option<oneshot::Sender<Result<(), Error>>>,
 
@@ -306,6 +318,18 @@ pub struct Bootstrapper<MetadataStorage, StorageSyncer, StreamingClient> {
     // The handler for output fallback behaviour
     output_fallback_handler: OutputFallbackHandler,
 
+    // The number of consecutive failed attempts to restore the storage from a backup
+    restore_failures: u32,
+
+    // The background task restoring the storage from a backup (if a restore is in progress)
+    restore_task: Option<JoinHandle<anyhow::Result<()>>>,
+
+    // The earliest time at which to retry restoring the storage (after a failed restore)
+    restore_retry_time: Option<Instant>,
+
+    // If the storage has been restored from a backup
+    restored_from_backup: bool,
+
     // The speculative state tracking the active data stream
     speculative_stream_state: Option<SpeculativeStreamState>,
 
@@ -321,6 +345,9 @@ pub struct Bootstrapper<MetadataStorage, StorageSyncer, StreamingClient> {
     // The storage synchronizer used to update local storage
     storage_synchronizer: StorageSyncer,
 
+    // The time service
+    time_service: TimeService,
+
     // The epoch states verified by this node (held in memory)
     verified_epoch_states: VerifiedEpochStates,
 }
@@ -338,6 +365,8 @@ impl<
         streaming_client: StreamingClient,
         storage: Arc<dyn DbReader>,
         storage_synchronizer: StorageSyncer,
+        backup_restorer: Option<Arc<dyn BackupRestoreInterface>>,
+        time_service: TimeService,
     ) -> Self {
         // Load the latest epoch state from storage
         let latest_epoch_state = utils::fetch_latest_epoch_state(storage.clone())
@@ -347,15 +376,21 @@ impl<
         Self {
             state_value_syncer: StateValueSyncer::new(),
             active_data_stream: None,
+            backup_restorer,
             bootstrap_notifier_channel: None,
             bootstrapped: false,
             driver_configuration,
             metadata_storage,
             output_fallback_handler,
+            restore_failures: 0,
+            restore_task: None,
+            restore_retry_time: None,
+            restored_from_backup: false,
             speculative_stream_state: None,
             streaming_client,
             storage,
             storage_synchronizer,
+            time_service,
             verified_epoch_states,
         }
     }
@@ -431,6 +466,9 @@ impl<
                 SampleRate::Duration(Duration::from_secs(PENDING_DATA_LOG_FREQ_SECS)),
                 info!("Waiting for the storage synchronizer to handle pending data!")
             );
+        } else if self.should_restore_from_backup()? {
+            // Restore the storage from the backup before syncing from the network
+            self.drive_restore_from_backup().await?;
         } else {
             // Fetch a new data stream to start streaming data
             self.initialize_active_data_stream(global_data_summary)
@@ -441,6 +479,158 @@ impl<
         self.notify_listeners_if_bootstrapped().await
     }
 
+    /// Returns true iff the bootstrapper should restore the storage from a
+    /// backup, i.e., the node is configured to restore from a backup and
+    /// the storage hasn't synced beyond genesis (or a restore is in progress).
+    fn should_restore_from_backup(&self) -> Result<bool, Error> {
+        if !self.get_bootstrapping_mode().is_restore_from_backup() || self.restored_from_backup {
+            return Ok(false);
+        }
+        if self.restore_task.is_some() {
+            return Ok(true);
+        }
+
+        let highest_synced_version = utils::fetch_pre_committed_version(self.storage.clone())?;
+        Ok(highest_synced_version == GENESIS_TRANSACTION_VERSION)
+    }
+
+    /// Drives the restore of the storage from the backup. The restore runs
+    /// in a background task (so that the driver isn't blocked for the
+    /// duration of the restore), which is polled each time progress is
+    /// driven. Failed restores are retried with an exponential backoff.
+    async fn drive_restore_from_backup(&mut self) -> Result<(), Error> {
+        // If a restore is in progress, check if it has completed
+        if let Some(restore_task) = self.restore_task.take() {
+            if !restore_task.is_finished() {
+                self.restore_task = Some(restore_task);
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(BOOTSTRAPPER_LOG_INTERVAL_SECS)),
+                    info!(LogSchema::new(LogEntry::Bootstrapper)
+                        .message("Waiting for the storage to be restored from the backup!"))
+                );
+                return Ok(());
+            }
+
+            // Process the result of the restore
+            let restore_result = match restore_task.await {
+                Ok(restore_result) => restore_result,
+                Err(error) => Err(anyhow::anyhow!("The restore task failed: {:?}", error)),
+            };
+            return match restore_result {
+                Ok(()) => {
+                    self.restore_failures = 0;
+                    self.handle_restored_storage()
+                },
+                Err(error) => {
+                    self.handle_failed_restore();
+                    Err(Error::BackupRestoreError(format!("{:?}", error)))
+                },
+            };
+        }
+
+        // If a previous restore failed, wait until we can retry the restore
+        if let Some(restore_retry_time) = self.restore_retry_time {
+            if self.time_service.now() < restore_retry_time {
+                return Ok(());
+            }
+        }
+
+        // Start restoring the storage from the backup
+        let backup_restorer = self.backup_restorer.clone().ok_or_else(|| {
+            Error::BackupRestoreError(
+                "The node is configured to restore from a backup, but no backup restorer was found!"
+                    .into(),
+            )
+        })?;
+        info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+            "Restoring the storage from the backup! Number of failed attempts: {:?}",
+            self.restore_failures
+        )));
+        let waypoint = self.driver_configuration.waypoint;
+        self.restore_task = Some(tokio::spawn(async move {
+            backup_restorer.restore_from_backup(waypoint).await
+        }));
+        self.restore_retry_time = None;
+
+        Ok(())
+    }
+
+    /// Handles a failed restore by scheduling the next restore attempt
+    /// (using an exponential backoff).
+    fn handle_failed_restore(&mut self) {
+        let retry_delay_secs = RESTORE_RETRY_BASE_DELAY_SECS
+            .saturating_mul(2u64.saturating_pow(self.restore_failures))
+            .min(RESTORE_RETRY_MAX_DELAY_SECS);
+        self.restore_failures = self.restore_failures.saturating_add(1);
+        self.restore_retry_time =
+            Some(self.time_service.now() + Duration::from_secs(retry_delay_secs));
+
+        warn!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+            "Failed to restore the storage from the backup! Retrying in {:?} seconds.",
+            retry_delay_secs
+        )));
+    }
+
+    /// Handles the storage restored from the backup, i.e., verifies the
+    /// restored storage against the waypoint and resets the verified epoch
+    /// states to the restored epoch (so that the rest can be synced from
+    /// the network).
+    fn handle_restored_storage(&mut self) -> Result<(), Error> {
+        self.restored_from_backup = true;
+
+        // Verify the restored storage against the waypoint
+        let waypoint = self.driver_configuration.waypoint;
+        let restored_ledger_info = utils::fetch_latest_synced_ledger_info(self.storage.clone())?;
+        let restored_version = restored_ledger_info.ledger_info().version();
+        self.verify_restored_storage(restored_version, &waypoint)?;
+
+        // Notify the storage synchronizer and reset the verified epoch states
+        self.storage_synchronizer
+            .handle_restored_storage(restored_version)?;
+        let latest_epoch_state = utils::fetch_latest_epoch_state(self.storage.clone())?;
+        self.verified_epoch_states = VerifiedEpochStates::new(latest_epoch_state);
+
+        info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+            "Restored the storage from the backup! Restored version: {:?}, epoch: {:?}.",
+            restored_version,
+            restored_ledger_info.ledger_info().epoch()
+        )));
+        Ok(())
+    }
+
+    /// Verifies the storage restored from a backup against the waypoint. If the
+    /// restored version is lower than the waypoint, the waypoint will be verified
+    /// later (i.e., when fetching the epoch ending ledger infos from the network).
+    fn verify_restored_storage(
+        &self,
+        restored_version: Version,
+        waypoint: &Waypoint,
+    ) -> Result<(), Error> {
+        let waypoint_version = waypoint.version();
+        if restored_version < waypoint_version {
+            return Ok(());
+        }
+
+        // Verify the epoch ending ledger info at the waypoint version
+        let waypoint_ledger_info = self
+            .storage
+            .get_epoch_ending_ledger_info(waypoint_version)
+            .map_err(|error| {
+                Error::StorageError(format!(
+                    "Failed to get the restored ledger info at the waypoint version: {:?}",
+                    error
+                ))
+            })?;
+        waypoint
+            .verify(waypoint_ledger_info.ledger_info())
+            .map_err(|error| {
+                Error::VerificationError(format!(
+                    "The restored storage failed to verify the waypoint: {:?}",
+                    error
+                ))
+            })
+    }
+
     /// Returns true iff the bootstrapper should continue to fetch epoch ending
     /// ledger infos (in order to make progress).
     fn should_fetch_epoch_ending_ledger_infos(&self) -> bool {
@@ -765,7 +955,7 @@ impl<
                     )
                     .await?
             },
-            BootstrappingMode::ExecuteOrApplyFromGenesis => {
+            BootstrappingMode::ExecuteOrApplyFromGenesis | BootstrappingMode::RestoreFromBackup => {
                 if self.output_fallback_handler.in_fallback_mode() {
                     metrics::set_gauge(
                         &metrics::DRIVER_FALLBACK_MODE,
@@ -1216,7 +1406,7 @@ impl<
                     ));
                 }
             },
-            BootstrappingMode::ExecuteOrApplyFromGenesis => {
+            BootstrappingMode::ExecuteOrApplyFromGenesis | BootstrappingMode::RestoreFromBackup => {
                 if let Some(transaction_list_with_proof) = transaction_list_with_proof {
                     utils::execute_transactions(
                         &mut self.storage_synchronizer,
@@ -1403,7 +1593,7 @@ impl<
                     ));
                 }
             },
-            BootstrappingMode::ExecuteOrApplyFromGenesis => {
+            BootstrappingMode::ExecuteOrApplyFromGenesis | BootstrappingMode::RestoreFromBackup => {
                 if let Some(transaction_list_with_proof) = transaction_list_with_proof {
                     transaction_list_with_proof.transactions.len()
                 } else if let Some(output_list_with_proof) = transaction_outputs_with_proof {
@@ -1516,7 +1706,9 @@ impl<
             .await?;
 
         // Fallback to output syncing if we need to
-        if let BootstrappingMode::ExecuteOrApplyFromGenesis = self.get_bootstrapping_mode() {
+        if let BootstrappingMode::ExecuteOrApplyFromGenesis | BootstrappingMode::RestoreFromBackup =
+            self.get_bootstrapping_mode()
+        {
             self.output_fallback_handler.fallback_to_outputs();
             metrics::set_gauge(
                 &metrics::DRIVER_FALLBACK_MODE,
@@ -1560,6 +1752,12 @@ impl<
         &mut self.state_value_syncer
     }
 
+    /// Returns true iff a restore from the backup is in progress (for testing purposes)
+    #[cfg(test)]
+    pub(crate) fn is_restoring_from_backup(&self) -> bool {
+        self.restore_task.is_some()
+    }
+
     /// Manually sets the waypoint for testing purposes
     #[cfg(test)]
     pub(crate) fn set_waypoint(&mut self, waypoint: Waypoint) {
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup_restore::BackupRestoreInterface,
     bootstrapper::Bootstrapper,
     continuous_syncer::ContinuousSyncer,
     driver_client::{ClientNotificationListener, DriverNotification},
@@ -171,6 +172,7 @@ impl<
         streaming_client: StreamingClient,
         storage: Arc<dyn DbReader>,
         time_service: TimeService,
+        backup_restorer: Option<Arc<dyn BackupRestoreInterface>>,
     ) -> Self {
         let output_fallback_handler =
             OutputFallbackHandler::new(driver_configuration.clone(), time_service.clone());
@@ -181,6 +183,8 @@ impl<
             streaming_client.clone(),
             storage.clone(),
             storage_synchronizer.clone(),
+            backup_restorer,
+            time_service.clone(),
         );
         let continuous_syncer = ContinuousSyncer::new(
             driver_configuration.clone(),
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup_restore::BackupRestoreInterface,
     driver::{DriverConfiguration, StateSyncDriver},
     driver_client::{ClientNotificationListener, DriverClient, DriverNotification},
     metadata_storage::MetadataStorageInterface,
@@ -58,6 +59,7 @@ impl DriverFactory {
         aptos_data_client: AptosDataClient,
         streaming_service_client: StreamingServiceClient,
         time_service: TimeService,
+        backup_restorer: Option<Arc<dyn BackupRestoreInterface>>,
     ) -> Self {
         let (driver_factory, _) = Self::create_and_spawn_driver_internal(
             create_runtime,
@@ -73,6 +75,7 @@ impl DriverFactory {
             aptos_data_client,
             streaming_service_client,
             time_service,
+            backup_restorer,
         );
         driver_factory
     }
@@ -99,6 +102,7 @@ impl DriverFactory {
         aptos_data_client: AptosDataClient,
         streaming_service_client: StreamingServiceClient,
         time_service: TimeService,
+        backup_restorer: Option<Arc<dyn BackupRestoreInterface>>,
     ) -> (Self, UnboundedSender<CommitNotification>) {
         // Notify subscribers of the initial on-chain config values
         match storage.reader.get_latest_state_checkpoint_version() {
@@ -180,6 +184,7 @@ impl DriverFactory {
             streaming_service_client,
             storage.reader,
             time_service,
+            backup_restorer,
         );
 
         // Spawn the driver
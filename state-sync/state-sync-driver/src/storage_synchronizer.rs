@@ -15,7 +15,7 @@ use crate::{
 };
 use aptos_config::config::StateSyncDriverConfig;
 use aptos_data_streaming_service::data_notification::NotificationId;
-use aptos_event_notifications::EventSubscriptionService;
+use aptos_event_notifications::{EventNotificationSender, EventSubscriptionService};
 use aptos_executor_types::{ChunkCommitNotification, ChunkExecutorTrait};
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
@@ -109,6 +109,11 @@ pub trait StorageSynchronizerInterface {
     /// Finish the chunk executor at this round of state sync by releasing
     /// any in-memory resources to prevent memory leak.
     fn finish_chunk_executor(&self);
+
+    /// Handles storage that was restored outside of state sync (e.g., from a
+    /// backup) at the given version. This resets the chunk executor and
+    /// notifies the event subscribers of the restored on-chain configs.
+    fn handle_restored_storage(&self, restored_version: Version) -> Result<(), Error>;
 }
 
 /// A simple struct that holds metadata related to data notifications
@@ -147,6 +152,9 @@ pub struct StorageSynchronizer<ChunkExecutor, MetadataStorage> {
     // A channel through which to notify the driver of storage errors
     error_notification_sender: mpsc::UnboundedSender<ErrorNotification>,
 
+    // The event subscription service to notify listeners of on-chain events
+    event_subscription_service: Arc<Mutex<EventSubscriptionService>>,
+
     // A channel through which to notify the executor of new data chunks
     executor_notifier: mpsc::Sender<StorageDataChunk>,
 
@@ -179,6 +187,7 @@ impl<
             commit_notification_sender: self.commit_notification_sender.clone(),
             driver_config: self.driver_config,
             error_notification_sender: self.error_notification_sender.clone(),
+            event_subscription_service: self.event_subscription_service.clone(),
             executor_notifier: self.executor_notifier.clone(),
             pending_data_chunks: self.pending_data_chunks.clone(),
             metadata_storage: self.metadata_storage.clone(),
@@ -264,7 +273,7 @@ impl<
         // Spawn the commit post-processor that handles commit notifications
         let commit_post_processor_handle = spawn_commit_post_processor(
             commit_post_processor_listener,
-            event_subscription_service,
+            event_subscription_service.clone(),
             mempool_notification_handler,
             storage_service_notification_handler,
             pending_data_chunks.clone(),
@@ -282,6 +291,7 @@ impl<
             commit_notification_sender,
             driver_config,
             error_notification_sender,
+            event_subscription_service,
             executor_notifier,
             pending_data_chunks,
             metadata_storage,
@@ -451,6 +461,25 @@ impl<
     fn finish_chunk_executor(&self) {
         self.chunk_executor.finish()
     }
+
+    fn handle_restored_storage(&self, restored_version: Version) -> Result<(), Error> {
+        // Reset the chunk executor to read the restored storage
+        self.reset_chunk_executor()?;
+
+        // Notify the event subscribers of the restored on-chain configs
+        self.event_subscription_service
+            .lock()
+            .notify_initial_configs(restored_version)
+            .map_err(|error| {
+                Error::EventNotificationError(format!(
+                    "Failed to notify subscribers of the restored on-chain configs: {:?}",
+                    error
+                ))
+            })?;
+
+        // Update the metric gauges
+        utils::initialize_sync_gauges(self.storage.reader.clone())
+    }
 }
 
 /// A simple container that holds the handles to the spawned storage synchronizer threads
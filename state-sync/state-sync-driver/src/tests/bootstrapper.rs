@@ -2,13 +2,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup_restore::BackupRestoreInterface,
     bootstrapper::{Bootstrapper, GENESIS_TRANSACTION_VERSION},
     driver::DriverConfiguration,
     error::Error,
     tests::{
         mocks::{
             create_mock_db_reader, create_mock_streaming_client, create_ready_storage_synchronizer,
-            MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient,
+            MockBackupRestorer, MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient,
         },
         utils::{
             create_data_stream_listener, create_empty_epoch_state, create_epoch_ending_ledger_info,
@@ -28,13 +29,20 @@ use aptos_data_streaming_service::{
 };
 use aptos_time_service::TimeService;
 use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
     transaction::{TransactionOutputListWithProof, Version},
     waypoint::Waypoint,
 };
 use claims::{assert_matches, assert_none, assert_ok};
 use futures::{channel::oneshot, FutureExt, SinkExt};
 use mockall::{predicate::eq, Sequence};
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
 #[tokio::test]
 async fn test_bootstrap_genesis_waypoint() {
@@ -953,6 +961,161 @@ async fn test_fetch_epoch_ending_ledger_infos_waypoint_mismatch() {
         .unwrap();
 }
 
+#[tokio::test]
+async fn test_restore_from_backup() {
+    // Create a driver configuration that restores from a backup
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::RestoreFromBackup;
+
+    // Create the mock storage synchronizer and expect the restored storage to be handled
+    let restored_version = 100;
+    let mut mock_storage_synchronizer = create_ready_storage_synchronizer(false);
+    mock_storage_synchronizer
+        .expect_handle_restored_storage()
+        .with(eq(restored_version))
+        .times(1)
+        .return_const(Ok(()));
+
+    // Update the driver configuration to use the genesis waypoint
+    let genesis_ledger_info = create_epoch_ending_ledger_info();
+    driver_configuration.waypoint = Waypoint::new_any(genesis_ledger_info.ledger_info());
+
+    // Create the bootstrapper with a mock backup restorer
+    let mut bootstrapper = create_bootstrapper_with_backup_restorer(
+        driver_configuration,
+        mock_storage_synchronizer,
+        Some(0),
+        restored_version,
+        genesis_ledger_info,
+        TimeService::mock(),
+    );
+
+    // Drive progress once and verify the restore runs in the background
+    let global_data_summary = create_global_summary(1);
+    drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap();
+    assert!(bootstrapper.is_restoring_from_backup());
+
+    // Drive progress and verify the storage was restored (but we're not yet bootstrapped)
+    drive_progress_until_restored(&mut bootstrapper, &global_data_summary)
+        .await
+        .unwrap();
+    assert!(!bootstrapper.is_bootstrapped());
+}
+
+#[tokio::test]
+async fn test_restore_from_backup_retry() {
+    // Create a driver configuration that restores from a backup
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::RestoreFromBackup;
+
+    // Create the mock storage synchronizer and expect the restored storage to be handled
+    let restored_version = 100;
+    let mut mock_storage_synchronizer = create_ready_storage_synchronizer(false);
+    mock_storage_synchronizer
+        .expect_handle_restored_storage()
+        .with(eq(restored_version))
+        .times(1)
+        .return_const(Ok(()));
+
+    // Update the driver configuration to use the genesis waypoint
+    let genesis_ledger_info = create_epoch_ending_ledger_info();
+    driver_configuration.waypoint = Waypoint::new_any(genesis_ledger_info.ledger_info());
+
+    // Create the bootstrapper with a mock backup restorer (that fails the first two restores)
+    let time_service = TimeService::mock();
+    let mut bootstrapper = create_bootstrapper_with_backup_restorer(
+        driver_configuration,
+        mock_storage_synchronizer,
+        Some(2),
+        restored_version,
+        genesis_ledger_info,
+        time_service.clone(),
+    );
+
+    // Drive progress and verify the first restore fails
+    let global_data_summary = create_global_summary(1);
+    let error = drive_progress_until_restored(&mut bootstrapper, &global_data_summary)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::BackupRestoreError(_));
+
+    // Verify the restore is only retried after the backoff (i.e., 1 second)
+    let time_service = time_service.into_mock();
+    for retry_delay_secs in [1, 2] {
+        // Verify the restore isn't retried before the backoff has elapsed
+        time_service.advance_secs(retry_delay_secs - 1);
+        drive_progress(&mut bootstrapper, &global_data_summary, false)
+            .await
+            .unwrap();
+        assert!(!bootstrapper.is_restoring_from_backup());
+
+        // Elapse the backoff and verify the restore is retried
+        time_service.advance_secs(1);
+        let result = drive_progress_until_restored(&mut bootstrapper, &global_data_summary).await;
+        if retry_delay_secs == 1 {
+            assert_matches!(result.unwrap_err(), Error::BackupRestoreError(_));
+        } else {
+            assert_ok!(result);
+        }
+    }
+    assert!(!bootstrapper.is_bootstrapped());
+}
+
+#[tokio::test]
+async fn test_restore_from_backup_missing_restorer() {
+    // Create a driver configuration that restores from a backup
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::RestoreFromBackup;
+
+    // Create the bootstrapper without a backup restorer
+    let mut bootstrapper = create_bootstrapper_with_backup_restorer(
+        driver_configuration,
+        create_ready_storage_synchronizer(false),
+        None,
+        100,
+        create_epoch_ending_ledger_info(),
+        TimeService::mock(),
+    );
+
+    // Drive progress and verify we get a backup restore error
+    let global_data_summary = create_global_summary(1);
+    let error = drive_progress(&mut bootstrapper, &global_data_summary, false)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::BackupRestoreError(_));
+}
+
+#[tokio::test]
+async fn test_restore_from_backup_waypoint_mismatch() {
+    // Create a driver configuration that restores from a backup
+    let mut driver_configuration = create_full_node_driver_configuration();
+    driver_configuration.config.bootstrapping_mode = BootstrappingMode::RestoreFromBackup;
+
+    // Update the driver configuration to use a waypoint at version 50
+    let waypoint_ledger_info = create_random_epoch_ending_ledger_info(50, 1);
+    driver_configuration.waypoint = Waypoint::new_any(waypoint_ledger_info.ledger_info());
+
+    // Create the bootstrapper with a mock backup restorer (that restores
+    // a different ledger info at the waypoint version).
+    let mut bootstrapper = create_bootstrapper_with_backup_restorer(
+        driver_configuration,
+        create_ready_storage_synchronizer(false),
+        Some(0),
+        100,
+        create_random_epoch_ending_ledger_info(50, 1),
+        TimeService::mock(),
+    );
+
+    // Drive progress and verify we get a verification error
+    let global_data_summary = create_global_summary(1);
+    let error = drive_progress_until_restored(&mut bootstrapper, &global_data_summary)
+        .await
+        .unwrap_err();
+    assert_matches!(error, Error::VerificationError(_));
+}
+
 #[tokio::test]
 async fn test_snapshot_sync_epoch_change() {
     // Create test data
@@ -1623,7 +1786,7 @@ fn create_bootstrapper(
     // Create the output fallback handler
     let time_service = time_service.unwrap_or_else(TimeService::mock);
     let output_fallback_handler =
-        OutputFallbackHandler::new(driver_configuration.clone(), time_service);
+        OutputFallbackHandler::new(driver_configuration.clone(), time_service.clone());
 
     // Create the bootstrapper
     let bootstrapper = Bootstrapper::new(
@@ -1633,6 +1796,8 @@ fn create_bootstrapper(
         mock_streaming_client,
         Arc::new(mock_database_reader),
         mock_storage_synchronizer,
+        None,
+        time_service,
     );
 
     (bootstrapper, output_fallback_handler)
@@ -1681,8 +1846,9 @@ fn create_bootstrapper_with_storage(
         .returning(move || Ok(Some(latest_synced_version)));
 
     // Create the output fallback handler
+    let time_service = TimeService::mock();
     let output_fallback_handler =
-        OutputFallbackHandler::new(driver_configuration.clone(), TimeService::mock());
+        OutputFallbackHandler::new(driver_configuration.clone(), time_service.clone());
 
     Bootstrapper::new(
         driver_configuration,
@@ -1691,6 +1857,94 @@ fn create_bootstrapper_with_storage(
         mock_streaming_client,
         Arc::new(mock_database_reader),
         mock_storage_synchronizer,
+        None,
+        time_service,
+    )
+}
+
+/// Creates a bootstrapper for testing with a mock backup restorer (if
+/// `num_failed_restores` is provided). The mock backup restorer will fail
+/// the given number of restores before succeeding, and the mock db reader
+/// will only report the `restored_version` once a restore has succeeded.
+fn create_bootstrapper_with_backup_restorer(
+    driver_configuration: DriverConfiguration,
+    mock_storage_synchronizer: MockStorageSynchronizer,
+    num_failed_restores: Option<usize>,
+    restored_version: Version,
+    waypoint_ledger_info: LedgerInfoWithSignatures,
+    time_service: TimeService,
+) -> Bootstrapper<MockMetadataStorage, MockStorageSynchronizer, MockStreamingClient> {
+    // Initialize the logger for tests
+    aptos_logger::Logger::init_for_testing();
+
+    // Create the mock backup restorer
+    let restored = Arc::new(AtomicBool::new(false));
+    let backup_restorer = if let Some(num_failed_restores) = num_failed_restores {
+        let mut mock_backup_restorer = MockBackupRestorer::new();
+        let mut expectation_sequence = Sequence::new();
+        mock_backup_restorer
+            .expect_restore_from_backup()
+            .times(num_failed_restores)
+            .returning(|_| Err(anyhow::anyhow!("Failed to restore the backup!")))
+            .in_sequence(&mut expectation_sequence);
+        let restored = restored.clone();
+        mock_backup_restorer
+            .expect_restore_from_backup()
+            .times(1)
+            .returning(move |_| {
+                restored.store(true, Ordering::Relaxed);
+                Ok(())
+            })
+            .in_sequence(&mut expectation_sequence);
+        Some(Arc::new(mock_backup_restorer) as Arc<dyn BackupRestoreInterface>)
+    } else {
+        None
+    };
+
+    // Create the mock db reader and set the expectations
+    let mut mock_database_reader = create_mock_db_reader();
+    mock_database_reader
+        .expect_get_latest_epoch_state()
+        .returning(|| Ok(create_empty_epoch_state()));
+    let restored_clone = restored.clone();
+    mock_database_reader
+        .expect_get_latest_ledger_info()
+        .returning(move || {
+            if restored_clone.load(Ordering::Relaxed) {
+                Ok(create_epoch_ending_ledger_info_for_epoch(
+                    1,
+                    restored_version,
+                ))
+            } else {
+                Ok(create_epoch_ending_ledger_info())
+            }
+        });
+    mock_database_reader
+        .expect_get_pre_committed_version()
+        .returning(move || {
+            if restored.load(Ordering::Relaxed) {
+                Ok(Some(restored_version))
+            } else {
+                Ok(Some(0))
+            }
+        });
+    mock_database_reader
+        .expect_get_epoch_ending_ledger_info()
+        .returning(move |_| Ok(waypoint_ledger_info.clone()));
+
+    // Create the output fallback handler
+    let output_fallback_handler =
+        OutputFallbackHandler::new(driver_configuration.clone(), time_service.clone());
+
+    Bootstrapper::new(
+        driver_configuration,
+        MockMetadataStorage::new(),
+        output_fallback_handler,
+        create_mock_streaming_client(),
+        Arc::new(mock_database_reader),
+        mock_storage_synchronizer,
+        backup_restorer,
+        time_service,
     )
 }
 
@@ -1717,6 +1971,28 @@ async fn drive_progress(
     }
 }
 
+/// Drives progress for the given bootstrapper until the restore
+/// from the backup (started by driving progress) has completed.
+async fn drive_progress_until_restored(
+    bootstrapper: &mut Bootstrapper<
+        MockMetadataStorage,
+        MockStorageSynchronizer,
+        MockStreamingClient,
+    >,
+    global_data_summary: &GlobalDataSummary,
+) -> Result<(), Error> {
+    loop {
+        // Attempt to drive progress
+        bootstrapper.drive_progress(global_data_summary).await?;
+
+        // Return if the restore has completed, otherwise wait for it
+        if !bootstrapper.is_restoring_from_backup() {
+            return Ok(());
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
 /// Manipulates the internal state of the verified epoch states used by
 /// the given bootstrapper and inserts a verified epoch ending ledger
 /// info at the specified `highest_version_to_insert` (if provided).
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    backup_restore::BackupRestoreInterface,
     error::Error,
     metadata_storage::MetadataStorageInterface,
     storage_synchronizer::{NotificationMetadata, StorageSynchronizerInterface},
@@ -40,6 +41,7 @@ use aptos_types::{
         AccountTransactionsWithProof, TransactionListWithProof, TransactionOutputListWithProof,
         TransactionToCommit, TransactionWithProof, Version,
     },
+    waypoint::Waypoint,
 };
 use async_trait::async_trait;
 use mockall::mock;
@@ -134,6 +136,15 @@ pub fn create_ready_storage_synchronizer(expect_reset_executor: bool) -> MockSto
     mock_storage_synchronizer
 }
 
+// This automatically creates a MockBackupRestorer.
+mock! {
+    pub BackupRestorer {}
+    #[async_trait]
+    impl BackupRestoreInterface for BackupRestorer {
+        async fn restore_from_backup(&self, waypoint: Waypoint) -> AnyhowResult<()>;
+    }
+}
+
 // This automatically creates a MockChunkExecutor.
 mock! {
     pub ChunkExecutor {}
@@ -491,6 +502,8 @@ mock! {
         fn reset_chunk_executor(&self) -> AnyhowResult<(), crate::error::Error>;
 
         fn finish_chunk_executor(&self);
+
+        fn handle_restored_storage(&self, restored_version: Version) -> AnyhowResult<(), crate::error::Error>;
     }
     impl Clone for StorageSynchronizer {
         fn clone(&self) -> Self;
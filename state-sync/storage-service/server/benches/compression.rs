@@ -0,0 +1,146 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#[macro_use]
+extern crate criterion;
+
+use aptos_compression::{algorithm::CompressionAlgorithm, client::CompressionClient};
+use aptos_config::config::StorageServiceConfig;
+use aptos_executor_test_helpers::integration_test_impl::test_execution_with_storage_impl;
+use aptos_storage_service_server::storage::{StorageReader, StorageReaderInterface};
+use aptos_storage_service_types::responses::DataResponse;
+use criterion::{measurement::Measurement, BenchmarkGroup, Criterion, Throughput};
+
+/// The max bytes limit to use for compression and decompression
+const MAX_COMPRESSION_SIZE: usize = 64 * 1024 * 1024; // 64 MiBi
+
+/// The compression algorithms to compare
+const ALGORITHMS: [CompressionAlgorithm; 2] =
+    [CompressionAlgorithm::Lz4, CompressionAlgorithm::Zstd];
+
+fn benchmark_groups(c: &mut Criterion) {
+    // Create the storage service responses (BCS encoded, as they are compressed and sent over the network)
+    let payloads = create_storage_service_responses()
+        .into_iter()
+        .map(|data_response| {
+            (
+                data_response.get_label(),
+                bcs::to_bytes(&data_response).unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    // Benchmark each algorithm on each payload
+    for (payload_name, raw_data) in payloads.iter() {
+        let mut group = c.benchmark_group(format!("compression/{}", payload_name));
+        group.throughput(Throughput::Bytes(raw_data.len() as u64));
+        for algorithm in ALGORITHMS {
+            print_compression_ratio(payload_name, raw_data, algorithm);
+            compress(&mut group, raw_data, algorithm);
+            decompress(&mut group, raw_data, algorithm);
+        }
+        group.finish();
+    }
+}
+
+/// Benchmarks the time to compress the raw data using the given algorithm
+fn compress<M: Measurement>(
+    g: &mut BenchmarkGroup<M>,
+    raw_data: &[u8],
+    algorithm: CompressionAlgorithm,
+) {
+    g.bench_function(format!("compress_{}", algorithm.get_label()), |b| {
+        b.iter_with_setup(
+            || raw_data.to_vec(),
+            |raw_data| {
+                aptos_compression::compress_with_header(
+                    raw_data,
+                    CompressionClient::StateSync,
+                    algorithm,
+                    MAX_COMPRESSION_SIZE,
+                )
+                .unwrap()
+            },
+        )
+    });
+}
+
+/// Benchmarks the time to decompress the raw data compressed using the given algorithm
+fn decompress<M: Measurement>(
+    g: &mut BenchmarkGroup<M>,
+    raw_data: &[u8],
+    algorithm: CompressionAlgorithm,
+) {
+    let compressed_data = compress_with_header(raw_data, algorithm);
+    g.bench_function(format!("decompress_{}", algorithm.get_label()), |b| {
+        b.iter(|| {
+            aptos_compression::decompress_with_header(
+                &compressed_data,
+                CompressionClient::StateSync,
+                MAX_COMPRESSION_SIZE,
+            )
+            .unwrap()
+        })
+    });
+}
+
+/// Prints the compression ratio of the given algorithm (criterion only reports durations)
+fn print_compression_ratio(payload_name: &str, raw_data: &[u8], algorithm: CompressionAlgorithm) {
+    let compressed_data = compress_with_header(raw_data, algorithm);
+    println!(
+        "{} with {}: {} bytes compressed to {} bytes (ratio: {:.2})",
+        payload_name,
+        algorithm.get_label(),
+        raw_data.len(),
+        compressed_data.len(),
+        raw_data.len() as f64 / compressed_data.len() as f64
+    );
+}
+
+/// Compresses the raw data using the given algorithm
+fn compress_with_header(raw_data: &[u8], algorithm: CompressionAlgorithm) -> Vec<u8> {
+    aptos_compression::compress_with_header(
+        raw_data.to_vec(),
+        CompressionClient::StateSync,
+        algorithm,
+        MAX_COMPRESSION_SIZE,
+    )
+    .unwrap()
+}
+
+/// Creates the storage service responses served from a database holding
+/// genesis and several blocks of executed user transactions.
+fn create_storage_service_responses() -> Vec<DataResponse> {
+    let db = test_execution_with_storage_impl();
+    let storage_reader = StorageReader::new(StorageServiceConfig::default(), db);
+    let latest_version = storage_reader
+        .get_data_summary()
+        .unwrap()
+        .synced_ledger_info
+        .unwrap()
+        .ledger_info()
+        .version();
+
+    // Skip the genesis transaction, so that the transaction and output
+    // chunks hold regular user transactions (genesis is covered by the
+    // state values).
+    let transactions_with_proof = storage_reader
+        .get_transactions_with_proof(latest_version, 1, latest_version, true)
+        .unwrap();
+    let outputs_with_proof = storage_reader
+        .get_transaction_outputs_with_proof(latest_version, 1, latest_version)
+        .unwrap();
+    let num_states = storage_reader.get_number_of_states(latest_version).unwrap();
+    let state_value_chunk_with_proof = storage_reader
+        .get_state_value_chunk_with_proof(latest_version, 0, num_states - 1)
+        .unwrap();
+
+    vec![
+        DataResponse::TransactionsWithProof(transactions_with_proof),
+        DataResponse::TransactionOutputsWithProof(outputs_with_proof),
+        DataResponse::StateValueChunkWithProof(state_value_chunk_with_proof),
+    ]
+}
+
+criterion_group!(compression_benches, benchmark_groups);
+criterion_main!(compression_benches);
@@ -16,6 +16,7 @@ use crate::{
     subscription::{SubscriptionRequest, SubscriptionStreamRequests},
     utils,
 };
+use aptos_compression::algorithm::CompressionAlgorithm;
 use aptos_config::{config::StorageServiceConfig, network_id::PeerNetworkId};
 use aptos_logger::{debug, sample, sample::SampleRate, trace, warn};
 use aptos_network::protocols::wire::handshake::v1::ProtocolId;
@@ -87,6 +88,9 @@ impl<T: StorageReaderInterface> Handler<T> {
         request: StorageServiceRequest,
         response_sender: ResponseSender,
     ) {
+        // Compress any response using the algorithm negotiated with the client
+        let request = request.with_compression_algorithm(get_compression_algorithm(protocol_id));
+
         // Log the request
         trace!(LogSchema::new(LogEntry::ReceivedStorageRequest)
             .request(&request)
@@ -202,12 +206,12 @@ impl<T: StorageReaderInterface> Handler<T> {
         match &request.data_request {
             DataRequest::GetServerProtocolVersion => {
                 let data_response = self.get_server_protocol_version();
-                StorageServiceResponse::new(data_response, request.use_compression)
+                create_storage_service_response(data_response, request)
                     .map_err(|error| error.into())
             },
             DataRequest::GetStorageServerSummary => {
                 let data_response = self.get_storage_server_summary();
-                StorageServiceResponse::new(data_response, request.use_compression)
+                create_storage_service_response(data_response, request)
                     .map_err(|error| error.into())
             },
             _ => self.process_cachable_request(peer_network_id, request),
@@ -424,8 +428,7 @@ impl<T: StorageReaderInterface> Handler<T> {
 
         // Create the storage response and time the operation
         let create_storage_response = || {
-            StorageServiceResponse::new(data_response, request.use_compression)
-                .map_err(|error| error.into())
+            create_storage_service_response(data_response, request).map_err(|error| error.into())
         };
         let storage_response = utils::execute_and_time_duration(
             &metrics::STORAGE_RESPONSE_CREATION_LATENCY,
@@ -601,3 +604,24 @@ fn log_storage_response(
         },
     };
 }
+
+/// Returns the algorithm with which to compress responses sent over the given protocol
+fn get_compression_algorithm(protocol_id: ProtocolId) -> CompressionAlgorithm {
+    match protocol_id {
+        ProtocolId::StorageServiceRpcZstd => CompressionAlgorithm::Zstd,
+        _ => CompressionAlgorithm::Lz4,
+    }
+}
+
+/// Creates a storage service response for the given request, using
+/// the compression settings of the request.
+pub(crate) fn create_storage_service_response(
+    data_response: DataResponse,
+    request: &StorageServiceRequest,
+) -> Result<StorageServiceResponse, aptos_storage_service_types::responses::Error> {
+    StorageServiceResponse::new_with_compression_algorithm(
+        data_response,
+        request.use_compression,
+        request.compression_algorithm,
+    )
+}
@@ -125,7 +125,8 @@ impl SubscriptionRequest {
             request => unreachable!("Unexpected subscription request: {:?}", request),
         };
         let storage_request =
-            StorageServiceRequest::new(data_request, self.request.use_compression);
+            StorageServiceRequest::new(data_request, self.request.use_compression)
+                .with_compression_algorithm(self.request.compression_algorithm);
         Ok(storage_request)
     }
 
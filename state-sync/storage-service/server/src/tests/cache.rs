@@ -4,10 +4,15 @@
 use crate::tests::{mock, mock::MockClient, utils};
 use aptos_config::config::StorageServiceConfig;
 use aptos_crypto::hash::HashValue;
-use aptos_storage_service_types::responses::{DataResponse, StorageServiceResponse};
+use aptos_network::protocols::wire::handshake::v1::ProtocolId;
+use aptos_storage_service_types::{
+    requests::{DataRequest, StorageServiceRequest, TransactionsWithProofRequest},
+    responses::{DataResponse, StorageServiceResponse},
+};
 use aptos_types::{
     proof::definition::SparseMerkleRangeProof, state_store::state_value::StateValueChunkWithProof,
 };
+use claims::assert_matches;
 use mockall::{
     predicate::{always, eq},
     Sequence,
@@ -81,6 +86,88 @@ async fn test_cachable_requests_compression() {
     }
 }
 
+#[tokio::test]
+async fn test_cachable_requests_compression_algorithms() {
+    // Create test data
+    let start_version = 0;
+    let end_version = 454;
+    let proof_version = end_version;
+    let include_events = false;
+    let protocol_ids = [
+        ProtocolId::StorageServiceRpc,
+        ProtocolId::StorageServiceRpcZstd,
+    ];
+
+    // Create the mock db reader
+    let mut db_reader = mock::create_mock_db_reader();
+    let mut expectation_sequence = Sequence::new();
+    let mut transaction_lists_with_proof = vec![];
+    for _ in protocol_ids {
+        // Create and save test transaction lists
+        let transaction_list_with_proof = utils::create_transaction_list_with_proof(
+            start_version,
+            end_version,
+            proof_version,
+            include_events,
+        );
+        transaction_lists_with_proof.push(transaction_list_with_proof.clone());
+
+        // Expect the data to be fetched from storage exactly once (per algorithm)
+        db_reader
+            .expect_get_transactions()
+            .times(1)
+            .with(
+                eq(start_version),
+                eq(end_version - start_version + 1),
+                eq(proof_version),
+                eq(include_events),
+            )
+            .return_once(move |_, _, _, _| Ok(transaction_list_with_proof))
+            .in_sequence(&mut expectation_sequence);
+    }
+
+    // Create the storage client and server
+    let (mut mock_client, mut service, _, _, _) = MockClient::new(Some(db_reader), None);
+    utils::update_storage_server_summary(&mut service, end_version, 10);
+    tokio::spawn(service.start());
+
+    // Repeatedly fetch the data over each protocol and verify the responses
+    for (i, protocol_id) in protocol_ids.iter().enumerate() {
+        for _ in 0..10 {
+            let data_request =
+                DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+                    proof_version,
+                    start_version,
+                    end_version,
+                    include_events,
+                });
+            let storage_request = StorageServiceRequest::new(data_request, true);
+            let response = mock_client
+                .process_request_with_protocol(storage_request, *protocol_id)
+                .await
+                .unwrap();
+
+            // Verify the response is compressed with the expected algorithm
+            if *protocol_id == ProtocolId::StorageServiceRpcZstd {
+                assert_matches!(
+                    response,
+                    StorageServiceResponse::CompressedResponseWithHeader(_, _)
+                );
+            } else {
+                assert_matches!(response, StorageServiceResponse::CompressedResponse(_, _));
+            }
+
+            // Verify the response data is correct
+            match response.get_data_response().unwrap() {
+                DataResponse::TransactionsWithProof(response) => {
+                    assert_eq!(response, transaction_lists_with_proof[i]);
+                },
+                _ => panic!("Expected transactions with proof but got: {:?}", response),
+            };
+        }
+    }
+}
+
 #[tokio::test]
 async fn test_cachable_requests_data_versions() {
     // Create test data
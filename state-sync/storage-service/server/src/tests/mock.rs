@@ -142,7 +142,19 @@ impl MockClient {
         &mut self,
         request: StorageServiceRequest,
     ) -> Result<StorageServiceResponse, StorageServiceError> {
-        let receiver = self.send_request(request, None, None).await;
+        self.process_request_with_protocol(request, ProtocolId::StorageServiceRpc)
+            .await
+    }
+
+    /// Send the given storage request (over the specified protocol) and wait for a response
+    pub async fn process_request_with_protocol(
+        &mut self,
+        request: StorageServiceRequest,
+        protocol_id: ProtocolId,
+    ) -> Result<StorageServiceResponse, StorageServiceError> {
+        let receiver = self
+            .send_request_with_protocol(request, None, None, protocol_id)
+            .await;
         self.wait_for_response(receiver).await
     }
 
@@ -153,11 +165,23 @@ impl MockClient {
         request: StorageServiceRequest,
         peer_id: Option<AccountAddress>,
         network_id: Option<NetworkId>,
+    ) -> Receiver<Result<bytes::Bytes, aptos_network::protocols::network::RpcError>> {
+        self.send_request_with_protocol(request, peer_id, network_id, ProtocolId::StorageServiceRpc)
+            .await
+    }
+
+    /// Send the specified storage request over the given protocol and return
+    /// the receiver on which to expect a result.
+    pub async fn send_request_with_protocol(
+        &mut self,
+        request: StorageServiceRequest,
+        peer_id: Option<AccountAddress>,
+        network_id: Option<NetworkId>,
+        protocol_id: ProtocolId,
     ) -> Receiver<Result<bytes::Bytes, aptos_network::protocols::network::RpcError>> {
         // Create the inbound rpc request
         let peer_id = peer_id.unwrap_or_else(PeerId::random);
         let network_id = network_id.unwrap_or_else(get_random_network_id);
-        let protocol_id = ProtocolId::StorageServiceRpc;
         let data = protocol_id
             .to_bytes(&StorageServiceMessage::Request(request))
             .unwrap();
@@ -2,8 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    error::Error, handler::Handler, metrics, moderator::RequestModerator, network::ResponseSender,
-    optimistic_fetch::OptimisticFetchRequest, storage::StorageReaderInterface,
+    error::Error,
+    handler::{create_storage_service_response, Handler},
+    metrics,
+    moderator::RequestModerator,
+    network::ResponseSender,
+    optimistic_fetch::OptimisticFetchRequest,
+    storage::StorageReaderInterface,
     subscription::SubscriptionStreamRequests,
 };
 use aptos_config::network_id::PeerNetworkId;
@@ -97,7 +102,6 @@ pub fn notify_peer_of_new_data<T: StorageReaderInterface>(
     response_sender: ResponseSender,
 ) -> aptos_storage_service_types::Result<DataResponse, Error> {
     // Handle the storage service request to fetch the missing data
-    let use_compression = missing_data_request.use_compression;
     let handler = Handler::new(
         cached_storage_server_summary,
         optimistic_fetches,
@@ -161,16 +165,18 @@ pub fn notify_peer_of_new_data<T: StorageReaderInterface>(
     };
 
     // Create the storage service response
-    let storage_response =
-        match StorageServiceResponse::new(transformed_data_response.clone(), use_compression) {
-            Ok(storage_response) => storage_response,
-            Err(error) => {
-                return Err(Error::UnexpectedErrorEncountered(format!(
-                    "Failed to create transformed response! Error: {:?}",
-                    error
-                )));
-            },
-        };
+    let storage_response = match create_storage_service_response(
+        transformed_data_response.clone(),
+        &missing_data_request,
+    ) {
+        Ok(storage_response) => storage_response,
+        Err(error) => {
+            return Err(Error::UnexpectedErrorEncountered(format!(
+                "Failed to create transformed response! Error: {:?}",
+                error
+            )));
+        },
+    };
 
     // Send the response to the peer
     handler.send_response(missing_data_request, Ok(storage_response), response_sender);
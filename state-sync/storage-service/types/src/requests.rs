@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::COMPRESSION_SUFFIX_LABEL;
+use aptos_compression::algorithm::CompressionAlgorithm;
 use aptos_types::transaction::Version;
 use serde::{Deserialize, Serialize};
 
@@ -10,6 +11,8 @@ use serde::{Deserialize, Serialize};
 pub struct StorageServiceRequest {
     pub data_request: DataRequest, // The data to fetch from the storage service
     pub use_compression: bool,     // Whether or not the client wishes data to be compressed
+    #[serde(skip)]
+    pub compression_algorithm: CompressionAlgorithm, // The algorithm to compress data with (derived from the protocol, not sent over the wire)
 }
 
 impl StorageServiceRequest {
@@ -17,6 +20,15 @@ impl StorageServiceRequest {
         Self {
             data_request,
             use_compression,
+            compression_algorithm: CompressionAlgorithm::default(),
+        }
+    }
+
+    /// Returns the request with the given compression algorithm
+    pub fn with_compression_algorithm(self, compression_algorithm: CompressionAlgorithm) -> Self {
+        Self {
+            compression_algorithm,
+            ..self
         }
     }
 
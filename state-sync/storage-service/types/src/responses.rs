@@ -13,7 +13,9 @@ use crate::{
     responses::Error::DegenerateRangeError,
     Epoch, StorageServiceRequest, COMPRESSION_SUFFIX_LABEL,
 };
-use aptos_compression::{client::CompressionClient, CompressedData};
+use aptos_compression::{
+    algorithm::CompressionAlgorithm, client::CompressionClient, CompressedData,
+};
 use aptos_config::config::{
     AptosDataClientConfig, StorageServiceConfig, MAX_APPLICATION_MESSAGE_SIZE,
 };
@@ -59,29 +61,61 @@ impl From<aptos_compression::Error> for Error {
 pub enum StorageServiceResponse {
     CompressedResponse(String, CompressedData), // Store the label and the data (e.g., for logging/metrics)
     RawResponse(DataResponse),
+    CompressedResponseWithHeader(String, CompressedData), // The algorithm is identified by the data header
 }
 
 impl StorageServiceResponse {
-    /// Creates a new response and performs compression if required
+    /// Creates a new response and performs compression (using LZ4) if required
     pub fn new(data_response: DataResponse, perform_compression: bool) -> Result<Self, Error> {
-        if perform_compression {
-            // Serialize and compress the raw data
-            let raw_data = bcs::to_bytes(&data_response)
-                .map_err(|error| Error::UnexpectedErrorEncountered(error.to_string()))?;
-            let compressed_data = aptos_compression::compress(
-                raw_data,
-                CompressionClient::StateSync,
-                MAX_APPLICATION_MESSAGE_SIZE,
-            )?;
-
-            // Create the compressed response
-            let label = data_response.get_label().to_string() + COMPRESSION_SUFFIX_LABEL;
-            Ok(StorageServiceResponse::CompressedResponse(
-                label,
-                compressed_data,
-            ))
-        } else {
-            Ok(StorageServiceResponse::RawResponse(data_response))
+        Self::new_with_compression_algorithm(
+            data_response,
+            perform_compression,
+            CompressionAlgorithm::Lz4,
+        )
+    }
+
+    /// Creates a new response and performs compression (using the given
+    /// algorithm) if required. LZ4 responses are not prefixed with an
+    /// algorithm header, to remain compatible with older clients.
+    pub fn new_with_compression_algorithm(
+        data_response: DataResponse,
+        perform_compression: bool,
+        compression_algorithm: CompressionAlgorithm,
+    ) -> Result<Self, Error> {
+        if !perform_compression {
+            return Ok(StorageServiceResponse::RawResponse(data_response));
+        }
+
+        // Serialize the raw data
+        let raw_data = bcs::to_bytes(&data_response)
+            .map_err(|error| Error::UnexpectedErrorEncountered(error.to_string()))?;
+        let label = data_response.get_label().to_string() + COMPRESSION_SUFFIX_LABEL;
+
+        // Compress the data and create the compressed response
+        match compression_algorithm {
+            CompressionAlgorithm::Lz4 => {
+                let compressed_data = aptos_compression::compress(
+                    raw_data,
+                    CompressionClient::StateSync,
+                    MAX_APPLICATION_MESSAGE_SIZE,
+                )?;
+                Ok(StorageServiceResponse::CompressedResponse(
+                    label,
+                    compressed_data,
+                ))
+            },
+            compression_algorithm => {
+                let compressed_data = aptos_compression::compress_with_header(
+                    raw_data,
+                    CompressionClient::StateSync,
+                    compression_algorithm,
+                    MAX_APPLICATION_MESSAGE_SIZE,
+                )?;
+                Ok(StorageServiceResponse::CompressedResponseWithHeader(
+                    label,
+                    compressed_data,
+                ))
+            },
         }
     }
 
@@ -99,13 +133,24 @@ impl StorageServiceResponse {
                 Ok(data_response)
             },
             StorageServiceResponse::RawResponse(data_response) => Ok(data_response.clone()),
+            StorageServiceResponse::CompressedResponseWithHeader(_, compressed_data) => {
+                let raw_data = aptos_compression::decompress_with_header(
+                    compressed_data,
+                    CompressionClient::StateSync,
+                    MAX_APPLICATION_MESSAGE_SIZE,
+                )?;
+                let data_response = bcs::from_bytes::<DataResponse>(&raw_data)
+                    .map_err(|error| Error::UnexpectedErrorEncountered(error.to_string()))?;
+                Ok(data_response)
+            },
         }
     }
 
     /// Returns a summary label for the response
     pub fn get_label(&self) -> String {
         match self {
-            StorageServiceResponse::CompressedResponse(label, _) => label.clone(),
+            StorageServiceResponse::CompressedResponse(label, _)
+            | StorageServiceResponse::CompressedResponseWithHeader(label, _) => label.clone(),
             StorageServiceResponse::RawResponse(data_response) => {
                 data_response.get_label().to_string()
             },
@@ -114,7 +159,10 @@ impl StorageServiceResponse {
 
     /// Returns true iff the data response is compressed
     pub fn is_compressed(&self) -> bool {
-        matches!(self, Self::CompressedResponse(_, _))
+        matches!(
+            self,
+            Self::CompressedResponse(_, _) | Self::CompressedResponseWithHeader(_, _)
+        )
     }
 }
 
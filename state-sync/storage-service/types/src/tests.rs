@@ -10,9 +10,12 @@ use crate::{
         SubscriptionStreamMetadata, TransactionOutputsWithProofRequest,
         TransactionsOrOutputsWithProofRequest, TransactionsWithProofRequest,
     },
-    responses::{CompleteDataRange, DataSummary, ProtocolMetadata},
+    responses::{
+        CompleteDataRange, DataResponse, DataSummary, ProtocolMetadata, StorageServiceResponse,
+    },
     Epoch, StorageServiceRequest,
 };
+use aptos_compression::algorithm::CompressionAlgorithm;
 use aptos_config::config::AptosDataClientConfig;
 use aptos_crypto::hash::HashValue;
 use aptos_time_service::{TimeService, TimeServiceTrait};
@@ -501,6 +504,63 @@ proptest! {
     }
 }
 
+#[test]
+fn test_response_compression_algorithms() {
+    // Create a data response
+    let data_response = DataResponse::NumberOfStatesAtVersion(get_random_u64());
+
+    // Verify the response is compressed (and decompressed) with each algorithm
+    for compression_algorithm in [CompressionAlgorithm::Lz4, CompressionAlgorithm::Zstd] {
+        let response = StorageServiceResponse::new_with_compression_algorithm(
+            data_response.clone(),
+            true,
+            compression_algorithm,
+        )
+        .unwrap();
+        assert!(response.is_compressed());
+        assert_eq!(
+            response.get_label(),
+            StorageServiceResponse::new(data_response.clone(), true)
+                .unwrap()
+                .get_label()
+        );
+        assert_eq!(response.get_data_response().unwrap(), data_response);
+
+        // Verify that only LZ4 responses omit the algorithm header
+        let is_lz4_response = matches!(response, StorageServiceResponse::CompressedResponse(_, _));
+        assert_eq!(
+            is_lz4_response,
+            compression_algorithm == CompressionAlgorithm::Lz4
+        );
+    }
+
+    // Verify that uncompressed responses ignore the algorithm
+    let response = StorageServiceResponse::new_with_compression_algorithm(
+        data_response.clone(),
+        false,
+        CompressionAlgorithm::Zstd,
+    )
+    .unwrap();
+    assert_eq!(response, StorageServiceResponse::RawResponse(data_response));
+}
+
+#[test]
+fn test_request_compression_algorithm_not_serialized() {
+    // Create a request with each compression algorithm
+    let data_request = DataRequest::GetServerProtocolVersion;
+    let lz4_request = StorageServiceRequest::new(data_request.clone(), true);
+    let zstd_request = StorageServiceRequest::new(data_request, true)
+        .with_compression_algorithm(CompressionAlgorithm::Zstd);
+
+    // Verify the requests serialize identically, and deserialize with the default algorithm
+    let lz4_bytes = bcs::to_bytes(&lz4_request).unwrap();
+    let zstd_bytes = bcs::to_bytes(&zstd_request).unwrap();
+    assert_eq!(lz4_bytes, zstd_bytes);
+    let request = bcs::from_bytes::<StorageServiceRequest>(&zstd_bytes).unwrap();
+    assert_eq!(request, lz4_request);
+    assert_ne!(request, zstd_request);
+}
+
 /// Creates a new data range using the specified bounds
 fn create_data_range(lowest: u64, highest: u64) -> CompleteDataRange<u64> {
     CompleteDataRange::new(lowest, highest).unwrap()
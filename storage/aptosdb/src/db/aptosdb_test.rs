@@ -150,6 +150,52 @@ fn test_error_if_version_pruned() {
     assert!(db.error_if_ledger_pruned("Transaction", 10).is_ok());
 }
 
+#[test]
+fn test_archive_mode() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::open(
+        StorageDirPaths::from_path(&tmp_dir),
+        /*readonly=*/ false,
+        PrunerConfig {
+            enable_archive_mode: true,
+            ..Default::default()
+        },
+        RocksdbConfigs::default(),
+        false, /* enable_indexer */
+        BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
+        DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+        None,
+    )
+    .unwrap();
+    assert!(db.ledger_pruner.is_pruner_enabled());
+    assert!(db
+        .state_store
+        .state_db
+        .state_merkle_pruner
+        .is_pruner_enabled());
+    assert!(!db.state_store.state_kv_pruner.is_pruner_enabled());
+
+    db.state_store
+        .state_db
+        .state_merkle_pruner
+        .save_min_readable_version(10)
+        .unwrap();
+    db.state_store
+        .state_db
+        .epoch_snapshot_pruner
+        .save_min_readable_version(10)
+        .unwrap();
+    db.ledger_pruner.save_min_readable_version(10).unwrap();
+    assert_eq!(db.get_first_state_value_version().unwrap(), Some(0));
+    assert!(db.error_if_state_kv_pruned("StateValue", 5).is_ok());
+    assert_eq!(
+        db.error_if_state_merkle_pruned("State merkle", 5)
+            .unwrap_err()
+            .to_string(),
+        "AptosDB Other Error: State merkle at version 5 is pruned, Merkle proofs are not available but state values can still be read without proof. snapshots are available at >= 10, epoch snapshots are available at >= 10"
+    );
+}
+
 #[test]
 fn test_get_transaction_auxiliary_data() {
     let tmp_dir = TempPath::new();
@@ -235,6 +281,7 @@ pub fn test_state_merkle_pruning_impl(
                 prune_window: 10,
                 batch_size: 1,
             },
+            enable_archive_mode: false,
         },
         RocksdbConfigs::default(),
         false, /* enable_indexer */
@@ -28,8 +28,18 @@ impl AptosDB {
             Arc::clone(&state_merkle_db),
             pruner_config.epoch_snapshot_pruner_config.into(),
         );
+        // In archive mode the state value history is kept, so historical state can still be read
+        // after the Merkle tree at that version is pruned.
+        let state_kv_pruner_config = if pruner_config.enable_archive_mode {
+            LedgerPrunerConfig {
+                enable: false,
+                ..pruner_config.ledger_pruner_config
+            }
+        } else {
+            pruner_config.ledger_pruner_config
+        };
         let state_kv_pruner =
-            StateKvPrunerManager::new(Arc::clone(&state_kv_db), pruner_config.ledger_pruner_config);
+            StateKvPrunerManager::new(Arc::clone(&state_kv_db), state_kv_pruner_config);
         let state_store = Arc::new(StateStore::new(
             Arc::clone(&ledger_db),
             Arc::clone(&state_merkle_db),
@@ -210,6 +220,14 @@ impl AptosDB {
             .get_min_readable_version();
         if version >= min_readable_epoch_snapshot_version {
             self.ledger_db.metadata_db().ensure_epoch_ending(version)
+        } else if version >= self.state_store.state_kv_pruner.get_min_readable_version() {
+            bail!(
+                "{} at version {} is pruned, Merkle proofs are not available but state values can still be read without proof. snapshots are available at >= {}, epoch snapshots are available at >= {}",
+                data_type,
+                version,
+                min_readable_version,
+                min_readable_epoch_snapshot_version,
+            )
         } else {
             bail!(
                 "{} at version {} is pruned. snapshots are available at >= {}, epoch snapshots are available at >= {}",
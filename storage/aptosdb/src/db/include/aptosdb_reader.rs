@@ -249,6 +249,15 @@ impl DbReader for AptosDB {
         })
     }
 
+    /// Get the first version that state values start existent.
+    fn get_first_state_value_version(&self) -> Result<Option<Version>> {
+        gauged_api("get_first_state_value_version", || {
+            Ok(Some(
+                self.state_store.state_kv_pruner.get_min_readable_version(),
+            ))
+        })
+    }
+
     /// Returns a batch of transactions for the purpose of synchronizing state to another node.
     ///
     /// If any version beyond ledger_version is requested, it is ignored.
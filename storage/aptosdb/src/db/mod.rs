@@ -5,6 +5,7 @@
 use crate::{
     backup::{backup_handler::BackupHandler, restore_utils},
     common::MAX_NUM_EPOCH_ENDING_LEDGER_INFO,
+    db_options::DbOpenMode,
     event_store::EventStore,
     ledger_db::{
         ledger_metadata_db::LedgerMetadataDb,
@@ -29,7 +30,8 @@ use crate::{
     utils::new_sharded_kv_schema_batch,
 };
 use aptos_config::config::{
-    PrunerConfig, RocksdbConfig, RocksdbConfigs, StorageDirPaths, NO_OP_STORAGE_PRUNER_CONFIG,
+    LedgerPrunerConfig, PrunerConfig, RocksdbConfig, RocksdbConfigs, StorageDirPaths,
+    NO_OP_STORAGE_PRUNER_CONFIG,
 };
 use aptos_crypto::HashValue;
 use aptos_db_indexer::{db_indexer::InternalIndexerDB, Indexer};
@@ -170,17 +172,21 @@ impl AptosDB {
         readonly: bool,
         max_num_nodes_per_lru_cache_shard: usize,
     ) -> Result<(LedgerDb, StateMerkleDb, StateKvDb)> {
-        let ledger_db = LedgerDb::new(db_paths.ledger_db_root_path(), rocksdb_configs, readonly)?;
+        let ledger_db = LedgerDb::new(
+            db_paths.ledger_db_root_path(),
+            rocksdb_configs,
+            DbOpenMode::from_readonly(readonly),
+        )?;
         let state_kv_db = StateKvDb::new(
             db_paths,
             rocksdb_configs,
-            readonly,
+            DbOpenMode::from_readonly(readonly),
             ledger_db.metadata_db_arc(),
         )?;
         let state_merkle_db = StateMerkleDb::new(
             db_paths,
             rocksdb_configs,
-            readonly,
+            DbOpenMode::from_readonly(readonly),
             max_num_nodes_per_lru_cache_shard,
         )?;
 
@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    db_debugger::ShardingConfig, ledger_db::LedgerDb, state_kv_db::StateKvDb,
-    state_merkle_db::StateMerkleDb,
+    db_debugger::ShardingConfig, db_options::DbOpenMode, ledger_db::LedgerDb,
+    state_kv_db::StateKvDb, state_merkle_db::StateMerkleDb,
 };
 use aptos_config::config::{RocksdbConfigs, StorageDirPaths};
 use aptos_storage_interface::Result;
@@ -20,11 +20,23 @@ pub struct DbDir {
     #[clap(long, value_parser)]
     db_dir: PathBuf,
 
+    /// If set, the DB is opened as a RocksDB secondary instance keeping its files under this
+    /// directory, which allows reading the DB of a running node.
+    #[clap(long, value_parser)]
+    secondary_db_dir: Option<PathBuf>,
+
     #[clap(flatten)]
     pub sharding_config: ShardingConfig,
 }
 
 impl DbDir {
+    fn open_mode(&self, readonly: bool) -> DbOpenMode {
+        match &self.secondary_db_dir {
+            Some(secondary_db_dir) => DbOpenMode::Secondary(secondary_db_dir.clone()),
+            None => DbOpenMode::from_readonly(readonly),
+        }
+    }
+
     pub fn open_state_merkle_db(&self) -> Result<StateMerkleDb> {
         StateMerkleDb::new(
             &StorageDirPaths::from_path(&self.db_dir),
@@ -32,20 +44,24 @@ impl DbDir {
                 enable_storage_sharding: self.sharding_config.enable_storage_sharding,
                 ..Default::default()
             },
-            false,
+            self.open_mode(true),
             0,
         )
     }
 
     pub fn open_state_kv_db(&self) -> Result<StateKvDb> {
         let leger_db = self.open_ledger_db()?;
+        self.open_state_kv_db_with_ledger_db(&leger_db)
+    }
+
+    pub fn open_state_kv_db_with_ledger_db(&self, leger_db: &LedgerDb) -> Result<StateKvDb> {
         StateKvDb::new(
             &StorageDirPaths::from_path(&self.db_dir),
             RocksdbConfigs {
                 enable_storage_sharding: self.sharding_config.enable_storage_sharding,
                 ..Default::default()
             },
-            true,
+            self.open_mode(true),
             leger_db.metadata_db_arc(),
         )
     }
@@ -57,7 +73,7 @@ impl DbDir {
                 enable_storage_sharding: self.sharding_config.enable_storage_sharding,
                 ..Default::default()
             },
-            true,
+            self.open_mode(true),
         )
     }
 }
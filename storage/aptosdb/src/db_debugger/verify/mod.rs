@@ -0,0 +1,473 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    db_debugger::common::DbDir, ledger_db::LedgerDb,
+    schema::transaction_accumulator::TransactionAccumulatorSchema, state_kv_db::StateKvDb,
+    state_merkle_db::StateMerkleDb,
+};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_jellyfish_merkle::iterator::JellyfishMerkleIterator;
+use aptos_storage_interface::{db_ensure as ensure, db_other_bail as bail, AptosDbError, Result};
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{accumulator::InMemoryEventAccumulator, position::Position},
+    transaction::{TransactionInfo, Version},
+};
+use clap::Parser;
+use rayon::prelude::*;
+use std::sync::Arc;
+
+#[derive(Parser)]
+#[clap(
+    about = "Verify the DB end to end: ledger data against the TransactionInfos and the transaction \
+    accumulator, and state Merkle tree leaves against the state values."
+)]
+pub struct Cmd {
+    #[clap(flatten)]
+    db_dir: DbDir,
+
+    /// First version to verify, defaults to the first version not pruned from the ledger.
+    #[clap(long)]
+    start_version: Option<Version>,
+
+    /// Last version to verify, defaults to the version of the latest LedgerInfo.
+    #[clap(long)]
+    end_version: Option<Version>,
+
+    /// Number of versions verified by each of the parallel tasks walking the ledger.
+    #[clap(long, default_value_t = 10_000)]
+    chunk_size: usize,
+
+    /// Number of leaves, evenly spread over the latest state snapshot, checked against the state
+    /// values.
+    #[clap(long, default_value_t = 10_000)]
+    num_leaf_samples: usize,
+}
+
+#[derive(Debug)]
+struct Inconsistency {
+    version: Version,
+    reason: String,
+}
+
+impl Inconsistency {
+    fn new(version: Version, reason: String) -> Option<Self> {
+        Some(Self { version, reason })
+    }
+}
+
+impl Cmd {
+    pub fn run(self) -> Result<()> {
+        ensure!(self.chunk_size > 0, "chunk_size must be greater than 0.");
+
+        let ledger_db = self.db_dir.open_ledger_db()?;
+        let state_kv_db = self.db_dir.open_state_kv_db_with_ledger_db(&ledger_db)?;
+        let state_merkle_db = Arc::new(self.db_dir.open_state_merkle_db()?);
+
+        let ledger_info = ledger_db.metadata_db().get_latest_ledger_info()?;
+        println!("Latest LedgerInfo: {:?}", ledger_info);
+        let ledger_version = ledger_info.ledger_info().version();
+
+        let start_version = match self.start_version {
+            Some(version) => version,
+            None => ledger_db.metadata_db().get_pruner_progress().unwrap_or(0),
+        };
+        let end_version = self.end_version.unwrap_or(ledger_version);
+        ensure!(
+            start_version <= end_version && end_version <= ledger_version,
+            "Invalid version range [{}, {}], latest version is {}.",
+            start_version,
+            end_version,
+            ledger_version,
+        );
+        println!("Verifying versions [{start_version}, {end_version}]...");
+
+        let (ledger_res, state_res) = rayon::join(
+            || self.verify_ledger(&ledger_db, &ledger_info, start_version, end_version),
+            || self.verify_state(&ledger_db, &state_kv_db, &state_merkle_db, end_version),
+        );
+        let first_inconsistency = [ledger_res?, state_res?]
+            .into_iter()
+            .flatten()
+            .min_by_key(|inconsistency| inconsistency.version);
+
+        match first_inconsistency {
+            Some(Inconsistency { version, reason }) => {
+                bail!("First inconsistent version: {}. {}", version, reason)
+            },
+            None => {
+                println!("Done. No inconsistency found.");
+                Ok(())
+            },
+        }
+    }
+
+    fn verify_ledger(
+        &self,
+        ledger_db: &LedgerDb,
+        ledger_info: &LedgerInfoWithSignatures,
+        start_version: Version,
+        end_version: Version,
+    ) -> Result<Option<Inconsistency>> {
+        let ledger_version = ledger_info.ledger_info().version();
+        let root_hash = ledger_db
+            .transaction_accumulator_db()
+            .get_root_hash(ledger_version)?;
+        let expected_root_hash = ledger_info.ledger_info().transaction_accumulator_hash();
+        if root_hash != expected_root_hash {
+            return Ok(Inconsistency::new(
+                ledger_version,
+                format!(
+                    "Transaction accumulator root hash {:x} doesn't match the one in the latest LedgerInfo {:x}.",
+                    root_hash, expected_root_hash,
+                ),
+            ));
+        }
+
+        let chunk_start_versions: Vec<_> = (start_version..=end_version)
+            .step_by(self.chunk_size)
+            .collect();
+        let inconsistencies = chunk_start_versions
+            .into_par_iter()
+            .map(|chunk_start_version| {
+                let num_versions = std::cmp::min(
+                    self.chunk_size as u64,
+                    end_version - chunk_start_version + 1,
+                ) as usize;
+                Self::verify_ledger_chunk(
+                    ledger_db,
+                    ledger_version,
+                    root_hash,
+                    chunk_start_version,
+                    num_versions,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(inconsistencies
+            .into_iter()
+            .flatten()
+            .min_by_key(|inconsistency| inconsistency.version))
+    }
+
+    fn verify_ledger_chunk(
+        ledger_db: &LedgerDb,
+        ledger_version: Version,
+        root_hash: HashValue,
+        start_version: Version,
+        num_versions: usize,
+    ) -> Result<Option<Inconsistency>> {
+        let mut txn_info_iter = ledger_db
+            .transaction_info_db()
+            .get_transaction_info_iter(start_version, num_versions)?;
+        let mut txn_iter = ledger_db
+            .transaction_db()
+            .get_transaction_iter(start_version, num_versions)?;
+        let mut events_iter = ledger_db
+            .event_db()
+            .get_events_by_version_iter(start_version, num_versions)?;
+        let mut write_set_iter = ledger_db
+            .write_set_db()
+            .get_write_set_iter(start_version, num_versions)?;
+
+        let mut txn_info_hashes = Vec::with_capacity(num_versions);
+        for version in start_version..start_version + num_versions as u64 {
+            // Each iterator must yield exactly one item per version, a gap or a read error is
+            // reported at the version it's hit.
+            let txn_info: TransactionInfo =
+                match Self::next_item(&mut txn_info_iter, version, "TransactionInfo") {
+                    Ok(txn_info) => txn_info,
+                    Err(inconsistency) => return Ok(Some(inconsistency)),
+                };
+            let txn = match Self::next_item(&mut txn_iter, version, "Transaction") {
+                Ok(txn) => txn,
+                Err(inconsistency) => return Ok(Some(inconsistency)),
+            };
+            let events = match Self::next_item(&mut events_iter, version, "Events") {
+                Ok(events) => events,
+                Err(inconsistency) => return Ok(Some(inconsistency)),
+            };
+            let write_set = match Self::next_item(&mut write_set_iter, version, "WriteSet") {
+                Ok(write_set) => write_set,
+                Err(inconsistency) => return Ok(Some(inconsistency)),
+            };
+
+            let txn_info_hash = txn_info.hash();
+            let leaf_hash =
+                ledger_db
+                    .transaction_accumulator_db_raw()
+                    .get::<TransactionAccumulatorSchema>(&Position::from_leaf_index(version))?;
+            if leaf_hash != Some(txn_info_hash) {
+                return Ok(Inconsistency::new(
+                    version,
+                    format!(
+                        "TransactionInfo hash {:x} doesn't match the accumulator leaf hash {:?}.",
+                        txn_info_hash, leaf_hash,
+                    ),
+                ));
+            }
+
+            let txn_hash = txn.hash();
+            if txn_hash != txn_info.transaction_hash() {
+                return Ok(Inconsistency::new(
+                    version,
+                    format!(
+                        "Transaction hash {:x} doesn't match the one in TransactionInfo {:x}.",
+                        txn_hash,
+                        txn_info.transaction_hash(),
+                    ),
+                ));
+            }
+
+            let event_hashes: Vec<_> = events.iter().map(CryptoHash::hash).collect();
+            let event_root_hash = InMemoryEventAccumulator::from_leaves(&event_hashes).root_hash();
+            if event_root_hash != txn_info.event_root_hash() {
+                return Ok(Inconsistency::new(
+                    version,
+                    format!(
+                        "Event root hash {:x} doesn't match the one in TransactionInfo {:x}.",
+                        event_root_hash,
+                        txn_info.event_root_hash(),
+                    ),
+                ));
+            }
+
+            let write_set_hash = CryptoHash::hash(&write_set);
+            if write_set_hash != txn_info.state_change_hash() {
+                return Ok(Inconsistency::new(
+                    version,
+                    format!(
+                        "WriteSet hash {:x} doesn't match the state change hash in TransactionInfo {:x}.",
+                        write_set_hash,
+                        txn_info.state_change_hash(),
+                    ),
+                ));
+            }
+
+            txn_info_hashes.push(txn_info_hash);
+        }
+
+        let range_proof = ledger_db
+            .transaction_accumulator_db()
+            .get_transaction_range_proof(
+                Some(start_version),
+                num_versions as u64,
+                ledger_version,
+            )?;
+        if let Err(err) = range_proof.verify(root_hash, Some(start_version), &txn_info_hashes) {
+            return Ok(Inconsistency::new(
+                start_version,
+                format!(
+                    "Range proof of {} versions doesn't verify against the accumulator root hash: {}",
+                    num_versions, err,
+                ),
+            ));
+        }
+
+        println!(
+            "Ledger good for versions [{}, {}].",
+            start_version,
+            start_version + num_versions as u64 - 1
+        );
+        Ok(None)
+    }
+
+    fn next_item<T>(
+        iter: &mut impl Iterator<Item = Result<T>>,
+        version: Version,
+        name: &str,
+    ) -> std::result::Result<T, Inconsistency> {
+        match iter.next() {
+            Some(Ok(item)) => Ok(item),
+            Some(Err(err)) => Err(Inconsistency {
+                version,
+                reason: format!("Failed to read the {name}: {err}"),
+            }),
+            None => Err(Inconsistency {
+                version,
+                reason: format!("{name} is missing."),
+            }),
+        }
+    }
+
+    fn verify_state(
+        &self,
+        ledger_db: &LedgerDb,
+        state_kv_db: &StateKvDb,
+        state_merkle_db: &Arc<StateMerkleDb>,
+        end_version: Version,
+    ) -> Result<Option<Inconsistency>> {
+        let snapshot_version =
+            match state_merkle_db.get_state_snapshot_version_before(end_version + 1)? {
+                Some(version) => version,
+                None => {
+                    println!("No state snapshot at or before version {end_version}.");
+                    return Ok(None);
+                },
+            };
+
+        let root_hash = state_merkle_db.get_root_hash(snapshot_version)?;
+        let state_checkpoint_hash = ledger_db
+            .transaction_info_db()
+            .get_transaction_info(snapshot_version)?
+            .state_checkpoint_hash();
+        if state_checkpoint_hash != Some(root_hash) {
+            return Ok(Inconsistency::new(
+                snapshot_version,
+                format!(
+                    "State root hash {:x} doesn't match the state checkpoint hash in TransactionInfo {:?}.",
+                    root_hash, state_checkpoint_hash,
+                ),
+            ));
+        }
+
+        let num_leaves = state_merkle_db.get_leaf_count(snapshot_version)?;
+        let num_samples = std::cmp::min(self.num_leaf_samples, num_leaves);
+        println!(
+            "Checking {} of the {} leaves of the state snapshot at version {}...",
+            num_samples, num_leaves, snapshot_version
+        );
+        let inconsistencies = (0..num_samples)
+            .into_par_iter()
+            .map(|i| {
+                Self::verify_leaf(
+                    state_kv_db,
+                    state_merkle_db,
+                    snapshot_version,
+                    i * num_leaves / num_samples,
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let inconsistency = inconsistencies.into_iter().flatten().next();
+        if inconsistency.is_none() {
+            println!("State good for the snapshot at version {snapshot_version}.");
+        }
+        Ok(inconsistency)
+    }
+
+    fn verify_leaf(
+        state_kv_db: &StateKvDb,
+        state_merkle_db: &Arc<StateMerkleDb>,
+        version: Version,
+        leaf_index: usize,
+    ) -> Result<Option<Inconsistency>> {
+        let (_key_hash, (state_key, _leaf_version)) = JellyfishMerkleIterator::new_by_index(
+            Arc::clone(state_merkle_db),
+            version,
+            leaf_index,
+        )?
+        .next()
+        .transpose()?
+        .ok_or_else(|| {
+            AptosDbError::NotFound(format!("Leaf #{leaf_index} at version {version}"))
+        })?;
+        let (leaf, _proof) = state_merkle_db.get_with_proof_ext(&state_key, version, 0)?;
+        let (value_hash, (_, leaf_version)) = leaf.ok_or_else(|| {
+            AptosDbError::NotFound(format!("Leaf of {state_key:?} at version {version}"))
+        })?;
+
+        match state_kv_db.get_state_value_with_version_by_version(&state_key, version)? {
+            None => Ok(Inconsistency::new(
+                version,
+                format!("State value of {state_key:?} is missing, leaf version {leaf_version}."),
+            )),
+            Some((value_version, value)) => {
+                let hash = CryptoHash::hash(&value);
+                if value_version != leaf_version || hash != value_hash {
+                    Ok(Inconsistency::new(
+                        version,
+                        format!(
+                            "State value of {:?} (version {}, hash {:x}) doesn't match the leaf (version {}, hash {:x}).",
+                            state_key, value_version, hash, leaf_version, value_hash,
+                        ),
+                    ))
+                } else {
+                    Ok(None)
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        db::{
+            test_helper::{arb_blocks_to_commit_with_block_nums, update_in_memory_state},
+            AptosDB,
+        },
+        schema::write_set::WriteSetSchema,
+    };
+    use aptos_config::config::{RocksdbConfigs, StorageDirPaths};
+    use aptos_storage_interface::DbReader;
+    use aptos_temppath::TempPath;
+    use proptest::prelude::*;
+
+    fn verify(db_dir: &TempPath, enable_storage_sharding: bool) -> Result<()> {
+        let mut args = vec![
+            "verify",
+            "--db-dir",
+            db_dir.path().to_str().unwrap(),
+            "--chunk-size",
+            "5",
+        ];
+        if enable_storage_sharding {
+            args.push("--enable-storage-sharding");
+        }
+        Cmd::try_parse_from(args).unwrap().run()
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1))]
+
+        #[test]
+        fn test_verify_missing_write_set(input in arb_blocks_to_commit_with_block_nums(10, 20)) {
+            use aptos_config::config::DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD;
+            let tmp_dir = TempPath::new();
+
+            let db = if input.1 { AptosDB::new_for_test_with_sharding(&tmp_dir, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD) } else { AptosDB::new_for_test(&tmp_dir) };
+            let mut in_memory_state = db.state_store.buffered_state().lock().current_state().clone();
+            let mut version = 0;
+            for (txns_to_commit, ledger_info_with_sigs) in input.0.iter() {
+                update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+                db.save_transactions_for_test(
+                    txns_to_commit,
+                    version,
+                    version.checked_sub(1),
+                    Some(ledger_info_with_sigs),
+                    true,
+                    in_memory_state.clone()
+                )
+                    .unwrap();
+                version += txns_to_commit.len() as u64;
+            }
+            let db_version = db.expect_synced_version();
+            drop(db);
+
+            prop_assert!(verify(&tmp_dir, input.1).is_ok());
+
+            let missing_version = db_version / 2;
+            let (ledger_db, _state_merkle_db, _state_kv_db) = AptosDB::open_dbs(
+                &StorageDirPaths::from_path(tmp_dir.path()),
+                RocksdbConfigs {
+                    enable_storage_sharding: input.1,
+                    ..Default::default()
+                },
+                /*readonly=*/ false,
+                /*max_num_nodes_per_lru_cache_shard=*/ 0,
+            ).unwrap();
+            ledger_db.write_set_db_raw().delete::<WriteSetSchema>(&missing_version).unwrap();
+            drop(ledger_db);
+
+            let err = verify(&tmp_dir, input.1).unwrap_err();
+            prop_assert!(
+                err.to_string().contains(&format!("First inconsistent version: {missing_version}.")),
+                "{}",
+                err,
+            );
+        }
+    }
+}
@@ -3,14 +3,68 @@
 
 use crate::schema::*;
 use aptos_config::config::RocksdbConfig;
+use aptos_rocksdb_options::gen_rocksdb_options;
 use aptos_schemadb::{
     BlockBasedOptions, Cache, ColumnFamilyDescriptor, ColumnFamilyName, DBCompressionType, Options,
-    SliceTransform, DEFAULT_COLUMN_FAMILY_NAME,
+    SliceTransform, DB, DEFAULT_COLUMN_FAMILY_NAME,
 };
+use aptos_storage_interface::Result;
 use aptos_types::transaction::Version;
+use std::path::PathBuf;
 
 const VERSION_SIZE: usize = std::mem::size_of::<Version>();
 
+/// How the RocksDB instances backing the DB are opened.
+#[derive(Clone, Debug)]
+pub(crate) enum DbOpenMode {
+    ReadWrite,
+    ReadOnly,
+    /// Follows a DB opened for writing by another process (e.g. a running node) as a RocksDB
+    /// secondary instance. Each instance keeps its own files under a sub directory of the given
+    /// path, named after the instance.
+    #[cfg_attr(not(feature = "db-debugger"), allow(dead_code))]
+    Secondary(PathBuf),
+}
+
+impl DbOpenMode {
+    pub(crate) fn from_readonly(readonly: bool) -> Self {
+        if readonly {
+            Self::ReadOnly
+        } else {
+            Self::ReadWrite
+        }
+    }
+
+    pub(crate) fn is_secondary(&self) -> bool {
+        matches!(self, Self::Secondary(_))
+    }
+
+    pub(crate) fn open_db(
+        &self,
+        path: PathBuf,
+        name: &str,
+        db_config: &RocksdbConfig,
+        cfds: Vec<ColumnFamilyDescriptor>,
+    ) -> Result<DB> {
+        Ok(match self {
+            Self::ReadWrite => {
+                DB::open_cf(&gen_rocksdb_options(db_config, false), path, name, cfds)?
+            },
+            Self::ReadOnly => {
+                DB::open_cf_readonly(&gen_rocksdb_options(db_config, true), path, name, cfds)?
+            },
+            Self::Secondary(secondary_root_path) => {
+                let secondary_path = secondary_root_path.join(name);
+                std::fs::create_dir_all(&secondary_path)?;
+                let mut db_opts = gen_rocksdb_options(db_config, true);
+                // Secondary instances need to keep all files open to follow the primary.
+                db_opts.set_max_open_files(-1);
+                DB::open_cf_as_secondary(&db_opts, path, secondary_path, name, cfds)?
+            },
+        })
+    }
+}
+
 pub(super) fn ledger_db_column_families() -> Vec<ColumnFamilyName> {
     vec![
         /* empty cf */ DEFAULT_COLUMN_FAMILY_NAME,
@@ -40,8 +40,9 @@ pub struct FastSyncStorageWrapper {
 }
 
 impl FastSyncStorageWrapper {
-    /// If the db is empty and configured to do fast sync, we return a FastSyncStorageWrapper
-    /// Otherwise, we returns AptosDB directly and the FastSyncStorageWrapper is None
+    /// If the db is empty and configured to do fast sync (or to restore from a backup),
+    /// we return a FastSyncStorageWrapper. Otherwise, we returns AptosDB directly and
+    /// the FastSyncStorageWrapper is None
     pub fn initialize_dbs(
         config: &NodeConfig,
         internal_indexer_db: Option<InternalIndexerDB>,
@@ -63,12 +64,10 @@ impl FastSyncStorageWrapper {
         }
 
         let mut db_dir = config.storage.dir();
-        // when the db is empty and configured to do fast sync, we will create a second DB
-        if config
-            .state_sync
-            .state_sync_driver
-            .bootstrapping_mode
-            .is_fast_sync()
+        // when the db is empty and configured to do fast sync (or to restore
+        // from a backup), we will create a second DB
+        let bootstrapping_mode = config.state_sync.state_sync_driver.bootstrapping_mode;
+        if (bootstrapping_mode.is_fast_sync() || bootstrapping_mode.is_restore_from_backup())
             && (db_main
                 .ledger_db
                 .metadata_db()
@@ -111,6 +110,12 @@ impl FastSyncStorageWrapper {
         *self.fast_sync_status.read()
     }
 
+    /// Marks the fast sync as finished after the fast sync db has been restored
+    /// externally (e.g., from a backup), so that all reads and writes use it.
+    pub fn finish_external_restore(&self) {
+        *self.fast_sync_status.write() = FastSyncStatus::FINISHED;
+    }
+
     /// Check if the fast sync finished already
     fn is_fast_sync_bootstrap_finished(&self) -> bool {
         let status = self.get_fast_sync_status();
@@ -12,7 +12,7 @@ use crate::{
         ledger_db_column_families, ledger_metadata_db_column_families,
         transaction_accumulator_db_column_families, transaction_auxiliary_data_db_column_families,
         transaction_db_column_families, transaction_info_db_column_families,
-        write_set_db_column_families,
+        write_set_db_column_families, DbOpenMode,
     },
     event_store::EventStore,
     ledger_db::{
@@ -25,7 +25,6 @@ use crate::{
 };
 use aptos_config::config::{RocksdbConfig, RocksdbConfigs};
 use aptos_logger::prelude::info;
-use aptos_rocksdb_options::gen_rocksdb_options;
 use aptos_schemadb::{ColumnFamilyDescriptor, ColumnFamilyName, SchemaBatch, DB};
 use aptos_storage_interface::Result;
 use aptos_types::transaction::Version;
@@ -111,7 +110,7 @@ impl LedgerDb {
     pub(crate) fn new<P: AsRef<Path>>(
         db_root_path: P,
         rocksdb_configs: RocksdbConfigs,
-        readonly: bool,
+        open_mode: DbOpenMode,
     ) -> Result<Self> {
         let sharding = rocksdb_configs.enable_storage_sharding;
         let ledger_metadata_db_path = Self::metadata_db_path(db_root_path.as_ref(), sharding);
@@ -123,7 +122,7 @@ impl LedgerDb {
                 LEDGER_DB_NAME
             },
             &rocksdb_configs.ledger_db_config,
-            readonly,
+            &open_mode,
         )?);
 
         info!(
@@ -159,7 +158,7 @@ impl LedgerDb {
             ledger_db_folder.join(EVENT_DB_NAME),
             EVENT_DB_NAME,
             &rocksdb_configs.ledger_db_config,
-            readonly,
+            &open_mode,
         )?);
         let event_db = EventDb::new(event_db_raw.clone(), EventStore::new(event_db_raw));
 
@@ -168,7 +167,7 @@ impl LedgerDb {
                 ledger_db_folder.join(TRANSACTION_ACCUMULATOR_DB_NAME),
                 TRANSACTION_ACCUMULATOR_DB_NAME,
                 &rocksdb_configs.ledger_db_config,
-                readonly,
+                &open_mode,
             )?));
 
         let transaction_auxiliary_data_db =
@@ -176,27 +175,27 @@ impl LedgerDb {
                 ledger_db_folder.join(TRANSACTION_AUXILIARY_DATA_DB_NAME),
                 TRANSACTION_AUXILIARY_DATA_DB_NAME,
                 &rocksdb_configs.ledger_db_config,
-                readonly,
+                &open_mode,
             )?));
         let transaction_db = TransactionDb::new(Arc::new(Self::open_rocksdb(
             ledger_db_folder.join(TRANSACTION_DB_NAME),
             TRANSACTION_DB_NAME,
             &rocksdb_configs.ledger_db_config,
-            readonly,
+            &open_mode,
         )?));
 
         let transaction_info_db = TransactionInfoDb::new(Arc::new(Self::open_rocksdb(
             ledger_db_folder.join(TRANSACTION_INFO_DB_NAME),
             TRANSACTION_INFO_DB_NAME,
             &rocksdb_configs.ledger_db_config,
-            readonly,
+            &open_mode,
         )?));
 
         let write_set_db = WriteSetDb::new(Arc::new(Self::open_rocksdb(
             ledger_db_folder.join(WRITE_SET_DB_NAME),
             WRITE_SET_DB_NAME,
             &rocksdb_configs.ledger_db_config,
-            readonly,
+            &open_mode,
         )?));
 
         // TODO(grao): Handle data inconsistency.
@@ -237,7 +236,7 @@ impl LedgerDb {
             enable_storage_sharding: sharding,
             ..Default::default()
         };
-        let ledger_db = Self::new(db_root_path, rocksdb_configs, /*readonly=*/ false)?;
+        let ledger_db = Self::new(db_root_path, rocksdb_configs, DbOpenMode::ReadWrite)?;
         let cp_ledger_db_folder = cp_root_path.as_ref().join(LEDGER_DB_FOLDER_NAME);
 
         info!(
@@ -357,23 +356,14 @@ impl LedgerDb {
         path: PathBuf,
         name: &str,
         db_config: &RocksdbConfig,
-        readonly: bool,
+        open_mode: &DbOpenMode,
     ) -> Result<DB> {
-        let db = if readonly {
-            DB::open_cf_readonly(
-                &gen_rocksdb_options(db_config, true),
-                path.clone(),
-                name,
-                Self::gen_cfds_by_name(db_config, name),
-            )?
-        } else {
-            DB::open_cf(
-                &gen_rocksdb_options(db_config, false),
-                path.clone(),
-                name,
-                Self::gen_cfds_by_name(db_config, name),
-            )?
-        };
+        let db = open_mode.open_db(
+            path.clone(),
+            name,
+            db_config,
+            Self::gen_cfds_by_name(db_config, name),
+        )?;
 
         info!("Opened {name} at {path:?}!");
 
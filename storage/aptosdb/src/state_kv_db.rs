@@ -5,7 +5,7 @@
 
 use crate::{
     common::NUM_STATE_SHARDS,
-    db_options::gen_state_kv_cfds,
+    db_options::{gen_state_kv_cfds, DbOpenMode},
     metrics::OTHER_TIMERS_SECONDS,
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
@@ -18,7 +18,6 @@ use aptos_config::config::{RocksdbConfig, RocksdbConfigs, StorageDirPaths};
 use aptos_crypto::hash::CryptoHash;
 use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use aptos_logger::prelude::info;
-use aptos_rocksdb_options::gen_rocksdb_options;
 use aptos_schemadb::{ReadOptions, SchemaBatch, DB};
 use aptos_storage_interface::Result;
 use aptos_types::{
@@ -44,7 +43,7 @@ impl StateKvDb {
     pub(crate) fn new(
         db_paths: &StorageDirPaths,
         rocksdb_configs: RocksdbConfigs,
-        readonly: bool,
+        open_mode: DbOpenMode,
         ledger_db: Arc<DB>,
     ) -> Result<Self> {
         let sharding = rocksdb_configs.enable_storage_sharding;
@@ -60,7 +59,7 @@ impl StateKvDb {
         Self::open(
             db_paths,
             rocksdb_configs.state_kv_db_config,
-            readonly,
+            open_mode,
             sharding,
         )
     }
@@ -68,7 +67,7 @@ impl StateKvDb {
     pub(crate) fn open(
         db_paths: &StorageDirPaths,
         state_kv_db_config: RocksdbConfig,
-        readonly: bool,
+        open_mode: DbOpenMode,
         enable_sharding: bool,
     ) -> Result<Self> {
         let state_kv_metadata_db_path =
@@ -78,7 +77,7 @@ impl StateKvDb {
             state_kv_metadata_db_path.clone(),
             STATE_KV_METADATA_DB_NAME,
             &state_kv_db_config,
-            readonly,
+            &open_mode,
             enable_sharding,
         )?);
 
@@ -91,7 +90,7 @@ impl StateKvDb {
         let state_kv_db_shards = {
             arr![{
                 let shard_root_path = db_paths.state_kv_db_shard_root_path(shard_id as u8);
-                let db = Self::open_shard(shard_root_path, shard_id as u8, &state_kv_db_config, readonly, enable_sharding)?;
+                let db = Self::open_shard(shard_root_path, shard_id as u8, &state_kv_db_config, &open_mode, enable_sharding)?;
                 shard_id += 1;
                 Arc::new(db)
             }; 16]
@@ -103,8 +102,11 @@ impl StateKvDb {
             enabled_sharding: true,
         };
 
-        if let Some(overall_kv_commit_progress) = get_state_kv_commit_progress(&state_kv_db)? {
-            truncate_state_kv_db_shards(&state_kv_db, overall_kv_commit_progress)?;
+        // A secondary instance can't write, the primary takes care of the truncation.
+        if !open_mode.is_secondary() {
+            if let Some(overall_kv_commit_progress) = get_state_kv_commit_progress(&state_kv_db)? {
+                truncate_state_kv_db_shards(&state_kv_db, overall_kv_commit_progress)?;
+            }
         }
 
         Ok(state_kv_db)
@@ -241,7 +243,7 @@ impl StateKvDb {
         db_root_path: P,
         shard_id: u8,
         state_kv_db_config: &RocksdbConfig,
-        readonly: bool,
+        open_mode: &DbOpenMode,
         enable_sharding: bool,
     ) -> Result<DB> {
         let db_name = format!("state_kv_db_shard_{}", shard_id);
@@ -249,7 +251,7 @@ impl StateKvDb {
             Self::db_shard_path(db_root_path, shard_id),
             &db_name,
             state_kv_db_config,
-            readonly,
+            open_mode,
             enable_sharding,
         )
     }
@@ -258,24 +260,15 @@ impl StateKvDb {
         path: PathBuf,
         name: &str,
         state_kv_db_config: &RocksdbConfig,
-        readonly: bool,
+        open_mode: &DbOpenMode,
         enable_sharding: bool,
     ) -> Result<DB> {
-        Ok(if readonly {
-            DB::open_cf_readonly(
-                &gen_rocksdb_options(state_kv_db_config, true),
-                path,
-                name,
-                gen_state_kv_cfds(state_kv_db_config, enable_sharding),
-            )?
-        } else {
-            DB::open_cf(
-                &gen_rocksdb_options(state_kv_db_config, false),
-                path,
-                name,
-                gen_state_kv_cfds(state_kv_db_config, enable_sharding),
-            )?
-        })
+        open_mode.open_db(
+            path,
+            name,
+            state_kv_db_config,
+            gen_state_kv_cfds(state_kv_db_config, enable_sharding),
+        )
     }
 
     fn db_shard_path<P: AsRef<Path>>(db_root_path: P, shard_id: u8) -> PathBuf {
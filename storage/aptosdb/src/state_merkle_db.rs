@@ -3,7 +3,7 @@
 
 use crate::{
     common::NUM_STATE_SHARDS,
-    db_options::gen_state_merkle_cfds,
+    db_options::{gen_state_merkle_cfds, DbOpenMode},
     lru_node_cache::LruNodeCache,
     metrics::{NODE_CACHE_SECONDS, OTHER_TIMERS_SECONDS},
     schema::{
@@ -22,7 +22,6 @@ use aptos_jellyfish_merkle::{
     node_type::NodeKey, JellyfishMerkleTree, TreeReader, TreeUpdateBatch, TreeWriter,
 };
 use aptos_logger::prelude::*;
-use aptos_rocksdb_options::gen_rocksdb_options;
 use aptos_schemadb::{SchemaBatch, DB};
 #[cfg(test)]
 use aptos_scratchpad::get_state_shard_id;
@@ -67,7 +66,7 @@ impl StateMerkleDb {
     pub(crate) fn new(
         db_paths: &StorageDirPaths,
         rocksdb_configs: RocksdbConfigs,
-        readonly: bool,
+        open_mode: DbOpenMode,
         max_nodes_per_lru_cache_shard: usize,
     ) -> Result<Self> {
         let sharding = rocksdb_configs.enable_storage_sharding;
@@ -88,7 +87,7 @@ impl StateMerkleDb {
                 state_merkle_db_path,
                 STATE_MERKLE_DB_NAME,
                 &state_merkle_db_config,
-                readonly,
+                &open_mode,
             )?);
             return Ok(Self {
                 state_merkle_metadata_db: Arc::clone(&db),
@@ -103,7 +102,7 @@ impl StateMerkleDb {
         Self::open(
             db_paths,
             state_merkle_db_config,
-            readonly,
+            open_mode,
             enable_cache,
             version_caches,
             lru_cache,
@@ -168,7 +167,7 @@ impl StateMerkleDb {
         let state_merkle_db = Self::new(
             &StorageDirPaths::from_path(db_root_path),
             rocksdb_configs,
-            /*readonly=*/ false,
+            DbOpenMode::ReadWrite,
             /*max_nodes_per_lru_cache_shard=*/ 0,
         )?;
         let cp_state_merkle_db_path = cp_root_path.as_ref().join(STATE_MERKLE_DB_FOLDER_NAME);
@@ -560,7 +559,7 @@ impl StateMerkleDb {
     fn open(
         db_paths: &StorageDirPaths,
         state_merkle_db_config: RocksdbConfig,
-        readonly: bool,
+        open_mode: DbOpenMode,
         enable_cache: bool,
         version_caches: HashMap<Option<u8>, VersionedNodeCache>,
         lru_cache: LruNodeCache,
@@ -574,7 +573,7 @@ impl StateMerkleDb {
             state_merkle_metadata_db_path.clone(),
             STATE_MERKLE_METADATA_DB_NAME,
             &state_merkle_db_config,
-            readonly,
+            &open_mode,
         )?);
 
         info!(
@@ -585,7 +584,7 @@ impl StateMerkleDb {
         let mut shard_id: usize = 0;
         let state_merkle_db_shards = arr![{
             let shard_root_path = db_paths.state_merkle_db_shard_root_path(shard_id as u8);
-            let db = Self::open_shard(shard_root_path, shard_id as u8, &state_merkle_db_config, readonly)?;
+            let db = Self::open_shard(shard_root_path, shard_id as u8, &state_merkle_db_config, &open_mode)?;
             shard_id += 1;
             Arc::new(db)
         }; 16];
@@ -599,13 +598,16 @@ impl StateMerkleDb {
             lru_cache,
         };
 
-        if let Some(overall_state_merkle_commit_progress) =
-            get_state_merkle_commit_progress(&state_merkle_db)?
-        {
-            truncate_state_merkle_db_shards(
-                &state_merkle_db,
-                overall_state_merkle_commit_progress,
-            )?;
+        // A secondary instance can't write, the primary takes care of the truncation.
+        if !open_mode.is_secondary() {
+            if let Some(overall_state_merkle_commit_progress) =
+                get_state_merkle_commit_progress(&state_merkle_db)?
+            {
+                truncate_state_merkle_db_shards(
+                    &state_merkle_db,
+                    overall_state_merkle_commit_progress,
+                )?;
+            }
         }
 
         Ok(state_merkle_db)
@@ -615,14 +617,14 @@ impl StateMerkleDb {
         db_root_path: P,
         shard_id: u8,
         state_merkle_db_config: &RocksdbConfig,
-        readonly: bool,
+        open_mode: &DbOpenMode,
     ) -> Result<DB> {
         let db_name = format!("state_merkle_db_shard_{}", shard_id);
         Self::open_db(
             Self::db_shard_path(db_root_path, shard_id),
             &db_name,
             state_merkle_db_config,
-            readonly,
+            open_mode,
         )
     }
 
@@ -630,23 +632,14 @@ impl StateMerkleDb {
         path: PathBuf,
         name: &str,
         state_merkle_db_config: &RocksdbConfig,
-        readonly: bool,
+        open_mode: &DbOpenMode,
     ) -> Result<DB> {
-        Ok(if readonly {
-            DB::open_cf_readonly(
-                &gen_rocksdb_options(state_merkle_db_config, true),
-                path,
-                name,
-                gen_state_merkle_cfds(state_merkle_db_config),
-            )?
-        } else {
-            DB::open_cf(
-                &gen_rocksdb_options(state_merkle_db_config, false),
-                path,
-                name,
-                gen_state_merkle_cfds(state_merkle_db_config),
-            )?
-        })
+        open_mode.open_db(
+            path,
+            name,
+            state_merkle_db_config,
+            gen_state_merkle_cfds(state_merkle_db_config),
+        )
     }
 
     fn db_shard_path<P: AsRef<Path>>(db_root_path: P, shard_id: u8) -> PathBuf {
@@ -5,7 +5,7 @@
 use crate::{
     backup_types::state_snapshot::manifest::{StateSnapshotBackup, StateSnapshotChunk},
     metadata::Metadata,
-    metrics::backup::BACKUP_TIMER,
+    metrics::backup::{BACKUP_TIMER, STATE_SNAPSHOT_CHUNKS},
     storage::{BackupHandleRef, BackupStorage, FileHandle, ShellSafeName},
     utils::{
         backup_service_client::BackupServiceClient, read_record_bytes::ReadRecordBytes,
@@ -15,7 +15,7 @@ use crate::{
 use anyhow::{anyhow, ensure, Result};
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_logger::prelude::*;
-use aptos_metrics_core::TimerHelper;
+use aptos_metrics_core::{IntCounterHelper, TimerHelper};
 use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
     proof::TransactionInfoWithProof,
@@ -26,7 +26,7 @@ use bytes::{BufMut, Bytes, BytesMut};
 use clap::Parser;
 use futures::{StreamExt, TryStream, TryStreamExt};
 use once_cell::sync::Lazy;
-use std::{convert::TryInto, str::FromStr, sync::Arc, time::Instant};
+use std::{collections::HashMap, convert::TryInto, str::FromStr, sync::Arc, time::Instant};
 use tokio::{io::AsyncWriteExt, sync::mpsc::Sender};
 use tokio_stream::wrappers::ReceiverStream;
 
@@ -37,6 +37,14 @@ pub struct StateSnapshotBackupOpt {
         help = "Epoch at the end of which a state snapshot is to be taken."
     )]
     pub epoch: u64,
+    #[clap(
+        long = "state-snapshot-base-manifest",
+        help = "Manifest of an earlier state snapshot. If set, an incremental snapshot is taken: \
+        chunks whose content hasn't changed since the base snapshot are not written again, but \
+        refer to the files of the base instead. Files are only shared with a base encrypted \
+        with the same key (or with a plaintext base if not encrypting)."
+    )]
+    pub base_manifest: Option<FileHandle>,
 }
 
 struct Chunk {
@@ -55,13 +63,23 @@ struct ChunkerState<RecordStream> {
     current_idx: usize,
     chunk_first_idx: usize,
     max_chunk_size: usize,
+    /// Last keys of the chunks in the base snapshot when taking an incremental snapshot, sorted.
+    /// Chunks are cut at these keys as well, so that key ranges unchanged since the base result
+    /// in identical chunks.
+    boundaries: Vec<HashValue>,
+    /// Index of the first boundary not smaller than `chunk_first_key`.
+    next_boundary: usize,
 }
 
 impl<RecordStream> ChunkerState<RecordStream>
 where
     RecordStream: TryStream<Ok = Bytes, Error = anyhow::Error> + Unpin,
 {
-    async fn new(mut record_stream: RecordStream, max_chunk_size: usize) -> Result<Self> {
+    async fn new(
+        mut record_stream: RecordStream,
+        max_chunk_size: usize,
+        boundaries: Vec<HashValue>,
+    ) -> Result<Self> {
         let first_record = record_stream
             .try_next()
             .await?
@@ -69,6 +87,7 @@ where
 
         let chunk_first_key = Self::parse_key(&first_record)?;
         let prev_record_len = first_record.len();
+        let next_boundary = boundaries.partition_point(|key| *key < chunk_first_key);
 
         let mut buf = BytesMut::new();
         buf.put_slice(&(first_record.len() as u32).to_be_bytes());
@@ -82,6 +101,8 @@ where
             current_idx: 0,
             chunk_first_idx: 0,
             max_chunk_size,
+            boundaries,
+            next_boundary,
         })
     }
 
@@ -96,8 +117,11 @@ where
         while let Some(record_bytes) = input.try_next().await? {
             let _timer = BACKUP_TIMER.timer_with(&["state_snapshot_process_records"]);
 
-            // If buf + current_record exceeds max_chunk_size, dump current buf to a new chunk
-            let chunk_cut_opt = should_cut_chunk(&self.buf, &record_bytes, self.max_chunk_size)
+            // If buf + current_record exceeds max_chunk_size, or current_record belongs to the next
+            // chunk of the base snapshot, dump current buf to a new chunk
+            let should_cut = should_cut_chunk(&self.buf, &record_bytes, self.max_chunk_size)
+                || self.crosses_boundary(&record_bytes)?;
+            let chunk_cut_opt = should_cut
                 .then(|| {
                     let bytes = self.buf.split().freeze();
                     let last_key = Self::parse_key(&bytes[bytes.len() - self.prev_record_len..])?;
@@ -112,6 +136,8 @@ where
 
                     self.chunk_first_idx = self.current_idx + 1;
                     self.chunk_first_key = Self::parse_key(&record_bytes)?;
+                    self.next_boundary += self.boundaries[self.next_boundary..]
+                        .partition_point(|key| *key < self.chunk_first_key);
 
                     Result::<_>::Ok(chunk)
                 })
@@ -145,6 +171,8 @@ where
             current_idx,
             chunk_first_idx,
             max_chunk_size: _,
+            boundaries: _,
+            next_boundary: _,
         } = self;
         ensure!(
             state_snapshot_file.is_none(),
@@ -164,6 +192,13 @@ where
         })
     }
 
+    fn crosses_boundary(&self, record: &[u8]) -> Result<bool> {
+        Ok(match self.boundaries.get(self.next_boundary) {
+            Some(boundary) if !self.buf.is_empty() => Self::parse_key(record)? > *boundary,
+            _ => false,
+        })
+    }
+
     fn parse_key(record: &[u8]) -> Result<HashValue> {
         let (key, _): (StateKey, StateValue) = bcs::from_bytes(record)?;
         Ok(key.hash())
@@ -178,9 +213,13 @@ impl<RecordStream> Chunker<RecordStream>
 where
     RecordStream: TryStream<Ok = Bytes, Error = anyhow::Error> + Unpin,
 {
-    async fn new(record_stream: RecordStream, max_chunk_size: usize) -> Result<Self> {
+    async fn new(
+        record_stream: RecordStream,
+        max_chunk_size: usize,
+        boundaries: Vec<HashValue>,
+    ) -> Result<Self> {
         Ok(Self {
-            state: Some(ChunkerState::new(record_stream, max_chunk_size).await?),
+            state: Some(ChunkerState::new(record_stream, max_chunk_size, boundaries).await?),
         })
     }
 
@@ -204,6 +243,10 @@ pub struct StateSnapshotBackupController {
     client: Arc<BackupServiceClient>,
     storage: Arc<dyn BackupStorage>,
     concurrent_data_requests: usize,
+    base_manifest: Option<FileHandle>,
+    /// Chunks of the base snapshot by (first_key, last_key), whose blobs can be reused if the
+    /// content hash matches. Populated from `base_manifest` before chunks are written.
+    base_chunks: HashMap<(HashValue, HashValue), (HashValue, FileHandle)>,
 }
 
 impl StateSnapshotBackupController {
@@ -220,6 +263,8 @@ impl StateSnapshotBackupController {
             client,
             storage,
             concurrent_data_requests: global_opt.concurrent_data_requests,
+            base_manifest: opt.base_manifest,
+            base_chunks: HashMap::new(),
         }
     }
 
@@ -240,8 +285,10 @@ impl StateSnapshotBackupController {
             .create_backup_with_random_suffix(&self.backup_name())
             .await?;
 
+        let boundaries = self.load_base_manifest().await?;
+
         let record_stream = Box::pin(self.record_stream(self.concurrent_data_requests).await?);
-        let chunker = Chunker::new(record_stream, self.max_chunk_size).await?;
+        let chunker = Chunker::new(record_stream, self.max_chunk_size, boundaries).await?;
 
         let start = Instant::now();
         let chunk_stream = futures::stream::try_unfold(chunker, |mut chunker| async {
@@ -386,6 +433,48 @@ impl StateSnapshotBackupController {
             .unwrap()
     }
 
+    /// Remembers the reusable chunks of the base snapshot if any, and returns the last keys of all
+    /// chunks in it.
+    async fn load_base_manifest(&mut self) -> Result<Vec<HashValue>> {
+        let base_manifest = match &self.base_manifest {
+            Some(handle) => handle,
+            None => return Ok(Vec::new()),
+        };
+        let base: StateSnapshotBackup = self.storage.load_json_file(base_manifest).await?;
+        ensure!(
+            base.version <= self.version(),
+            "Base snapshot at version {} is newer than the snapshot to take at version {}.",
+            base.version,
+            self.version(),
+        );
+        info!(
+            base_manifest = base_manifest,
+            base_version = base.version,
+            "Taking incremental state snapshot."
+        );
+
+        let boundaries = base.chunks.iter().map(|c| c.last_key).collect();
+        let encryption_key_id = self.storage.encryption_key_id();
+        if base.encryption_key_id != encryption_key_id {
+            // The blobs of the base can't be referred to by a snapshot encrypted with another key.
+            warn!(
+                base_encryption_key_id = base.encryption_key_id,
+                encryption_key_id = encryption_key_id,
+                "Base snapshot is encrypted with another key, no chunk will be reused."
+            );
+            return Ok(boundaries);
+        }
+        self.base_chunks = base
+            .chunks
+            .into_iter()
+            .filter_map(|c| {
+                c.blobs_hash
+                    .map(|hash| ((c.first_key, c.last_key), (hash, c.blobs)))
+            })
+            .collect();
+        Ok(boundaries)
+    }
+
     async fn get_version_for_epoch_ending(&self, epoch: u64) -> Result<u64> {
         let ledger_info: LedgerInfoWithSignatures = bcs::from_bytes(
             self.client
@@ -416,12 +505,23 @@ impl StateSnapshotBackupController {
             last_key,
         } = chunk;
 
-        let (chunk_handle, mut chunk_file) = self
-            .storage
-            .create_for_write(backup_handle, &Self::chunk_name(first_idx))
-            .await?;
-        chunk_file.write_all(&bytes).await?;
-        chunk_file.shutdown().await?;
+        let blobs_hash = HashValue::sha3_256_of(&bytes);
+        let chunk_handle = match self.base_chunks.get(&(first_key, last_key)) {
+            Some((base_hash, base_handle)) if *base_hash == blobs_hash => {
+                STATE_SNAPSHOT_CHUNKS.inc_with(&["reused"]);
+                base_handle.clone()
+            },
+            _ => {
+                let (chunk_handle, mut chunk_file) = self
+                    .storage
+                    .create_for_write(backup_handle, &Self::chunk_name(first_idx))
+                    .await?;
+                chunk_file.write_all(&bytes).await?;
+                chunk_file.shutdown().await?;
+                STATE_SNAPSHOT_CHUNKS.inc_with(&["written"]);
+                chunk_handle
+            },
+        };
         let (proof_handle, mut proof_file) = self
             .storage
             .create_for_write(backup_handle, &Self::chunk_proof_name(first_idx, last_idx))
@@ -443,6 +543,7 @@ impl StateSnapshotBackupController {
             last_key,
             blobs: chunk_handle,
             proof: proof_handle,
+            blobs_hash: Some(blobs_hash),
         })
     }
 
@@ -468,6 +569,8 @@ impl StateSnapshotBackupController {
             root_hash: txn_info.transaction_info().ensure_state_checkpoint_hash()?,
             chunks,
             proof: proof_handle,
+            base_manifest: self.base_manifest.clone(),
+            encryption_key_id: self.storage.encryption_key_id(),
         };
 
         let (manifest_handle, mut manifest_file) = self
@@ -483,6 +586,7 @@ impl StateSnapshotBackupController {
             self.epoch,
             self.version(),
             manifest_handle.clone(),
+            self.storage.encryption_key_id(),
         );
         self.storage
             .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
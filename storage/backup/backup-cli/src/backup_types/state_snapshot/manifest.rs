@@ -21,7 +21,14 @@ pub struct StateSnapshotChunk {
     pub last_key: HashValue,
     /// Repeated `len(record) + record` where `record` is BCS serialized tuple
     /// `(key, state_value)`
+    /// In an incremental snapshot, this can be a file belonging to an earlier backup, if the
+    /// content of this chunk hasn't changed since then.
     pub blobs: FileHandle,
+    /// SHA3-256 hash of the content of the `blobs` file. Absent in backups taken before
+    /// incremental snapshots were introduced, in which case the chunk can't be shared with later
+    /// incremental snapshots.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blobs_hash: Option<HashValue>,
     /// BCS serialized `SparseMerkleRangeProof` that proves this chunk adds up to the root hash
     /// indicated in the backup (`StateSnapshotBackup::root_hash`).
     pub proof: FileHandle,
@@ -49,4 +56,15 @@ pub struct StateSnapshotBackup {
     /// `EpochStateBackup` recovered prior to this to the DB; Requiring it to be in the same epoch
     /// limits the requirement on such `EpochStateBackup` to no older than the same epoch.
     pub proof: FileHandle,
+    /// Set if this is an incremental snapshot: manifest of the snapshot it was taken against.
+    /// Chunks whose content is the same as in the base point to the blobs of the base, while
+    /// proofs are always rewritten against `root_hash` of this snapshot, so such a snapshot is
+    /// restored and verified exactly like a full one. The base is recorded for bookkeeping only.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_manifest: Option<FileHandle>,
+    /// ID of the key the files of this snapshot are encrypted with, if any. Chunks are only shared
+    /// with a later incremental snapshot encrypted with the same key (or both in plaintext), so
+    /// all the files of a snapshot are encrypted with the key recorded here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key_id: Option<String>,
 }
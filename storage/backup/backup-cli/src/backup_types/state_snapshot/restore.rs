@@ -23,6 +23,7 @@ use crate::{
     },
 };
 use anyhow::{anyhow, ensure, Result};
+use aptos_crypto::HashValue;
 use aptos_db::state_restore::StateSnapshotRestoreMode;
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
@@ -137,6 +138,13 @@ impl StateSnapshotRestoreController {
         if let Some(epoch_history) = self.epoch_history.as_ref() {
             epoch_history.verify_ledger_info(&li)?;
         }
+        if let Some(base_manifest) = manifest.base_manifest.as_ref() {
+            info!(
+                base_manifest = base_manifest,
+                "Restoring incremental state snapshot, chunks shared with earlier snapshots are \
+                verified against this snapshot's root hash."
+            );
+        }
 
         let receiver = Arc::new(Mutex::new(Some(self.run_mode.get_state_restore_receiver(
             self.version,
@@ -188,7 +196,9 @@ impl StateSnapshotRestoreController {
             let storage = storage.clone();
             async move {
                 tokio::spawn(async move {
-                    let blobs = Self::read_state_value(&storage, chunk.blobs.clone()).await?;
+                    let blobs =
+                        Self::read_state_value(&storage, chunk.blobs.clone(), chunk.blobs_hash)
+                            .await?;
                     let proof = storage.load_bcs_file(&chunk.proof).await?;
                     Result::<_>::Ok((chunk_idx, chunk, blobs, proof))
                 })
@@ -255,8 +265,22 @@ impl StateSnapshotRestoreController {
     async fn read_state_value(
         storage: &Arc<dyn BackupStorage>,
         file_handle: FileHandle,
+        expected_hash: Option<HashValue>,
     ) -> Result<Vec<(StateKey, StateValue)>> {
-        let mut file = storage.open_for_read(&file_handle).await?;
+        let bytes = storage.read_all(&file_handle).await?;
+        if let Some(expected_hash) = expected_hash {
+            // The chunk proof is verified anyway when the chunk is added, this is to detect early
+            // if a file shared by several incremental snapshots got corrupted.
+            let hash = HashValue::sha3_256_of(&bytes);
+            ensure!(
+                hash == expected_hash,
+                "State chunk {} hash mismatch. hash: {}, expected: {}",
+                file_handle,
+                hash,
+                expected_hash,
+            );
+        }
+        let mut file = bytes.as_slice();
 
         let mut chunk = vec![];
 
@@ -5,21 +5,29 @@
 use crate::{
     backup_types::state_snapshot::{
         backup::{StateSnapshotBackupController, StateSnapshotBackupOpt},
+        manifest::StateSnapshotBackup,
         restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
     },
-    storage::{local_fs::LocalFs, BackupStorage},
+    storage::{
+        encryption::{EncryptedStorage, EncryptionKey},
+        local_fs::LocalFs,
+        BackupStorage, FileHandle, ShellSafeName,
+    },
     utils::{
         backup_service_client::BackupServiceClient,
+        storage_ext::BackupStorageExt,
         test_utils::{start_local_backup_service, tmp_db_with_random_content},
         ConcurrentDownloadsOpt, GlobalBackupOpt, GlobalRestoreOpt, ReplayConcurrencyLevelOpt,
         RocksdbOpt, TrustedWaypointOpt,
     },
 };
+use aptos_crypto::HashValue;
 use aptos_db::{state_restore::StateSnapshotRestoreMode, AptosDB};
 use aptos_storage_interface::DbReader;
 use aptos_temppath::TempPath;
-use std::{convert::TryInto, sync::Arc};
-use tokio::time::Duration;
+use aptos_types::transaction::Version;
+use std::{convert::TryInto, path::Path, str::FromStr, sync::Arc};
+use tokio::{io::AsyncWriteExt, runtime::Runtime, time::Duration};
 
 #[test]
 fn end_to_end() {
@@ -61,7 +69,10 @@ fn end_to_end() {
     let manifest_handle = rt
         .block_on(
             StateSnapshotBackupController::new(
-                StateSnapshotBackupOpt { epoch },
+                StateSnapshotBackupOpt {
+                    epoch,
+                    base_manifest: None,
+                },
                 GlobalBackupOpt {
                     max_chunk_size: 500,
                     concurrent_data_requests: 2,
@@ -111,3 +122,327 @@ fn end_to_end() {
 
     rt.shutdown_timeout(Duration::from_secs(1));
 }
+
+/// A source DB with its backup service and a backup storage, to take (incremental) state
+/// snapshots of the latest epoch ending version.
+struct IncrementalBackupTest {
+    _src_db_dir: TempPath,
+    backup_dir: TempPath,
+    store: Arc<dyn BackupStorage>,
+    rt: Runtime,
+    client: Arc<BackupServiceClient>,
+    epoch: u64,
+    version: Version,
+}
+
+impl IncrementalBackupTest {
+    fn new() -> Self {
+        let (src_db_dir, src_db, _blocks) = tmp_db_with_random_content();
+        let backup_dir = TempPath::new();
+        backup_dir.create_as_dir().unwrap();
+        let store: Arc<dyn BackupStorage> = Arc::new(LocalFs::new(backup_dir.path().to_path_buf()));
+
+        let epoch = src_db
+            .get_latest_ledger_info()
+            .unwrap()
+            .ledger_info()
+            .next_block_epoch()
+            - 1;
+        let version = src_db
+            .get_epoch_ending_ledger_infos(epoch, epoch + 1)
+            .unwrap()
+            .ledger_info_with_sigs
+            .pop()
+            .unwrap()
+            .ledger_info()
+            .version();
+
+        let (rt, port) = start_local_backup_service(src_db);
+        let client = Arc::new(BackupServiceClient::new(format!(
+            "http://localhost:{}",
+            port
+        )));
+
+        Self {
+            _src_db_dir: src_db_dir,
+            backup_dir,
+            store,
+            rt,
+            client,
+            epoch,
+            version,
+        }
+    }
+
+    fn backup(&self, base_manifest: Option<FileHandle>) -> FileHandle {
+        self.backup_to(Arc::clone(&self.store), base_manifest)
+    }
+
+    fn backup_to(
+        &self,
+        store: Arc<dyn BackupStorage>,
+        base_manifest: Option<FileHandle>,
+    ) -> FileHandle {
+        self.rt
+            .block_on(
+                StateSnapshotBackupController::new(
+                    StateSnapshotBackupOpt {
+                        epoch: self.epoch,
+                        base_manifest,
+                    },
+                    GlobalBackupOpt {
+                        max_chunk_size: 500,
+                        concurrent_data_requests: 2,
+                    },
+                    self.client.clone(),
+                    store,
+                )
+                .run(),
+            )
+            .unwrap()
+    }
+
+    fn load_manifest(&self, manifest_handle: &FileHandle) -> StateSnapshotBackup {
+        self.rt
+            .block_on(self.store.load_json_file(manifest_handle))
+            .unwrap()
+    }
+}
+
+#[test]
+fn incremental() {
+    let test = IncrementalBackupTest::new();
+    let base_manifest_handle = test.backup(None);
+    let manifest_handle = test.backup(Some(base_manifest_handle.clone()));
+
+    // Nothing changed in between, so all chunks are shared with the base.
+    let base_manifest = test.load_manifest(&base_manifest_handle);
+    let manifest = test.load_manifest(&manifest_handle);
+    assert_eq!(manifest.base_manifest, Some(base_manifest_handle));
+    assert_eq!(manifest.root_hash, base_manifest.root_hash);
+    assert!(manifest.chunks.len() > 1);
+    assert_eq!(
+        manifest.chunks.iter().map(|c| &c.blobs).collect::<Vec<_>>(),
+        base_manifest
+            .chunks
+            .iter()
+            .map(|c| &c.blobs)
+            .collect::<Vec<_>>(),
+    );
+    assert!(manifest
+        .chunks
+        .iter()
+        .zip(base_manifest.chunks.iter())
+        .all(|(c, base_c)| c.proof != base_c.proof));
+
+    restore_and_verify(
+        &test.rt,
+        test.store.clone(),
+        manifest_handle,
+        test.version,
+        manifest.root_hash,
+    );
+
+    test.rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn incremental_with_changed_chunks() {
+    let test = IncrementalBackupTest::new();
+    let (rt, store) = (&test.rt, &test.store);
+    let full_manifest_handle = test.backup(None);
+    let full_manifest = test.load_manifest(&full_manifest_handle);
+    assert!(full_manifest.chunks.len() > 1);
+
+    // Fake a base snapshot in which every other chunk has different content, as if the state in
+    // those key ranges changed since the base was taken.
+    let changed = |chunk_idx: usize| chunk_idx % 2 == 0;
+    let base_manifest_handle = rt.block_on(async {
+        let backup_handle = store
+            .create_backup_with_random_suffix("stale_base")
+            .await
+            .unwrap();
+        let mut base_manifest: StateSnapshotBackup =
+            store.load_json_file(&full_manifest_handle).await.unwrap();
+        for (chunk_idx, chunk) in base_manifest.chunks.iter_mut().enumerate() {
+            if changed(chunk_idx) {
+                let bytes = format!("stale chunk {}", chunk_idx).into_bytes();
+                let (blobs, mut file) = store
+                    .create_for_write(
+                        &backup_handle,
+                        &format!("{}.chunk", chunk_idx).try_into().unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                file.write_all(&bytes).await.unwrap();
+                file.shutdown().await.unwrap();
+                chunk.blobs = blobs;
+                chunk.blobs_hash = Some(HashValue::sha3_256_of(&bytes));
+            }
+        }
+        let (handle, mut file) = store
+            .create_for_write(
+                &backup_handle,
+                &ShellSafeName::from_str("state.manifest").unwrap(),
+            )
+            .await
+            .unwrap();
+        file.write_all(&serde_json::to_vec(&base_manifest).unwrap())
+            .await
+            .unwrap();
+        file.shutdown().await.unwrap();
+        handle
+    });
+    let base_manifest = test.load_manifest(&base_manifest_handle);
+
+    let manifest_handle = test.backup(Some(base_manifest_handle.clone()));
+    let manifest = test.load_manifest(&manifest_handle);
+    assert_eq!(manifest.base_manifest, Some(base_manifest_handle));
+    assert_eq!(manifest.chunks.len(), base_manifest.chunks.len());
+
+    // Only the changed chunks are written again, with the same content as in the full snapshot,
+    // the others refer to the files of the base.
+    let backup_handle = Path::new(&manifest_handle).parent().unwrap();
+    for (chunk_idx, ((chunk, base_chunk), full_chunk)) in manifest
+        .chunks
+        .iter()
+        .zip(base_manifest.chunks.iter())
+        .zip(full_manifest.chunks.iter())
+        .enumerate()
+    {
+        assert_eq!(chunk.blobs_hash, full_chunk.blobs_hash);
+        if changed(chunk_idx) {
+            assert_ne!(chunk.blobs, base_chunk.blobs);
+            assert!(Path::new(&chunk.blobs).starts_with(backup_handle));
+            assert_eq!(
+                rt.block_on(store.read_all(&chunk.blobs)).unwrap(),
+                rt.block_on(store.read_all(&full_chunk.blobs)).unwrap(),
+            );
+        } else {
+            assert_eq!(chunk.blobs, base_chunk.blobs);
+        }
+    }
+    assert_eq!(
+        num_written_chunks(&test.backup_dir, backup_handle),
+        (0..manifest.chunks.len())
+            .filter(|idx| changed(*idx))
+            .count()
+    );
+
+    restore_and_verify(
+        rt,
+        store.clone(),
+        manifest_handle,
+        test.version,
+        manifest.root_hash,
+    );
+
+    test.rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+#[test]
+fn incremental_with_another_encryption_key() {
+    let test = IncrementalBackupTest::new();
+    let base_manifest_handle = test.backup(None);
+    let base_manifest = test.load_manifest(&base_manifest_handle);
+    assert_eq!(base_manifest.encryption_key_id, None);
+
+    // The chunks of a plaintext base are not shared with an encrypted snapshot, even though their
+    // content didn't change.
+    let key = EncryptionKey::new([1; 32]);
+    let key_id = key.id().to_string();
+    let encrypted_store: Arc<dyn BackupStorage> =
+        Arc::new(EncryptedStorage::new(test.store.clone(), key));
+    let manifest_handle =
+        test.backup_to(encrypted_store.clone(), Some(base_manifest_handle.clone()));
+    let manifest: StateSnapshotBackup = test
+        .rt
+        .block_on(encrypted_store.load_json_file(&manifest_handle))
+        .unwrap();
+    assert_eq!(manifest.base_manifest, Some(base_manifest_handle));
+    assert_eq!(manifest.encryption_key_id, Some(key_id));
+    assert_eq!(manifest.chunks.len(), base_manifest.chunks.len());
+    for (chunk, base_chunk) in manifest.chunks.iter().zip(base_manifest.chunks.iter()) {
+        assert_eq!(chunk.blobs_hash, base_chunk.blobs_hash);
+        assert_ne!(chunk.blobs, base_chunk.blobs);
+    }
+    assert_eq!(
+        num_written_chunks(
+            &test.backup_dir,
+            Path::new(&manifest_handle).parent().unwrap()
+        ),
+        manifest.chunks.len()
+    );
+
+    restore_and_verify(
+        &test.rt,
+        encrypted_store,
+        manifest_handle,
+        test.version,
+        manifest.root_hash,
+    );
+
+    test.rt.shutdown_timeout(Duration::from_secs(1));
+}
+
+/// Returns the number of chunk files in the backup
+fn num_written_chunks(backup_dir: &TempPath, backup_handle: &Path) -> usize {
+    std::fs::read_dir(backup_dir.path().join(backup_handle))
+        .unwrap()
+        .filter(|entry| {
+            entry
+                .as_ref()
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .ends_with(".chunk")
+        })
+        .count()
+}
+
+fn restore_and_verify(
+    rt: &Runtime,
+    store: Arc<dyn BackupStorage>,
+    manifest_handle: FileHandle,
+    version: Version,
+    root_hash: HashValue,
+) {
+    let tgt_db_dir = TempPath::new();
+    tgt_db_dir.create_as_dir().unwrap();
+
+    rt.block_on(
+        StateSnapshotRestoreController::new(
+            StateSnapshotRestoreOpt {
+                manifest_handle,
+                version,
+                validate_modules: false,
+                restore_mode: StateSnapshotRestoreMode::Default,
+            },
+            GlobalRestoreOpt {
+                dry_run: false,
+                db_dir: Some(tgt_db_dir.path().to_path_buf()),
+                target_version: None, // max
+                trusted_waypoints: TrustedWaypointOpt::default(),
+                rocksdb_opt: RocksdbOpt::default(),
+                concurrent_downloads: ConcurrentDownloadsOpt::default(),
+                replay_concurrency_level: ReplayConcurrencyLevelOpt::default(),
+                enable_state_indices: false,
+            }
+            .try_into()
+            .unwrap(),
+            store,
+            None, /* epoch_history */
+        )
+        .run(),
+    )
+    .unwrap();
+
+    let tgt_db = AptosDB::new_readonly_for_test(&tgt_db_dir);
+    assert_eq!(
+        tgt_db
+            .get_state_snapshot_before(version + 1)
+            .unwrap()
+            .unwrap(),
+        (version, root_hash)
+    );
+}
@@ -93,7 +93,10 @@ fn test_end_to_end_impl(d: TestData) {
     let state_snapshot_manifest = d.state_snapshot_epoch.map(|epoch| {
         rt.block_on(
             StateSnapshotBackupController::new(
-                StateSnapshotBackupOpt { epoch },
+                StateSnapshotBackupOpt {
+                    epoch,
+                    base_manifest: None,
+                },
                 global_backup_opt.clone(),
                 Arc::clone(&client),
                 Arc::clone(&store),
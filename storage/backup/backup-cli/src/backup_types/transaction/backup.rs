@@ -205,8 +205,12 @@ impl TransactionBackupController {
             .await?;
         manifest_file.shutdown().await?;
 
-        let metadata =
-            Metadata::new_transaction_backup(first_version, last_version, manifest_handle.clone());
+        let metadata = Metadata::new_transaction_backup(
+            first_version,
+            last_version,
+            manifest_handle.clone(),
+            self.storage.encryption_key_id(),
+        );
         self.storage
             .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
             .await?;
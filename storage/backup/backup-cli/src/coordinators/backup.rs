@@ -21,7 +21,7 @@ use crate::{
 };
 use anyhow::{anyhow, ensure, Result};
 use aptos_db::backup::backup_handler::DbState;
-use aptos_infallible::duration_since_epoch;
+use aptos_infallible::{duration_since_epoch, Mutex};
 use aptos_logger::prelude::*;
 use aptos_types::transaction::Version;
 use clap::Parser;
@@ -56,6 +56,14 @@ pub struct BackupCoordinatorOpt {
         is already at 19, then snapshot at 15 will be taken instead of at 10 (not at 18)."
     )]
     pub state_snapshot_interval_epochs: usize,
+    #[clap(
+        long,
+        help = "Take state snapshots incrementally: each snapshot only writes the chunks that \
+        changed since the previous snapshot in the backup storage, and refers to the files of the \
+        previous one for the rest. Saves a lot of storage if snapshots are taken frequently, at the \
+        cost of snapshots depending on files of earlier ones."
+    )]
+    pub incremental_state_snapshots: bool,
     // Defaulting to 1M, which converts to a 20 minutes delay of a transaction showing up in a backup,
     // from a 1K TPS chain, and a few minutes replay time.
     #[clap(
@@ -89,6 +97,10 @@ pub struct BackupCoordinator {
     global_opt: GlobalBackupOpt,
     metadata_cache_opt: MetadataCacheOpt,
     state_snapshot_interval_epochs: usize,
+    incremental_state_snapshots: bool,
+    /// Manifest of the latest state snapshot in the backup storage, which the next snapshot is
+    /// based on if `incremental_state_snapshots` is set.
+    latest_state_snapshot_manifest: Mutex<Option<FileHandle>>,
     transaction_batch_size: usize,
     concurrent_downloads: usize,
 }
@@ -107,6 +119,8 @@ impl BackupCoordinator {
             global_opt,
             metadata_cache_opt: opt.metadata_cache_opt,
             state_snapshot_interval_epochs: opt.state_snapshot_interval_epochs,
+            incremental_state_snapshots: opt.incremental_state_snapshots,
+            latest_state_snapshot_manifest: Mutex::new(None),
             transaction_batch_size: opt.transaction_batch_size,
             concurrent_downloads: opt.concurrent_downloads.get(),
         }
@@ -114,13 +128,16 @@ impl BackupCoordinator {
 
     pub async fn run(&self) -> Result<()> {
         // Connect to both the local node and the backup storage.
-        let backup_state = metadata::cache::sync_and_load(
+        let metadata_view = metadata::cache::sync_and_load(
             &self.metadata_cache_opt,
             Arc::clone(&self.storage),
             self.concurrent_downloads,
         )
-        .await?
-        .get_storage_state()?;
+        .await?;
+        let backup_state = metadata_view.get_storage_state()?;
+        *self.latest_state_snapshot_manifest.lock() = metadata_view
+            .select_state_snapshot(Version::MAX)?
+            .map(|snapshot| snapshot.manifest);
 
         // On new DbState retrieved:
         // `watch_db_state` informs `backup_epoch_endings` via channel 1,
@@ -255,14 +272,23 @@ impl BackupCoordinator {
             return Ok(last_snapshot_epoch_in_backup);
         }
 
-        StateSnapshotBackupController::new(
-            StateSnapshotBackupOpt { epoch },
+        let base_manifest = if self.incremental_state_snapshots {
+            self.latest_state_snapshot_manifest.lock().clone()
+        } else {
+            None
+        };
+        let manifest = StateSnapshotBackupController::new(
+            StateSnapshotBackupOpt {
+                epoch,
+                base_manifest,
+            },
             self.global_opt.clone(),
             Arc::clone(&self.client),
             Arc::clone(&self.storage),
         )
         .run()
         .await?;
+        *self.latest_state_snapshot_manifest.lock() = Some(manifest);
 
         Ok(Some(epoch))
     }
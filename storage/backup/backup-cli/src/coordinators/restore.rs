@@ -8,19 +8,21 @@ use crate::{
         state_snapshot::restore::{StateSnapshotRestoreController, StateSnapshotRestoreOpt},
         transaction::restore::TransactionRestoreBatchController,
     },
+    coordinators::restore_target::RestoreTarget,
     metadata,
-    metadata::{cache::MetadataCacheOpt, TransactionBackupMeta},
+    metadata::{cache::MetadataCacheOpt, view::MetadataView, TransactionBackupMeta},
     metrics::restore::{
         COORDINATOR_FAIL_TS, COORDINATOR_START_TS, COORDINATOR_SUCC_TS, COORDINATOR_TARGET_VERSION,
     },
-    storage::BackupStorage,
-    utils::{unix_timestamp_sec, GlobalRestoreOptions},
+    storage::{BackupStorage, FileHandle},
+    utils::{unix_timestamp_sec, GlobalRestoreOptions, RestoreRunMode},
 };
 use anyhow::{anyhow, bail, ensure, Result};
 use aptos_db::state_restore::StateSnapshotRestoreMode;
 use aptos_executor_types::VerifyExecutionMode;
 use aptos_logger::prelude::*;
 use aptos_types::transaction::Version;
+use chrono::{DateTime, Utc};
 use clap::Parser;
 use std::sync::Arc;
 
@@ -42,6 +44,28 @@ pub struct RestoreCoordinatorOpt {
     pub ledger_history_start_version: Option<Version>,
     #[clap(long, help = "Skip restoring epoch ending info, used for debugging.")]
     pub skip_epoch_endings: bool,
+    #[clap(
+        long,
+        conflicts_with_all = &["target_version", "target_epoch"],
+        help = "Instead of --target-version, restore to the last version committed at or before \
+        this time, e.g. 2024-05-01T12:00:00Z. The version is found by the block timestamps in \
+        the transaction backups."
+    )]
+    pub target_time: Option<DateTime<Utc>>,
+    #[clap(
+        long,
+        conflicts_with = "target_version",
+        help = "Instead of --target-version, restore to the last version of this epoch."
+    )]
+    pub target_epoch: Option<u64>,
+}
+
+impl RestoreCoordinatorOpt {
+    fn target(&self) -> Option<RestoreTarget> {
+        self.target_time
+            .map(RestoreTarget::Time)
+            .or(self.target_epoch.map(RestoreTarget::Epoch))
+    }
 }
 
 pub struct RestoreCoordinator {
@@ -51,6 +75,7 @@ pub struct RestoreCoordinator {
     replay_all: bool,
     ledger_history_start_version: Option<Version>,
     skip_epoch_endings: bool,
+    target: Option<RestoreTarget>,
 }
 
 impl RestoreCoordinator {
@@ -62,6 +87,7 @@ impl RestoreCoordinator {
         Self {
             storage,
             global_opt,
+            target: opt.target(),
             metadata_cache_opt: opt.metadata_cache_opt,
             replay_all: opt.replay_all,
             ledger_history_start_version: opt.ledger_history_start_version,
@@ -104,7 +130,7 @@ impl RestoreCoordinator {
     /// b. replay the txn till the target version
     ///
     /// we are support the resume from any point when the restore is interrupted.
-    async fn run_impl(self) -> Result<()> {
+    async fn run_impl(mut self) -> Result<()> {
         // if replay_all is set, we will replay all transactions from the lhs to the target version
         let mut replay_all_mode = false;
         if self.replay_all {
@@ -126,6 +152,11 @@ impl RestoreCoordinator {
         let max_txn_ver = metadata_view
             .max_transaction_version()?
             .ok_or_else(|| anyhow!("No transaction backup found."))?;
+        if let Some(target) = self.target {
+            self.global_opt.target_version = self
+                .resolve_target(target, &metadata_view, max_txn_ver)
+                .await?;
+        }
         let target_version = std::cmp::min(self.global_opt.target_version, max_txn_ver);
         info!(
             "User specified target version: {}, max transaction version: {}, Target version is set to {}",
@@ -212,6 +243,22 @@ impl RestoreCoordinator {
         let transaction_backups =
             metadata_view.select_transaction_backups(txn_start_version, target_version)?;
         let epoch_ending_backups = metadata_view.select_epoch_ending_backups(target_version)?;
+        self.ensure_decryptable(
+            kv_snapshot
+                .iter()
+                .chain(std::iter::once(&tree_snapshot))
+                .map(|s| (&s.manifest, &s.encryption_key_id))
+                .chain(
+                    transaction_backups
+                        .iter()
+                        .map(|t| (&t.manifest, &t.encryption_key_id)),
+                )
+                .chain(
+                    epoch_ending_backups
+                        .iter()
+                        .map(|e| (&e.manifest, &e.encryption_key_id)),
+                ),
+        )?;
         let epoch_handles = epoch_ending_backups
             .iter()
             .filter(|e| e.first_version <= target_version)
@@ -386,6 +433,69 @@ impl RestoreCoordinator {
             .unwrap_or_else(|| self.target_version())
     }
 
+    /// Resolves the target version from the backups, without touching the DB.
+    async fn resolve_target(
+        &self,
+        target: RestoreTarget,
+        metadata_view: &MetadataView,
+        max_txn_ver: Version,
+    ) -> Result<Version> {
+        let epoch_history = EpochHistoryRestoreController::new(
+            metadata_view
+                .select_epoch_ending_backups(Version::MAX)?
+                .into_iter()
+                .map(|backup| backup.manifest)
+                .collect(),
+            GlobalRestoreOptions {
+                target_version: Version::MAX,
+                run_mode: Arc::new(RestoreRunMode::Verify),
+                ..self.global_opt.clone()
+            },
+            Arc::clone(&self.storage),
+        )
+        .run()
+        .await?;
+        let resolved = target
+            .resolve(
+                &self.storage,
+                metadata_view,
+                &epoch_history.epoch_endings,
+                max_txn_ver,
+            )
+            .await?;
+        info!(
+            version = resolved.version,
+            timestamp_usecs = resolved.timestamp_usecs,
+            "Restore target {} resolved to version {}, committed at {}.",
+            target,
+            resolved.version,
+            DateTime::<Utc>::from_timestamp_micros(resolved.timestamp_usecs as i64)
+                .map_or_else(|| resolved.timestamp_usecs.to_string(), |t| t.to_rfc3339()),
+        );
+        Ok(resolved.version)
+    }
+
+    /// Fails early if any of the backups to restore from is encrypted with a key other than the
+    /// supplied one, if any.
+    fn ensure_decryptable<'a>(
+        &self,
+        backups: impl Iterator<Item = (&'a FileHandle, &'a Option<String>)>,
+    ) -> Result<()> {
+        let supplied_key_id = self.storage.encryption_key_id();
+        for (manifest, key_id) in backups {
+            if let Some(key_id) = key_id {
+                ensure!(
+                    supplied_key_id.as_ref() == Some(key_id),
+                    "Backup {} is encrypted with key {}, but the supplied key is {:?}.",
+                    manifest,
+                    key_id,
+                    supplied_key_id,
+                );
+            }
+        }
+        Ok(())
+    }
+
     #[allow(dead_code)]
     fn get_actual_target_version(
         &self,
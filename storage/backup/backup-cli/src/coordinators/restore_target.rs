@@ -0,0 +1,300 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Resolves a restore target given as a point in time or an epoch into a version, using nothing
+//! but the backups.
+
+use crate::{
+    backup_types::transaction::manifest::TransactionBackup,
+    metadata::view::MetadataView,
+    storage::BackupStorage,
+    utils::{read_record_bytes::ReadRecordBytes, storage_ext::BackupStorageExt},
+};
+use anyhow::{bail, ensure, format_err, Result};
+use aptos_types::{
+    contract_event::ContractEvent,
+    ledger_info::LedgerInfo,
+    transaction::{Transaction, TransactionInfo, Version},
+    write_set::WriteSet,
+};
+use chrono::{DateTime, Utc};
+use std::{fmt, sync::Arc};
+use tokio::io::BufReader;
+
+#[derive(Clone, Copy, Debug)]
+pub enum RestoreTarget {
+    /// The last version committed at or before this time.
+    Time(DateTime<Utc>),
+    /// The last version of this epoch.
+    Epoch(u64),
+}
+
+impl fmt::Display for RestoreTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RestoreTarget::Time(time) => write!(f, "time {}", time.to_rfc3339()),
+            RestoreTarget::Epoch(epoch) => write!(f, "end of epoch {}", epoch),
+        }
+    }
+}
+
+/// A version and when it was committed.
+#[derive(Debug, Eq, PartialEq)]
+pub struct ResolvedTarget {
+    pub version: Version,
+    pub timestamp_usecs: u64,
+}
+
+impl RestoreTarget {
+    /// `epoch_endings` is expected to be a verified epoch history since genesis, and
+    /// `max_txn_version` the latest version in the transaction backups.
+    pub async fn resolve(
+        &self,
+        storage: &Arc<dyn BackupStorage>,
+        metadata_view: &MetadataView,
+        epoch_endings: &[LedgerInfo],
+        max_txn_version: Version,
+    ) -> Result<ResolvedTarget> {
+        match *self {
+            RestoreTarget::Epoch(epoch) => resolve_epoch(epoch_endings, epoch),
+            RestoreTarget::Time(time) => {
+                ensure!(time.timestamp() >= 0, "Time before 1970 is not supported.");
+                let timestamp_usecs = time.timestamp_micros() as u64;
+                let (first_version, last_version) =
+                    time_search_range(epoch_endings, timestamp_usecs, max_txn_version);
+                let mut txns = Vec::new();
+                let mut past_target = false;
+                if first_version <= last_version {
+                    for backup in
+                        metadata_view.select_transaction_backups(first_version, last_version)?
+                    {
+                        let manifest: TransactionBackup =
+                            storage.load_json_file(&backup.manifest).await?;
+                        for chunk in manifest.chunks {
+                            if chunk.last_version < first_version
+                                || chunk.first_version > last_version
+                            {
+                                continue;
+                            }
+                            let mut file =
+                                BufReader::new(storage.open_for_read(&chunk.transactions).await?);
+                            let mut version = chunk.first_version;
+                            while let Some(record_bytes) = file.read_record_bytes().await? {
+                                let (txn, _, _, _): (
+                                    Transaction,
+                                    TransactionInfo,
+                                    Vec<ContractEvent>,
+                                    WriteSet,
+                                ) = bcs::from_bytes(&record_bytes)?;
+                                if (first_version..=last_version).contains(&version) {
+                                    let block_timestamp = block_timestamp_usecs(&txn);
+                                    past_target |=
+                                        block_timestamp.map_or(false, |ts| ts > timestamp_usecs);
+                                    txns.push((version, block_timestamp));
+                                }
+                                version += 1;
+                            }
+                        }
+                        // No need to read on after the first block committed after the target
+                        // time.
+                        if past_target {
+                            break;
+                        }
+                    }
+                }
+                resolve_time(
+                    epoch_endings,
+                    timestamp_usecs,
+                    first_version,
+                    txns.into_iter(),
+                )
+            },
+        }
+    }
+}
+
+fn resolve_epoch(epoch_endings: &[LedgerInfo], epoch: u64) -> Result<ResolvedTarget> {
+    match epoch_endings.get(epoch as usize) {
+        Some(li) => Ok(ResolvedTarget {
+            version: li.version(),
+            timestamp_usecs: li.timestamp_usecs(),
+        }),
+        None => bail!(
+            "Epoch {} has not ended as far as the backups go, the latest ended epoch is {:?}.",
+            epoch,
+            epoch_endings.last().map(|li| li.epoch()),
+        ),
+    }
+}
+
+/// Versions committed at the target time are within the epoch in which the target time falls,
+/// given each epoch ending LedgerInfo carries the timestamp of the last block in the epoch.
+fn time_search_range(
+    epoch_endings: &[LedgerInfo],
+    timestamp_usecs: u64,
+    max_txn_version: Version,
+) -> (Version, Version) {
+    let idx = epoch_endings.partition_point(|li| li.timestamp_usecs() <= timestamp_usecs);
+    let first_version = match idx {
+        0 => 0,
+        idx => epoch_endings[idx - 1].version() + 1,
+    };
+    let last_version = epoch_endings
+        .get(idx)
+        .map_or(max_txn_version, |li| li.version());
+    (first_version, last_version)
+}
+
+/// The block timestamp, if the transaction starts a block.
+fn block_timestamp_usecs(txn: &Transaction) -> Option<u64> {
+    match txn {
+        Transaction::GenesisTransaction(_) => Some(0),
+        Transaction::BlockMetadata(block_metadata) => Some(block_metadata.timestamp_usecs()),
+        Transaction::BlockMetadataExt(block_metadata) => Some(block_metadata.timestamp_usecs()),
+        _ => None,
+    }
+}
+
+/// Finds the last version before the first block committed after the target time, given the
+/// block timestamps of transactions in the search range, in order.
+fn resolve_time(
+    epoch_endings: &[LedgerInfo],
+    timestamp_usecs: u64,
+    first_version: Version,
+    txns: impl Iterator<Item = (Version, Option<u64>)>,
+) -> Result<ResolvedTarget> {
+    let mut resolved = match first_version.checked_sub(1) {
+        // The previous epoch ended at or before the target time.
+        Some(version) => {
+            let li = epoch_endings
+                .iter()
+                .find(|li| li.version() == version)
+                .expect("First version in range follows an epoch ending.");
+            Some(ResolvedTarget {
+                version,
+                timestamp_usecs: li.timestamp_usecs(),
+            })
+        },
+        None => None,
+    };
+    let mut expected_version = first_version;
+    for (version, block_timestamp) in txns {
+        ensure!(
+            version == expected_version,
+            "Transaction backups not continuous, expecting version {}, got {}.",
+            expected_version,
+            version,
+        );
+        expected_version += 1;
+        match block_timestamp {
+            Some(ts) if ts > timestamp_usecs => break,
+            Some(ts) => {
+                resolved = Some(ResolvedTarget {
+                    version,
+                    timestamp_usecs: ts,
+                })
+            },
+            None => {
+                if let Some(resolved) = resolved.as_mut() {
+                    resolved.version = version;
+                }
+            },
+        }
+    }
+    resolved.ok_or_else(|| {
+        format_err!(
+            "Nothing committed at or before timestamp {} (usecs).",
+            timestamp_usecs
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_types::{block_info::BlockInfo, epoch_state::EpochState};
+
+    fn epoch_ending(epoch: u64, version: Version, timestamp_usecs: u64) -> LedgerInfo {
+        LedgerInfo::new(
+            BlockInfo::new(
+                epoch,
+                0,
+                HashValue::zero(),
+                HashValue::zero(),
+                version,
+                timestamp_usecs,
+                Some(EpochState::empty()),
+            ),
+            HashValue::zero(),
+        )
+    }
+
+    #[test]
+    fn test_resolve_epoch() {
+        let epoch_endings = vec![epoch_ending(0, 0, 0), epoch_ending(1, 99, 1000)];
+
+        assert_eq!(resolve_epoch(&epoch_endings, 1).unwrap(), ResolvedTarget {
+            version: 99,
+            timestamp_usecs: 1000,
+        });
+        assert!(resolve_epoch(&epoch_endings, 2).is_err());
+    }
+
+    #[test]
+    fn test_time_search_range() {
+        let epoch_endings = vec![
+            epoch_ending(0, 0, 0),
+            epoch_ending(1, 99, 1000),
+            epoch_ending(2, 199, 2000),
+        ];
+
+        assert_eq!(time_search_range(&epoch_endings, 500, 300), (1, 99));
+        assert_eq!(time_search_range(&epoch_endings, 1000, 300), (100, 199));
+        assert_eq!(time_search_range(&epoch_endings, 1999, 300), (100, 199));
+        assert_eq!(time_search_range(&epoch_endings, 5000, 300), (200, 300));
+    }
+
+    #[test]
+    fn test_resolve_time() {
+        let epoch_endings = vec![epoch_ending(0, 0, 0), epoch_ending(1, 99, 1000)];
+        // Blocks at version 100 (time 1100), 103 (time 1200) and 105 (time 1300).
+        let txns = || {
+            (100..=106).map(|version| {
+                let block_timestamp = match version {
+                    100 => Some(1100),
+                    103 => Some(1200),
+                    105 => Some(1300),
+                    _ => None,
+                };
+                (version, block_timestamp)
+            })
+        };
+        let resolve = |timestamp_usecs| {
+            resolve_time(&epoch_endings, timestamp_usecs, 100, txns())
+                .unwrap()
+                .version
+        };
+
+        assert_eq!(resolve(1050), 99);
+        assert_eq!(resolve(1100), 102);
+        assert_eq!(resolve(1250), 104);
+        assert_eq!(resolve(1300), 106);
+        assert_eq!(
+            resolve_time(&epoch_endings, 1250, 100, txns()).unwrap(),
+            ResolvedTarget {
+                version: 104,
+                timestamp_usecs: 1200,
+            }
+        );
+
+        // gap in transactions
+        assert!(resolve_time(
+            &epoch_endings,
+            1250,
+            100,
+            txns().filter(|(version, _)| *version != 101)
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,676 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backup_types::{
+        epoch_ending::manifest::EpochEndingBackup, state_snapshot::manifest::StateSnapshotBackup,
+        transaction::manifest::TransactionBackup,
+    },
+    metadata::{
+        cache::{sync_and_load_by_file, MetadataCacheOpt},
+        view::MetadataView,
+        CompactionTimestampsMeta, EpochEndingBackupMeta, Metadata, StateSnapshotBackupMeta,
+        TransactionBackupMeta,
+    },
+    storage::{BackupStorage, FileHandle, FileHandleRef, ShellSafeName},
+    utils::{read_record_bytes::ReadRecordBytes, storage_ext::BackupStorageExt},
+};
+use anyhow::{ensure, Result};
+use aptos_infallible::duration_since_epoch;
+use aptos_logger::prelude::*;
+use aptos_types::{ledger_info::LedgerInfoWithSignatures, transaction::Version};
+use clap::Parser;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet, HashSet},
+    fmt,
+    sync::Arc,
+};
+
+#[derive(Clone, Parser)]
+pub struct RetentionPolicyOpt {
+    #[clap(
+        long,
+        help = "Keep what's needed to restore to any version committed in this many seconds \
+        until now, i.e. the transaction backups since then and the latest state snapshot before \
+        them to start replaying from."
+    )]
+    pub keep_transactions_for_secs: u64,
+    #[clap(
+        long,
+        help = "Keep one state snapshot for every --state-snapshot-interval-secs for this many \
+        seconds until now, together with the transaction backup its version is in, so each of \
+        them can be restored to. The latest state snapshot is always kept."
+    )]
+    pub keep_state_snapshots_for_secs: u64,
+    #[clap(
+        long,
+        default_value_t = 604800,
+        help = "See --keep-state-snapshots-for-secs. [Defaults to a week]"
+    )]
+    pub state_snapshot_interval_secs: u64,
+}
+
+/// Backups, grouped by type.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Backups {
+    pub epoch_endings: Vec<EpochEndingBackupMeta>,
+    pub state_snapshots: Vec<StateSnapshotBackupMeta>,
+    pub transactions: Vec<TransactionBackupMeta>,
+}
+
+impl Backups {
+    fn contains(&self, metadata: &Metadata) -> bool {
+        match metadata {
+            Metadata::EpochEndingBackup(e) => self.epoch_endings.contains(e),
+            Metadata::StateSnapshotBackup(s) => self.state_snapshots.contains(s),
+            Metadata::TransactionBackup(t) => self.transactions.contains(t),
+            Metadata::Identity(_) | Metadata::CompactionTimestamps(_) => false,
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.epoch_endings.is_empty()
+            && self.state_snapshots.is_empty()
+            && self.transactions.is_empty()
+    }
+}
+
+/// The last version and the timestamp (in seconds) of each epoch, indexed by epoch.
+#[derive(Debug, Default)]
+pub struct EpochEndingTimes(Vec<(Version, u64)>);
+
+impl EpochEndingTimes {
+    /// Approximates the commit time of `version` with the time its epoch ended, which is good
+    /// enough for retention periods much longer than an epoch. None if the epoch is not known to
+    /// have ended yet.
+    fn time_of(&self, version: Version) -> Option<u64> {
+        let idx = self.0.partition_point(|(v, _)| *v < version);
+        self.0.get(idx).map(|(_, ts)| *ts)
+    }
+
+    /// The first version of the first epoch ended at or after `time`. Versions before it are
+    /// committed before `time`.
+    fn first_version_since(&self, time: u64) -> Version {
+        match self.0.partition_point(|(_, ts)| *ts < time) {
+            0 => 0,
+            idx => self.0[idx - 1].0 + 1,
+        }
+    }
+}
+
+/// Which backups to keep and which to remove.
+#[derive(Debug, Default)]
+pub struct RetentionPlan {
+    pub retained: Backups,
+    pub removed: Backups,
+    /// Files referred to by the removed backups only.
+    pub files_to_delete: Vec<FileHandle>,
+}
+
+impl RetentionPlan {
+    pub fn new(
+        policy: &RetentionPolicyOpt,
+        view: &MetadataView,
+        epoch_ending_times: &EpochEndingTimes,
+        now_secs: u64,
+    ) -> Result<Self> {
+        ensure!(
+            policy.state_snapshot_interval_secs > 0,
+            "State snapshot interval can't be 0."
+        );
+        let mut plan = Self::default();
+
+        // Restoring to any version needs the epoch history since genesis (the epoch ending
+        // backups are tiny anyway), so only ones covered by others are removed.
+        (plan.retained.epoch_endings, plan.removed.epoch_endings) = split_continuous(
+            view.all_epoch_ending_backups(),
+            |e| (e.first_epoch, e.last_epoch),
+            "Epoch ending",
+        )?;
+
+        let mut snapshots = view.all_state_snapshots().to_vec();
+        snapshots.sort_by_key(|s| s.version);
+        let mut retained_snapshots = BTreeSet::new();
+        // The latest, to start the next incremental snapshot from and to restore to the latest
+        // version quickly.
+        if !snapshots.is_empty() {
+            retained_snapshots.insert(snapshots.len() - 1);
+        }
+        // The one to replay the retained transactions from.
+        let window_start = epoch_ending_times
+            .first_version_since(now_secs.saturating_sub(policy.keep_transactions_for_secs));
+        let replay_start = snapshots.iter().rposition(|s| s.version <= window_start);
+        if let Some(idx) = replay_start {
+            retained_snapshots.insert(idx);
+        }
+        // Without a state snapshot to start from, transactions are replayed since genesis.
+        let replay_start_version = replay_start.map_or(0, |idx| snapshots[idx].version);
+        // One for each interval.
+        let snapshots_since = now_secs.saturating_sub(policy.keep_state_snapshots_for_secs);
+        let mut intervals = HashSet::new();
+        for (idx, snapshot) in snapshots.iter().enumerate() {
+            let time = epoch_ending_times
+                .time_of(snapshot.version)
+                .unwrap_or(now_secs);
+            if time >= snapshots_since
+                && intervals.insert(time / policy.state_snapshot_interval_secs)
+            {
+                retained_snapshots.insert(idx);
+            }
+        }
+        for (idx, snapshot) in snapshots.into_iter().enumerate() {
+            if retained_snapshots.contains(&idx) {
+                plan.retained.state_snapshots.push(snapshot);
+            } else {
+                plan.removed.state_snapshots.push(snapshot);
+            }
+        }
+
+        let (transactions, redundant_transactions) = split_continuous(
+            view.all_transaction_backups(),
+            |t| (t.first_version, t.last_version),
+            "Transaction",
+        )?;
+        plan.removed.transactions = redundant_transactions;
+        for backup in transactions {
+            let needed = backup.last_version >= replay_start_version
+                || plan.retained.state_snapshots.iter().any(|s| {
+                    backup.first_version <= s.version && s.version <= backup.last_version
+                });
+            if needed {
+                plan.retained.transactions.push(backup);
+            } else {
+                plan.removed.transactions.push(backup);
+            }
+        }
+
+        Ok(plan)
+    }
+}
+
+impl fmt::Display for RetentionPlan {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Backups to remove:")?;
+        for e in &self.removed.epoch_endings {
+            writeln!(
+                f,
+                "  epoch ending, epochs [{}, {}], manifest: {}",
+                e.first_epoch, e.last_epoch, e.manifest
+            )?;
+        }
+        for s in &self.removed.state_snapshots {
+            writeln!(
+                f,
+                "  state snapshot, epoch {}, version {}, manifest: {}",
+                s.epoch, s.version, s.manifest
+            )?;
+        }
+        for t in &self.removed.transactions {
+            writeln!(
+                f,
+                "  transaction, versions [{}, {}], manifest: {}",
+                t.first_version, t.last_version, t.manifest
+            )?;
+        }
+        writeln!(f, "Files to delete: {}", self.files_to_delete.len())?;
+        writeln!(
+            f,
+            "Backups retained: {} epoch ending, {} state snapshot (at versions {:?}), {} \
+            transaction.",
+            self.retained.epoch_endings.len(),
+            self.retained.state_snapshots.len(),
+            self.retained
+                .state_snapshots
+                .iter()
+                .map(|s| s.version)
+                .collect::<Vec<_>>(),
+            self.retained.transactions.len(),
+        )
+    }
+}
+
+#[derive(Clone, Copy)]
+enum BackupType {
+    EpochEnding,
+    StateSnapshot,
+    Transaction,
+}
+
+/// Applies a `RetentionPolicyOpt` to the backups in a storage.
+///
+/// Entries of the removed backups are taken out of the metadata files first, so nothing picks
+/// up a backup which is being deleted. Files left behind by an interrupted run are then not
+/// referred to by anything, costing only the space.
+pub struct RetentionCoordinator {
+    storage: Arc<dyn BackupStorage>,
+    metadata_cache_opt: MetadataCacheOpt,
+    policy: RetentionPolicyOpt,
+    concurrent_downloads: usize,
+    dry_run: bool,
+}
+
+impl RetentionCoordinator {
+    pub fn new(
+        storage: Arc<dyn BackupStorage>,
+        metadata_cache_opt: MetadataCacheOpt,
+        policy: RetentionPolicyOpt,
+        concurrent_downloads: usize,
+        dry_run: bool,
+    ) -> Self {
+        Self {
+            storage,
+            metadata_cache_opt,
+            policy,
+            concurrent_downloads,
+            dry_run,
+        }
+    }
+
+    /// Returns the plan, which is carried out unless it's a dry run.
+    pub async fn run(self) -> Result<RetentionPlan> {
+        info!(dry_run = self.dry_run, "Backup retention started.");
+        let (metadata_by_file, file_handles) = sync_and_load_by_file(
+            &self.metadata_cache_opt,
+            Arc::clone(&self.storage),
+            self.concurrent_downloads,
+        )
+        .await?;
+        let view = MetadataView::new(
+            metadata_by_file
+                .iter()
+                .flat_map(|(_file_handle, metadata_vec)| metadata_vec.iter().cloned())
+                .collect(),
+            file_handles,
+        );
+
+        let epoch_ending_times = self
+            .load_epoch_ending_times(view.all_epoch_ending_backups())
+            .await?;
+        let mut plan = RetentionPlan::new(
+            &self.policy,
+            &view,
+            &epoch_ending_times,
+            duration_since_epoch().as_secs(),
+        )?;
+        plan.files_to_delete = self.find_files_to_delete(&plan).await?;
+        info!(
+            epoch_ending_backups = plan.removed.epoch_endings.len(),
+            state_snapshots = plan.removed.state_snapshots.len(),
+            transaction_backups = plan.removed.transactions.len(),
+            files = plan.files_to_delete.len(),
+            "Backups to remove decided."
+        );
+        if self.dry_run || plan.removed.is_empty() {
+            return Ok(plan);
+        }
+
+        self.remove_metadata(&view, metadata_by_file, &plan.removed)
+            .await?;
+        stream::iter(&plan.files_to_delete)
+            .map(|file_handle| self.storage.delete_file(file_handle))
+            .buffer_unordered(self.concurrent_downloads)
+            .try_collect::<()>()
+            .await?;
+        info!("Backup retention succeeded.");
+
+        Ok(plan)
+    }
+
+    async fn load_epoch_ending_times(
+        &self,
+        backups: &[EpochEndingBackupMeta],
+    ) -> Result<EpochEndingTimes> {
+        let storage = &self.storage;
+        let ledger_infos: Vec<Vec<LedgerInfoWithSignatures>> = stream::iter(backups)
+            .map(|backup| async move {
+                let manifest: EpochEndingBackup = storage.load_json_file(&backup.manifest).await?;
+                let mut ledger_infos = Vec::new();
+                for chunk in &manifest.chunks {
+                    let mut file = storage.open_for_read(&chunk.ledger_infos).await?;
+                    while let Some(record_bytes) = file.read_record_bytes().await? {
+                        ledger_infos.push(bcs::from_bytes(&record_bytes)?);
+                    }
+                }
+                Result::<_>::Ok(ledger_infos)
+            })
+            .buffered(self.concurrent_downloads)
+            .try_collect()
+            .await?;
+
+        let times: BTreeMap<u64, (Version, u64)> = ledger_infos
+            .iter()
+            .flatten()
+            .map(|li| {
+                let li = li.ledger_info();
+                (li.epoch(), (li.version(), li.timestamp_usecs() / 1_000_000))
+            })
+            .collect();
+        Ok(EpochEndingTimes(times.into_values().collect()))
+    }
+
+    async fn find_files_to_delete(&self, plan: &RetentionPlan) -> Result<Vec<FileHandle>> {
+        let removed = &plan.removed;
+        let mut to_delete = self
+            .files_in(
+                removed
+                    .epoch_endings
+                    .iter()
+                    .map(|e| (BackupType::EpochEnding, &e.manifest))
+                    .chain(
+                        removed
+                            .state_snapshots
+                            .iter()
+                            .map(|s| (BackupType::StateSnapshot, &s.manifest)),
+                    )
+                    .chain(
+                        removed
+                            .transactions
+                            .iter()
+                            .map(|t| (BackupType::Transaction, &t.manifest)),
+                    ),
+            )
+            .await?;
+        // Chunks of an incremental state snapshot can be those of older ones.
+        let retained = self
+            .files_in(
+                plan.retained
+                    .state_snapshots
+                    .iter()
+                    .map(|s| (BackupType::StateSnapshot, &s.manifest)),
+            )
+            .await?;
+        to_delete.retain(|file_handle| !retained.contains(file_handle));
+
+        Ok(to_delete.into_iter().collect())
+    }
+
+    /// All files of the backups, including the manifests.
+    async fn files_in<'a>(
+        &self,
+        manifests: impl Iterator<Item = (BackupType, &'a FileHandle)>,
+    ) -> Result<BTreeSet<FileHandle>> {
+        stream::iter(manifests)
+            .map(|(backup_type, manifest)| self.files_in_backup(backup_type, manifest))
+            .buffered(self.concurrent_downloads)
+            .try_fold(BTreeSet::new(), |mut all, files| async move {
+                all.extend(files);
+                Ok(all)
+            })
+            .await
+    }
+
+    async fn files_in_backup(
+        &self,
+        backup_type: BackupType,
+        manifest: &FileHandleRef,
+    ) -> Result<Vec<FileHandle>> {
+        let mut files: Vec<FileHandle> = match backup_type {
+            BackupType::EpochEnding => {
+                let manifest: EpochEndingBackup = self.storage.load_json_file(manifest).await?;
+                manifest
+                    .chunks
+                    .into_iter()
+                    .map(|chunk| chunk.ledger_infos)
+                    .collect()
+            },
+            BackupType::StateSnapshot => {
+                let manifest: StateSnapshotBackup = self.storage.load_json_file(manifest).await?;
+                std::iter::once(manifest.proof)
+                    .chain(
+                        manifest
+                            .chunks
+                            .into_iter()
+                            .flat_map(|chunk| [chunk.blobs, chunk.proof]),
+                    )
+                    .collect()
+            },
+            BackupType::Transaction => {
+                let manifest: TransactionBackup = self.storage.load_json_file(manifest).await?;
+                manifest
+                    .chunks
+                    .into_iter()
+                    .flat_map(|chunk| [chunk.transactions, chunk.proof])
+                    .collect()
+            },
+        };
+        files.push(manifest.to_string());
+        Ok(files)
+    }
+
+    /// Rewrites the metadata files with entries of removed backups, without those entries.
+    async fn remove_metadata(
+        &self,
+        view: &MetadataView,
+        metadata_by_file: Vec<(FileHandle, Vec<Metadata>)>,
+        removed: &Backups,
+    ) -> Result<()> {
+        let now = duration_since_epoch().as_secs();
+        let mut moved = HashSet::new();
+        for (idx, (file_handle, metadata_vec)) in metadata_by_file
+            .into_iter()
+            .filter(|(_, metadata_vec)| metadata_vec.iter().any(|m| removed.contains(m)))
+            .enumerate()
+        {
+            let remaining = metadata_vec
+                .iter()
+                .filter(|m| !removed.contains(m))
+                .map(Metadata::to_text_line)
+                .collect::<Result<Vec<_>>>()?;
+            if !remaining.is_empty() {
+                let name: ShellSafeName = format!("retained_{}_{}.meta", now, idx).parse()?;
+                let new_file = self.storage.save_metadata_lines(&name, &remaining).await?;
+                info!(
+                    file_handle = file_handle,
+                    new_file_handle = new_file,
+                    "Metadata file rewritten."
+                );
+            }
+            self.storage.backup_metadata_file(&file_handle).await?;
+            moved.insert(file_handle);
+        }
+
+        // Or the compactor tries to move them again.
+        if let Some(mut compaction_meta) = view.select_latest_compaction_timestamps() {
+            compaction_meta
+                .compaction_timestamps
+                .retain(|file_handle, _| !moved.contains(file_handle));
+            let metadata = Metadata::new_compaction_timestamps(CompactionTimestampsMeta::new(
+                compaction_meta.compaction_timestamps,
+                now,
+            ));
+            self.storage
+                .save_metadata_line(&metadata.name(), &metadata.to_text_line()?)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Picks a continuous chain from `backups`, preferring longer ranges, returning those in it and
+/// those whose ranges are covered by it.
+fn split_continuous<T: Clone>(
+    backups: &[T],
+    range: impl Fn(&T) -> (u64, u64),
+    name: &str,
+) -> Result<(Vec<T>, Vec<T>)> {
+    let mut backups = backups.to_vec();
+    backups.sort_by_key(|b| {
+        let (first, last) = range(b);
+        (first, Reverse(last))
+    });
+
+    let mut chain: Vec<T> = Vec::new();
+    let mut redundant = Vec::new();
+    for backup in backups {
+        let (first, last) = range(&backup);
+        match chain.last().map(|b| range(b).1 + 1) {
+            Some(next) if last < next => redundant.push(backup),
+            Some(next) => {
+                ensure!(
+                    first == next,
+                    "{} backup ranges not continuous, expecting {}, got {}. Fix it before \
+                    applying retention.",
+                    name,
+                    next,
+                    first,
+                );
+                chain.push(backup);
+            },
+            None => chain.push(backup),
+        }
+    }
+    Ok((chain, redundant))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn epoch_ending(first_epoch: u64, last_epoch: u64) -> Metadata {
+        Metadata::new_epoch_ending_backup(
+            first_epoch,
+            last_epoch,
+            0,
+            0,
+            format!("epoch_ending_{}-{}", first_epoch, last_epoch),
+            None,
+        )
+    }
+
+    fn state_snapshot(version: Version) -> Metadata {
+        Metadata::new_state_snapshot_backup(
+            version / 100,
+            version,
+            format!("state_snapshot_{}", version),
+            None,
+        )
+    }
+
+    fn transaction(first_version: Version, last_version: Version) -> Metadata {
+        Metadata::new_transaction_backup(
+            first_version,
+            last_version,
+            format!("transaction_{}-{}", first_version, last_version),
+            None,
+        )
+    }
+
+    fn versions<T>(backups: &[T], version: impl Fn(&T) -> Version) -> Vec<Version> {
+        backups.iter().map(version).collect()
+    }
+
+    #[test]
+    fn test_retention_plan() {
+        // Epoch N ends at version N * 100 + 99, at time (N + 1) * 1000.
+        let times = EpochEndingTimes((0..4).map(|n| (n * 100 + 99, (n + 1) * 1000)).collect());
+        let view = MetadataView::new(
+            vec![
+                epoch_ending(0, 1),
+                epoch_ending(1, 1),
+                epoch_ending(2, 3),
+                state_snapshot(50),
+                state_snapshot(150),
+                state_snapshot(250),
+                state_snapshot(350),
+                transaction(0, 99),
+                transaction(100, 149),
+                transaction(100, 199),
+                transaction(200, 299),
+                transaction(300, 399),
+            ],
+            vec![],
+        );
+        let policy = RetentionPolicyOpt {
+            keep_transactions_for_secs: 1600,
+            keep_state_snapshots_for_secs: 2000,
+            state_snapshot_interval_secs: 2000,
+        };
+
+        let plan = RetentionPlan::new(&policy, &view, &times, 4500).unwrap();
+        assert_eq!(
+            plan.retained
+                .epoch_endings
+                .iter()
+                .map(|e| (e.first_epoch, e.last_epoch))
+                .collect::<Vec<_>>(),
+            vec![(0, 1), (2, 3)]
+        );
+        assert_eq!(
+            plan.removed
+                .epoch_endings
+                .iter()
+                .map(|e| (e.first_epoch, e.last_epoch))
+                .collect::<Vec<_>>(),
+            vec![(1, 1)]
+        );
+        // 150 to replay the last 1600 seconds from, 250 and 350 one per interval.
+        assert_eq!(
+            versions(&plan.retained.state_snapshots, |s| s.version),
+            vec![150, 250, 350]
+        );
+        assert_eq!(
+            versions(&plan.removed.state_snapshots, |s| s.version),
+            vec![50]
+        );
+        assert_eq!(
+            versions(&plan.retained.transactions, |t| t.first_version),
+            vec![100, 200, 300]
+        );
+        assert_eq!(
+            versions(&plan.removed.transactions, |t| t.last_version),
+            vec![149, 99]
+        );
+
+        // Keeping all state snapshots keeps the transactions they are in.
+        let policy = RetentionPolicyOpt {
+            keep_state_snapshots_for_secs: 4500,
+            state_snapshot_interval_secs: 1000,
+            ..policy
+        };
+        let plan = RetentionPlan::new(&policy, &view, &times, 4500).unwrap();
+        assert!(plan.removed.state_snapshots.is_empty());
+        assert_eq!(
+            versions(&plan.retained.transactions, |t| t.first_version),
+            vec![0, 100, 200, 300]
+        );
+    }
+
+    #[test]
+    fn test_retention_plan_nothing_old() {
+        let times = EpochEndingTimes(vec![(99, 1000)]);
+        let view = MetadataView::new(
+            vec![
+                epoch_ending(0, 0),
+                state_snapshot(50),
+                transaction(0, 99),
+                transaction(100, 199),
+            ],
+            vec![],
+        );
+        let policy = RetentionPolicyOpt {
+            keep_transactions_for_secs: 1000,
+            keep_state_snapshots_for_secs: 1000,
+            state_snapshot_interval_secs: 1000,
+        };
+
+        let plan = RetentionPlan::new(&policy, &view, &times, 1500).unwrap();
+        assert!(plan.removed.is_empty());
+    }
+
+    #[test]
+    fn test_split_continuous() {
+        let range = |r: &(u64, u64)| *r;
+
+        let (chain, redundant) =
+            split_continuous(&[(10, 19), (0, 9), (0, 4), (20, 29)], range, "Test").unwrap();
+        assert_eq!(chain, vec![(0, 9), (10, 19), (20, 29)]);
+        assert_eq!(redundant, vec![(0, 4)]);
+
+        // gap
+        assert!(split_continuous(&[(0, 9), (11, 19)], range, "Test").is_err());
+        // partial overlap
+        assert!(split_continuous(&[(0, 9), (5, 19)], range, "Test").is_err());
+    }
+}
@@ -93,6 +93,24 @@ pub async fn sync_and_load(
     storage: Arc<dyn BackupStorage>,
     concurrent_downloads: usize,
 ) -> Result<MetadataView> {
+    let (metadata_by_file, remote_file_handles) =
+        sync_and_load_by_file(opt, storage, concurrent_downloads).await?;
+    Ok(MetadataView::new(
+        metadata_by_file
+            .into_iter()
+            .flat_map(|(_file_handle, metadata_vec)| metadata_vec)
+            .collect(),
+        remote_file_handles,
+    ))
+}
+
+/// Like `sync_and_load`, but keeps track of which metadata file each entry comes from. Also
+/// returns all file handles listed from the remote storage.
+pub(crate) async fn sync_and_load_by_file(
+    opt: &MetadataCacheOpt,
+    storage: Arc<dyn BackupStorage>,
+    concurrent_downloads: usize,
+) -> Result<(Vec<(FileHandle, Vec<Metadata>)>, Vec<FileHandle>)> {
     let timer = Instant::now();
     let cache_dir = opt.cache_dir();
     create_dir_all(&cache_dir).await.err_notes(&cache_dir)?; // create if not present already
@@ -191,10 +209,12 @@ pub async fn sync_and_load(
 
     info!("Loading all metadata files to memory.");
     // Load metadata from synced cache files.
-    let mut metadata_vec = Vec::new();
+    let mut metadata_by_file = Vec::new();
     for h in new_remote_hashes.into_iter().chain(up_to_date_local_hashes) {
         let cached_file = cache_dir.join(h);
-        metadata_vec.extend(
+        let file_handle = (*remote_file_handle_by_hash.get(h).expect("In map.")).clone();
+        metadata_by_file.push((
+            file_handle,
             OpenOptions::new()
                 .read(true)
                 .open(&cached_file)
@@ -202,16 +222,15 @@ pub async fn sync_and_load(
                 .err_notes(&cached_file)?
                 .load_metadata_lines()
                 .await
-                .err_notes(&cached_file)?
-                .into_iter(),
-        )
+                .err_notes(&cached_file)?,
+        ))
     }
     info!(
         total_time = timer.elapsed().as_secs(),
         "Metadata cache loaded.",
     );
 
-    Ok(MetadataView::new(metadata_vec, remote_file_handles))
+    Ok((metadata_by_file, remote_file_handles))
 }
 
 trait FileHandleHash {
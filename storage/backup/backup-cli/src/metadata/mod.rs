@@ -12,7 +12,7 @@ use aptos_types::transaction::Version;
 use serde::{Deserialize, Serialize};
 use std::{cmp::Ordering, collections::HashMap, convert::TryInto};
 
-#[derive(Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 #[allow(clippy::enum_variant_names)] // to introduce: BackupperId, etc
 pub(crate) enum Metadata {
     EpochEndingBackup(EpochEndingBackupMeta),
@@ -29,6 +29,7 @@ impl Metadata {
         first_version: Version,
         last_version: Version,
         manifest: FileHandle,
+        encryption_key_id: Option<String>,
     ) -> Self {
         Self::EpochEndingBackup(EpochEndingBackupMeta {
             first_epoch,
@@ -36,14 +37,21 @@ impl Metadata {
             first_version,
             last_version,
             manifest,
+            encryption_key_id,
         })
     }
 
-    pub fn new_state_snapshot_backup(epoch: u64, version: Version, manifest: FileHandle) -> Self {
+    pub fn new_state_snapshot_backup(
+        epoch: u64,
+        version: Version,
+        manifest: FileHandle,
+        encryption_key_id: Option<String>,
+    ) -> Self {
         Self::StateSnapshotBackup(StateSnapshotBackupMeta {
             epoch,
             version,
             manifest,
+            encryption_key_id,
         })
     }
 
@@ -51,11 +59,13 @@ impl Metadata {
         first_version: Version,
         last_version: Version,
         manifest: FileHandle,
+        encryption_key_id: Option<String>,
     ) -> Self {
         Self::TransactionBackup(TransactionBackupMeta {
             first_version,
             last_version,
             manifest,
+            encryption_key_id,
         })
     }
 
@@ -180,6 +190,9 @@ pub struct EpochEndingBackupMeta {
     pub first_version: Version,
     pub last_version: Version,
     pub manifest: FileHandle,
+    /// ID of the key the backup files are encrypted with, see `EncryptedStorage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key_id: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd)]
@@ -187,6 +200,9 @@ pub struct StateSnapshotBackupMeta {
     pub epoch: u64,
     pub version: Version,
     pub manifest: FileHandle,
+    /// ID of the key the backup files are encrypted with, see `EncryptedStorage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key_id: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd)]
@@ -194,6 +210,9 @@ pub struct TransactionBackupMeta {
     pub first_version: Version,
     pub last_version: Version,
     pub manifest: FileHandle,
+    /// ID of the key the backup files are encrypted with, see `EncryptedStorage`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub encryption_key_id: Option<String>,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq, Ord, PartialOrd)]
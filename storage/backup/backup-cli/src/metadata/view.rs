@@ -109,6 +109,14 @@ impl MetadataView {
         &self.state_snapshot_backups
     }
 
+    pub fn all_transaction_backups(&self) -> &[TransactionBackupMeta] {
+        &self.transaction_backups
+    }
+
+    pub fn all_epoch_ending_backups(&self) -> &[EpochEndingBackupMeta] {
+        &self.epoch_ending_backups
+    }
+
     pub fn select_state_snapshot(
         &self,
         target_version: Version,
@@ -136,25 +144,35 @@ impl MetadataView {
         target_version: Version,
     ) -> Result<Vec<TransactionBackupMeta>> {
         // This can be more flexible, but for now we assume and check backups are continuous in
-        // range (which is always true when we backup from a single backup coordinator)
-        let mut next_ver = 0;
+        // range (which is always true when we backup from a single backup coordinator), starting
+        // from the one covering `start_version`. Older ones can be removed by the retention policy.
+        let mut next_ver = None;
         let mut res = Vec::new();
         for backup in self.transaction_backups.iter().sorted() {
             if backup.first_version > target_version {
                 break;
             }
-            ensure!(
-                backup.first_version == next_ver,
-                "Transaction backup ranges not continuous, expecting version {}, got {}.",
-                next_ver,
-                backup.first_version,
-            );
-
-            if backup.last_version >= start_version {
-                res.push(backup.clone());
+            if backup.last_version < start_version {
+                continue;
+            }
+            match next_ver {
+                None => ensure!(
+                    backup.first_version <= start_version,
+                    "No transaction backup covers version {}, the earliest one after it starts at \
+                    version {}.",
+                    start_version,
+                    backup.first_version,
+                ),
+                Some(next_ver) => ensure!(
+                    backup.first_version == next_ver,
+                    "Transaction backup ranges not continuous, expecting version {}, got {}.",
+                    next_ver,
+                    backup.first_version,
+                ),
             }
 
-            next_ver = backup.last_version + 1;
+            res.push(backup.clone());
+            next_ver = Some(backup.last_version + 1);
         }
 
         Ok(res)
@@ -66,3 +66,13 @@ pub static THROUGHPUT_COUNTER: Lazy<IntCounterVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static STATE_SNAPSHOT_CHUNKS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_db_backup_state_snapshot_chunks",
+        "Number of state snapshot chunks backed up, by whether the chunk is written or reused from \
+        the base snapshot of an incremental snapshot.",
+        &["type"]
+    )
+    .unwrap()
+});
@@ -39,6 +39,12 @@ pub struct CommandAdapterOpt {
     config: PathBuf,
 }
 
+impl CommandAdapterOpt {
+    pub fn new(config: PathBuf) -> Self {
+        Self { config }
+    }
+}
+
 impl FromStr for CommandAdapterOpt {
     type Err = &'static str;
 
@@ -160,6 +166,21 @@ impl BackupStorage for CommandAdapter {
         Ok(())
     }
 
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        let cmd = self
+            .config
+            .commands
+            .delete_file
+            .as_ref()
+            .ok_or_else(|| format_err!("delete_file command not defined."))?;
+        self.cmd(cmd, vec![EnvVar::file_handle(file_handle.to_string())])
+            .spawn()?
+            .join()
+            .await
+            .err_notes(file_handle)?;
+        Ok(())
+    }
+
     async fn save_metadata_lines(
         &self,
         name: &ShellSafeName,
@@ -0,0 +1,251 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+mod stream;
+
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    storage::{
+        encryption::stream::{decrypting_reader, EncryptingWriter},
+        BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+        TextLine,
+    },
+    utils::error_notes::ErrorNotes,
+};
+use aes_gcm::{
+    aead::{Aead, Nonce, OsRng, Payload},
+    AeadCore, Aes256Gcm, Key, KeyInit,
+};
+use anyhow::{ensure, format_err, Result};
+use aptos_crypto::HashValue;
+use async_trait::async_trait;
+use clap::Parser;
+use std::{
+    io::Cursor,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
+
+/// Every encrypted file starts with this. The first byte is never valid in UTF-8, so a plaintext
+/// file, like a metadata file or a json manifest, is never mistaken for an encrypted one.
+const MAGIC: &[u8] = b"\xffAPTENC\x00";
+const FORMAT_VERSION: u8 = 1;
+const KEY_SIZE: usize = 32;
+const NONCE_SIZE: usize = 12;
+const TAG_SIZE: usize = 16;
+const WRAPPED_KEY_SIZE: usize = KEY_SIZE + TAG_SIZE;
+const KEY_ID_SALT: &[u8] = b"APTOS_BACKUP_ENCRYPTION_KEY_ID";
+
+#[derive(Clone, Debug, Default, Parser)]
+pub struct EncryptionOpt {
+    #[clap(
+        long,
+        value_parser,
+        help = "File holding a hex encoded 32 byte key (generate one with `openssl rand -hex 32`). \
+        When backing up, files are encrypted with a random per-file key which is in turn \
+        encrypted with this key. When restoring, this key is used to decrypt encrypted files, \
+        and plaintext ones are read as is."
+    )]
+    pub encryption_key_file: Option<PathBuf>,
+}
+
+impl EncryptionOpt {
+    /// Wraps `storage` with an `EncryptedStorage` if a key file is specified.
+    pub async fn wrap_storage(
+        &self,
+        storage: Arc<dyn BackupStorage>,
+    ) -> Result<Arc<dyn BackupStorage>> {
+        Ok(match &self.encryption_key_file {
+            Some(path) => Arc::new(EncryptedStorage::new(
+                storage,
+                EncryptionKey::load_from_file(path).await?,
+            )),
+            None => storage,
+        })
+    }
+}
+
+/// The key encrypting the per-file data keys, identified by a fingerprint of itself.
+pub struct EncryptionKey {
+    id: String,
+    key: Key<Aes256Gcm>,
+}
+
+impl EncryptionKey {
+    pub fn new(bytes: [u8; KEY_SIZE]) -> Self {
+        let fingerprint = HashValue::sha3_256_of(&[KEY_ID_SALT, &bytes].concat());
+        Self {
+            id: hex::encode(&fingerprint.as_ref()[..8]),
+            key: Key::<Aes256Gcm>::clone_from_slice(&bytes),
+        }
+    }
+
+    pub async fn load_from_file(path: &Path) -> Result<Self> {
+        let content = tokio::fs::read_to_string(path).await.err_notes(path)?;
+        let bytes = hex::decode(content.trim())
+            .map_err(|e| format_err!("Key file {:?} is not hex encoded: {}", path, e))?;
+        let bytes: [u8; KEY_SIZE] = bytes.try_into().map_err(|b: Vec<u8>| {
+            format_err!(
+                "Key in {:?} has {} bytes, expecting {}.",
+                path,
+                b.len(),
+                KEY_SIZE
+            )
+        })?;
+        Ok(Self::new(bytes))
+    }
+
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Generates a data key for a new file, returning the cipher with it and the file header
+    /// carrying it encrypted under this key.
+    fn new_data_key(&self) -> Result<(Aes256Gcm, Vec<u8>)> {
+        let data_key = Aes256Gcm::generate_key(OsRng);
+        let nonce = Aes256Gcm::generate_nonce(OsRng);
+        let mut header = header_prefix(&self.id)?;
+        let wrapped_key = Aes256Gcm::new(&self.key)
+            .encrypt(&nonce, Payload {
+                msg: data_key.as_slice(),
+                aad: &header,
+            })
+            .map_err(|e| format_err!("Failed to encrypt data key: {}", e))?;
+
+        header.extend_from_slice(nonce.as_slice());
+        header.extend_from_slice(&wrapped_key);
+        Ok((Aes256Gcm::new(&data_key), header))
+    }
+
+    /// Reads the rest of the header after the magic bytes and returns the cipher with the data
+    /// key of the file.
+    async fn open_data_key<R: AsyncRead + Unpin>(&self, reader: &mut R) -> Result<Aes256Gcm> {
+        let version = reader.read_u8().await?;
+        ensure!(
+            version == FORMAT_VERSION,
+            "Unknown encryption format version {}.",
+            version
+        );
+        let mut key_id = vec![0u8; reader.read_u8().await? as usize];
+        reader.read_exact(&mut key_id).await?;
+        let key_id = String::from_utf8(key_id)?;
+        ensure!(
+            key_id == self.id,
+            "File is encrypted with key {}, but the supplied key is {}.",
+            key_id,
+            self.id,
+        );
+        let mut nonce = [0u8; NONCE_SIZE];
+        reader.read_exact(&mut nonce).await?;
+        let mut wrapped_key = [0u8; WRAPPED_KEY_SIZE];
+        reader.read_exact(&mut wrapped_key).await?;
+
+        let data_key = Aes256Gcm::new(&self.key)
+            .decrypt(Nonce::<Aes256Gcm>::from_slice(&nonce), Payload {
+                msg: &wrapped_key,
+                aad: &header_prefix(&key_id)?,
+            })
+            .map_err(|_| format_err!("Failed to decrypt data key, header corrupted?"))?;
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&data_key)))
+    }
+}
+
+/// The part of the header authenticated when encrypting the data key.
+fn header_prefix(key_id: &str) -> Result<Vec<u8>> {
+    ensure!(key_id.len() <= u8::MAX as usize, "Key ID too long.");
+    let mut prefix = MAGIC.to_vec();
+    prefix.push(FORMAT_VERSION);
+    prefix.push(key_id.len() as u8);
+    prefix.extend_from_slice(key_id.as_bytes());
+    Ok(prefix)
+}
+
+/// Wraps another BackupStorage, encrypting files written via `create_for_write` with a random
+/// per-file data key, which is itself encrypted with the `EncryptionKey` and stored in the header
+/// of the file. Content is authenticated in segments so it can be streamed in both directions,
+/// and truncation or reordering of segments is detected.
+///
+/// Metadata files are left in plaintext and record the ID of the key the backup is encrypted
+/// with (see `encryption_key_id()`), so one can tell which key is needed before a restore.
+/// Plaintext files are read as is, so a backup started before encryption was turned on stays
+/// restorable.
+pub struct EncryptedStorage {
+    storage: Arc<dyn BackupStorage>,
+    key: EncryptionKey,
+}
+
+impl EncryptedStorage {
+    pub fn new(storage: Arc<dyn BackupStorage>, key: EncryptionKey) -> Self {
+        Self { storage, key }
+    }
+}
+
+#[async_trait]
+impl BackupStorage for EncryptedStorage {
+    async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
+        self.storage.create_backup(name).await
+    }
+
+    async fn create_for_write(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        let (file_handle, file) = self.storage.create_for_write(backup_handle, name).await?;
+        let (cipher, header) = self.key.new_data_key()?;
+        Ok((
+            file_handle,
+            Box::new(EncryptingWriter::new(file, cipher, header)),
+        ))
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let mut file = self.storage.open_for_read(file_handle).await?;
+        let mut magic = Vec::with_capacity(MAGIC.len());
+        (&mut file)
+            .take(MAGIC.len() as u64)
+            .read_to_end(&mut magic)
+            .await
+            .err_notes(file_handle)?;
+        if magic != MAGIC {
+            return Ok(Box::new(Cursor::new(magic).chain(file)));
+        }
+
+        let cipher = self
+            .key
+            .open_data_key(&mut file)
+            .await
+            .err_notes(file_handle)?;
+        Ok(Box::new(decrypting_reader(file, cipher)))
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        self.storage.list_metadata_files().await
+    }
+
+    async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        self.storage.backup_metadata_file(file_handle).await
+    }
+
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        self.storage.delete_file(file_handle).await
+    }
+
+    async fn save_metadata_lines(
+        &self,
+        name: &ShellSafeName,
+        lines: &[TextLine],
+    ) -> Result<FileHandle> {
+        self.storage.save_metadata_lines(name, lines).await
+    }
+
+    fn encryption_key_id(&self) -> Option<String> {
+        Some(self.key.id().to_string())
+    }
+}
@@ -0,0 +1,207 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Streaming authenticated encryption of a file, after its header.
+//!
+//! The plaintext is cut into segments of `SEGMENT_SIZE` bytes (the last one can be shorter, or
+//! empty), each of which is encrypted separately and written as:
+//!
+//!   | is_last: u8 | ciphertext length: u32 BE | ciphertext with tag |
+//!
+//! The nonce of a segment is its index plus the `is_last` flag. Data keys are never reused across
+//! files, so segments can't be reordered, and a file truncated at a segment boundary is detected
+//! by the missing final segment.
+
+use crate::storage::encryption::{NONCE_SIZE, TAG_SIZE};
+use aes_gcm::{
+    aead::{Aead, Nonce},
+    Aes256Gcm,
+};
+use anyhow::{ensure, format_err, Result};
+use futures::{
+    ready,
+    stream::{self, TryStreamExt},
+    task::{Context, Poll},
+};
+use std::{io, pin::Pin};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite};
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+const SEGMENT_SIZE: usize = 64 * 1024;
+
+fn segment_nonce(index: u64, is_last: bool) -> Nonce<Aes256Gcm> {
+    let mut nonce = [0u8; NONCE_SIZE];
+    nonce[..8].copy_from_slice(&index.to_be_bytes());
+    nonce[NONCE_SIZE - 1] = is_last as u8;
+    Nonce::<Aes256Gcm>::clone_from_slice(&nonce)
+}
+
+/// Encrypts what's written to it into the wrapped writer, after writing `header` out first.
+/// Plaintext is buffered until a full segment is collected, so nothing but the header is
+/// guaranteed to reach the inner writer before `shutdown()`.
+pub(super) struct EncryptingWriter {
+    inner: Box<dyn AsyncWrite + Send + Unpin>,
+    cipher: Aes256Gcm,
+    next_segment: u64,
+    plaintext: Vec<u8>,
+    /// Encrypted bytes not yet accepted by `inner`, starting from `pending_pos`.
+    pending: Vec<u8>,
+    pending_pos: usize,
+    finished: bool,
+}
+
+impl EncryptingWriter {
+    pub fn new(
+        inner: Box<dyn AsyncWrite + Send + Unpin>,
+        cipher: Aes256Gcm,
+        header: Vec<u8>,
+    ) -> Self {
+        Self {
+            inner,
+            cipher,
+            next_segment: 0,
+            plaintext: Vec::with_capacity(SEGMENT_SIZE),
+            pending: header,
+            pending_pos: 0,
+            finished: false,
+        }
+    }
+
+    fn seal_segment(&mut self, is_last: bool) -> io::Result<()> {
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                &segment_nonce(self.next_segment, is_last),
+                self.plaintext.as_slice(),
+            )
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e.to_string()))?;
+        self.pending.push(is_last as u8);
+        self.pending
+            .extend_from_slice(&(ciphertext.len() as u32).to_be_bytes());
+        self.pending.extend_from_slice(&ciphertext);
+        self.plaintext.clear();
+        self.next_segment += 1;
+        Ok(())
+    }
+
+    fn poll_write_pending(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while self.pending_pos < self.pending.len() {
+            let n = ready!(
+                Pin::new(&mut self.inner).poll_write(cx, &self.pending[self.pending_pos..])
+            )?;
+            if n == 0 {
+                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()));
+            }
+            self.pending_pos += n;
+        }
+        self.pending.clear();
+        self.pending_pos = 0;
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for EncryptingWriter {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+        loop {
+            ready!(this.poll_write_pending(cx))?;
+            if this.plaintext.len() < SEGMENT_SIZE {
+                break;
+            }
+            // Only sealed once more data comes in, since whichever segment turns out to be the
+            // last needs to be marked so.
+            this.seal_segment(false)?;
+        }
+
+        let len = buf.len().min(SEGMENT_SIZE - this.plaintext.len());
+        this.plaintext.extend_from_slice(&buf[..len]);
+        Poll::Ready(Ok(len))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_pending(cx))?;
+        Pin::new(&mut this.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        ready!(this.poll_write_pending(cx))?;
+        if !this.finished {
+            this.seal_segment(true)?;
+            this.finished = true;
+            ready!(this.poll_write_pending(cx))?;
+        }
+        Pin::new(&mut this.inner).poll_shutdown(cx)
+    }
+}
+
+struct DecryptState {
+    reader: Box<dyn AsyncRead + Send + Unpin>,
+    cipher: Aes256Gcm,
+    next_segment: u64,
+    finished: bool,
+}
+
+/// Decrypts the segments coming out of `reader`, which is expected to be positioned right after
+/// the header.
+pub(super) fn decrypting_reader(
+    reader: Box<dyn AsyncRead + Send + Unpin>,
+    cipher: Aes256Gcm,
+) -> impl AsyncRead + Send + Unpin {
+    let state = DecryptState {
+        reader,
+        cipher,
+        next_segment: 0,
+        finished: false,
+    };
+    let segments = stream::try_unfold(state, |mut state| async move {
+        if state.finished {
+            ensure!(
+                state.reader.read(&mut [0u8; 1]).await? == 0,
+                "Unexpected data after the last segment."
+            );
+            return Ok(None);
+        }
+
+        let is_last = match state.reader.read_u8().await {
+            Ok(flag) if flag <= 1 => flag == 1,
+            Ok(flag) => return Err(format_err!("Invalid segment flag {}.", flag)),
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(format_err!("File truncated, last segment missing."))
+            },
+            Err(e) => return Err(e.into()),
+        };
+        let len = state.reader.read_u32().await? as usize;
+        ensure!(
+            len <= SEGMENT_SIZE + TAG_SIZE,
+            "Segment too large: {} bytes.",
+            len
+        );
+        let mut ciphertext = vec![0u8; len];
+        state.reader.read_exact(&mut ciphertext).await?;
+        let plaintext = state
+            .cipher
+            .decrypt(
+                &segment_nonce(state.next_segment, is_last),
+                ciphertext.as_slice(),
+            )
+            .map_err(|_| {
+                format_err!(
+                    "Failed to decrypt segment {}, file corrupted?",
+                    state.next_segment
+                )
+            })?;
+
+        state.next_segment += 1;
+        state.finished = is_last;
+        Result::<_>::Ok(Some((plaintext, state)))
+    })
+    .map_err(|e| io::Error::new(io::ErrorKind::Other, e));
+
+    Box::pin(segments).into_async_read().compat()
+}
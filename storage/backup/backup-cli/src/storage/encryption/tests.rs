@@ -0,0 +1,172 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::*;
+use crate::storage::{
+    local_fs::LocalFs,
+    test_util::{
+        arb_backups, arb_metadata_files, test_save_and_list_metadata_files_impl,
+        test_write_and_read_impl,
+    },
+};
+use aptos_temppath::TempPath;
+use proptest::prelude::*;
+use std::str::FromStr;
+use tokio::{io::AsyncWriteExt, runtime::Runtime};
+
+const BACKUP: &str = "backup";
+const FILE: &str = "file";
+/// Spans a few segments, with the last one partially filled.
+const CONTENT_SIZE: usize = 3 * 64 * 1024 + 100;
+
+fn new_storage(dir: &TempPath, key: [u8; 32]) -> EncryptedStorage {
+    EncryptedStorage::new(
+        Arc::new(LocalFs::new(dir.path().to_path_buf())),
+        EncryptionKey::new(key),
+    )
+}
+
+fn new_tmpdir() -> TempPath {
+    let tmpdir = TempPath::new();
+    tmpdir.create_as_dir().unwrap();
+    tmpdir
+}
+
+async fn write(storage: &dyn BackupStorage, content: &[u8]) -> FileHandle {
+    let backup_handle = storage
+        .create_backup(&ShellSafeName::from_str(BACKUP).unwrap())
+        .await
+        .unwrap();
+    let (file_handle, mut file) = storage
+        .create_for_write(&backup_handle, &ShellSafeName::from_str(FILE).unwrap())
+        .await
+        .unwrap();
+    file.write_all(content).await.unwrap();
+    file.shutdown().await.unwrap();
+    file_handle
+}
+
+async fn read(storage: &dyn BackupStorage, file_handle: &FileHandleRef) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
+    storage
+        .open_for_read(file_handle)
+        .await?
+        .read_to_end(&mut buf)
+        .await?;
+    Ok(buf)
+}
+
+fn raw_path(dir: &TempPath, file_handle: &FileHandleRef) -> PathBuf {
+    dir.path().join(file_handle)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(10))]
+
+    #[test]
+    fn test_write_and_read(
+        backups in arb_backups()
+    ) {
+        let tmpdir = new_tmpdir();
+        let store = new_storage(&tmpdir, [1; 32]);
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(test_write_and_read_impl(Box::new(store), backups));
+    }
+
+    #[test]
+    fn test_save_list_metadata_files(
+        input in arb_metadata_files(),
+    ) {
+        let tmpdir = new_tmpdir();
+        let store = new_storage(&tmpdir, [1; 32]);
+
+        let rt = Runtime::new().unwrap();
+        rt.block_on(test_save_and_list_metadata_files_impl(Box::new(store), input));
+    }
+}
+
+#[tokio::test]
+async fn test_encrypted_at_rest() {
+    let tmpdir = new_tmpdir();
+    let storage = new_storage(&tmpdir, [1; 32]);
+    let content = vec![7u8; CONTENT_SIZE];
+
+    for content in [vec![], content] {
+        let file_handle = write(&storage, &content).await;
+        let raw = std::fs::read(raw_path(&tmpdir, &file_handle)).unwrap();
+        assert!(raw.starts_with(MAGIC));
+        assert!(!raw.windows(64).any(|w| w == [7u8; 64]));
+        assert_eq!(read(&storage, &file_handle).await.unwrap(), content);
+        std::fs::remove_file(raw_path(&tmpdir, &file_handle)).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn test_wrong_key() {
+    let tmpdir = new_tmpdir();
+    let file_handle = write(&new_storage(&tmpdir, [1; 32]), b"content").await;
+
+    let err = read(&new_storage(&tmpdir, [2; 32]), &file_handle)
+        .await
+        .unwrap_err();
+    assert!(format!("{:#}", err).contains(EncryptionKey::new([1; 32]).id()));
+}
+
+#[tokio::test]
+async fn test_tampered() {
+    let tmpdir = new_tmpdir();
+    let storage = new_storage(&tmpdir, [1; 32]);
+    let file_handle = write(&storage, &vec![7u8; CONTENT_SIZE]).await;
+    let path = raw_path(&tmpdir, &file_handle);
+    let raw = std::fs::read(&path).unwrap();
+
+    // flip a bit in the second segment
+    let mut tampered = raw.clone();
+    tampered[raw.len() / 2] ^= 1;
+    std::fs::write(&path, &tampered).unwrap();
+    assert!(read(&storage, &file_handle).await.is_err());
+
+    // drop the last segment
+    let last_segment_size = 1 + 4 + 100 + TAG_SIZE;
+    std::fs::write(&path, &raw[..raw.len() - last_segment_size]).unwrap();
+    assert!(read(&storage, &file_handle).await.is_err());
+
+    // append garbage
+    let mut appended = raw.clone();
+    appended.push(0);
+    std::fs::write(&path, &appended).unwrap();
+    assert!(read(&storage, &file_handle).await.is_err());
+
+    std::fs::write(&path, &raw).unwrap();
+    assert_eq!(
+        read(&storage, &file_handle).await.unwrap(),
+        vec![7u8; CONTENT_SIZE]
+    );
+}
+
+#[tokio::test]
+async fn test_read_plaintext() {
+    let tmpdir = new_tmpdir();
+    let plain_storage = LocalFs::new(tmpdir.path().to_path_buf());
+    let file_handle = write(&plain_storage, b"plain").await;
+
+    let storage = new_storage(&tmpdir, [1; 32]);
+    assert_eq!(read(&storage, &file_handle).await.unwrap(), b"plain");
+}
+
+#[tokio::test]
+async fn test_load_key_file() {
+    let tmpdir = new_tmpdir();
+    let path = tmpdir.path().join("key");
+
+    std::fs::write(&path, format!("{}\n", hex::encode([1u8; 32]))).unwrap();
+    let key = EncryptionKey::load_from_file(&path).await.unwrap();
+    assert_eq!(key.id(), EncryptionKey::new([1; 32]).id());
+    assert_ne!(key.id(), EncryptionKey::new([2; 32]).id());
+
+    std::fs::write(&path, hex::encode([1u8; 16])).unwrap();
+    assert!(EncryptionKey::load_from_file(&path).await.is_err());
+    std::fs::write(&path, "not hex").unwrap();
+    assert!(EncryptionKey::load_from_file(&path).await.is_err());
+}
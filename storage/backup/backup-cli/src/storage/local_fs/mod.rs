@@ -22,7 +22,7 @@ use std::{
     str::FromStr,
 };
 use tokio::{
-    fs::{create_dir_all, read_dir, rename, OpenOptions},
+    fs::{create_dir_all, read_dir, remove_file, rename, OpenOptions},
     io::{AsyncRead, AsyncWrite, AsyncWriteExt},
 };
 
@@ -147,6 +147,12 @@ impl BackupStorage for LocalFs {
         Ok(())
     }
 
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        let path = self.dir.join(file_handle);
+        remove_file(&path).await.err_notes(&path)?;
+        Ok(())
+    }
+
     async fn save_metadata_lines(
         &self,
         name: &ShellSafeName,
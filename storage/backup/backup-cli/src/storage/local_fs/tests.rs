@@ -4,8 +4,8 @@
 
 use super::*;
 use crate::storage::test_util::{
-    arb_backups, arb_metadata_files, test_save_and_list_metadata_files_impl,
-    test_write_and_read_impl,
+    arb_backups, arb_metadata_files, test_delete_file_impl,
+    test_save_and_list_metadata_files_impl, test_write_and_read_impl,
 };
 use aptos_temppath::TempPath;
 use proptest::prelude::*;
@@ -38,3 +38,13 @@ proptest! {
         rt.block_on(test_save_and_list_metadata_files_impl(Box::new(store), input));
     }
 }
+
+#[test]
+fn test_delete_file() {
+    let tmpdir = TempPath::new();
+    tmpdir.create_as_dir().unwrap();
+    let store = LocalFs::new(tmpdir.path().to_path_buf());
+
+    let rt = Runtime::new().unwrap();
+    rt.block_on(test_delete_file_impl(Box::new(store)));
+}
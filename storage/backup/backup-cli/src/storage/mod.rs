@@ -3,7 +3,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod command_adapter;
+pub mod encryption;
 pub mod local_fs;
+pub mod s3;
 
 #[cfg(test)]
 mod test_util;
@@ -12,9 +14,11 @@ mod tests;
 
 use crate::storage::{
     command_adapter::{CommandAdapter, CommandAdapterOpt},
+    encryption::EncryptionOpt,
     local_fs::{LocalFs, LocalFsOpt},
+    s3::{S3Opt, S3},
 };
-use anyhow::{ensure, Result};
+use anyhow::{bail, ensure, Result};
 use async_trait::async_trait;
 use clap::{ArgGroup, Parser};
 use once_cell::sync::Lazy;
@@ -23,7 +27,7 @@ use proptest::prelude::*;
 use regex::Regex;
 #[cfg(test)]
 use std::convert::TryInto;
-use std::{convert::TryFrom, ops::Deref, str::FromStr, sync::Arc};
+use std::{convert::TryFrom, ops::Deref, path::PathBuf, str::FromStr, sync::Arc};
 use tokio::io::{AsyncRead, AsyncWrite};
 
 /// String returned by a specific storage implementation to identify a backup, probably a folder name
@@ -178,6 +182,8 @@ pub trait BackupStorage: Send + Sync {
     async fn list_metadata_files(&self) -> Result<Vec<FileHandle>>;
     /// Move a metadata file to the metadata file backup folder.
     async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()>;
+    /// Delete a file created by `create_for_write()`, used to remove backups no longer needed.
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()>;
     /// Save a vector of metadata lines to file and return the file handle of saved file.
     /// If the file exists, this will overwrite
     async fn save_metadata_lines(
@@ -185,6 +191,11 @@ pub trait BackupStorage: Send + Sync {
         name: &ShellSafeName,
         lines: &[TextLine],
     ) -> Result<FileHandle>;
+    /// ID of the key files created by `create_for_write` are encrypted with, if they are. It's
+    /// recorded in the metadata so a restore can tell upfront whether it has the right key.
+    fn encryption_key_id(&self) -> Option<String> {
+        None
+    }
 }
 
 #[derive(Parser)]
@@ -198,6 +209,13 @@ pub enum StorageOpt {
     https://github.com/aptos-labs/aptos-core/tree/main/storage/backup/backup-cli/src/storage/command_adapter/sample_configs/"
     )]
     CommandAdapter(CommandAdapterOpt),
+    #[clap(
+        about = "Select the S3 backup storage type, which talks to AWS S3 or an S3-compatible \
+    object store (MinIO, for example) directly, without needing any external tool installed. See a \
+    sample config here: \
+    https://github.com/aptos-labs/aptos-core/tree/main/storage/backup/backup-cli/src/storage/s3/sample_configs/"
+    )]
+    S3(S3Opt),
 }
 
 impl StorageOpt {
@@ -205,6 +223,7 @@ impl StorageOpt {
         Ok(match self {
             StorageOpt::LocalFs(opt) => Arc::new(LocalFs::new_with_opt(opt)),
             StorageOpt::CommandAdapter(opt) => Arc::new(CommandAdapter::new_with_opt(opt).await?),
+            StorageOpt::S3(opt) => Arc::new(S3::new_with_opt(opt).await?),
         })
     }
 }
@@ -213,7 +232,7 @@ impl StorageOpt {
 #[clap(group(
     ArgGroup::new("storage")
     .required(true)
-    .args(&["local_fs_dir", "command_adapter_config"]),
+    .args(&["local_fs_dir", "command_adapter_config", "s3_config"]),
 ))]
 pub struct DBToolStorageOpt {
     #[clap(
@@ -229,14 +248,46 @@ pub struct DBToolStorageOpt {
     https://github.com/aptos-labs/aptos-networks/tree/main/testnet/backups "
     )]
     command_adapter_config: Option<CommandAdapterOpt>,
+    #[clap(
+        long,
+        help = "Select the S3 backup storage type, which talks to AWS S3 or an S3-compatible \
+    object store directly. Takes the path to a config file, see a sample config here: \
+    https://github.com/aptos-labs/aptos-core/tree/main/storage/backup/backup-cli/src/storage/s3/sample_configs/"
+    )]
+    s3_config: Option<S3Opt>,
+    #[clap(flatten)]
+    encryption: EncryptionOpt,
 }
 
 impl DBToolStorageOpt {
+    /// Creates the storage options from paths, e.g. for selecting a backup storage via the node
+    /// config instead of the command line.
+    pub fn new(
+        local_fs_dir: Option<PathBuf>,
+        command_adapter_config: Option<PathBuf>,
+        s3_config: Option<PathBuf>,
+        encryption_key_file: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            local_fs_dir: local_fs_dir.map(|dir| LocalFsOpt { dir }),
+            command_adapter_config: command_adapter_config.map(CommandAdapterOpt::new),
+            s3_config: s3_config.map(S3Opt::new),
+            encryption: EncryptionOpt {
+                encryption_key_file,
+            },
+        }
+    }
+
     pub async fn init_storage(self) -> Result<Arc<dyn BackupStorage>> {
-        Ok(if self.local_fs_dir.is_some() {
-            Arc::new(LocalFs::new_with_opt(self.local_fs_dir.unwrap()))
+        let storage: Arc<dyn BackupStorage> = if let Some(opt) = self.local_fs_dir {
+            Arc::new(LocalFs::new_with_opt(opt))
+        } else if let Some(opt) = self.s3_config {
+            Arc::new(S3::new_with_opt(opt).await?)
+        } else if let Some(opt) = self.command_adapter_config {
+            Arc::new(CommandAdapter::new_with_opt(opt).await?)
         } else {
-            Arc::new(CommandAdapter::new_with_opt(self.command_adapter_config.unwrap()).await?)
-        })
+            bail!("No backup storage is configured!")
+        };
+        self.encryption.wrap_storage(storage).await
     }
 }
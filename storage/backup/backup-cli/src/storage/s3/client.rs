@@ -0,0 +1,398 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    storage::s3::{
+        config::S3Config,
+        signer::{sha256_hex, sign, uri_encode, uri_encode_path, Credentials, RequestToSign},
+    },
+    utils::error_notes::ErrorNotes,
+};
+use anyhow::{bail, ensure, format_err, Result};
+use aptos_logger::warn;
+use bytes::Bytes;
+use futures::TryStreamExt;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::{Method, Response, StatusCode};
+use sha2_0_10_6::{Digest, Sha256};
+use std::time::Duration;
+use tokio::io::AsyncRead;
+use tokio_util::compat::FuturesAsyncReadCompatExt;
+
+/// Minimal client of the S3 REST API, covering only what the backup storage needs.
+pub(super) struct S3Client {
+    config: S3Config,
+    credentials: Credentials,
+    http: reqwest::Client,
+}
+
+impl S3Client {
+    pub fn new(config: S3Config, credentials: Credentials) -> Self {
+        Self {
+            config,
+            credentials,
+            http: reqwest::Client::builder()
+                .no_proxy()
+                .build()
+                .expect("Http client should build."),
+        }
+    }
+
+    pub fn config(&self) -> &S3Config {
+        &self.config
+    }
+
+    pub async fn put_object(&self, key: &str, body: Bytes) -> Result<()> {
+        self.request(Method::PUT, key, &[], &[], body)
+            .await
+            .err_notes(key)?;
+        Ok(())
+    }
+
+    /// Returns the upload ID.
+    pub async fn create_multipart_upload(&self, key: &str) -> Result<String> {
+        let body = self
+            .request(
+                Method::POST,
+                key,
+                &[("uploads", String::new())],
+                &[],
+                Bytes::new(),
+            )
+            .await
+            .err_notes(key)?
+            .text()
+            .await?;
+        xml_values(&body, "UploadId")
+            .into_iter()
+            .next()
+            .ok_or_else(|| format_err!("No UploadId in response: {}", body))
+    }
+
+    /// Returns the ETag of the uploaded part.
+    pub async fn upload_part(
+        &self,
+        key: &str,
+        upload_id: &str,
+        part_number: usize,
+        body: Bytes,
+    ) -> Result<String> {
+        let response = self
+            .request(
+                Method::PUT,
+                key,
+                &[
+                    ("partNumber", part_number.to_string()),
+                    ("uploadId", upload_id.to_string()),
+                ],
+                &[],
+                body,
+            )
+            .await
+            .err_notes((key, part_number))?;
+        let etag = response
+            .headers()
+            .get(reqwest::header::ETAG)
+            .ok_or_else(|| format_err!("No ETag for part {} of {}.", part_number, key))?
+            .to_str()?
+            .to_string();
+        Ok(etag)
+    }
+
+    /// `etags` are those of part 1, 2, ... in that order.
+    pub async fn complete_multipart_upload(
+        &self,
+        key: &str,
+        upload_id: &str,
+        etags: &[String],
+    ) -> Result<()> {
+        let parts: String = etags
+            .iter()
+            .enumerate()
+            .map(|(idx, etag)| {
+                format!(
+                    "<Part><PartNumber>{}</PartNumber><ETag>{}</ETag></Part>",
+                    idx + 1,
+                    etag
+                )
+            })
+            .collect();
+        let body = format!(
+            "<CompleteMultipartUpload>{}</CompleteMultipartUpload>",
+            parts
+        );
+        let response = self
+            .request(
+                Method::POST,
+                key,
+                &[("uploadId", upload_id.to_string())],
+                &[],
+                Bytes::from(body),
+            )
+            .await
+            .err_notes(key)?
+            .text()
+            .await?;
+        // S3 can report a failure with a 200 status once it started streaming the response.
+        ensure!(
+            !response.contains("<Error>"),
+            "Failed to complete multipart upload of {}: {}",
+            key,
+            response,
+        );
+        Ok(())
+    }
+
+    pub async fn abort_multipart_upload(&self, key: &str, upload_id: &str) -> Result<()> {
+        self.request(
+            Method::DELETE,
+            key,
+            &[("uploadId", upload_id.to_string())],
+            &[],
+            Bytes::new(),
+        )
+        .await
+        .err_notes(key)?;
+        Ok(())
+    }
+
+    /// Without the s3:ListBucket permission, S3 responds to a HEAD of a missing object with 403
+    /// rather than 404. A HEAD response has no body to tell that apart from other access errors,
+    /// so 403 is taken as not found as well; the request that follows fails if access is indeed
+    /// denied.
+    pub async fn object_exists(&self, key: &str) -> Result<bool> {
+        match self
+            .request(Method::HEAD, key, &[], &[], Bytes::new())
+            .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) if is_not_found(&e) || is_forbidden(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn get_object(&self, key: &str) -> Result<impl AsyncRead + Send + Unpin> {
+        let response = self
+            .request(Method::GET, key, &[], &[], Bytes::new())
+            .await
+            .err_notes(key)?;
+        Ok(response
+            .bytes_stream()
+            .map_err(|e| futures::io::Error::new(futures::io::ErrorKind::Other, e))
+            .into_async_read()
+            .compat())
+    }
+
+    /// Lists all keys starting with `prefix`, following continuation tokens.
+    pub async fn list_objects(&self, prefix: &str) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let mut query = vec![
+                ("list-type", "2".to_string()),
+                ("prefix", prefix.to_string()),
+            ];
+            if let Some(token) = continuation_token.take() {
+                query.push(("continuation-token", token));
+            }
+            let body = self
+                .request(Method::GET, "", &query, &[], Bytes::new())
+                .await
+                .err_notes(prefix)?
+                .text()
+                .await?;
+            keys.extend(xml_values(&body, "Key"));
+            if xml_values(&body, "IsTruncated").first().map(String::as_str) != Some("true") {
+                break;
+            }
+            continuation_token = Some(
+                xml_values(&body, "NextContinuationToken")
+                    .into_iter()
+                    .next()
+                    .ok_or_else(|| format_err!("Truncated listing without token: {}", body))?,
+            );
+        }
+        Ok(keys)
+    }
+
+    pub async fn copy_object(&self, src_key: &str, dst_key: &str) -> Result<()> {
+        let copy_source = format!(
+            "/{}",
+            uri_encode_path(&format!("{}/{}", self.config.bucket, src_key))
+        );
+        let response = self
+            .request(
+                Method::PUT,
+                dst_key,
+                &[],
+                &[("x-amz-copy-source", copy_source)],
+                Bytes::new(),
+            )
+            .await
+            .err_notes((src_key, dst_key))?
+            .text()
+            .await?;
+        ensure!(
+            !response.contains("<Error>"),
+            "Failed to copy {} to {}: {}",
+            src_key,
+            dst_key,
+            response,
+        );
+        Ok(())
+    }
+
+    pub async fn delete_object(&self, key: &str) -> Result<()> {
+        self.request(Method::DELETE, key, &[], &[], Bytes::new())
+            .await
+            .err_notes(key)?;
+        Ok(())
+    }
+
+    /// Signs and sends a request, retrying on connection errors, throttling and server side
+    /// errors.
+    async fn request(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, String)],
+        headers: &[(&'static str, String)],
+        body: Bytes,
+    ) -> Result<Response> {
+        let payload_sha256 = sha256_hex(&body);
+        let checksum = base64::encode(Sha256::digest(&body));
+        let mut headers = headers.to_vec();
+        if method == Method::PUT && !body.is_empty() {
+            headers.push(("x-amz-checksum-sha256", checksum));
+        }
+
+        let mut num_retries = 0;
+        loop {
+            match self
+                .request_once(
+                    method.clone(),
+                    key,
+                    query,
+                    &headers,
+                    body.clone(),
+                    &payload_sha256,
+                )
+                .await
+            {
+                Ok(response) => return Ok(response),
+                Err(e) if num_retries < self.config.max_retries && is_retryable(&e) => {
+                    num_retries += 1;
+                    warn!(
+                        method = method.as_str(),
+                        key = key,
+                        num_retries = num_retries,
+                        error = %e,
+                        "S3 request failed, retrying.",
+                    );
+                    tokio::time::sleep(Duration::from_millis(self.config.retry_delay_ms)).await;
+                },
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    async fn request_once(
+        &self,
+        method: Method,
+        key: &str,
+        query: &[(&str, String)],
+        headers: &[(&'static str, String)],
+        body: Bytes,
+        payload_sha256: &str,
+    ) -> Result<Response> {
+        let mut url = reqwest::Url::parse(&self.config.bucket_url())?;
+        let path = format!(
+            "{}/{}",
+            url.path().trim_end_matches('/'),
+            uri_encode_path(key)
+        );
+        url.set_path(&path);
+        if !query.is_empty() {
+            let query_str = query
+                .iter()
+                .map(|(k, v)| format!("{}={}", uri_encode(k), uri_encode(v)))
+                .collect::<Vec<_>>()
+                .join("&");
+            url.set_query(Some(&query_str));
+        }
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+        let signed_headers = sign(
+            &self.credentials,
+            &self.config.region,
+            chrono::Utc::now(),
+            &RequestToSign {
+                method: method.as_str(),
+                host: &host,
+                encoded_path: &path,
+                query,
+                headers,
+                payload_sha256,
+            },
+        );
+
+        let mut request = self.http.request(method, url).body(body);
+        for (k, v) in signed_headers {
+            request = request.header(k, v);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if status.is_success() {
+            Ok(response)
+        } else {
+            let body = response.text().await.unwrap_or_default();
+            bail!(S3Error { status, body })
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("S3 request failed with status {status}: {body}")]
+struct S3Error {
+    status: StatusCode,
+    body: String,
+}
+
+fn is_retryable(e: &anyhow::Error) -> bool {
+    match e.downcast_ref::<S3Error>() {
+        Some(e) => e.status.is_server_error() || e.status == StatusCode::TOO_MANY_REQUESTS,
+        // Connection errors, timeouts, etc.
+        None => true,
+    }
+}
+
+fn is_not_found(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<S3Error>()
+        .map_or(false, |e| e.status == StatusCode::NOT_FOUND)
+}
+
+fn is_forbidden(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<S3Error>()
+        .map_or(false, |e| e.status == StatusCode::FORBIDDEN)
+}
+
+/// Extracts the text content of all elements named `tag`. Good enough for the flat responses of
+/// the few APIs we use, whose values never contain markup.
+fn xml_values(xml: &str, tag: &str) -> Vec<String> {
+    static RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"<(\w+)>([^<]*)</(\w+)>").unwrap());
+    RE.captures_iter(xml)
+        .filter(|c| &c[1] == tag && &c[3] == tag)
+        .map(|c| unescape_xml(&c[2]))
+        .collect()
+}
+
+fn unescape_xml(s: &str) -> String {
+    s.replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
@@ -0,0 +1,122 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::utils::error_notes::ErrorNotes;
+use anyhow::{ensure, Result};
+use serde::Deserialize;
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+/// S3 rejects multipart uploads with a part (other than the last one) smaller than this.
+pub const MIN_PART_SIZE: usize = 5 * 1024 * 1024;
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct S3Config {
+    /// Name of the bucket holding the backups.
+    pub bucket: String,
+    /// Key prefix under which everything is stored, without leading or trailing slashes.
+    /// Empty means the root of the bucket.
+    #[serde(default)]
+    pub prefix: String,
+    /// Region used to sign requests.
+    #[serde(default = "S3Config::default_region")]
+    pub region: String,
+    /// Service endpoint, like "http://localhost:9000" for a local MinIO instance.
+    /// If not set, the AWS endpoint of the region is used.
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    /// Address the bucket as "{endpoint}/{bucket}" instead of "{bucket}.{endpoint}". Most
+    /// S3-compatible services (MinIO, for example) require this.
+    #[serde(default)]
+    pub path_style: bool,
+    /// Size of each part in a multipart upload. Files smaller than this are uploaded with a
+    /// single request.
+    #[serde(default = "S3Config::default_part_size")]
+    pub part_size: usize,
+    /// Number of parts of a single file being uploaded concurrently.
+    #[serde(default = "S3Config::default_concurrent_part_uploads")]
+    pub concurrent_part_uploads: usize,
+    /// Number of times a failed request is retried before giving up.
+    #[serde(default = "S3Config::default_max_retries")]
+    pub max_retries: usize,
+    /// Delay between retries.
+    #[serde(default = "S3Config::default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+}
+
+impl S3Config {
+    fn default_region() -> String {
+        "us-east-1".to_string()
+    }
+
+    fn default_part_size() -> usize {
+        64 * 1024 * 1024
+    }
+
+    fn default_concurrent_part_uploads() -> usize {
+        4
+    }
+
+    fn default_max_retries() -> usize {
+        5
+    }
+
+    fn default_retry_delay_ms() -> u64 {
+        1000
+    }
+
+    pub async fn load_from_file(path: &Path) -> Result<Self> {
+        let path_str = path.to_str().unwrap_or_default();
+        let mut file = tokio::fs::File::open(path).await.err_notes(path_str)?;
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).await.err_notes(path_str)?;
+
+        Self::sanitize(serde_yaml::from_slice(&content)?)
+    }
+
+    pub fn load_from_str(content: &str) -> Result<Self> {
+        Self::sanitize(serde_yaml::from_str(content)?)
+    }
+
+    fn sanitize(mut config: Self) -> Result<Self> {
+        ensure!(!config.bucket.is_empty(), "Bucket name can't be empty.");
+        ensure!(
+            config.part_size >= MIN_PART_SIZE,
+            "part_size must be at least {} bytes, got {}.",
+            MIN_PART_SIZE,
+            config.part_size,
+        );
+        ensure!(
+            config.concurrent_part_uploads > 0,
+            "concurrent_part_uploads must be positive."
+        );
+        config.prefix = config.prefix.trim_matches('/').to_string();
+        if let Some(endpoint) = &mut config.endpoint {
+            *endpoint = endpoint.trim_end_matches('/').to_string();
+        }
+        Ok(config)
+    }
+
+    /// Base URL of the bucket, without a trailing slash.
+    pub fn bucket_url(&self) -> String {
+        match (&self.endpoint, self.path_style) {
+            (Some(endpoint), true) => format!("{}/{}", endpoint, self.bucket),
+            (Some(endpoint), false) => match endpoint.split_once("://") {
+                Some((scheme, host)) => format!("{}://{}.{}", scheme, self.bucket, host),
+                None => format!("https://{}.{}", self.bucket, endpoint),
+            },
+            (None, true) => format!("https://s3.{}.amazonaws.com/{}", self.region, self.bucket),
+            (None, false) => format!("https://{}.s3.{}.amazonaws.com", self.bucket, self.region),
+        }
+    }
+
+    /// Maps a path relative to the backup storage root to an object key.
+    pub fn key(&self, path: &str) -> String {
+        if self.prefix.is_empty() {
+            path.to_string()
+        } else {
+            format!("{}/{}", self.prefix, path)
+        }
+    }
+}
@@ -0,0 +1,180 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+mod client;
+pub mod config;
+mod signer;
+mod sink;
+
+#[cfg(test)]
+mod tests;
+
+pub use signer::Credentials;
+
+use crate::{
+    storage::{
+        s3::{client::S3Client, config::S3Config, sink::S3UploadSink},
+        BackupHandle, BackupHandleRef, BackupStorage, FileHandle, FileHandleRef, ShellSafeName,
+        TextLine,
+    },
+    utils::PathToString,
+};
+use anyhow::{ensure, format_err, Result};
+use async_trait::async_trait;
+use bytes::Bytes;
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+use std::{
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+};
+use tokio::io::{AsyncRead, AsyncWrite};
+
+#[derive(Parser, Clone, Debug, Serialize, Deserialize)]
+pub struct S3Opt {
+    #[clap(
+        long = "config",
+        help = "Config file for the S3 backup store. Credentials are read from the \
+        AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY and optionally AWS_SESSION_TOKEN env vars."
+    )]
+    config: PathBuf,
+}
+
+impl S3Opt {
+    pub fn new(config: PathBuf) -> Self {
+        Self { config }
+    }
+}
+
+impl FromStr for S3Opt {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(S3Opt {
+            config: PathBuf::from(s),
+        })
+    }
+}
+
+/// A BackupStorage that talks to S3 or an S3-compatible object store directly, without relying
+/// on external command line tools. See `S3Config`.
+///
+/// Layout under the configured prefix is the same as that of `LocalFs`, so a backup can be
+/// moved between the two by copying the files over.
+pub struct S3 {
+    client: Arc<S3Client>,
+}
+
+impl S3 {
+    const METADATA_BACKUP_DIR: &'static str = "metadata_backup";
+    const METADATA_DIR: &'static str = "metadata";
+
+    pub fn new(config: S3Config, credentials: Credentials) -> Self {
+        Self {
+            client: Arc::new(S3Client::new(config, credentials)),
+        }
+    }
+
+    pub async fn new_with_opt(opt: S3Opt) -> Result<Self> {
+        let config = S3Config::load_from_file(&opt.config).await?;
+
+        Ok(Self::new(config, Credentials::from_env()?))
+    }
+
+    fn key(&self, file_handle: &FileHandleRef) -> String {
+        self.client.config().key(file_handle)
+    }
+}
+
+#[async_trait]
+impl BackupStorage for S3 {
+    async fn create_backup(&self, name: &ShellSafeName) -> Result<BackupHandle> {
+        // Nothing to create, "folders" are just key prefixes.
+        Ok(name.to_string())
+    }
+
+    async fn create_for_write(
+        &self,
+        backup_handle: &BackupHandleRef,
+        name: &ShellSafeName,
+    ) -> Result<(FileHandle, Box<dyn AsyncWrite + Send + Unpin>)> {
+        let file_handle = Path::new(backup_handle)
+            .join(name.as_ref())
+            .path_to_string()?;
+        let key = self.key(&file_handle);
+        ensure!(
+            !self.client.object_exists(&key).await?,
+            "File {} already exists.",
+            file_handle,
+        );
+        let sink = S3UploadSink::new(self.client.clone(), key);
+        Ok((file_handle, Box::new(sink)))
+    }
+
+    async fn open_for_read(
+        &self,
+        file_handle: &FileHandleRef,
+    ) -> Result<Box<dyn AsyncRead + Send + Unpin>> {
+        let reader = self.client.get_object(&self.key(file_handle)).await?;
+        Ok(Box::new(reader))
+    }
+
+    async fn list_metadata_files(&self) -> Result<Vec<FileHandle>> {
+        let prefix = self.key(&format!("{}/", Self::METADATA_DIR));
+        let rel_path = Path::new(Self::METADATA_DIR);
+
+        self.client
+            .list_objects(&prefix)
+            .await?
+            .into_iter()
+            .map(|key| {
+                let name = key.strip_prefix(&prefix).unwrap_or(&key);
+                rel_path.join(name).path_to_string()
+            })
+            .collect()
+    }
+
+    /// file_handle are expected to be the return results from list_metadata_files
+    /// file_handle is a path with `metadata` in the path, Ex: metadata/epoch_ending_1.meta
+    async fn backup_metadata_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        let name = Path::new(file_handle)
+            .file_name()
+            .and_then(OsStr::to_str)
+            .ok_or_else(|| format_err!("cannot extract filename from {}", file_handle))?;
+        let backup_file_handle = Path::new(Self::METADATA_BACKUP_DIR)
+            .join(name)
+            .path_to_string()?;
+
+        // S3 has no rename, copy then delete.
+        let key = self.key(file_handle);
+        self.client
+            .copy_object(&key, &self.key(&backup_file_handle))
+            .await?;
+        self.client.delete_object(&key).await
+    }
+
+    async fn delete_file(&self, file_handle: &FileHandleRef) -> Result<()> {
+        self.client.delete_object(&self.key(file_handle)).await
+    }
+
+    async fn save_metadata_lines(
+        &self,
+        name: &ShellSafeName,
+        lines: &[TextLine],
+    ) -> Result<FileHandle> {
+        let content = lines
+            .iter()
+            .map(|e| e.as_ref())
+            .collect::<Vec<&str>>()
+            .join("");
+        let file_handle = Path::new(Self::METADATA_DIR)
+            .join(name.as_ref())
+            .path_to_string()?;
+        self.client
+            .put_object(&self.key(&file_handle), Bytes::from(content))
+            .await?;
+        Ok(file_handle)
+    }
+}
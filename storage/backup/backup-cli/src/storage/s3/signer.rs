@@ -0,0 +1,161 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! AWS Signature Version 4 request signing, see
+//! https://docs.aws.amazon.com/IAM/latest/UserGuide/create-signed-request.html
+
+use anyhow::{format_err, Result};
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use sha2_0_10_6::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+type HmacSha256 = Hmac<Sha256>;
+
+const ALGORITHM: &str = "AWS4-HMAC-SHA256";
+const SERVICE: &str = "s3";
+
+/// Everything except the unreserved characters of RFC 3986.
+const URI_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+pub fn uri_encode(s: &str) -> String {
+    utf8_percent_encode(s, URI_ENCODE_SET).to_string()
+}
+
+/// Encodes each segment of a path, keeping the slashes.
+pub fn uri_encode_path(path: &str) -> String {
+    path.split('/')
+        .map(uri_encode)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+#[derive(Clone)]
+pub struct Credentials {
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    pub session_token: Option<String>,
+}
+
+impl Credentials {
+    pub fn from_env() -> Result<Self> {
+        let var = |key: &str| {
+            std::env::var(key).map_err(|e| format_err!("Failed to read env var {}: {}", key, e))
+        };
+        Ok(Self {
+            access_key_id: var("AWS_ACCESS_KEY_ID")?,
+            secret_access_key: var("AWS_SECRET_ACCESS_KEY")?,
+            session_token: std::env::var("AWS_SESSION_TOKEN").ok(),
+        })
+    }
+}
+
+/// The parts of a request covered by the signature.
+pub struct RequestToSign<'a> {
+    pub method: &'a str,
+    pub host: &'a str,
+    /// Already URI encoded absolute path.
+    pub encoded_path: &'a str,
+    /// Decoded query parameters.
+    pub query: &'a [(&'a str, String)],
+    /// Headers other than host, x-amz-date, x-amz-content-sha256 and x-amz-security-token, which
+    /// are always signed.
+    pub headers: &'a [(&'a str, String)],
+    pub payload_sha256: &'a str,
+}
+
+/// Returns all headers that need to be attached to the request, including "authorization".
+pub fn sign(
+    credentials: &Credentials,
+    region: &str,
+    now: DateTime<Utc>,
+    req: &RequestToSign,
+) -> Vec<(String, String)> {
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+
+    let mut headers: BTreeMap<String, String> = req
+        .headers
+        .iter()
+        .map(|(k, v)| (k.to_lowercase(), v.trim().to_string()))
+        .collect();
+    headers.insert("host".to_string(), req.host.to_string());
+    headers.insert("x-amz-date".to_string(), amz_date.clone());
+    headers.insert(
+        "x-amz-content-sha256".to_string(),
+        req.payload_sha256.to_string(),
+    );
+    if let Some(token) = &credentials.session_token {
+        headers.insert("x-amz-security-token".to_string(), token.clone());
+    }
+
+    let mut query = req
+        .query
+        .iter()
+        .map(|(k, v)| (uri_encode(k), uri_encode(v)))
+        .collect::<Vec<_>>();
+    query.sort();
+    let canonical_query = query
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("&");
+    let canonical_headers: String = headers
+        .iter()
+        .map(|(k, v)| format!("{}:{}\n", k, v))
+        .collect();
+    let signed_headers = headers.keys().cloned().collect::<Vec<_>>().join(";");
+
+    let canonical_request = format!(
+        "{}\n{}\n{}\n{}\n{}\n{}",
+        req.method,
+        req.encoded_path,
+        canonical_query,
+        canonical_headers,
+        signed_headers,
+        req.payload_sha256,
+    );
+    let scope = format!("{}/{}/{}/aws4_request", date, region, SERVICE);
+    let string_to_sign = format!(
+        "{}\n{}\n{}\n{}",
+        ALGORITHM,
+        amz_date,
+        scope,
+        sha256_hex(canonical_request.as_bytes()),
+    );
+
+    let signing_key = [region, SERVICE, "aws4_request"].iter().fold(
+        hmac_sha256(
+            format!("AWS4{}", credentials.secret_access_key).as_bytes(),
+            date.as_bytes(),
+        ),
+        |key, data| hmac_sha256(&key, data.as_bytes()),
+    );
+    let signature = hex::encode(hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+    headers.remove("host");
+    let mut res = headers.into_iter().collect::<Vec<_>>();
+    res.push((
+        "authorization".to_string(),
+        format!(
+            "{} Credential={}/{}, SignedHeaders={}, Signature={}",
+            ALGORITHM, credentials.access_key_id, scope, signed_headers, signature,
+        ),
+    ));
+    res
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut mac = HmacSha256::new_from_slice(key).expect("HMAC accepts keys of any size.");
+    mac.update(data);
+    mac.finalize().into_bytes().to_vec()
+}
@@ -0,0 +1,163 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{storage::s3::client::S3Client, utils::error_notes::ErrorNotes};
+use anyhow::{format_err, Result};
+use aptos_logger::warn;
+use bytes::{Bytes, BytesMut};
+use futures::{
+    ready,
+    stream::{self, StreamExt, TryStreamExt},
+    task::{Context, Poll},
+    Future,
+};
+use std::{io, pin::Pin, sync::Arc};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, DuplexStream},
+    sync::oneshot,
+    task::JoinHandle,
+};
+
+/// Size of the in-memory pipe between the writer and the uploading task.
+const PIPE_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// An `AsyncWrite` that streams what's written to it into an S3 object.
+///
+/// Bytes are piped to a background task which cuts them into parts and uploads them concurrently
+/// with a multipart upload (or a single PUT for small files). The object only becomes visible
+/// once `shutdown()` succeeds; if the sink is dropped without being shut down, the upload is
+/// aborted.
+pub(super) struct S3UploadSink {
+    pipe: DuplexStream,
+    shutdown_tx: Option<oneshot::Sender<()>>,
+    upload: Option<JoinHandle<Result<()>>>,
+}
+
+impl S3UploadSink {
+    pub fn new(client: Arc<S3Client>, key: String) -> Self {
+        let (pipe, reader) = tokio::io::duplex(PIPE_BUFFER_SIZE);
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let upload = tokio::spawn(async move {
+            upload(client, &key, reader, shutdown_rx)
+                .await
+                .err_notes(&key)
+        });
+        Self {
+            pipe,
+            shutdown_tx: Some(shutdown_tx),
+            upload: Some(upload),
+        }
+    }
+}
+
+impl AsyncWrite for S3UploadSink {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.pipe).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.pipe).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        if self.shutdown_tx.is_some() {
+            ready!(Pin::new(&mut self.pipe).poll_shutdown(cx))?;
+            // Tells the upload task the EOF it sees is intended. It might have already quit due
+            // to an error, which will be surfaced by joining it.
+            let _ = self.shutdown_tx.take().unwrap().send(());
+        }
+
+        match self.upload.as_mut() {
+            Some(upload) => {
+                let res = ready!(Pin::new(upload).poll(cx));
+                self.upload = None;
+                Poll::Ready(match res {
+                    Ok(Ok(())) => Ok(()),
+                    Ok(Err(e)) => Err(io::Error::new(io::ErrorKind::Other, e)),
+                    Err(e) => Err(io::Error::new(io::ErrorKind::Other, e)),
+                })
+            },
+            None => Poll::Ready(Ok(())),
+        }
+    }
+}
+
+async fn upload(
+    client: Arc<S3Client>,
+    key: &str,
+    mut reader: DuplexStream,
+    shutdown_rx: oneshot::Receiver<()>,
+) -> Result<()> {
+    let part_size = client.config().part_size;
+    let first_part = read_part(&mut reader, part_size).await?;
+    if first_part.len() < part_size {
+        ensure_shut_down(shutdown_rx, key).await?;
+        return client.put_object(key, first_part).await;
+    }
+
+    let upload_id = client.create_multipart_upload(key).await?;
+    let res = async {
+        let etags = upload_parts(&client, key, &upload_id, first_part, reader).await?;
+        ensure_shut_down(shutdown_rx, key).await?;
+        client
+            .complete_multipart_upload(key, &upload_id, &etags)
+            .await
+    }
+    .await;
+
+    if res.is_err() {
+        if let Err(e) = client.abort_multipart_upload(key, &upload_id).await {
+            // Leftover parts are cleaned up by the bucket lifecycle rules if there are any.
+            warn!(key = key, upload_id = upload_id, error = %e, "Failed to abort upload.");
+        }
+    }
+    res
+}
+
+/// Uploads `first_part` followed by the rest of the data coming out of `reader`, returning the
+/// ETags of all parts in order.
+async fn upload_parts(
+    client: &S3Client,
+    key: &str,
+    upload_id: &str,
+    first_part: Bytes,
+    reader: DuplexStream,
+) -> Result<Vec<String>> {
+    let part_size = client.config().part_size;
+    let rest = stream::try_unfold(reader, move |mut reader| async move {
+        let part = read_part(&mut reader, part_size).await?;
+        Result::<_>::Ok((!part.is_empty()).then_some((part, reader)))
+    });
+
+    stream::once(async { Ok(first_part) })
+        .chain(rest)
+        .enumerate()
+        .map(|(idx, part)| async move {
+            // part numbers start from 1
+            client.upload_part(key, upload_id, idx + 1, part?).await
+        })
+        .buffered(client.config().concurrent_part_uploads)
+        .try_collect()
+        .await
+}
+
+/// Reads until `size` bytes are read or EOF is hit.
+async fn read_part<R: AsyncRead + Unpin>(reader: &mut R, size: usize) -> Result<Bytes> {
+    let mut buf = BytesMut::with_capacity(size);
+    while buf.len() < size {
+        if reader.read_buf(&mut buf).await? == 0 {
+            break;
+        }
+    }
+    Ok(buf.freeze())
+}
+
+async fn ensure_shut_down(shutdown_rx: oneshot::Receiver<()>, key: &str) -> Result<()> {
+    shutdown_rx
+        .await
+        .map_err(|_| format_err!("Writer of {} dropped without being shut down.", key))
+}
@@ -48,6 +48,40 @@ pub async fn test_write_and_read_impl(
     }
 }
 
+pub async fn test_delete_file_impl(store: Box<dyn BackupStorage>) {
+    let backup_handle = store
+        .create_backup(&"backup".parse().unwrap())
+        .await
+        .unwrap();
+    let mut handles = Vec::new();
+    for name in ["to_delete", "to_keep"] {
+        let (handle, mut file) = store
+            .create_for_write(&backup_handle, &name.parse().unwrap())
+            .await
+            .unwrap();
+        file.write_all(name.as_bytes()).await.unwrap();
+        file.shutdown().await.unwrap();
+        handles.push(handle);
+    }
+
+    store.delete_file(&handles[0]).await.unwrap();
+
+    let read = |handle: String| {
+        let store = &store;
+        async move {
+            let mut buf = Vec::new();
+            store
+                .open_for_read(&handle)
+                .await?
+                .read_to_end(&mut buf)
+                .await?;
+            Result::<_>::Ok(buf)
+        }
+    };
+    assert!(read(handles[0].clone()).await.is_err());
+    assert_eq!(read(handles[1].clone()).await.unwrap(), b"to_keep");
+}
+
 pub fn arb_backups(
 ) -> impl Strategy<Value = HashMap<ShellSafeName, HashMap<ShellSafeName, Vec<u8>>>> {
     hash_map(
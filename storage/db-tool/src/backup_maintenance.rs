@@ -2,8 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 use anyhow::Result;
 use aptos_backup_cli::{
-    coordinators::backup::BackupCompactor, metadata::cache::MetadataCacheOpt,
-    storage::DBToolStorageOpt, utils::ConcurrentDownloadsOpt,
+    coordinators::{
+        backup::BackupCompactor,
+        retention::{RetentionCoordinator, RetentionPolicyOpt},
+    },
+    metadata::cache::MetadataCacheOpt,
+    storage::DBToolStorageOpt,
+    utils::ConcurrentDownloadsOpt,
 };
 use clap::{Parser, Subcommand};
 
@@ -14,6 +19,8 @@ pub enum Command {
     Compact(CompactionOpt),
     #[clap(about = "Cleanup the backup metadata files")]
     Cleanup(CleanupOpt),
+    #[clap(about = "Remove backups no longer needed according to a retention policy")]
+    Retain(RetentionOpt),
 }
 
 #[derive(Parser)]
@@ -48,6 +55,21 @@ pub struct CleanupOpt {
     pub storage: DBToolStorageOpt,
 }
 
+#[derive(Parser)]
+pub struct RetentionOpt {
+    #[clap(flatten)]
+    pub policy: RetentionPolicyOpt,
+    #[clap(flatten)]
+    pub metadata_cache_opt: MetadataCacheOpt,
+    #[clap(flatten)]
+    pub storage: DBToolStorageOpt,
+    #[clap(flatten)]
+    pub concurrent_downloads: ConcurrentDownloadsOpt,
+    /// Only print what would be removed, without touching the storage.
+    #[clap(long)]
+    pub dry_run: bool,
+}
+
 impl Command {
     pub async fn run(self) -> Result<()> {
         match self {
@@ -63,6 +85,18 @@ impl Command {
                 );
                 compactor.run().await?
             },
+            Command::Retain(opt) => {
+                let plan = RetentionCoordinator::new(
+                    opt.storage.init_storage().await?,
+                    opt.metadata_cache_opt,
+                    opt.policy,
+                    opt.concurrent_downloads.get(),
+                    opt.dry_run,
+                )
+                .run()
+                .await?;
+                println!("{}", plan);
+            },
             Command::Cleanup(_) => {
                 // TODO: add cleanup logic for removing obsolete metadata files
             },
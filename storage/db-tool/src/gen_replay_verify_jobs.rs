@@ -76,6 +76,7 @@ impl Opt {
             epoch: latest_epoch,
             version: global_end_version,
             manifest: "".to_string(),
+            encryption_key_id: None,
         };
         let job_ranges = metadata_view
             .all_state_snapshots()
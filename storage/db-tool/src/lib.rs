@@ -38,6 +38,8 @@ pub enum DBTool {
 
     #[clap(subcommand)]
     Restore(restore::Command),
+
+    Verify(db_debugger::verify::Cmd),
 }
 
 impl DBTool {
@@ -54,6 +56,7 @@ impl DBTool {
             },
             DBTool::GenReplayVerifyJobs(cmd) => cmd.run().await,
             DBTool::Restore(cmd) => cmd.run().await,
+            DBTool::Verify(cmd) => Ok(cmd.run()?),
         }
     }
 }
@@ -46,6 +46,15 @@ fn test_various_cmd_parsing() {
         ".",
     ]);
 
+    run_cmd(&[
+        "aptos-db-tool",
+        "verify",
+        "--db-dir",
+        ".",
+        "--secondary-db-dir",
+        ".",
+    ]);
+
     run_cmd(&["aptos-db-tool", "backup", "verify", "--local-fs-dir", "."]);
     run_cmd(&[
         "aptos-db-tool",
@@ -588,6 +597,32 @@ mod dbtool_tests {
         rt.shutdown_timeout(Duration::from_secs(1));
     }
 
+    #[test]
+    fn test_verify_db_of_running_node() {
+        let db_dir = TempPath::new();
+        let secondary_db_dir = TempPath::new();
+        // Keep the DB open for writing, as a running node does.
+        let _db = test_execution_with_storage_impl_inner(false, db_dir.path());
+
+        Runtime::new()
+            .unwrap()
+            .block_on(
+                DBTool::try_parse_from([
+                    "aptos-db-tool",
+                    "verify",
+                    "--db-dir",
+                    db_dir.path().to_str().unwrap(),
+                    "--secondary-db-dir",
+                    secondary_db_dir.path().to_str().unwrap(),
+                    "--chunk-size",
+                    "5",
+                ])
+                .unwrap()
+                .run(),
+            )
+            .unwrap();
+    }
+
     #[test]
     #[ignore]
     // TODO(grao): Re-enable this test.
@@ -6,9 +6,10 @@ use aptos_config::config::internal_indexer_db_config::InternalIndexerDBConfig;
 use aptos_db_indexer_schemas::{
     metadata::{MetadataKey, MetadataValue, StateSnapshotProgress},
     schema::{
-        event_by_key::EventByKeySchema, event_by_version::EventByVersionSchema,
-        indexer_metadata::InternalIndexerMetadataSchema, state_keys::StateKeysSchema,
-        transaction_by_account::TransactionByAccountSchema,
+        event_by_key::EventByKeySchema, event_by_type::EventByTypeSchema,
+        event_by_version::EventByVersionSchema, indexer_metadata::InternalIndexerMetadataSchema,
+        state_keys::StateKeysSchema, transaction_by_account::TransactionByAccountSchema,
+        transaction_by_entry_function::TransactionByEntryFunctionSchema,
     },
     utils::{
         error_if_too_many_requested, get_first_seq_num_and_limit, AccountTransactionVersionIter,
@@ -28,9 +29,16 @@ use aptos_types::{
         state_key::{prefix::StateKeyPrefix, StateKey},
         state_value::StateValue,
     },
-    transaction::{AccountTransactionsWithProof, Transaction, Version},
+    transaction::{
+        AccountTransactionsWithProof, EntryFunction, Multisig, MultisigTransactionPayload,
+        Transaction, TransactionPayload, TransactionWithProof, Version,
+    },
     write_set::{TransactionWrite, WriteSet},
 };
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag, TypeTag},
+};
 use std::{
     cmp::min,
     sync::{
@@ -114,6 +122,14 @@ impl InternalIndexerDB {
         self.get_version(&MetadataKey::TransactionVersion)
     }
 
+    pub fn get_event_by_type_version(&self) -> Result<Option<Version>> {
+        self.get_version(&MetadataKey::EventByTypeVersion)
+    }
+
+    pub fn get_transaction_by_entry_function_version(&self) -> Result<Option<Version>> {
+        self.get_version(&MetadataKey::TransactionByEntryFunctionVersion)
+    }
+
     pub fn event_enabled(&self) -> bool {
         self.config.enable_event
     }
@@ -126,6 +142,14 @@ impl InternalIndexerDB {
         self.config.enable_statekeys
     }
 
+    pub fn event_by_type_enabled(&self) -> bool {
+        self.config.enable_event_by_type
+    }
+
+    pub fn transaction_by_entry_function_enabled(&self) -> bool {
+        self.config.enable_transaction_by_entry_function
+    }
+
     pub fn get_inner_db_ref(&self) -> &Arc<DB> {
         &self.db
     }
@@ -227,6 +251,71 @@ impl InternalIndexerDB {
         Ok(result)
     }
 
+    /// Given the `struct_tag` of an event type, returns the events of that type identified by
+    /// transaction version and index among all events emitted by the same transaction, starting
+    /// at the `start_event_index`-th event of that type emitted by the transaction at
+    /// `start_version`. Result won't contain records with a transaction version >
+    /// `ledger_version` and is in ascending order.
+    pub fn lookup_events_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_version: Version,
+        start_event_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<
+        Vec<(
+            Version, // transaction version it belongs to
+            u64,     // index among events for the same transaction
+        )>,
+    > {
+        ensure!(limit > 0, "limit should > 0, got {}", limit);
+        let mut iter = self.db.iter::<EventByTypeSchema>()?;
+        iter.seek(&(struct_tag.clone(), start_version, 0))?;
+
+        let mut num_skipped = 0;
+        let mut result: Vec<(Version, u64)> = Vec::new();
+        for res in iter {
+            let ((tag, ver, idx), ()) = res?;
+            if tag != *struct_tag || ver > ledger_version || result.len() as u64 == limit {
+                break;
+            }
+            if ver == start_version && num_skipped < start_event_index {
+                num_skipped += 1;
+                continue;
+            }
+            result.push((ver, idx));
+        }
+
+        Ok(result)
+    }
+
+    /// Given an entry function, returns the versions of the transactions calling it, starting at
+    /// `start_version`. Result won't contain versions > `ledger_version` and is in ascending
+    /// order.
+    pub fn lookup_transactions_by_entry_function(
+        &self,
+        module_id: &ModuleId,
+        function_name: &Identifier,
+        start_version: Version,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<Version>> {
+        let mut iter = self.db.iter::<TransactionByEntryFunctionSchema>()?;
+        iter.seek(&(module_id.clone(), function_name.clone(), start_version))?;
+
+        let mut result = Vec::new();
+        for res in iter.take(limit as usize) {
+            let ((module, function, ver), ()) = res?;
+            if module != *module_id || function != *function_name || ver > ledger_version {
+                break;
+            }
+            result.push(ver);
+        }
+
+        Ok(result)
+    }
+
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn get_restore_version_and_progress(
         &self,
@@ -376,6 +465,18 @@ impl DBIndexer {
                         &version,
                     )?;
                 }
+                if self.indexer_db.transaction_by_entry_function_enabled() {
+                    if let Some(entry_function) = get_entry_function(txn.payload()) {
+                        batch.put::<TransactionByEntryFunctionSchema>(
+                            &(
+                                entry_function.module().clone(),
+                                entry_function.function().to_owned(),
+                                version,
+                            ),
+                            &(),
+                        )?;
+                    }
+                }
             }
 
             if self.indexer_db.event_enabled() {
@@ -397,6 +498,19 @@ impl DBIndexer {
                 });
             }
 
+            if self.indexer_db.event_by_type_enabled() {
+                events.iter().enumerate().for_each(|(idx, event)| {
+                    if let TypeTag::Struct(struct_tag) = event.type_tag() {
+                        batch
+                            .put::<EventByTypeSchema>(
+                                &(struct_tag.as_ref().clone(), version, idx as u64),
+                                &(),
+                            )
+                            .expect("Failed to put events by type to a batch");
+                    }
+                });
+            }
+
             if self.indexer_db.statekeys_enabled() {
                 writeset.iter().for_each(|(state_key, write_op)| {
                     if write_op.is_creation() || write_op.is_modification() {
@@ -431,6 +545,18 @@ impl DBIndexer {
                 &MetadataValue::Version(version - 1),
             )?;
         }
+        if self.indexer_db.event_by_type_enabled() {
+            batch.put::<InternalIndexerMetadataSchema>(
+                &MetadataKey::EventByTypeVersion,
+                &MetadataValue::Version(version - 1),
+            )?;
+        }
+        if self.indexer_db.transaction_by_entry_function_enabled() {
+            batch.put::<InternalIndexerMetadataSchema>(
+                &MetadataKey::TransactionByEntryFunctionVersion,
+                &MetadataValue::Version(version - 1),
+            )?;
+        }
         batch.put::<InternalIndexerMetadataSchema>(
             &MetadataKey::LatestVersion,
             &MetadataValue::Version(version - 1),
@@ -469,6 +595,38 @@ impl DBIndexer {
         Ok(AccountTransactionsWithProof::new(txns_with_proofs))
     }
 
+    pub fn get_transactions_by_entry_function(
+        &self,
+        module_id: &ModuleId,
+        function_name: &Identifier,
+        start_version: Version,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<Vec<TransactionWithProof>> {
+        self.indexer_db
+            .ensure_cover_ledger_version(ledger_version)?;
+        error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+        self.indexer_db
+            .lookup_transactions_by_entry_function(
+                module_id,
+                function_name,
+                start_version,
+                limit,
+                ledger_version,
+            )?
+            .into_iter()
+            .map(|txn_version| {
+                self.main_db_reader.get_transaction_by_version(
+                    txn_version,
+                    ledger_version,
+                    include_events,
+                )
+            })
+            .collect()
+    }
+
     pub fn get_prefixed_state_value_iterator(
         &self,
         key_prefix: &StateKeyPrefix,
@@ -572,4 +730,46 @@ impl DBIndexer {
 
         Ok(events_with_version)
     }
+
+    pub fn get_events_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_version: Version,
+        start_event_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>> {
+        self.indexer_db
+            .ensure_cover_ledger_version(ledger_version)?;
+        error_if_too_many_requested(limit, MAX_REQUEST_LIMIT)?;
+
+        self.indexer_db
+            .lookup_events_by_type(
+                struct_tag,
+                start_version,
+                start_event_index,
+                limit,
+                ledger_version,
+            )?
+            .into_iter()
+            .map(|(ver, idx)| {
+                let event = self
+                    .main_db_reader
+                    .get_event_by_version_and_index(ver, idx)?;
+                Ok(EventWithVersion::new(ver, event))
+            })
+            .collect()
+    }
+}
+
+/// Returns the entry function called by the given payload (if any)
+fn get_entry_function(payload: &TransactionPayload) -> Option<&EntryFunction> {
+    match payload {
+        TransactionPayload::EntryFunction(entry_function) => Some(entry_function),
+        TransactionPayload::Multisig(Multisig {
+            transaction_payload: Some(MultisigTransactionPayload::EntryFunction(entry_function)),
+            ..
+        }) => Some(entry_function),
+        _ => None,
+    }
 }
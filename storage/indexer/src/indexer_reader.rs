@@ -13,7 +13,11 @@ use aptos_types::{
         state_value::StateValue,
         table::{TableHandle, TableInfo},
     },
-    transaction::{AccountTransactionsWithProof, Version},
+    transaction::{AccountTransactionsWithProof, TransactionWithProof, Version},
+};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag},
 };
 use std::sync::Arc;
 
@@ -44,6 +48,20 @@ impl IndexerReader for IndexerReaders {
         self.db_indexer_reader.is_some()
     }
 
+    fn is_event_by_type_index_enabled(&self) -> bool {
+        self.db_indexer_reader.as_ref().map_or(false, |db_indexer| {
+            db_indexer.indexer_db.event_by_type_enabled()
+        })
+    }
+
+    fn is_transaction_by_entry_function_index_enabled(&self) -> bool {
+        self.db_indexer_reader.as_ref().map_or(false, |db_indexer| {
+            db_indexer
+                .indexer_db
+                .transaction_by_entry_function_enabled()
+        })
+    }
+
     fn get_table_info(&self, handle: TableHandle) -> anyhow::Result<Option<TableInfo>> {
         if let Some(table_info_reader) = &self.table_info_reader {
             return Ok(table_info_reader.get_table_info_with_retry(handle)?);
@@ -106,6 +124,30 @@ impl IndexerReader for IndexerReaders {
         anyhow::bail!("DB indexer reader is not available")
     }
 
+    fn get_events_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_version: Version,
+        start_event_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> anyhow::Result<Vec<EventWithVersion>> {
+        if let Some(db_indexer_reader) = &self.db_indexer_reader {
+            if db_indexer_reader.indexer_db.event_by_type_enabled() {
+                return Ok(db_indexer_reader.get_events_by_type(
+                    struct_tag,
+                    start_version,
+                    start_event_index,
+                    limit,
+                    ledger_version,
+                )?);
+            } else {
+                anyhow::bail!("Internal event by type index is not enabled")
+            }
+        }
+        anyhow::bail!("DB indexer reader is not available")
+    }
+
     fn get_account_transactions(
         &self,
         address: AccountAddress,
@@ -130,6 +172,35 @@ impl IndexerReader for IndexerReaders {
         anyhow::bail!("DB indexer reader is not available")
     }
 
+    fn get_transactions_by_entry_function(
+        &self,
+        module_id: &ModuleId,
+        function_name: &Identifier,
+        start_version: Version,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> anyhow::Result<Vec<TransactionWithProof>> {
+        if let Some(db_indexer_reader) = &self.db_indexer_reader {
+            if db_indexer_reader
+                .indexer_db
+                .transaction_by_entry_function_enabled()
+            {
+                return Ok(db_indexer_reader.get_transactions_by_entry_function(
+                    module_id,
+                    function_name,
+                    start_version,
+                    limit,
+                    include_events,
+                    ledger_version,
+                )?);
+            } else {
+                anyhow::bail!("Internal transaction by entry function index is not enabled")
+            }
+        }
+        anyhow::bail!("DB indexer reader is not available")
+    }
+
     fn get_prefixed_state_value_iterator(
         &self,
         key_prefix: &StateKeyPrefix,
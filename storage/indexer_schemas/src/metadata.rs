@@ -38,6 +38,8 @@ pub enum MetadataKey {
     EventVersion,
     StateVersion,
     TransactionVersion,
+    EventByTypeVersion,
+    TransactionByEntryFunctionVersion,
 }
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
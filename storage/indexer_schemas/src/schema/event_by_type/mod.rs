@@ -0,0 +1,78 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for an event index via which all the events of a
+//! given type (represented by a <txn_version, event_idx> tuple so that each can be fetched from
+//! `EventSchema`) can be found by the fully qualified struct tag of the event type.
+//!
+//! The struct tag is prefixed by its length, so that the keys of different event types never
+//! interleave and all the events of the same type are sorted by version.
+//!
+//! ```text
+//! |<--------------------key-------------------->|<-value->|
+//! | tag_len | struct_tag | txn_ver | event_idx |   ()    |
+//! ```
+
+use crate::{schema::EVENT_BY_TYPE_CF_NAME, utils::ensure_slice_len_eq};
+use anyhow::{ensure, Result};
+use aptos_schemadb::{
+    define_pub_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use move_core_types::language_storage::StructTag;
+use std::mem::size_of;
+
+define_pub_schema!(EventByTypeSchema, Key, (), EVENT_BY_TYPE_CF_NAME);
+
+type Index = u64;
+type Key = (StructTag, Version, Index);
+
+impl KeyCodec<EventByTypeSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref struct_tag, version, index) = *self;
+
+        let struct_tag_bytes = bcs::to_bytes(struct_tag)?;
+        let mut encoded = Vec::with_capacity(
+            size_of::<u32>() + struct_tag_bytes.len() + size_of::<(Version, Index)>(),
+        );
+        encoded.write_u32::<BigEndian>(struct_tag_bytes.len() as u32)?;
+        encoded.extend(struct_tag_bytes);
+        encoded.write_u64::<BigEndian>(version)?;
+        encoded.write_u64::<BigEndian>(index)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(mut data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() >= size_of::<u32>(),
+            "Unexpected data len {}.",
+            data.len()
+        );
+        let struct_tag_len = data.read_u32::<BigEndian>()? as usize;
+        ensure_slice_len_eq(data, struct_tag_len + size_of::<(Version, Index)>())?;
+
+        let struct_tag = bcs::from_bytes(&data[..struct_tag_len])?;
+        let mut data = &data[struct_tag_len..];
+        let version = data.read_u64::<BigEndian>()?;
+        let index = data.read_u64::<BigEndian>()?;
+
+        Ok((struct_tag, version, index))
+    }
+}
+
+impl ValueCodec<EventByTypeSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
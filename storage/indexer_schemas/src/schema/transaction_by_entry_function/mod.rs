@@ -0,0 +1,78 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module defines physical storage schema for a transaction index via which the versions of
+//! all the transactions calling a given entry function can be found. With the version one can
+//! resort to `TransactionSchema` for the transaction content.
+//!
+//! The entry function is prefixed by its length, so that the keys of different entry functions
+//! never interleave and all the calls to the same entry function are sorted by version.
+//!
+//! ```text
+//! |<------------------------key------------------------>|<-value->|
+//! | function_len | module_id | function_name | txn_ver |   ()    |
+//! ```
+
+use crate::{schema::TRANSACTION_BY_ENTRY_FUNCTION_CF_NAME, utils::ensure_slice_len_eq};
+use anyhow::{ensure, Result};
+use aptos_schemadb::{
+    define_pub_schema,
+    schema::{KeyCodec, ValueCodec},
+};
+use aptos_types::transaction::Version;
+use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use std::mem::size_of;
+
+define_pub_schema!(
+    TransactionByEntryFunctionSchema,
+    Key,
+    (),
+    TRANSACTION_BY_ENTRY_FUNCTION_CF_NAME
+);
+
+type Key = (ModuleId, Identifier, Version);
+
+impl KeyCodec<TransactionByEntryFunctionSchema> for Key {
+    fn encode_key(&self) -> Result<Vec<u8>> {
+        let (ref module_id, ref function_name, version) = *self;
+
+        let function_bytes = bcs::to_bytes(&(module_id, function_name))?;
+        let mut encoded =
+            Vec::with_capacity(size_of::<u32>() + function_bytes.len() + size_of::<Version>());
+        encoded.write_u32::<BigEndian>(function_bytes.len() as u32)?;
+        encoded.extend(function_bytes);
+        encoded.write_u64::<BigEndian>(version)?;
+
+        Ok(encoded)
+    }
+
+    fn decode_key(mut data: &[u8]) -> Result<Self> {
+        ensure!(
+            data.len() >= size_of::<u32>(),
+            "Unexpected data len {}.",
+            data.len()
+        );
+        let function_len = data.read_u32::<BigEndian>()? as usize;
+        ensure_slice_len_eq(data, function_len + size_of::<Version>())?;
+
+        let (module_id, function_name) = bcs::from_bytes(&data[..function_len])?;
+        let version = (&data[function_len..]).read_u64::<BigEndian>()?;
+
+        Ok((module_id, function_name, version))
+    }
+}
+
+impl ValueCodec<TransactionByEntryFunctionSchema> for () {
+    fn encode_value(&self) -> Result<Vec<u8>> {
+        Ok(Vec::new())
+    }
+
+    fn decode_value(data: &[u8]) -> Result<Self> {
+        ensure_slice_len_eq(data, 0)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,309 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Chaos injection for the local swarm.
+//!
+//! There is no Chaos Mesh on a developer machine, so network chaos is translated into the faults
+//! each node injects into its own connections (see [`aptos_netcore::transport::fault_injection`]).
+//! The faults of a node are written to a file in its directory, which the node reloads
+//! periodically. Fault injection is only enabled on the nodes once chaos is injected.
+
+use crate::{
+    LocalNode, LocalSwarm, Result, SwarmChaos, SwarmNetEm, SwarmNetworkBandwidth,
+    SwarmNetworkDelay, SwarmNetworkLoss, SwarmNetworkPartition,
+};
+use aptos_config::config::{NodeConfig, OverrideNodeConfig};
+use aptos_logger::info;
+use aptos_netcore::transport::fault_injection::{FaultInjectionConfig, LinkFault};
+use aptos_sdk::types::PeerId;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::Path,
+    time::Duration,
+};
+
+const FAULT_INJECTION_CONFIG_FILE: &str = "fault_injection.yaml";
+
+/// Points all the networks of a node at the file the swarm writes the node's faults to
+pub(crate) fn enable_fault_injection(config: &mut NodeConfig, node_dir: &Path) {
+    let path = node_dir.join(FAULT_INJECTION_CONFIG_FILE);
+    for network in config
+        .validator_network
+        .iter_mut()
+        .chain(config.full_node_networks.iter_mut())
+    {
+        network.fault_injection_config_path = Some(path.clone());
+    }
+}
+
+/// Enables fault injection on the node (unless it's already enabled) and restarts it, as the
+/// transport of the node is only wrapped at startup. Returns true iff the node was restarted.
+fn enable_node_fault_injection(node: &mut LocalNode) -> Result<bool> {
+    let config = node.config();
+    if config
+        .validator_network
+        .iter()
+        .chain(config.full_node_networks.iter())
+        .any(|network| network.fault_injection_config_path.is_some())
+    {
+        return Ok(false);
+    }
+
+    let node_dir = node.base_dir();
+    let mut override_config = OverrideNodeConfig::load_config(node.config_path())?;
+    enable_fault_injection(override_config.override_config_mut(), &node_dir);
+    override_config.save_config(node.config_path())?;
+    enable_fault_injection(node.config_mut(), &node_dir);
+
+    info!("Restarting node {} to enable fault injection", node.name());
+    node.stop();
+    node.start()?;
+    Ok(true)
+}
+
+/// Converts a rate in megabits per second (as used by the Chaos Mesh templates)
+fn mbps_to_bytes_per_sec(rate_in_mbps: u64) -> Option<u64> {
+    Some(rate_in_mbps * 1_000_000 / 8).filter(|rate| *rate > 0)
+}
+
+/// Combines two faults applied to the same link
+fn combine(first: LinkFault, second: LinkFault) -> LinkFault {
+    LinkFault {
+        delay_ms: first.delay_ms + second.delay_ms,
+        jitter_ms: first.jitter_ms + second.jitter_ms,
+        loss_percentage: (first.loss_percentage + second.loss_percentage).min(100),
+        loss_correlation_percentage: first
+            .loss_correlation_percentage
+            .max(second.loss_correlation_percentage),
+        rate_bytes_per_sec: match (first.rate_bytes_per_sec, second.rate_bytes_per_sec) {
+            (Some(first), Some(second)) => Some(first.min(second)),
+            (first, second) => first.or(second),
+        },
+    }
+}
+
+/// The network faults between the nodes of the swarm
+#[derive(Debug, Default)]
+struct SwarmFaults {
+    // Faults of the bytes sent from the first peer to the second one
+    links: BTreeMap<(PeerId, PeerId), LinkFault>,
+    partitions: BTreeSet<(PeerId, PeerId)>,
+}
+
+impl SwarmFaults {
+    fn add_link_fault(&mut self, source: PeerId, target: PeerId, fault: LinkFault) {
+        if source != target {
+            let link_fault = self.links.entry((source, target)).or_default();
+            *link_fault = combine(*link_fault, fault);
+        }
+    }
+
+    fn add_partition(&mut self, first: PeerId, second: PeerId) {
+        self.partitions.insert((first, second));
+        self.partitions.insert((second, first));
+    }
+
+    /// Adds the faults of the chaos. Chaos applying to "all nodes" applies to all validators,
+    /// like in the k8s backend.
+    fn add_chaos(&mut self, chaos: &SwarmChaos, validators: &[PeerId]) {
+        match chaos {
+            SwarmChaos::Delay(SwarmNetworkDelay {
+                group_network_delays,
+            }) => {
+                for group in group_network_delays {
+                    let fault = LinkFault {
+                        delay_ms: group.latency_ms,
+                        jitter_ms: group.jitter_ms,
+                        ..LinkFault::default()
+                    };
+                    for source in &group.source_nodes {
+                        for target in &group.target_nodes {
+                            self.add_link_fault(*source, *target, fault);
+                            self.add_link_fault(*target, *source, fault);
+                        }
+                    }
+                }
+            },
+            SwarmChaos::Partition(SwarmNetworkPartition {
+                partition_percentage,
+            }) => {
+                let num_partitioned = validators.len() * *partition_percentage as usize / 100;
+                let (partitioned, others) = validators.split_at(num_partitioned);
+                for first in partitioned {
+                    for second in others {
+                        self.add_partition(*first, *second);
+                    }
+                }
+            },
+            SwarmChaos::Bandwidth(SwarmNetworkBandwidth {
+                group_network_bandwidths,
+            }) => {
+                for group in group_network_bandwidths {
+                    let fault = LinkFault {
+                        rate_bytes_per_sec: mbps_to_bytes_per_sec(group.rate),
+                        ..LinkFault::default()
+                    };
+                    for source in validators {
+                        for target in validators {
+                            self.add_link_fault(*source, *target, fault);
+                        }
+                    }
+                }
+            },
+            SwarmChaos::Loss(SwarmNetworkLoss {
+                loss_percentage,
+                correlation_percentage,
+            }) => {
+                let fault = LinkFault {
+                    loss_percentage: *loss_percentage,
+                    loss_correlation_percentage: *correlation_percentage,
+                    ..LinkFault::default()
+                };
+                for source in validators {
+                    for target in validators {
+                        self.add_link_fault(*source, *target, fault);
+                    }
+                }
+            },
+            SwarmChaos::NetEm(SwarmNetEm { group_netems }) => {
+                for group in group_netems {
+                    let fault = LinkFault {
+                        delay_ms: group.delay_latency_ms,
+                        jitter_ms: group.delay_jitter_ms,
+                        loss_percentage: group.loss_percentage,
+                        loss_correlation_percentage: group.loss_correlation_percentage,
+                        rate_bytes_per_sec: mbps_to_bytes_per_sec(group.rate_in_mbps),
+                    };
+                    for source in &group.source_nodes {
+                        for target in &group.target_nodes {
+                            self.add_link_fault(*source, *target, fault);
+                        }
+                    }
+                }
+            },
+            // Rejected when injected, there are no network faults for it
+            SwarmChaos::CpuStress(_) => {},
+        }
+    }
+
+    /// The faults the given node injects into the connections it dials
+    fn node_config(&self, peer_id: PeerId) -> FaultInjectionConfig {
+        let mut config = FaultInjectionConfig::default();
+        for ((source, target), fault) in &self.links {
+            if *source == peer_id {
+                config.peers.entry(*target).or_default().outbound = *fault;
+            } else if *target == peer_id {
+                config.peers.entry(*source).or_default().inbound = *fault;
+            }
+        }
+        for (first, second) in &self.partitions {
+            if *first == peer_id {
+                config.peers.entry(*second).or_default().partitioned = true;
+            }
+        }
+        config
+    }
+}
+
+impl LocalSwarm {
+    /// Writes the faults of all the injected chaos to the fault injection files of the nodes
+    pub(crate) fn apply_chaos(&self) -> Result<()> {
+        let validators: Vec<_> = self
+            .validators()
+            .map(|validator| validator.peer_id())
+            .collect();
+        let mut faults = SwarmFaults::default();
+        for chaos in self.chaoses() {
+            faults.add_chaos(chaos, &validators);
+        }
+
+        for node in self.validators().chain(self.fullnodes()) {
+            faults
+                .node_config(node.peer_id())
+                .save(&node.base_dir().join(FAULT_INJECTION_CONFIG_FILE))?;
+        }
+        Ok(())
+    }
+
+    /// Enables fault injection on the nodes that don't have it yet (i.e., on all the nodes when
+    /// chaos is first injected), and waits for the restarted nodes to be alive again.
+    pub(crate) async fn enable_fault_injection_on_all_nodes(&mut self) -> Result<()> {
+        let mut restarted = false;
+        for node in self.validators_mut() {
+            restarted |= enable_node_fault_injection(node)?;
+        }
+        for node in self.fullnodes_mut() {
+            restarted |= enable_node_fault_injection(node)?;
+        }
+        if restarted {
+            self.wait_all_alive(Duration::from_secs(60)).await?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GroupNetworkDelay;
+
+    #[test]
+    fn test_delay_applies_both_directions() {
+        let (first, second) = (PeerId::random(), PeerId::random());
+        let mut faults = SwarmFaults::default();
+        faults.add_chaos(
+            &SwarmChaos::Delay(SwarmNetworkDelay {
+                group_network_delays: vec![GroupNetworkDelay {
+                    name: "delay".to_string(),
+                    source_nodes: vec![first],
+                    target_nodes: vec![second],
+                    latency_ms: 100,
+                    jitter_ms: 0,
+                    correlation_percentage: 0,
+                }],
+            }),
+            &[first, second],
+        );
+
+        let link_faults = faults.node_config(first).peers[&second];
+        assert_eq!(link_faults.outbound.delay_ms, 100);
+        assert_eq!(link_faults.inbound.delay_ms, 100);
+        assert!(!link_faults.partitioned);
+    }
+
+    #[test]
+    fn test_partition_and_loss() {
+        let validators: Vec<_> = (0..4).map(|_| PeerId::random()).collect();
+        let mut faults = SwarmFaults::default();
+        faults.add_chaos(
+            &SwarmChaos::Partition(SwarmNetworkPartition {
+                partition_percentage: 50,
+            }),
+            &validators,
+        );
+        faults.add_chaos(
+            &SwarmChaos::Loss(SwarmNetworkLoss {
+                loss_percentage: 70,
+                correlation_percentage: 10,
+            }),
+            &validators,
+        );
+        faults.add_chaos(
+            &SwarmChaos::Loss(SwarmNetworkLoss {
+                loss_percentage: 50,
+                correlation_percentage: 20,
+            }),
+            &validators,
+        );
+
+        let config = faults.node_config(validators[0]);
+        assert!(!config.peers[&validators[1]].partitioned);
+        assert!(config.peers[&validators[2]].partitioned);
+        assert!(config.peers[&validators[3]].partitioned);
+        assert!(!config.peers.contains_key(&validators[0]));
+        for link_faults in config.peers.values() {
+            assert_eq!(link_faults.outbound.loss_percentage, 100);
+            assert_eq!(link_faults.inbound.loss_correlation_percentage, 20);
+        }
+    }
+}
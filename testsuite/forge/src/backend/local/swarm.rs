@@ -2,6 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use super::chaos::enable_fault_injection;
 use crate::{
     ChainInfo, FullNode, HealthCheckError, LocalNode, LocalVersion, Node, Swarm, SwarmChaos,
     SwarmExt, Validator, Version,
@@ -27,7 +28,7 @@ use aptos_sdk::{
 };
 use prometheus_http_query::response::{PromqlResult, Sample};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs,
     fs::File,
     io::Write,
@@ -99,6 +100,7 @@ pub struct LocalSwarm {
     root_account: Arc<LocalAccount>,
     chain_id: ChainId,
     root_key: ConfigKey<Ed25519PrivateKey>,
+    chaoses: HashSet<SwarmChaos>,
 
     launched: bool,
     #[allow(dead_code)]
@@ -259,6 +261,7 @@ impl LocalSwarm {
             root_account,
             chain_id: ChainId::test(),
             root_key,
+            chaoses: HashSet::new(),
             launched: false,
             guard,
         })
@@ -337,7 +340,7 @@ impl LocalSwarm {
     pub fn add_validator_fullnode(
         &mut self,
         version: &Version,
-        config: OverrideNodeConfig,
+        mut config: OverrideNodeConfig,
         validator_peer_id: PeerId,
     ) -> Result<PeerId> {
         let validator = self
@@ -357,6 +360,9 @@ impl LocalSwarm {
         let name = self.node_name_counter.to_string();
         let index = self.node_name_counter;
         self.node_name_counter += 1;
+        if !self.chaoses.is_empty() {
+            enable_fault_injection(config.override_config_mut(), &self.dir.join(&name));
+        }
         let fullnode_config = FullnodeNodeConfig::validator_fullnode(
             name,
             self.dir.as_ref(),
@@ -385,10 +391,17 @@ impl LocalSwarm {
         Ok(peer_id)
     }
 
-    fn add_fullnode(&mut self, version: &Version, config: OverrideNodeConfig) -> Result<PeerId> {
+    fn add_fullnode(
+        &mut self,
+        version: &Version,
+        mut config: OverrideNodeConfig,
+    ) -> Result<PeerId> {
         let name = self.node_name_counter.to_string();
         let index = self.node_name_counter;
         self.node_name_counter += 1;
+        if !self.chaoses.is_empty() {
+            enable_fault_injection(config.override_config_mut(), &self.dir.join(&name));
+        }
         let fullnode_config = FullnodeNodeConfig::public_fullnode(
             name,
             self.dir.as_ref(),
@@ -465,6 +478,10 @@ impl LocalSwarm {
     pub fn dir(&self) -> &Path {
         self.dir.as_ref()
     }
+
+    pub fn chaoses(&self) -> impl Iterator<Item = &SwarmChaos> {
+        self.chaoses.iter()
+    }
 }
 
 impl Drop for LocalSwarm {
@@ -587,16 +604,26 @@ impl Swarm for LocalSwarm {
         self.dir.display().to_string()
     }
 
-    async fn inject_chaos(&mut self, _chaos: SwarmChaos) -> Result<()> {
-        todo!()
+    async fn inject_chaos(&mut self, chaos: SwarmChaos) -> Result<()> {
+        if let SwarmChaos::CpuStress(_) = chaos {
+            bail!("CPU stress is not supported by the local swarm");
+        }
+        info!("Injecting chaos: {:?}", chaos);
+        self.chaoses.insert(chaos);
+        self.apply_chaos()?;
+        self.enable_fault_injection_on_all_nodes().await
     }
 
-    async fn remove_chaos(&mut self, _chaos: SwarmChaos) -> Result<()> {
-        todo!()
+    async fn remove_chaos(&mut self, chaos: SwarmChaos) -> Result<()> {
+        if !self.chaoses.remove(&chaos) {
+            bail!("Chaos {:?} not found", chaos);
+        }
+        self.apply_chaos()
     }
 
     async fn remove_all_chaos(&mut self) -> Result<()> {
-        todo!()
+        self.chaoses.clear();
+        self.apply_chaos()
     }
 
     async fn ensure_no_validator_restart(&self) -> Result<()> {
@@ -13,7 +13,9 @@ use aptos_crypto::{
 use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
 use aptos_types::{
     block_metadata_ext::BlockMetadataExt,
-    contract_event, event,
+    contract_event,
+    equivocation::EquivocationKind,
+    event,
     state_store::{
         state_key::StateKey,
         state_value::{PersistedStateValueMetadata, StateValueMetadata},
@@ -98,6 +100,7 @@ pub fn get_registry() -> Result<Registry> {
     tracer.trace_type::<contract_event::ContractEvent>(&samples)?;
     tracer.trace_type::<language_storage::TypeTag>(&samples)?;
     tracer.trace_type::<ValidatorTransaction>(&samples)?;
+    tracer.trace_type::<EquivocationKind>(&samples)?;
     tracer.trace_type::<BlockMetadataExt>(&samples)?;
     tracer.trace_type::<BlockEpiloguePayload>(&samples)?;
     tracer.trace_type::<transaction::Transaction>(&samples)?;
@@ -136,3 +136,114 @@ async fn test_account_key_rotation() {
         .await
         .expect("New key should be able to transfer");
 }
+
+#[tokio::test]
+async fn test_offline_signed_fee_payer_transfer() {
+    let (_swarm, mut cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .build_with_cli(2)
+        .await;
+    let temp_dir = tempfile::tempdir().unwrap();
+    let unsigned_file = temp_dir.path().join("transfer.bcs");
+
+    // The sender only holds the amount it transfers, the fee payer pays for gas
+    let sender = cli
+        .create_cli_account(KeyGen::from_os_rng().generate_ed25519_private_key(), 0)
+        .await
+        .unwrap();
+    cli.transfer_coins(0, sender, 100, None).await.unwrap();
+
+    // Gas can't be estimated by simulating the transaction signed by the sender alone
+    cli.save_unsigned_transfers(sender, 0, &[100], Some(1), None, unsigned_file.clone())
+        .await
+        .expect_err("--max-gas is required with a fee payer");
+    let gas_options = GasOptions {
+        gas_unit_price: None,
+        max_gas: Some(10_000),
+        expiration_secs: 60,
+    };
+    let summaries = cli
+        .save_unsigned_transfers(
+            sender,
+            0,
+            &[100],
+            Some(1),
+            Some(gas_options),
+            unsigned_file.clone(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(summaries[0].pending, Some(true));
+    assert!(temp_dir.path().join("transfer.bcs.json").exists());
+    cli.assert_account_balance_now(sender, 100).await;
+
+    let mut signature_files = vec![];
+    for index in [sender, 1] {
+        let signature_file = temp_dir.path().join(format!("signature-{}", index));
+        let summary = cli
+            .sign_transaction(index, unsigned_file.clone(), signature_file.clone())
+            .await
+            .unwrap();
+        assert_eq!(summary.signer, cli.account_id(index));
+        signature_files.push(signature_file);
+    }
+
+    // Every signer has to sign
+    cli.submit_signed_transaction(unsigned_file.clone(), signature_files[..1].to_vec())
+        .await
+        .expect_err("Fee payer signature is missing");
+    // Only signers of the transaction can sign
+    cli.sign_transaction(
+        0,
+        unsigned_file.clone(),
+        temp_dir.path().join("signature-0"),
+    )
+    .await
+    .expect_err("Receiver is not a signer");
+
+    let summary = cli
+        .submit_signed_transaction(unsigned_file, signature_files)
+        .await
+        .unwrap();
+    assert_eq!(summary.success, Some(true));
+    cli.assert_account_balance_now(sender, 0).await;
+    assert!(cli.account_balance_now(1).await.unwrap() < DEFAULT_FUNDED_COINS);
+}
+
+#[tokio::test]
+async fn test_offline_signed_transfers() {
+    let (_swarm, cli, _faucet) = SwarmBuilder::new_local(1)
+        .with_aptos()
+        .build_with_cli(2)
+        .await;
+    let temp_dir = tempfile::tempdir().unwrap();
+    let unsigned_file = temp_dir.path().join("transfers.bcs");
+
+    // A command submitting several transactions saves each of them, in order
+    let summaries = cli
+        .save_unsigned_transfers(0, 1, &[10, 20], None, None, unsigned_file.clone())
+        .await
+        .unwrap();
+    let sequence_number = summaries[0].sequence_number.unwrap();
+    assert_eq!(summaries[1].sequence_number, Some(sequence_number + 1));
+
+    for (i, file) in [
+        unsigned_file.clone(),
+        temp_dir.path().join("transfers.bcs.1"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let signature_file = temp_dir.path().join(format!("signature-{}", i));
+        cli.sign_transaction(0, file.clone(), signature_file.clone())
+            .await
+            .unwrap();
+        let summary = cli
+            .submit_signed_transaction(file, vec![signature_file])
+            .await
+            .unwrap();
+        assert_eq!(summary.success, Some(true));
+    }
+    cli.assert_account_balance_now(1, DEFAULT_FUNDED_COINS + 30)
+        .await;
+}
@@ -0,0 +1,44 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::account_address::AccountAddress;
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter};
+
+/// The kind of consensus messages a validator equivocated on.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum EquivocationKind {
+    /// Two different votes for the same round.
+    Vote,
+    /// Two different proposals for the same round.
+    Proposal,
+}
+
+/// Proof that a validator signed two conflicting consensus messages for the same round.
+///
+/// The messages are kept BCS-serialized (as `aptos_consensus_types::vote::Vote` or
+/// `aptos_consensus_types::block::Block`), as the consensus types depend on this crate.
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EquivocationEvidence {
+    pub author: AccountAddress,
+    pub epoch: u64,
+    pub round: u64,
+    pub kind: EquivocationKind,
+    #[serde(with = "serde_bytes")]
+    pub first_message: Vec<u8>,
+    #[serde(with = "serde_bytes")]
+    pub second_message: Vec<u8>,
+}
+
+impl Debug for EquivocationEvidence {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EquivocationEvidence")
+            .field("author", &self.author)
+            .field("epoch", &self.epoch)
+            .field("round", &self.round)
+            .field("kind", &self.kind)
+            .field("first_message_len", &self.first_message.len())
+            .field("second_message_len", &self.second_message.len())
+            .finish()
+    }
+}
@@ -10,9 +10,13 @@ use crate::{
         state_value::StateValue,
         table::{TableHandle, TableInfo},
     },
-    transaction::{AccountTransactionsWithProof, Version},
+    transaction::{AccountTransactionsWithProof, TransactionWithProof, Version},
 };
 use anyhow::Result;
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, StructTag},
+};
 
 #[derive(Clone, Copy, Eq, PartialEq)]
 pub enum Order {
@@ -23,6 +27,10 @@ pub enum Order {
 pub trait IndexerReader: Send + Sync {
     fn is_internal_indexer_enabled(&self) -> bool;
 
+    fn is_event_by_type_index_enabled(&self) -> bool;
+
+    fn is_transaction_by_entry_function_index_enabled(&self) -> bool;
+
     fn get_table_info(&self, handle: TableHandle) -> Result<Option<TableInfo>>;
 
     fn get_events(
@@ -43,6 +51,15 @@ pub trait IndexerReader: Send + Sync {
         ledger_version: Version,
     ) -> Result<Vec<EventWithVersion>>;
 
+    fn get_events_by_type(
+        &self,
+        struct_tag: &StructTag,
+        start_version: Version,
+        start_event_index: u64,
+        limit: u64,
+        ledger_version: Version,
+    ) -> Result<Vec<EventWithVersion>>;
+
     fn get_account_transactions(
         &self,
         address: AccountAddress,
@@ -52,6 +69,16 @@ pub trait IndexerReader: Send + Sync {
         ledger_version: Version,
     ) -> Result<AccountTransactionsWithProof>;
 
+    fn get_transactions_by_entry_function(
+        &self,
+        module_id: &ModuleId,
+        function_name: &Identifier,
+        start_version: Version,
+        limit: u64,
+        include_events: bool,
+        ledger_version: Version,
+    ) -> Result<Vec<TransactionWithProof>>;
+
     fn get_prefixed_state_value_iterator(
         &self,
         key_prefix: &StateKeyPrefix,
@@ -3,9 +3,8 @@
 
 #[cfg(any(test, feature = "fuzzing"))]
 use crate::dkg::DKGTranscriptMetadata;
-use crate::{dkg::DKGTranscript, jwks};
+use crate::{dkg::DKGTranscript, equivocation::EquivocationEvidence, jwks};
 use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
-#[cfg(any(test, feature = "fuzzing"))]
 use move_core_types::account_address::AccountAddress;
 use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
@@ -14,6 +13,7 @@ use std::fmt::Debug;
 pub enum ValidatorTransaction {
     DKGResult(DKGTranscript),
     ObservedJWKUpdate(jwks::QuorumCertifiedUpdate),
+    EquivocationEvidence(EquivocationEvidence),
 }
 
 impl ValidatorTransaction {
@@ -38,6 +38,9 @@ impl ValidatorTransaction {
             ValidatorTransaction::ObservedJWKUpdate(update) => {
                 Topic::JWK_CONSENSUS(update.update.issuer.clone())
             },
+            ValidatorTransaction::EquivocationEvidence(evidence) => {
+                Topic::EQUIVOCATION(evidence.author)
+            },
         }
     }
 
@@ -47,6 +50,9 @@ impl ValidatorTransaction {
             ValidatorTransaction::ObservedJWKUpdate(_) => {
                 "validator_transaction__observed_jwk_update"
             },
+            ValidatorTransaction::EquivocationEvidence(_) => {
+                "validator_transaction__equivocation_evidence"
+            },
         }
     }
 }
@@ -56,4 +62,5 @@ impl ValidatorTransaction {
 pub enum Topic {
     DKG,
     JWK_CONSENSUS(jwks::Issuer),
+    EQUIVOCATION(AccountAddress),
 }